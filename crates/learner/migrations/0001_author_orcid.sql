@@ -0,0 +1,2 @@
+-- Stable author identifiers (https://orcid.org)
+ALTER TABLE authors ADD COLUMN orcid TEXT;
@@ -0,0 +1,11 @@
+-- DOIs cited by each paper, forming the local citation graph
+CREATE TABLE IF NOT EXISTS paper_references (
+    id INTEGER PRIMARY KEY,
+    paper_id INTEGER NOT NULL,
+    doi TEXT NOT NULL COLLATE NOCASE,
+    FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE,
+    UNIQUE(paper_id, doi)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_references_paper_id ON paper_references(paper_id);
+CREATE INDEX IF NOT EXISTS idx_paper_references_doi ON paper_references(doi);
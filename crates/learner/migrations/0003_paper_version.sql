@@ -0,0 +1,2 @@
+-- Source-reported paper versions (e.g. arXiv's v1, v2, ...)
+ALTER TABLE papers ADD COLUMN version TEXT;
@@ -0,0 +1,7 @@
+-- Keep the title search index in sync when a paper's title is edited
+CREATE TRIGGER IF NOT EXISTS papers_au AFTER UPDATE OF title ON papers BEGIN
+    INSERT INTO papers_fts(papers_fts, rowid, title)
+    VALUES ('delete', old.id, old.title);
+    INSERT INTO papers_fts(rowid, title)
+    VALUES (new.id, new.title);
+END;
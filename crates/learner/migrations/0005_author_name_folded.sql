@@ -0,0 +1,3 @@
+-- Author names with case and diacritics folded away, for accent-insensitive search.
+-- Filled in by the application (see `format::fold_diacritics`), including for existing rows.
+ALTER TABLE authors ADD COLUMN name_folded TEXT;
@@ -0,0 +1,2 @@
+-- Soft deletion: removed papers are archived and hidden from queries until purged or restored
+ALTER TABLE papers ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Subject keywords reported by each paper's source (e.g. arXiv categories)
+CREATE TABLE IF NOT EXISTS paper_keywords (
+    id INTEGER PRIMARY KEY,
+    paper_id INTEGER NOT NULL,
+    keyword TEXT NOT NULL COLLATE NOCASE,
+    FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE,
+    UNIQUE(paper_id, keyword)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_keywords_paper_id ON paper_keywords(paper_id);
+CREATE INDEX IF NOT EXISTS idx_paper_keywords_keyword ON paper_keywords(keyword);
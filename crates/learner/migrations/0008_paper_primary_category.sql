@@ -0,0 +1,2 @@
+-- Primary subject category reported by the source (e.g. arXiv's cs.LG)
+ALTER TABLE papers ADD COLUMN primary_category TEXT;
@@ -0,0 +1,3 @@
+-- Folded author names with their paper, so `Query::by_author` can scan this narrow index
+-- instead of the whole `authors` table. Lookups by `paper_id` use `idx_authors_paper_id`.
+CREATE INDEX IF NOT EXISTS idx_authors_name_folded ON authors(name_folded, paper_id);
@@ -0,0 +1,3 @@
+-- ISO 639-1 code of the paper's language, guessed from the abstract unless set by the user
+ALTER TABLE papers ADD COLUMN language TEXT;
+CREATE INDEX IF NOT EXISTS idx_papers_language ON papers(language) WHERE language IS NOT NULL;
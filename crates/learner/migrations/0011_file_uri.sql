@@ -0,0 +1,3 @@
+-- Where a stored document lives, as a URI from its `Storage` backend. Documents stored before
+-- this column existed leave it NULL and are found through `path` and `filename`.
+ALTER TABLE files ADD COLUMN uri TEXT;
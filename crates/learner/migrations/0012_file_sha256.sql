@@ -0,0 +1,3 @@
+-- Hex SHA-256 of a stored document when it was stored, so later audits can detect corruption.
+-- Documents stored before this column existed leave it NULL.
+ALTER TABLE files ADD COLUMN sha256 TEXT;
@@ -0,0 +1,11 @@
+-- When the paper was first added to the library, as SQLite's UTC `datetime('now')`. Unlike the
+-- row bookkeeping in `created_at`, this is the paper's own date added for sorting and filtering.
+-- Papers stored before this column existed take their `created_at`.
+ALTER TABLE papers ADD COLUMN added_at TEXT;
+UPDATE papers SET added_at = created_at;
+CREATE INDEX IF NOT EXISTS idx_papers_added_at ON papers(added_at);
+
+CREATE TRIGGER IF NOT EXISTS papers_added_at AFTER INSERT ON papers
+WHEN new.added_at IS NULL BEGIN
+    UPDATE papers SET added_at = datetime('now') WHERE id = new.id;
+END;
@@ -39,7 +39,7 @@ CREATE TABLE IF NOT EXISTS files (
     paper_id INTEGER NOT NULL,
     path TEXT NOT NULL,
     filename TEXT NOT NULL,
-    download_status TEXT NOT NULL,  -- 'success', 'failed', 'pending'
+    download_status TEXT NOT NULL,  -- 'Success', 'Failed', 'Pending'
     error_message TEXT,  -- NULL if successful
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now')),
@@ -4,7 +4,9 @@
 //! to the database. It supports several addition patterns:
 //!
 //! - Adding paper metadata only
+//! - Adding the metadata of many papers at once
 //! - Adding complete papers with documents
+//! - Adding papers with documents already on disk
 //! - Batch addition of documents for existing papers
 //!
 //! The implementation emphasizes:
@@ -43,9 +45,13 @@
 
 use std::collections::HashSet;
 
-use futures::future::try_join_all;
+use futures::future::join_all;
 
 use super::*;
+use crate::resource::StoredDocument;
+
+/// How many papers [`Add::documents`] checks for existing documents per statement.
+const EXISTING_DOCS_BATCH: usize = 100;
 
 // TODO (autoparallel): Would be good to have `Papers` and `Documents` and `Completes` instead,
 // possibly, and just have a simple API for single paper calls that just dumps into the 3 variants.
@@ -55,16 +61,51 @@ use super::*;
 /// aspect of paper and document management:
 ///
 /// - Metadata-only additions
+/// - Batch metadata additions
 /// - Complete paper additions (metadata + document)
+/// - Local paper additions (metadata + a document already on disk)
 /// - Batch document additions for existing papers
 #[derive(Debug)]
 pub enum Addition<'a> {
   /// Add just the paper metadata without associated documents
   Paper(&'a Paper),
+  /// Add the metadata of many papers in a single transaction
+  Papers(&'a [Paper]),
   /// Add both paper metadata and download its associated document
   Complete(&'a Paper),
   /// Add documents for papers matching a specified query
   Documents(Query<'a>),
+  /// Add paper metadata and store a PDF already on the local filesystem as its document
+  LocalDocument {
+    /// The paper the document belongs to
+    paper: &'a Paper,
+    /// Path of the PDF to copy into storage
+    path:  &'a Path,
+  },
+}
+
+/// What to do when an added paper is already stored under the same source and identifier.
+///
+/// Set with [`Add::on_conflict`]. Only the paper's metadata is affected; documents of
+/// [`Add::complete`] and [`Add::local_document`] additions are stored either way.
+///
+/// Archived papers are not duplicates: adding one again replaces the archived copy as
+/// [`Conflict::Overwrite`] would and restores it, whatever the strategy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Conflict {
+  /// Fail with [`LearnerError::DatabaseDuplicatePaper`], leaving the stored paper unchanged
+  #[default]
+  Error,
+  /// Keep the stored paper unchanged and add nothing
+  Skip,
+  /// Replace the stored metadata, authors, references and keywords with the new ones
+  Overwrite,
+  /// Keep the stored metadata, only filling in fields that are empty there
+  ///
+  /// The abstract, PDF URL, DOI, version, primary category and language are taken from the new
+  /// paper when the stored paper lacks them, authors are added only if none were stored, and
+  /// references and keywords are combined.
+  Merge,
 }
 
 /// Database instruction for adding papers and documents.
@@ -82,6 +123,8 @@ pub enum Addition<'a> {
 pub struct Add<'a> {
   /// The type of addition operation to perform
   addition: Addition<'a>,
+  /// How to handle a paper that is already stored
+  conflict: Conflict,
 }
 
 impl<'a> Add<'a> {
@@ -108,7 +151,43 @@ impl<'a> Add<'a> {
   /// # Ok(())
   /// # }
   /// ```
-  pub fn paper(paper: &'a Paper) -> Self { Self { addition: Addition::Paper(paper) } }
+  pub fn paper(paper: &'a Paper) -> Self {
+    Self { addition: Addition::Paper(paper), conflict: Conflict::default() }
+  }
+
+  /// Creates an instruction to add the metadata of many papers at once.
+  ///
+  /// All papers, with their authors, references and keywords, are written in a single
+  /// transaction, reusing prepared statements across papers, which is much faster than adding
+  /// them one by one. Papers are matched by [`Paper::identity`]: one that appears more than once
+  /// in `papers` is only added the first time, and one that is already stored is handled as
+  /// [`Add::on_conflict`] says. With [`Conflict::Error`], a stored paper fails the whole batch
+  /// and nothing is added.
+  ///
+  /// Executing this returns the papers that were added (or, when merging, their stored
+  /// versions) in input order; the papers left out of it were skipped.
+  ///
+  /// # Arguments
+  ///
+  /// * `papers` - The papers to add
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Add, Conflict};
+  /// # use learner::prelude::*;
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example(papers: Vec<Paper>) -> Result<(), Box<dyn std::error::Error>> {
+  /// # let mut learner = Learner::builder().build().await?;
+  /// let added =
+  ///   Add::papers(&papers).on_conflict(Conflict::Skip).execute(&mut learner.database).await?;
+  /// println!("Added {} of {} papers", added.len(), papers.len());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn papers(papers: &'a [Paper]) -> Self {
+    Self { addition: Addition::Papers(papers), conflict: Conflict::default() }
+  }
 
   /// Creates an instruction to add a complete paper with its document.
   ///
@@ -117,6 +196,11 @@ impl<'a> Add<'a> {
   /// 2. Download the paper's document
   /// 3. Store the document in the configured storage location
   ///
+  /// If the download fails, a `files` record with a `Failed` status and the error message is
+  /// stored before the error is returned, so the download can be retried later. On an offline
+  /// database (see [`Database::set_offline`]) nothing is stored and [`LearnerError::Offline`]
+  /// is returned.
+  ///
   /// # Arguments
   ///
   /// * `paper` - Reference to the paper to add
@@ -135,16 +219,23 @@ impl<'a> Add<'a> {
   /// # Ok(())
   /// # }
   /// ```
-  pub fn complete(paper: &'a Paper) -> Self { Self { addition: Addition::Complete(paper) } }
+  pub fn complete(paper: &'a Paper) -> Self {
+    Self { addition: Addition::Complete(paper), conflict: Conflict::default() }
+  }
 
   /// Creates an instruction to add documents for papers matching a query.
   ///
   /// This method supports batch document addition by:
   /// 1. Finding papers matching the query
   /// 2. Filtering out papers that already have documents
-  /// 3. Concurrently downloading missing documents
+  /// 3. Concurrently downloading missing documents, at most [`Database::set_max_concurrency`] at a
+  ///    time
   /// 4. Storing documents in the configured location
   ///
+  /// Documents that downloaded are stored even when others fail; the first failure is then
+  /// returned. On an offline database (see [`Database::set_offline`]) nothing is downloaded and
+  /// [`LearnerError::Offline`] is returned if any paper still needs a document.
+  ///
   /// # Arguments
   ///
   /// * `query` - Query to identify papers needing documents
@@ -164,7 +255,38 @@ impl<'a> Add<'a> {
   /// # Ok(())
   /// # }
   /// ```
-  pub fn documents(query: Query<'a>) -> Self { Self { addition: Addition::Documents(query) } }
+  pub fn documents(query: Query<'a>) -> Self {
+    Self { addition: Addition::Documents(query), conflict: Conflict::default() }
+  }
+
+  /// Creates an instruction to add a paper together with a PDF from the local filesystem.
+  ///
+  /// This works like [`Add::complete`], except the document is copied from `path` into the
+  /// configured storage location instead of being downloaded. If the paper is already stored,
+  /// only the document is added.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper` - Reference to the paper to add
+  /// * `path` - Path of the PDF to store for the paper
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use std::path::Path;
+  /// # use learner::database::Add;
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let learner = Learner::builder().build().await?;
+  /// # let retriever = learner.retriever;
+  /// let paper = retriever.get_paper("2301.07041").await?;
+  /// let instruction = Add::local_document(&paper, Path::new("downloads/2301.07041.pdf"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn local_document(paper: &'a Paper, path: &'a Path) -> Self {
+    Self { addition: Addition::LocalDocument { paper, path }, conflict: Conflict::default() }
+  }
 
   /// Converts a paper-only addition to a complete addition.
   ///
@@ -186,43 +308,166 @@ impl<'a> Add<'a> {
   /// ```
   pub fn with_document(self) -> Self {
     match self.addition {
-      Addition::Paper(paper) => Self { addition: Addition::Complete(paper) },
+      Addition::Paper(paper) =>
+        Self { addition: Addition::Complete(paper), conflict: Conflict::default() },
       _ => self,
     }
   }
 
-  /// Builds the SQL for inserting paper metadata.
-  fn build_paper_sql(paper: &Paper) -> (String, Vec<Option<String>>) {
+  /// Sets how a paper that is already stored is handled, see [`Conflict`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Add, Conflict};
+  /// # use learner::prelude::*;
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let mut learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("2301.07041").await?;
+  /// // Succeeds whether or not the paper was stored already
+  /// Add::paper(&paper).on_conflict(Conflict::Merge).execute(&mut learner.database).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn on_conflict(mut self, conflict: Conflict) -> Self {
+    self.conflict = conflict;
+    self
+  }
+
+  /// Builds the SQL for inserting paper metadata, resolving a duplicate as `conflict` says.
+  ///
+  /// [`Conflict::Error`] is handled before this runs, so it is treated like
+  /// [`Conflict::Skip`] here.
+  fn build_paper_sql(paper: &Paper, conflict: Conflict) -> (String, Vec<Option<String>>) {
+    let on_conflict = match conflict {
+      Conflict::Error | Conflict::Skip => "DO NOTHING",
+      Conflict::Overwrite =>
+        "DO UPDATE SET
+           title = excluded.title,
+           abstract_text = excluded.abstract_text,
+           publication_date = excluded.publication_date,
+           pdf_url = excluded.pdf_url,
+           doi = excluded.doi,
+           version = excluded.version,
+           primary_category = excluded.primary_category,
+           language = excluded.language,
+           archived = 0,
+           updated_at = datetime('now')",
+      Conflict::Merge =>
+        "DO UPDATE SET
+           abstract_text = CASE WHEN abstract_text = '' THEN excluded.abstract_text
+                                ELSE abstract_text END,
+           pdf_url = COALESCE(pdf_url, excluded.pdf_url),
+           doi = COALESCE(doi, excluded.doi),
+           version = COALESCE(version, excluded.version),
+           primary_category = COALESCE(primary_category, excluded.primary_category),
+           language = COALESCE(language, excluded.language),
+           updated_at = datetime('now')",
+    };
     (
-      "INSERT INTO papers (
+      format!(
+        "INSERT INTO papers (
             title, abstract_text, publication_date,
-            source, source_identifier, pdf_url, doi
-        ) VALUES (?, ?, ?, ?, ?, ?, ?)"
-        .to_string(),
+            source, source_identifier, pdf_url, doi, version, primary_category, language
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(source, source_identifier) {on_conflict}"
+      ),
       vec![
         Some(paper.title.clone()),
         Some(paper.abstract_text.clone()),
-        Some(paper.publication_date.to_rfc3339()),
+        Some(paper.publication_date.to_iso8601()),
         Some(paper.source.to_string()),
         Some(paper.source_identifier.clone()),
         paper.pdf_url.clone(),
         paper.doi.clone(),
+        paper.version.clone(),
+        paper.primary_category.clone(),
+        paper
+          .language
+          .clone()
+          .or_else(|| format::detect_language(&paper.abstract_text).map(str::to_string)),
       ],
     )
   }
 
+  /// Builds every statement that stores `paper`'s metadata, resolving a duplicate as
+  /// `conflict` says.
+  fn build_paper_statements(paper: &Paper, conflict: Conflict) -> PaperStatements {
+    let clear =
+      if conflict == Conflict::Overwrite { Update::build_clear_sql(paper) } else { Vec::new() };
+    PaperStatements {
+      paper: Self::build_paper_sql(paper, conflict),
+      clear,
+      author_count: Self::build_author_count_sql(paper),
+      authors: paper.authors.iter().map(|author| Self::build_author_sql(author, paper)).collect(),
+      references: paper
+        .references
+        .iter()
+        .map(|doi| Self::build_reference_sql(doi, paper))
+        .chain(paper.keywords.iter().map(|keyword| Self::build_keyword_sql(keyword, paper)))
+        .collect(),
+    }
+  }
+
   /// Builds the SQL for inserting author information.
-  fn build_author_sql(author: &Author, paper: &Paper) -> (String, Vec<Option<String>>) {
+  pub(super) fn build_author_sql(author: &Author, paper: &Paper) -> (String, Vec<Option<String>>) {
     (
-      "INSERT INTO authors (paper_id, name, affiliation, email)
-         SELECT id, ?, ?, ?
+      "INSERT INTO authors (paper_id, name, name_folded, affiliation, email, orcid)
+         SELECT id, ?, ?, ?, ?, ?
          FROM papers
          WHERE source = ? AND source_identifier = ?"
         .to_string(),
       vec![
         Some(author.name.clone()),
+        Some(format::fold_diacritics(&author.name)),
         author.affiliation.clone(),
         author.email.clone(),
+        author.orcid.clone(),
+        Some(paper.source.to_string()),
+        Some(paper.source_identifier.clone()),
+      ],
+    )
+  }
+
+  /// Builds the SQL for counting the authors stored for the paper.
+  fn build_author_count_sql(paper: &Paper) -> (String, Vec<Option<String>>) {
+    (
+      "SELECT COUNT(*)
+         FROM authors a
+         JOIN papers p ON p.id = a.paper_id
+         WHERE p.source = ? AND p.source_identifier = ?"
+        .to_string(),
+      vec![Some(paper.source.to_string()), Some(paper.source_identifier.clone())],
+    )
+  }
+
+  /// Builds the SQL for recording a DOI cited by the paper.
+  pub(super) fn build_reference_sql(doi: &str, paper: &Paper) -> (String, Vec<Option<String>>) {
+    (
+      "INSERT OR IGNORE INTO paper_references (paper_id, doi)
+         SELECT id, ?
+         FROM papers
+         WHERE source = ? AND source_identifier = ?"
+        .to_string(),
+      vec![
+        Some(doi.to_string()),
+        Some(paper.source.to_string()),
+        Some(paper.source_identifier.clone()),
+      ],
+    )
+  }
+
+  /// Builds the SQL for recording a subject keyword of the paper.
+  pub(super) fn build_keyword_sql(keyword: &str, paper: &Paper) -> (String, Vec<Option<String>>) {
+    (
+      "INSERT OR IGNORE INTO paper_keywords (paper_id, keyword)
+         SELECT id, ?
+         FROM papers
+         WHERE source = ? AND source_identifier = ?"
+        .to_string(),
+      vec![
+        Some(keyword.to_string()),
         Some(paper.source.to_string()),
         Some(paper.source_identifier.clone()),
       ],
@@ -230,26 +475,66 @@ impl<'a> Add<'a> {
   }
 
   /// Builds the SQL for recording document storage information.
+  ///
+  /// Any previous record for the paper (e.g. an earlier failed download) is replaced. The
+  /// document's URI and checksum are recorded along with, for documents on the filesystem, its
+  /// directory.
   fn build_document_sql(
     paper: &Paper,
-    storage_path: &Path,
-    filename: &Path,
+    storage: &dyn Storage,
+    document: &StoredDocument,
+  ) -> (String, Vec<Option<String>>) {
+    let uri = storage.uri(&document.key);
+    (
+      "INSERT OR REPLACE INTO files (paper_id, path, filename, uri, sha256, download_status)
+         SELECT p.id, ?, ?, ?, ?, 'Success'
+         FROM papers p
+         WHERE p.source = ? AND p.source_identifier = ?"
+        .to_string(),
+      vec![
+        Some(Self::storage_dir(&uri)),
+        Some(document.key.clone()),
+        Some(uri),
+        Some(document.sha256.clone()),
+        Some(paper.source.to_string()),
+        Some(paper.source_identifier.clone()),
+      ],
+    )
+  }
+
+  /// Builds the SQL for recording a failed document download along with its error message.
+  fn build_failed_document_sql(
+    paper: &Paper,
+    storage: &dyn Storage,
+    error: &LearnerError,
   ) -> (String, Vec<Option<String>>) {
+    let filename = paper.filename().to_string_lossy().to_string();
     (
-      "INSERT INTO files (paper_id, path, filename, download_status)
-         SELECT p.id, ?, ?, 'Success'
+      "INSERT OR REPLACE INTO files (paper_id, path, filename, download_status, error_message)
+         SELECT p.id, ?, ?, 'Failed', ?
          FROM papers p
          WHERE p.source = ? AND p.source_identifier = ?"
         .to_string(),
       vec![
-        Some(storage_path.to_string_lossy().to_string()),
-        Some(filename.to_string_lossy().to_string()),
+        Some(Self::storage_dir(&storage.uri(&filename))),
+        Some(filename),
+        Some(crate::error::report(error)),
         Some(paper.source.to_string()),
         Some(paper.source_identifier.clone()),
       ],
     )
   }
 
+  /// The directory of a `file://` document URI, or an empty string for other backends.
+  fn storage_dir(uri: &str) -> String {
+    Url::parse(uri)
+      .ok()
+      .filter(|url| url.scheme() == "file")
+      .and_then(|url| url.to_file_path().ok())
+      .and_then(|path| path.parent().map(|dir| dir.to_string_lossy().to_string()))
+      .unwrap_or_default()
+  }
+
   /// Builds the SQL for checking existing document records.
   fn build_existing_docs_sql(papers: &[&Paper]) -> (String, Vec<Option<String>>) {
     let mut params = Vec::new();
@@ -280,43 +565,101 @@ impl DatabaseInstruction for Add<'_> {
   type Output = Vec<Paper>;
 
   async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    db.ensure_writable()?;
     match &self.addition {
       Addition::Paper(paper) => {
         // Check for existing paper
-        if Query::by_source(&paper.source, &paper.source_identifier)
-          .execute(db)
-          .await?
-          .into_iter()
-          .next()
-          .is_some()
-        {
-          return Err(LearnerError::DatabaseDuplicatePaper(paper.title.clone()));
+        let exists = !Query::by_paper(paper).execute(db).await?.is_empty();
+        // A removed paper that is added again replaces its archived copy
+        let conflict =
+          if !exists && !Query::by_paper(paper).only_archived().execute(db).await?.is_empty() {
+            Conflict::Overwrite
+          } else {
+            self.conflict
+          };
+        match (exists, conflict) {
+          (true, Conflict::Error) =>
+            return Err(LearnerError::DatabaseDuplicatePaper(paper.title.clone())),
+          (true, Conflict::Skip) => return Ok(Vec::new()),
+          _ => (),
         }
 
-        let (paper_sql, paper_params) = Self::build_paper_sql(paper);
-        let author_statements: Vec<_> =
-          paper.authors.iter().map(|author| Self::build_author_sql(author, paper)).collect();
-
-        db.conn
+        let statements = Self::build_paper_statements(paper, conflict);
+        let inserted = db
+          .conn
           .call(move |conn| {
-            let tx = conn.transaction()?;
-            tx.execute(&paper_sql, params_from_iter(paper_params))?;
-
-            for (author_sql, author_params) in author_statements {
-              tx.execute(&author_sql, params_from_iter(author_params))?;
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            // Nothing changes if the paper appeared since the check above and is skipped
+            if !statements.execute(&tx)? {
+              return Ok(false);
             }
+            tx.commit()?;
+            Ok(true)
+          })
+          .await?;
 
+        match (inserted, exists && conflict == Conflict::Merge) {
+          (false, _) => Ok(Vec::new()),
+          (true, true) => Query::by_paper(paper).execute(db).await,
+          (true, false) => Ok(vec![(*paper).clone()]),
+        }
+      },
+
+      Addition::Papers(papers) => {
+        let mut seen = HashSet::new();
+        let unique: Vec<Paper> =
+          papers.iter().filter(|paper| seen.insert(paper.identity())).cloned().collect();
+        let conflict = self.conflict;
+
+        let outcome = db
+          .conn
+          .call(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let mut added = Vec::new();
+            for paper in unique {
+              let archived: Option<bool> = tx
+                .prepare_cached(
+                  "SELECT archived FROM papers WHERE source = ?1 AND source_identifier = ?2",
+                )?
+                .query_row([&paper.source, &paper.source_identifier], |row| row.get(0))
+                .optional()?;
+              // A removed paper that is added again replaces its archived copy
+              let (stored, conflict) = match archived {
+                None => (false, conflict),
+                Some(true) => (false, Conflict::Overwrite),
+                Some(false) => match conflict {
+                  Conflict::Error => return Ok(Err(paper.title)),
+                  Conflict::Skip => continue,
+                  conflict => (true, conflict),
+                },
+              };
+              if Self::build_paper_statements(&paper, conflict).execute(&tx)? {
+                added.push((paper, stored && conflict == Conflict::Merge));
+              }
+            }
             tx.commit()?;
-            Ok(())
+            Ok(Ok(added))
           })
           .await?;
 
-        Ok(vec![(*paper).clone()])
+        let mut added = Vec::new();
+        for (paper, merged) in outcome.map_err(LearnerError::DatabaseDuplicatePaper)? {
+          if merged {
+            added.extend(Query::by_paper(&paper).execute(db).await?);
+          } else {
+            added.push(paper);
+          }
+        }
+        Ok(added)
       },
 
       Addition::Complete(paper) => {
+        db.ensure_online()?;
+
         // Add paper first
-        if let Err(LearnerError::DatabaseDuplicatePaper(_)) = Add::paper(paper).execute(db).await {
+        if let Err(LearnerError::DatabaseDuplicatePaper(_)) =
+          Add::paper(paper).on_conflict(self.conflict).execute(db).await
+        {
           warn!(
             "Tried to add complete paper when paper existed in database already, attempting to \
              add only the document!"
@@ -324,20 +667,54 @@ impl DatabaseInstruction for Add<'_> {
         };
 
         // Add document
-        let storage_path = db.get_storage_path().await?;
-        let filename = paper.download_pdf(&storage_path).await?;
-
-        let (doc_sql, doc_params) = Self::build_document_sql(paper, &storage_path, &filename);
+        let storage = db.storage().await?;
+        let (doc_sql, doc_params, download_error) = match paper.store_download(&*storage).await {
+          Ok(document) => {
+            let (sql, params) = Self::build_document_sql(paper, &*storage, &document);
+            (sql, params, None)
+          },
+          Err(e) => {
+            warn!("Failed to download PDF for {}: {}", paper.source_identifier, report(&e));
+            let (sql, params) = Self::build_failed_document_sql(paper, &*storage, &e);
+            (sql, params, Some(e))
+          },
+        };
 
         db.conn
           .call(move |conn| {
-            let tx = conn.transaction()?;
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
             tx.execute(&doc_sql, params_from_iter(doc_params))?;
             tx.commit()?;
             Ok(())
           })
           .await?;
 
+        if let Some(e) = download_error {
+          return Err(e);
+        }
+
+        Ok(vec![(*paper).clone()])
+      },
+
+      Addition::LocalDocument { paper, path } => {
+        match Add::paper(paper).on_conflict(self.conflict).execute(db).await {
+          Ok(_) => (),
+          Err(LearnerError::DatabaseDuplicatePaper(_)) =>
+            debug!("{} is already stored, only adding its document", paper.source_identifier),
+          Err(e) => return Err(e),
+        }
+
+        let storage = db.storage().await?;
+        let document = paper.store_copy(&*storage, path).await?;
+        let (doc_sql, doc_params) = Self::build_document_sql(paper, &*storage, &document);
+
+        db.conn
+          .call(move |conn| {
+            conn.execute(&doc_sql, params_from_iter(doc_params))?;
+            Ok(())
+          })
+          .await?;
+
         Ok(vec![(*paper).clone()])
       },
 
@@ -347,12 +724,11 @@ impl DatabaseInstruction for Add<'_> {
           return Ok(Vec::new());
         }
 
-        let storage_path = db.get_storage_path().await?;
-        let mut added = Vec::new();
+        let storage = db.storage().await?;
 
-        // Process papers in batches
-        for chunk in papers.chunks(10) {
-          // Check which papers already have documents
+        // Check which papers already have documents, in batches to bound the statement size
+        let mut pending = Vec::new();
+        for chunk in papers.chunks(EXISTING_DOCS_BATCH) {
           let paper_refs: Vec<_> = chunk.iter().collect();
           let (check_sql, check_params) = Self::build_existing_docs_sql(&paper_refs);
 
@@ -370,55 +746,108 @@ impl DatabaseInstruction for Add<'_> {
             })
             .await?;
 
-          // Create future for each paper that needs downloading
-          let download_futures: Vec<_> = chunk
-            .iter()
-            .filter(|paper| {
-              let key = (paper.source.to_string(), paper.source_identifier.clone());
-              !existing_docs.contains(&key)
-            })
-            .map(|paper| {
-              let paper = paper.clone();
-              let storage_path = storage_path.clone();
-              async move { paper.download_pdf(&storage_path).await.map(|f| (paper, f)) }
-            })
-            .collect();
-
-          if download_futures.is_empty() {
-            continue;
+          pending.extend(chunk.iter().filter(|paper| {
+            let key = (paper.source.to_string(), paper.source_identifier.clone());
+            !existing_docs.contains(&key)
+          }));
+        }
+        if pending.is_empty() {
+          return Ok(Vec::new());
+        }
+        db.ensure_online()?;
+
+        // Download PDFs concurrently, as many at a time as `Database::set_max_concurrency` allows
+        let network: &Database = db;
+        let results = join_all(pending.into_iter().map(|paper| {
+          let storage = storage.clone();
+          async move {
+            let _permit = network.network_permit().await;
+            (paper, paper.store_download(&*storage).await)
           }
+        }))
+        .await;
 
-          // Download PDFs concurrently and collect results
-          let results = try_join_all(download_futures).await?;
-
-          // Prepare batch insert for successful downloads
-          let mut insert_sqls = Vec::new();
-          let mut insert_params = Vec::new();
-
-          for (paper, filename) in results {
-            let (sql, params) = Self::build_document_sql(&paper, &storage_path, &filename);
-            insert_sqls.push(sql);
-            insert_params.extend(params);
-            added.push(paper);
+        // Prepare batch insert for successful downloads, reporting the first failure after
+        let mut added = Vec::new();
+        let mut inserts = Vec::new();
+        let mut first_error = None;
+        for (paper, result) in results {
+          match result {
+            Ok(document) => {
+              inserts.push(Self::build_document_sql(paper, &*storage, &document));
+              added.push(paper.clone());
+            },
+            Err(e) => {
+              warn!("Failed to download PDF for {}: {}", paper.source_identifier, report(&e));
+              first_error.get_or_insert(e);
+            },
           }
+        }
 
-          if !insert_sqls.is_empty() {
-            // Execute batch insert
-            db.conn
-              .call(move |conn| {
-                let tx = conn.transaction()?;
-                for (sql, params) in insert_sqls.iter().zip(insert_params.chunks(4)) {
-                  tx.execute(sql, params_from_iter(params))?;
-                }
-                tx.commit()?;
-                Ok(())
-              })
-              .await?;
-          }
+        if !inserts.is_empty() {
+          // Execute batch insert
+          db.conn
+            .call(move |conn| {
+              let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+              for (sql, params) in inserts {
+                tx.execute(&sql, params_from_iter(params))?;
+              }
+              tx.commit()?;
+              Ok(())
+            })
+            .await?;
         }
 
+        if let Some(e) = first_error {
+          return Err(e);
+        }
         Ok(added)
       },
     }
   }
 }
+
+/// The statements that store one paper's metadata, see [`Add::build_paper_statements`].
+struct PaperStatements {
+  /// Inserts (or updates) the paper's own row
+  paper:        (String, Vec<Option<String>>),
+  /// Clears the stored authors, references and keywords before an overwrite
+  clear:        Vec<(String, Vec<Option<String>>)>,
+  /// Counts the authors already stored for the paper
+  author_count: (String, Vec<Option<String>>),
+  /// Inserts each author
+  authors:      Vec<(String, Vec<Option<String>>)>,
+  /// Inserts each reference and keyword
+  references:   Vec<(String, Vec<Option<String>>)>,
+}
+
+impl PaperStatements {
+  /// Runs the statements in `tx`, returning `false` without doing anything else if the
+  /// paper's row was left alone because it is a duplicate being skipped.
+  fn execute(self, tx: &rusqlite::Transaction<'_>) -> rusqlite::Result<bool> {
+    let (paper_sql, paper_params) = self.paper;
+    if tx.prepare_cached(&paper_sql)?.execute(params_from_iter(paper_params))? == 0 {
+      return Ok(false);
+    }
+
+    for (clear_sql, clear_params) in self.clear {
+      tx.prepare_cached(&clear_sql)?.execute(params_from_iter(clear_params))?;
+    }
+
+    // When merging, stored authors are kept as they are
+    let (author_count_sql, author_count_params) = self.author_count;
+    let stored_authors: i64 = tx
+      .prepare_cached(&author_count_sql)?
+      .query_row(params_from_iter(author_count_params), |row| row.get(0))?;
+    if stored_authors == 0 {
+      for (author_sql, author_params) in self.authors {
+        tx.prepare_cached(&author_sql)?.execute(params_from_iter(author_params))?;
+      }
+    }
+
+    for (reference_sql, reference_params) in self.references {
+      tx.prepare_cached(&reference_sql)?.execute(params_from_iter(reference_params))?;
+    }
+    Ok(true)
+  }
+}
@@ -0,0 +1,171 @@
+//! Merge instruction implementation for combining duplicate papers.
+//!
+//! The same paper is often stored twice when it is added from different sources, e.g. once
+//! from arXiv and once by its DOI. Merging folds one stored paper (the absorbed paper) into
+//! another (the survivor):
+//!
+//! - Authors are combined, matched by name ignoring case and diacritics
+//! - References and keywords are combined
+//! - Empty or missing fields of the survivor are filled in from the absorbed paper
+//! - The absorbed paper's document record moves over if the survivor has none
+//! - The absorbed paper is then deleted
+//!
+//! Everything happens in a single transaction, and the survivor keeps its source and
+//! identifier.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{
+//!   database::{Database, Merge, Query},
+//!   prelude::*,
+//! };
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut db = Database::open("papers.db").await?;
+//!
+//! let arxiv = Query::by_source("arxiv", "2301.07041").one(&mut db).await?;
+//! let doi = Query::by_source("doi", "10.48550/arxiv.2301.07041").one(&mut db).await?;
+//! let merged = Merge::papers(&arxiv, &doi).execute(&mut db).await?;
+//! println!("Kept {} with {} authors", merged.title, merged.authors.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::database::{Add, Update};
+
+/// Instruction for merging one stored paper into another.
+pub struct Merge<'a> {
+  /// The paper that is kept
+  survivor: &'a Paper,
+  /// The paper folded into the survivor and then deleted
+  absorbed: &'a Paper,
+}
+
+impl<'a> Merge<'a> {
+  /// Creates a merge instruction folding `absorbed` into `survivor`.
+  ///
+  /// Both papers are matched in the database by source and identifier; their other fields
+  /// are taken from the given values. Merging a paper into itself leaves it unchanged.
+  ///
+  /// # Arguments
+  ///
+  /// * `survivor` - The paper to keep, whose values win where both papers have one
+  /// * `absorbed` - The duplicate to fold in and delete
+  pub fn papers(survivor: &'a Paper, absorbed: &'a Paper) -> Self { Self { survivor, absorbed } }
+
+  /// Combines the two papers' metadata, preferring the survivor's values.
+  ///
+  /// The survivor's title and optional fields are kept unless empty or missing, and the longer
+  /// of the two abstracts is kept. Matching authors have their missing fields filled in the
+  /// same way.
+  fn merged(&self) -> Paper {
+    let (survivor, absorbed) = (self.survivor, self.absorbed);
+    let mut paper = survivor.clone();
+
+    if paper.title.trim().is_empty() {
+      paper.title = absorbed.title.clone();
+    }
+    if absorbed.abstract_text.trim().len() > paper.abstract_text.trim().len() {
+      paper.abstract_text = absorbed.abstract_text.clone();
+    }
+    paper.pdf_url = paper.pdf_url.or_else(|| absorbed.pdf_url.clone());
+    paper.doi = paper.doi.or_else(|| absorbed.doi.clone());
+    paper.version = paper.version.or_else(|| absorbed.version.clone());
+    paper.primary_category = paper.primary_category.or_else(|| absorbed.primary_category.clone());
+    paper.language = paper.language.or_else(|| absorbed.language.clone());
+
+    for author in &absorbed.authors {
+      let name = format::fold_diacritics(author.name.trim());
+      match paper.authors.iter_mut().find(|a| format::fold_diacritics(a.name.trim()) == name) {
+        Some(existing) => {
+          existing.affiliation = existing.affiliation.take().or_else(|| author.affiliation.clone());
+          existing.email = existing.email.take().or_else(|| author.email.clone());
+          existing.orcid = existing.orcid.take().or_else(|| author.orcid.clone());
+        },
+        None => paper.authors.push(author.clone()),
+      }
+    }
+
+    for reference in &absorbed.references {
+      if !paper.references.iter().any(|r| r.eq_ignore_ascii_case(reference)) {
+        paper.references.push(reference.clone());
+      }
+    }
+    for keyword in &absorbed.keywords {
+      if !paper.keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)) {
+        paper.keywords.push(keyword.clone());
+      }
+    }
+
+    paper
+  }
+}
+
+#[async_trait]
+impl DatabaseInstruction for Merge<'_> {
+  /// The merged survivor
+  type Output = Paper;
+
+  async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    db.ensure_writable()?;
+    if self.survivor.same_identity(self.absorbed) {
+      return Ok(self.survivor.clone());
+    }
+
+    let paper = self.merged();
+    let (paper_sql, paper_params) = Update::build_paper_sql(&paper);
+    let statements: Vec<_> = Update::build_clear_sql(&paper)
+      .into_iter()
+      .chain(paper.authors.iter().map(|author| Add::build_author_sql(author, &paper)))
+      .chain(paper.references.iter().map(|doi| Add::build_reference_sql(doi, &paper)))
+      .chain(paper.keywords.iter().map(|keyword| Add::build_keyword_sql(keyword, &paper)))
+      .collect();
+    let survivor = [paper.source.clone(), paper.source_identifier.clone()];
+    let absorbed = [self.absorbed.source.clone(), self.absorbed.source_identifier.clone()];
+
+    let missing = db
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if tx.execute(&paper_sql, params_from_iter(paper_params))? == 0 {
+          return Ok(Some(survivor));
+        }
+
+        // Keep the absorbed paper's document if the survivor has none
+        tx.execute(
+          "UPDATE files
+             SET paper_id = (SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2)
+             WHERE paper_id = (SELECT id FROM papers WHERE source = ?3 AND source_identifier = ?4)
+               AND NOT EXISTS (
+                 SELECT 1 FROM files WHERE paper_id = (
+                   SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2
+                 )
+               )",
+          [&survivor[0], &survivor[1], &absorbed[0], &absorbed[1]],
+        )?;
+        let deleted =
+          tx.execute("DELETE FROM papers WHERE source = ?1 AND source_identifier = ?2", [
+            &absorbed[0],
+            &absorbed[1],
+          ])?;
+        if deleted == 0 {
+          return Ok(Some(absorbed));
+        }
+
+        for (sql, params) in statements {
+          tx.execute(&sql, params_from_iter(params))?;
+        }
+
+        tx.commit()?;
+        Ok(None)
+      })
+      .await?;
+
+    if let Some([source_name, identifier]) = missing {
+      return Err(LearnerError::NotFound { source_name, identifier });
+    }
+    Ok(paper)
+  }
+}
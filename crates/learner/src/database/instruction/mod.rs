@@ -10,11 +10,14 @@
 //!
 //! # Architecture
 //!
-//! The module is organized around three main operation types:
+//! The module is organized around six main operation types:
 //!
 //! - [`query`] - Read operations for searching and retrieving papers
 //! - [`add`] - Write operations for adding papers and documents
-//! - [`remove`] - Delete operations for removing papers from the database
+//! - [`update`] - Write operations for editing stored paper metadata
+//! - [`remove`] - Delete operations for archiving or purging papers from the database
+//! - [`restore`] - Write operations for bringing archived papers back
+//! - [`merge`] - Write operations for combining duplicate papers into one
 //!
 //! Each operation type implements the [`DatabaseInstruction`] trait, providing
 //! a consistent interface while allowing for operation-specific behavior.
@@ -52,10 +55,13 @@
 use super::*;
 
 pub mod add;
+pub mod merge;
 pub mod query;
 pub mod remove;
+pub mod restore;
+pub mod update;
 
-use rusqlite::{params_from_iter, ToSql};
+use rusqlite::{params_from_iter, TransactionBehavior};
 
 use self::query::Query;
 
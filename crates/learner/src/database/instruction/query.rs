@@ -6,8 +6,16 @@
 //! - Full-text search across titles and abstracts
 //! - Source-specific identifier lookups
 //! - Author name searches
+//! - Author affiliation searches
+//! - Language filtering
+//! - Citation graph traversal between stored papers
+//! - Related papers through shared authors
+//! - Papers still missing a stored document
 //! - Publication date filtering
+//! - Combining several criteria, all of which must match
+//! - Alternatives (OR) and negation (NOT) of criteria
 //! - Custom result ordering
+//! - Hiding archived papers unless asked for
 //!
 //! The implementation prioritizes:
 //! - Efficient query execution using prepared statements
@@ -19,7 +27,7 @@
 //!
 //! ```no_run
 //! use learner::{
-//!   database::{Database, OrderField, Query},
+//!   database::{Database, OrderField, Query, QueryCriteria},
 //!   prelude::*,
 //! };
 //!
@@ -38,10 +46,26 @@
 //!
 //! // Lookup by source identifier
 //! let papers = Query::by_source("arxiv", "2301.07041").execute(&mut db).await?;
+//!
+//! // Papers matching every criterion
+//! let papers = Query::text("quantum")
+//!   .and(QueryCriteria::Author("Alice"))
+//!   .and(QueryCriteria::Source("arxiv"))
+//!   .execute(&mut db)
+//!   .await?;
+//!
+//! // Papers by Alice or Bob, from any source but DOI
+//! let papers = Query::by_author("Alice")
+//!   .or(QueryCriteria::Author("Bob"))
+//!   .and(!QueryCriteria::Source("doi"))
+//!   .execute(&mut db)
+//!   .await?;
 //! # Ok(())
 //! # }
 //! ```
 
+use std::{collections::HashMap, ops::Not};
+
 use super::*;
 
 /// Represents different ways to query papers in the database.
@@ -52,8 +76,19 @@ use super::*;
 /// - Text-based searching using SQLite FTS
 /// - Direct lookups by source identifiers
 /// - Author-based searches
+/// - Affiliation-based searches
+/// - Keyword-based searches
+/// - Language filtering
+/// - Citation lookups among stored papers
+/// - Related papers through shared authors
+/// - Papers without a successfully stored document
+/// - Source filtering
 /// - Publication date filtering
 /// - Complete collection retrieval
+///
+/// A [`Query`] starts from one criterion and can be narrowed by more with [`Query::and`].
+/// Criteria also compose into expressions of their own with [`QueryCriteria::and`],
+/// [`QueryCriteria::or`] and `!` (see [`QueryCriteria::Not`]).
 #[derive(Debug)]
 pub enum QueryCriteria<'a> {
   /// Full-text search across titles and abstracts using SQLite FTS
@@ -65,12 +100,123 @@ pub enum QueryCriteria<'a> {
     /// The source-specific identifier
     identifier: &'a str,
   },
-  /// Search by author name with partial matching
+  /// Search by author name with partial matching, ignoring case and diacritics
   Author(&'a str),
+  /// Search by author affiliation with partial matching
+  Affiliation(&'a str),
+  /// Search by subject keyword with exact matching, ignoring case
+  Keyword(&'a str),
+  /// Papers in a language, by its ISO 639-1 code, ignoring case
+  Language(&'a str),
+  /// Stored papers whose DOI is cited by the given paper
+  ReferencesOf {
+    /// The citing paper's source system
+    source:     &'a str,
+    /// The citing paper's source-specific identifier
+    identifier: &'a str,
+  },
+  /// Stored papers that cite the given paper's DOI
+  CitedBy {
+    /// The cited paper's source system
+    source:     &'a str,
+    /// The cited paper's source-specific identifier
+    identifier: &'a str,
+  },
+  /// Other papers sharing at least one author name, most shared authors first
+  RelatedTo {
+    /// The source system of the paper to relate to
+    source:     &'a str,
+    /// The source-specific identifier of the paper to relate to
+    identifier: &'a str,
+  },
+  /// Papers with no successfully downloaded or stored document
+  MissingDocuments,
+  /// Papers from a source system, e.g. `arxiv`
+  Source(&'a str),
   /// Retrieve the complete paper collection
   All,
   /// Filter papers by publication date
   BeforeDate(DateTime<Utc>),
+  /// Papers added to the database at or after a time, whenever they were published
+  AddedAfter(DateTime<Utc>),
+  /// Papers matching every one of the criteria
+  And(Vec<QueryCriteria<'a>>),
+  /// Papers matching at least one of the criteria
+  Or(Vec<QueryCriteria<'a>>),
+  /// Papers not matching the criterion, written `!criteria`
+  Not(Box<QueryCriteria<'a>>),
+}
+
+impl<'a> QueryCriteria<'a> {
+  /// Combines this criterion with `other`, matching papers that match both.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, QueryCriteria};
+  /// // Papers by Alice that are either from arXiv or tagged cs.LG
+  /// let by_alice = QueryCriteria::Author("Alice").and(QueryCriteria::Source("arxiv"));
+  /// let query = Query::new(by_alice.or(QueryCriteria::Keyword("cs.LG")));
+  /// ```
+  pub fn and(self, other: QueryCriteria<'a>) -> Self {
+    match self {
+      Self::And(mut all) => {
+        all.push(other);
+        Self::And(all)
+      },
+      criteria => Self::And(vec![criteria, other]),
+    }
+  }
+
+  /// Combines this criterion with `other`, matching papers that match either.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, QueryCriteria};
+  /// let query = Query::new(QueryCriteria::Author("Alice").or(QueryCriteria::Author("Bob")));
+  /// ```
+  pub fn or(self, other: QueryCriteria<'a>) -> Self {
+    match self {
+      Self::Or(mut any) => {
+        any.push(other);
+        Self::Or(any)
+      },
+      criteria => Self::Or(vec![criteria, other]),
+    }
+  }
+
+  /// Checks that the criterion makes sense as part of a query.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidQuery`] for an empty [`And`](QueryCriteria::And) or
+  /// [`Or`](QueryCriteria::Or) group, an `Or` group holding [`All`](QueryCriteria::All) (which
+  /// matches every paper) and a negated `All` (which matches none).
+  fn validate(&self) -> Result<()> {
+    match self {
+      Self::And(group) | Self::Or(group) if group.is_empty() =>
+        Err(LearnerError::InvalidQuery("a criteria group needs at least one criterion".into())),
+      Self::Or(any) if any.iter().any(|criteria| matches!(criteria, Self::All)) =>
+        Err(LearnerError::InvalidQuery("an OR group holding `All` matches every paper".into())),
+      Self::Not(criteria) if matches!(**criteria, Self::All) =>
+        Err(LearnerError::InvalidQuery("negating `All` matches no paper".into())),
+      Self::And(group) | Self::Or(group) => group.iter().try_for_each(Self::validate),
+      Self::Not(criteria) => criteria.validate(),
+      _ => Ok(()),
+    }
+  }
+
+  /// Whether the criterion orders its matches by relevance, as text searches (by rank) and
+  /// [`RelatedTo`](QueryCriteria::RelatedTo) (by shared authors) do.
+  fn is_ranked(&self) -> bool { matches!(self, Self::Text(_) | Self::RelatedTo { .. }) }
+}
+
+impl<'a> Not for QueryCriteria<'a> {
+  type Output = Self;
+
+  /// Negates the criterion, so it matches the papers it did not match.
+  fn not(self) -> Self { Self::Not(Box::new(self)) }
 }
 
 /// Available fields for ordering query results.
@@ -86,18 +232,30 @@ pub enum OrderField {
   PublicationDate,
   /// Order by source system and identifier
   Source,
+  /// Order by when the paper was added to the database
+  ///
+  /// Papers added within the same second keep the order they were added in.
+  DateAdded,
 }
 
-impl OrderField {
-  /// Converts the ordering field to its SQL representation.
-  ///
-  /// Returns the appropriate SQL column names for ORDER BY clauses,
-  /// handling both single-column and multi-column ordering.
-  fn as_sql_str(&self) -> &'static str {
+/// Which papers a [`Query`] considers based on whether they are archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFilter {
+  /// Only papers that are not archived, the default
+  Exclude,
+  /// Papers whether or not they are archived
+  Include,
+  /// Only archived papers
+  Only,
+}
+
+impl ArchiveFilter {
+  /// Returns the SQL condition on `id` that papers must meet to pass this filter, if any.
+  fn condition(self) -> Option<&'static str> {
     match self {
-      OrderField::Title => "title",
-      OrderField::PublicationDate => "publication_date",
-      OrderField::Source => "source, source_identifier",
+      Self::Exclude => Some("id IN (SELECT id FROM papers WHERE archived = 0)"),
+      Self::Include => None,
+      Self::Only => Some("id IN (SELECT id FROM papers WHERE archived = 1)"),
     }
   }
 }
@@ -111,17 +269,34 @@ impl OrderField {
 /// - Result ordering configuration
 /// - SQL generation and execution
 /// - Paper reconstruction from rows
+///
+/// Papers archived by [`Remove`](super::Remove) are left out of every query unless
+/// [`Query::include_archived`] or [`Query::only_archived`] is used.
 #[derive(Debug)]
 pub struct Query<'a> {
-  /// The search criteria to apply
-  criteria:   QueryCriteria<'a>,
+  /// The search criteria to apply, all of which must match; never empty
+  criteria:   Vec<QueryCriteria<'a>>,
   /// Optional field to sort results by
   order_by:   Option<OrderField>,
   /// Whether to sort in descending order
   descending: bool,
+  /// Optional maximum number of papers to return
+  limit:      Option<usize>,
+  /// Whether text searches should produce highlighted snippets
+  snippets:   bool,
+  /// Whether archived papers are left out, included, or the only ones returned
+  archived:   ArchiveFilter,
 }
 
 impl<'a> Query<'a> {
+  /// Marks the end of a matched term in snippets from [`Query::execute_with_snippets`].
+  ///
+  /// A control character, so it never clashes with the text itself; see
+  /// [`Query::snippet_segments`] for rendering snippets.
+  pub const SNIPPET_CLOSE: &'static str = "\u{3}";
+  /// Marks the start of a matched term in snippets from [`Query::execute_with_snippets`].
+  pub const SNIPPET_OPEN: &'static str = "\u{2}";
+
   /// Creates a new query with the given criteria.
   ///
   /// # Arguments
@@ -135,7 +310,14 @@ impl<'a> Query<'a> {
   /// let query = Query::new(QueryCriteria::All);
   /// ```
   pub fn new(criteria: QueryCriteria<'a>) -> Self {
-    Self { criteria, order_by: None, descending: false }
+    Self {
+      criteria:   vec![criteria],
+      order_by:   None,
+      descending: false,
+      limit:      None,
+      snippets:   false,
+      archived:   ArchiveFilter::Exclude,
+    }
   }
 
   /// Creates a full-text search query.
@@ -199,8 +381,8 @@ impl<'a> Query<'a> {
 
   /// Creates a query to find papers by author name.
   ///
-  /// Performs a partial match on author names, allowing for flexible
-  /// name searches.
+  /// Performs a partial match on author names that ignores case and diacritics, so
+  /// searching for "Muller" also finds "Müller".
   ///
   /// # Arguments
   ///
@@ -214,6 +396,154 @@ impl<'a> Query<'a> {
   /// ```
   pub fn by_author(name: &'a str) -> Self { Self::new(QueryCriteria::Author(name)) }
 
+  /// Creates a query to find papers with at least one author from an institution.
+  ///
+  /// Performs a partial match on author affiliations. Authors without a recorded
+  /// affiliation never match.
+  ///
+  /// # Arguments
+  ///
+  /// * `affiliation` - The institution name to search for
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// let query = Query::by_affiliation("Example University");
+  /// ```
+  pub fn by_affiliation(affiliation: &'a str) -> Self {
+    Self::new(QueryCriteria::Affiliation(affiliation))
+  }
+
+  /// Creates a query to find papers tagged with a subject keyword.
+  ///
+  /// Keywords (e.g. arXiv categories like `cs.LG`) must match exactly, ignoring case.
+  ///
+  /// # Arguments
+  ///
+  /// * `keyword` - The keyword to search for
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// let query = Query::by_keyword("cs.LG");
+  /// ```
+  pub fn by_keyword(keyword: &'a str) -> Self { Self::new(QueryCriteria::Keyword(keyword)) }
+
+  /// Creates a query to find papers written in a language.
+  ///
+  /// The language is matched by ISO 639-1 code (e.g. `en`), ignoring case, against
+  /// [`Paper::language`]. Papers whose language is unknown never match.
+  ///
+  /// # Arguments
+  ///
+  /// * `language` - The language code to search for
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// let query = Query::by_language("de");
+  /// ```
+  pub fn by_language(language: &'a str) -> Self { Self::new(QueryCriteria::Language(language)) }
+
+  /// Creates a query for the stored papers that `paper` cites.
+  ///
+  /// Only references with a DOI matching a paper in the database are returned, so this
+  /// walks the local citation graph rather than the full reference list.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper` - The citing paper
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("10.1145/3292500.3330701").await?;
+  /// let query = Query::references_of(&paper);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn references_of(paper: &'a Paper) -> Self {
+    Self::new(QueryCriteria::ReferencesOf {
+      source:     &paper.source,
+      identifier: &paper.source_identifier,
+    })
+  }
+
+  /// Creates a query for the stored papers that cite `paper`.
+  ///
+  /// Matches on the DOI stored for `paper`, so papers without a DOI are never cited.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper` - The cited paper
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("10.1145/3292500.3330701").await?;
+  /// let query = Query::cited_by(&paper);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn cited_by(paper: &'a Paper) -> Self {
+    Self::new(QueryCriteria::CitedBy {
+      source:     &paper.source,
+      identifier: &paper.source_identifier,
+    })
+  }
+
+  /// Creates a query for other stored papers that share authors with `paper`.
+  ///
+  /// Author names are compared exactly (ignoring case), and results are ranked by the
+  /// number of shared authors. The paper itself is never included.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper` - The paper to find related work for
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("2301.07041").await?;
+  /// let query = Query::related_to(&paper);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn related_to(paper: &'a Paper) -> Self {
+    Self::new(QueryCriteria::RelatedTo {
+      source:     &paper.source,
+      identifier: &paper.source_identifier,
+    })
+  }
+
+  /// Creates a query for papers that have no successfully stored document.
+  ///
+  /// Papers whose download failed are included, so they can be retried.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Add, Query};
+  /// // Download every document that is still missing
+  /// let instruction = Add::documents(Query::missing_documents());
+  /// ```
+  pub fn missing_documents() -> Self { Self::new(QueryCriteria::MissingDocuments) }
+
   /// Creates a query that returns all papers.
   ///
   /// # Examples
@@ -240,6 +570,70 @@ impl<'a> Query<'a> {
   /// ```
   pub fn before_date(date: DateTime<Utc>) -> Self { Self::new(QueryCriteria::BeforeDate(date)) }
 
+  /// Creates a query for papers added to the database at or after a time.
+  ///
+  /// This looks at when each paper was stored, not when it was published, so it finds
+  /// recent imports of old papers too.
+  ///
+  /// # Arguments
+  ///
+  /// * `date` - The earliest time a matching paper was added
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// # use chrono::{DateTime, Utc};
+  /// let date = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+  /// let query = Query::added_after(date);
+  /// ```
+  pub fn added_after(date: DateTime<Utc>) -> Self { Self::new(QueryCriteria::AddedAfter(date)) }
+
+  /// Widens the most recently added criterion to papers that match it or `criteria`.
+  ///
+  /// `or` binds tighter than [`Query::and`], so `Query::text(t).and(a).or(b)` finds papers
+  /// matching `t` and either `a` or `b`. Called right after the constructor, the constructor's
+  /// criterion becomes part of the group, so results are no longer ranked by it.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, QueryCriteria};
+  /// // Papers about lattices by Alice or by Bob
+  /// let query =
+  ///   Query::text("lattice").and(QueryCriteria::Author("Alice")).or(QueryCriteria::Author("Bob"));
+  /// ```
+  pub fn or(mut self, criteria: QueryCriteria<'a>) -> Self {
+    let last = self.criteria.pop().expect("a query has at least one criterion");
+    self.criteria.push(last.or(criteria));
+    self
+  }
+
+  /// Narrows the query to papers that also match `criteria`.
+  ///
+  /// Every criterion has to match. The first one, from the constructor, still decides the order
+  /// of the results (e.g. search relevance) and whether [`Query::with_snippets`] has anything to
+  /// highlight; the ones added here only filter.
+  ///
+  /// # Arguments
+  ///
+  /// * `criteria` - Another criterion the papers must match
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, QueryCriteria};
+  /// # use chrono::{DateTime, Utc};
+  /// let date = DateTime::parse_from_rfc3339("2023-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+  /// // Papers about quantum, by Alice, published before 2023
+  /// let query =
+  ///   Query::text("quantum").and(QueryCriteria::Author("Alice")).and(QueryCriteria::BeforeDate(date));
+  /// ```
+  pub fn and(mut self, criteria: QueryCriteria<'a>) -> Self {
+    self.criteria.push(criteria);
+    self
+  }
+
   /// Sets the field to order results by.
   ///
   /// # Arguments
@@ -270,21 +664,263 @@ impl<'a> Query<'a> {
     self
   }
 
+  /// Limits the number of papers returned.
+  ///
+  /// The limit applies after ordering, so combined with [`Query::order_by`] it returns the
+  /// first `limit` papers in that order. Without an ordering, results keep the criteria's
+  /// own order (e.g. search relevance).
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, OrderField};
+  /// // The ten most recently published papers
+  /// let query = Query::list_all().order_by(OrderField::PublicationDate).descending().limit(10);
+  /// ```
+  pub fn limit(mut self, limit: usize) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  /// Requests a highlighted excerpt of where each paper matched a [`Query::text`] search.
+  ///
+  /// The snippets are returned by [`Query::execute_with_snippets`], with every matched term
+  /// wrapped in [`Query::SNIPPET_OPEN`] and [`Query::SNIPPET_CLOSE`]. Other criteria have
+  /// nothing to highlight and produce no snippets.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, Query};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open("papers.db").await?;
+  /// for (paper, snippet) in
+  ///   Query::text("quantum").with_snippets().execute_with_snippets(&mut db).await?
+  /// {
+  ///   println!("{}: {}", paper.source_identifier, snippet.unwrap_or(paper.title));
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_snippets(mut self) -> Self {
+    self.snippets = true;
+    self
+  }
+
+  /// Includes archived papers in the results alongside the rest.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// // Every stored paper, including ones that were removed without purging
+  /// let query = Query::list_all().include_archived();
+  /// ```
+  pub fn include_archived(mut self) -> Self {
+    self.archived = ArchiveFilter::Include;
+    self
+  }
+
+  /// Restricts the results to archived papers.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Query;
+  /// // Archived papers that could be restored
+  /// let query = Query::text("quantum").only_archived();
+  /// ```
+  pub fn only_archived(mut self) -> Self {
+    self.archived = ArchiveFilter::Only;
+    self
+  }
+
+  /// Returns whether this query still leaves archived papers out, as it does by default.
+  pub(super) fn excludes_archived(&self) -> bool { self.archived == ArchiveFilter::Exclude }
+
+  /// Executes the query, pairing each paper with its snippet from [`Query::with_snippets`].
+  ///
+  /// The snippet is `None` unless snippets were requested for a text search.
+  pub async fn execute_with_snippets(
+    &self,
+    db: &mut Database,
+  ) -> Result<Vec<(Paper, Option<String>)>> {
+    self.fetch(db).await
+  }
+
+  /// Splits a snippet from [`Query::execute_with_snippets`] into its text, each segment paired
+  /// with whether it is a matched term, so it can be styled without showing the markers.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::database::Query;
+  /// let snippet = format!("Notes on {}Quantum{} Codes", Query::SNIPPET_OPEN, Query::SNIPPET_CLOSE);
+  /// assert_eq!(Query::snippet_segments(&snippet), [
+  ///   ("Notes on ", false),
+  ///   ("Quantum", true),
+  ///   (" Codes", false)
+  /// ]);
+  /// ```
+  pub fn snippet_segments(snippet: &str) -> Vec<(&str, bool)> {
+    let mut segments = Vec::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find(Self::SNIPPET_OPEN) {
+      segments.push((&rest[..start], false));
+      rest = &rest[start + Self::SNIPPET_OPEN.len()..];
+      let end = rest.find(Self::SNIPPET_CLOSE).unwrap_or(rest.len());
+      segments.push((&rest[..end], true));
+      rest = rest.get(end + Self::SNIPPET_CLOSE.len()..).unwrap_or_default();
+    }
+    segments.push((rest, false));
+    segments.retain(|(text, _)| !text.is_empty());
+    segments
+  }
+
+  /// Executes the query and returns its single matching paper.
+  ///
+  /// This is a convenience terminal for lookups that should identify exactly one paper, such
+  /// as [`Query::by_source`], sparing callers from checking the length of the result.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::NotFound`] when no paper matches and
+  /// [`LearnerError::Ambiguous`] when more than one does.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, Query};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open("papers.db").await?;
+  /// let paper = Query::by_source("arxiv", "2301.07041").one(&mut db).await?;
+  /// println!("Found {}", paper.title);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn one(&self, db: &mut Database) -> Result<Paper> {
+    let mut papers = self.execute(db).await?;
+    match papers.len() {
+      1 => Ok(papers.remove(0)),
+      0 => {
+        let (source_name, identifier) = self.lookup_target();
+        Err(LearnerError::NotFound { source_name, identifier })
+      },
+      count => Err(LearnerError::Ambiguous(count)),
+    }
+  }
+
+  /// Describes what this query looked up, for use in [`LearnerError::NotFound`].
+  ///
+  /// Paper-based criteria report the paper's source and identifier, while search criteria
+  /// report the kind of search and its term. Only the first criterion is described.
+  fn lookup_target(&self) -> (String, String) { Self::describe(&self.criteria[0]) }
+
+  /// Describes a single criterion as the kind of lookup and its term.
+  fn describe(criteria: &QueryCriteria) -> (String, String) {
+    match criteria {
+      QueryCriteria::SourceId { source, identifier }
+      | QueryCriteria::ReferencesOf { source, identifier }
+      | QueryCriteria::CitedBy { source, identifier }
+      | QueryCriteria::RelatedTo { source, identifier } =>
+        (source.to_string(), identifier.to_string()),
+      QueryCriteria::Text(query) => ("text".to_string(), query.to_string()),
+      QueryCriteria::Author(name) => ("author".to_string(), name.to_string()),
+      QueryCriteria::Affiliation(affiliation) =>
+        ("affiliation".to_string(), affiliation.to_string()),
+      QueryCriteria::Keyword(keyword) => ("keyword".to_string(), keyword.to_string()),
+      QueryCriteria::Language(language) => ("language".to_string(), language.to_string()),
+      QueryCriteria::MissingDocuments => ("missing_documents".to_string(), String::new()),
+      QueryCriteria::Source(source) => ("source".to_string(), source.to_string()),
+      QueryCriteria::All => ("all".to_string(), String::new()),
+      QueryCriteria::BeforeDate(date) => ("before_date".to_string(), date.to_rfc3339()),
+      QueryCriteria::AddedAfter(date) => ("added_after".to_string(), date.to_rfc3339()),
+      QueryCriteria::And(group) | QueryCriteria::Or(group) => {
+        let kind = if matches!(criteria, QueryCriteria::And(_)) { "and" } else { "or" };
+        let terms: Vec<_> = group
+          .iter()
+          .map(|criteria| match Self::describe(criteria) {
+            (kind, term) if term.is_empty() => kind,
+            (kind, term) => format!("{kind}:{term}"),
+          })
+          .collect();
+        (kind.to_string(), terms.join(&format!(" {kind} ")))
+      },
+      QueryCriteria::Not(criteria) => {
+        let (kind, term) = Self::describe(criteria);
+        (format!("not {kind}"), term)
+      },
+    }
+  }
+
   /// Builds the SQL for retrieving paper IDs based on search criteria.
-  fn build_criteria_sql(&self) -> (String, Vec<impl ToSql>) {
-    match &self.criteria {
+  ///
+  /// The first criterion's query decides the order and any snippets, and every further
+  /// criterion, like the archive filter, is intersected with it as an `id IN (...)` subquery.
+  /// Ordering, and the limit when nothing is left to filter or sort in memory, are applied to the
+  /// outer query, since SQLite does not keep the order of a subquery.
+  fn build_criteria_sql(&self) -> (String, Vec<String>) {
+    let (first, rest) = self.criteria.split_first().expect("a query has at least one criterion");
+    let ranked = first.is_ranked();
+    let (sql, mut params) = Self::criterion_sql(first, self.snippets, ranked);
+
+    let mut conditions = Vec::with_capacity(rest.len());
+    for criteria in rest {
+      let (subquery, subquery_params) = Self::criterion_sql(criteria, false, false);
+      conditions.push(format!("id IN ({subquery})"));
+      params.extend(subquery_params);
+    }
+    conditions.extend(self.archived.condition().map(str::to_string));
+
+    let mut sql = format!("SELECT * FROM ({sql})");
+    if !conditions.is_empty() {
+      sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+    }
+    if ranked {
+      sql.push_str(" ORDER BY position, id");
+    }
+    if let (None, Some(limit)) = (self.order_by, self.limit) {
+      sql.push_str(&format!(" LIMIT {limit}"));
+    }
+    (sql, params)
+  }
+
+  /// Builds the SQL selecting the ids of the papers matching a single criterion, as an `id`
+  /// column, followed by the snippet for text searches when `snippets` is set.
+  ///
+  /// With `ranked`, criteria that rank their matches (see [`QueryCriteria::is_ranked`]) add a
+  /// final `position` column to sort by, best match first. The SQL itself is never ordered.
+  ///
+  /// Parameters are anonymous (`?`) and bound in the order they appear, so the SQL of several
+  /// criteria can be combined by concatenating their parameters.
+  fn criterion_sql(
+    criteria: &QueryCriteria,
+    snippets: bool,
+    ranked: bool,
+  ) -> (String, Vec<String>) {
+    match criteria {
       QueryCriteria::Text(query) => (
-        "SELECT p.id
+        format!(
+          "SELECT p.id{}{}
                  FROM papers p
                  JOIN papers_fts f ON p.id = f.rowid
-                 WHERE papers_fts MATCH ?1 || '*'
-                 ORDER BY rank"
-          .into(),
+                 WHERE papers_fts MATCH ? || '*'",
+          if snippets {
+            format!(
+              ", snippet(papers_fts, 0, '{}', '{}', '…', 16)",
+              Self::SNIPPET_OPEN,
+              Self::SNIPPET_CLOSE
+            )
+          } else {
+            String::new()
+          },
+          if ranked { ", rank AS position" } else { "" }
+        ),
         vec![(*query).to_string()],
       ),
       QueryCriteria::SourceId { source, identifier } => (
         "SELECT id FROM papers 
-                 WHERE source = ?1 AND source_identifier = ?2"
+                 WHERE source = ? AND source_identifier = ?"
           .into(),
         vec![source.to_string(), (*identifier).to_string()],
       ),
@@ -292,117 +928,246 @@ impl<'a> Query<'a> {
         "SELECT DISTINCT p.id
                  FROM papers p
                  JOIN authors a ON p.id = a.paper_id
-                 WHERE a.name LIKE ?1"
+                 WHERE a.name_folded LIKE ?"
+          .into(),
+        vec![format!("%{}%", format::fold_diacritics(name))],
+      ),
+      QueryCriteria::Affiliation(affiliation) => (
+        "SELECT DISTINCT p.id
+                 FROM papers p
+                 JOIN authors a ON p.id = a.paper_id
+                 WHERE a.affiliation IS NOT NULL AND a.affiliation LIKE ?"
           .into(),
-        vec![format!("%{}%", name)],
+        vec![format!("%{}%", affiliation)],
       ),
+      QueryCriteria::Keyword(keyword) => (
+        "SELECT DISTINCT paper_id AS id
+                 FROM paper_keywords
+                 WHERE keyword = ?"
+          .into(),
+        vec![keyword.trim().to_string()],
+      ),
+      QueryCriteria::Language(language) => (
+        "SELECT id FROM papers
+                 WHERE language = ? COLLATE NOCASE"
+          .into(),
+        vec![language.trim().to_string()],
+      ),
+      QueryCriteria::ReferencesOf { source, identifier } => (
+        "SELECT DISTINCT cited.id
+                 FROM papers citing
+                 JOIN paper_references r ON r.paper_id = citing.id
+                 JOIN papers cited ON cited.doi = r.doi COLLATE NOCASE
+                 WHERE citing.source = ? AND citing.source_identifier = ?"
+          .into(),
+        vec![source.to_string(), (*identifier).to_string()],
+      ),
+      QueryCriteria::CitedBy { source, identifier } => (
+        "SELECT DISTINCT r.paper_id AS id
+                 FROM papers cited
+                 JOIN paper_references r ON r.doi = cited.doi COLLATE NOCASE
+                 WHERE cited.source = ? AND cited.source_identifier = ?"
+          .into(),
+        vec![source.to_string(), (*identifier).to_string()],
+      ),
+      // Papers sharing the most authors come first
+      QueryCriteria::RelatedTo { source, identifier } => (
+        format!(
+          "SELECT other.paper_id AS id{}
+                 FROM papers p
+                 JOIN authors own ON own.paper_id = p.id
+                 JOIN authors other ON other.name = own.name COLLATE NOCASE
+                   AND other.paper_id != p.id
+                 WHERE p.source = ? AND p.source_identifier = ?
+                 GROUP BY other.paper_id",
+          if ranked { ", -COUNT(DISTINCT other.name) AS position" } else { "" }
+        ),
+        vec![source.to_string(), (*identifier).to_string()],
+      ),
+      QueryCriteria::MissingDocuments => (
+        "SELECT p.id
+                 FROM papers p
+                 LEFT JOIN files f ON f.paper_id = p.id AND f.download_status = 'Success'
+                 WHERE f.id IS NULL"
+          .into(),
+        Vec::new(),
+      ),
+      QueryCriteria::Source(source) =>
+        ("SELECT id FROM papers WHERE source = ?".into(), vec![source.to_string()]),
       QueryCriteria::All => ("SELECT id FROM papers".into(), Vec::new()),
+      QueryCriteria::And(group) | QueryCriteria::Or(group) => {
+        let operator = if matches!(criteria, QueryCriteria::And(_)) { " AND " } else { " OR " };
+        let mut conditions = Vec::with_capacity(group.len());
+        let mut params = Vec::new();
+        for criteria in group {
+          let (subquery, subquery_params) = Self::criterion_sql(criteria, false, false);
+          conditions.push(format!("id IN ({subquery})"));
+          params.extend(subquery_params);
+        }
+        (format!("SELECT id FROM papers WHERE ({})", conditions.join(operator)), params)
+      },
+      QueryCriteria::Not(criteria) => {
+        let (subquery, params) = Self::criterion_sql(criteria, false, false);
+        (format!("SELECT id FROM papers WHERE id NOT IN ({subquery})"), params)
+      },
+      // Partial dates are stored as `YYYY` or `YYYY-MM`, and are compared from their first day
       QueryCriteria::BeforeDate(date) => (
-        "SELECT id FROM papers 
-                 WHERE publication_date < ?1"
+        "SELECT id FROM papers
+                 WHERE CASE length(publication_date)
+                         WHEN 4 THEN publication_date || '-01-01T00:00:00+00:00'
+                         WHEN 7 THEN publication_date || '-01T00:00:00+00:00'
+                         ELSE publication_date
+                       END < ?"
           .into(),
         vec![date.to_rfc3339()],
       ),
+      // `added_at` is SQLite's `datetime('now')`, a UTC `YYYY-MM-DD HH:MM:SS` string
+      QueryCriteria::AddedAfter(date) =>
+        ("SELECT id FROM papers WHERE added_at >= ?".into(), vec![date
+          .format("%Y-%m-%d %H:%M:%S")
+          .to_string()]),
     }
   }
 
-  /// Builds the SQL for retrieving complete paper data.
-  fn build_paper_sql(&self) -> String {
-    let base = "SELECT title, abstract_text, publication_date,
-                           source, source_identifier, pdf_url, doi
-                    FROM papers 
-                    WHERE id = ?1";
-
-    if let Some(order_field) = &self.order_by {
-      let direction = if self.descending { "DESC" } else { "ASC" };
-      format!("{} ORDER BY {} {}", base, order_field.as_sql_str(), direction)
-    } else {
-      base.to_string()
-    }
-  }
-}
-
-#[async_trait]
-impl DatabaseInstruction for Query<'_> {
-  type Output = Vec<Paper>;
-
-  async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+  /// Runs the query, returning each paper along with its snippet, if one was requested.
+  ///
+  /// Papers, authors, references and keywords are each fetched with a single query per batch
+  /// of matching ids and assembled in memory.
+  async fn fetch(&self, db: &mut Database) -> Result<Vec<(Paper, Option<String>)>> {
+    self.criteria.iter().try_for_each(QueryCriteria::validate)?;
     let (criteria_sql, params) = self.build_criteria_sql();
-    let paper_sql = self.build_paper_sql();
     let order_by = self.order_by;
     let descending = self.descending;
+    let limit = self.limit;
+    let snippets = self.snippets && matches!(self.criteria[0], QueryCriteria::Text(_));
 
     let papers = db
       .conn
       .call(move |conn| {
-        let mut papers = Vec::new();
         let tx = conn.transaction()?;
 
         // Get paper IDs based on search criteria
-        let paper_ids = {
+        let mut paper_ids = {
           let mut stmt = tx.prepare_cached(&criteria_sql)?;
           let mut rows = stmt.query(params_from_iter(params))?;
           let mut ids = Vec::new();
           while let Some(row) = rows.next()? {
-            ids.push(row.get::<_, i64>(0)?);
+            let snippet = if snippets { row.get::<_, Option<String>>(1)? } else { None };
+            ids.push((row.get::<_, i64>(0)?, snippet));
           }
           ids
         };
 
-        // Fetch complete paper data for each ID
-        for paper_id in paper_ids {
-          let mut paper_stmt = tx.prepare_cached(&paper_sql)?;
-          let paper = paper_stmt.query_row([paper_id], |row| {
-            Ok(Paper {
-              title:             row.get(0)?,
-              abstract_text:     row.get(1)?,
-              publication_date:  DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .map_err(|e| {
-                  rusqlite::Error::FromSqlConversionFailure(
-                    2,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                  )
-                })?,
-              source:            row.get::<_, String>(3)?,
-              source_identifier: row.get(4)?,
-              pdf_url:           row.get(5)?,
-              doi:               row.get(6)?,
-              authors:           Vec::new(),
-            })
-          })?;
-
-          // Get authors for this paper
-          let mut author_stmt = tx.prepare_cached(
-            "SELECT name, affiliation, email
-                     FROM authors
-                     WHERE paper_id = ?",
-          )?;
-
-          let authors = author_stmt
-            .query_map([paper_id], |row| {
-              Ok(Author {
-                name:        row.get(0)?,
-                affiliation: row.get(1)?,
-                email:       row.get(2)?,
-              })
-            })?
-            .collect::<rusqlite::Result<Vec<_>>>()?;
-
-          let mut paper = paper;
-          paper.authors = authors;
-          papers.push(paper);
+        // Without a sort, the criteria order is final and papers past the limit can be skipped
+        if let (None, Some(limit)) = (order_by, limit) {
+          paper_ids.truncate(limit);
         }
+        let ids = unique_ids(&paper_ids);
+
+        // Each paper along with when it was added, for `OrderField::DateAdded`
+        let mut rows = HashMap::new();
+        for_each_by_ids(
+          &tx,
+          "SELECT id, title, abstract_text, publication_date, source, source_identifier,
+                  pdf_url, doi, version, added_at, primary_category, language
+           FROM papers
+           WHERE id IN ({ids})",
+          &ids,
+          |row| {
+            let paper = Paper {
+              title:             row.get(1)?,
+              abstract_text:     row.get(2)?,
+              publication_date:  row.get::<_, String>(3)?.parse::<PartialDate>().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                  3,
+                  rusqlite::types::Type::Text,
+                  Box::new(e),
+                )
+              })?,
+              source:            row.get::<_, String>(4)?,
+              source_identifier: row.get(5)?,
+              pdf_url:           row.get(6)?,
+              doi:               row.get(7)?,
+              authors:           Vec::new(),
+              references:        Vec::new(),
+              keywords:          Vec::new(),
+              version:           row.get(8)?,
+              primary_category:  row.get(10)?,
+              language:          row.get(11)?,
+            };
+            rows.insert(row.get::<_, i64>(0)?, (paper, row.get::<_, String>(9)?));
+            Ok(())
+          },
+        )?;
+
+        for_each_by_ids(
+          &tx,
+          "SELECT paper_id, name, affiliation, email, orcid
+           FROM authors
+           WHERE paper_id IN ({ids})
+           ORDER BY paper_id, id",
+          &ids,
+          |row| {
+            if let Some((paper, _)) = rows.get_mut(&row.get::<_, i64>(0)?) {
+              paper.authors.push(Author {
+                name:        row.get(1)?,
+                affiliation: row.get(2)?,
+                email:       row.get(3)?,
+                orcid:       row.get(4)?,
+              });
+            }
+            Ok(())
+          },
+        )?;
+
+        for_each_by_ids(
+          &tx,
+          "SELECT paper_id, doi
+           FROM paper_references
+           WHERE paper_id IN ({ids})
+           ORDER BY paper_id, id",
+          &ids,
+          |row| {
+            if let Some((paper, _)) = rows.get_mut(&row.get::<_, i64>(0)?) {
+              paper.references.push(row.get(1)?);
+            }
+            Ok(())
+          },
+        )?;
+
+        for_each_by_ids(
+          &tx,
+          "SELECT paper_id, keyword
+           FROM paper_keywords
+           WHERE paper_id IN ({ids})
+           ORDER BY paper_id, id",
+          &ids,
+          |row| {
+            if let Some((paper, _)) = rows.get_mut(&row.get::<_, i64>(0)?) {
+              paper.keywords.push(row.get(1)?);
+            }
+            Ok(())
+          },
+        )?;
+
+        // Assemble in criteria order, along with when each paper was added and its row id
+        let mut papers: Vec<_> = paper_ids
+          .into_iter()
+          .filter_map(|(paper_id, snippet)| {
+            let (paper, added_at) = rows.get(&paper_id)?.clone();
+            Some(((added_at, paper_id), paper, snippet))
+          })
+          .collect();
 
         // Sort if needed
         if let Some(order_field) = order_by {
-          papers.sort_by(|a, b| {
+          papers.sort_by(|(a_added, a, _), (b_added, b, _)| {
             let cmp = match order_field {
               OrderField::Title => a.title.cmp(&b.title),
               OrderField::PublicationDate => a.publication_date.cmp(&b.publication_date),
               OrderField::Source => (a.source.to_string(), &a.source_identifier)
                 .cmp(&(b.source.to_string(), &b.source_identifier)),
+              OrderField::DateAdded => a_added.cmp(b_added),
             };
             if descending {
               cmp.reverse()
@@ -411,11 +1176,53 @@ impl DatabaseInstruction for Query<'_> {
             }
           });
         }
+        if let Some(limit) = limit {
+          papers.truncate(limit);
+        }
 
-        Ok(papers)
+        Ok(papers.into_iter().map(|(_, paper, snippet)| (paper, snippet)).collect())
       })
       .await?;
 
     Ok(papers)
   }
 }
+
+/// Most paper ids bound in a single `IN (...)` list, well below SQLite's variable limit.
+const ID_BATCH_SIZE: usize = 500;
+
+/// Returns the distinct ids among the matched papers.
+fn unique_ids(paper_ids: &[(i64, Option<String>)]) -> Vec<i64> {
+  let mut ids: Vec<_> = paper_ids.iter().map(|(id, _)| *id).collect();
+  ids.sort_unstable();
+  ids.dedup();
+  ids
+}
+
+/// Runs `sql` for each batch of `ids`, passing every returned row to `f`.
+///
+/// The `{ids}` placeholder in `sql` is replaced by one bound parameter per id in the batch.
+fn for_each_by_ids(
+  tx: &rusqlite::Transaction,
+  sql: &str,
+  ids: &[i64],
+  mut f: impl FnMut(&rusqlite::Row) -> rusqlite::Result<()>,
+) -> rusqlite::Result<()> {
+  for batch in ids.chunks(ID_BATCH_SIZE) {
+    let mut stmt = tx.prepare_cached(&sql.replace("{ids}", &vec!["?"; batch.len()].join(", ")))?;
+    let mut rows = stmt.query(params_from_iter(batch))?;
+    while let Some(row) = rows.next()? {
+      f(row)?;
+    }
+  }
+  Ok(())
+}
+
+#[async_trait]
+impl DatabaseInstruction for Query<'_> {
+  type Output = Vec<Paper>;
+
+  async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    Ok(self.fetch(db).await?.into_iter().map(|(paper, _)| paper).collect())
+  }
+}
@@ -1,11 +1,14 @@
 //! Remove instruction implementation for paper deletion from the database.
 //!
 //! This module provides functionality for safely removing papers and their associated
-//! data from the database. It supports:
+//! data from the database. By default papers are archived: they are hidden from queries
+//! but kept, so they can be brought back with [`Restore`](super::restore::Restore).
+//! Purging deletes them for good. It supports:
 //!
 //! - Query-based paper removal
 //! - Dry run simulation
-//! - Cascade deletion of related data
+//! - Soft deletion through archiving
+//! - Cascade deletion of related data when purging
 //! - Atomic transactions
 //!
 //! The implementation emphasizes:
@@ -25,9 +28,12 @@
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let mut db = Database::open("papers.db").await?;
 //!
-//! // Remove a specific paper
+//! // Archive a specific paper
 //! Remove::by_source("arxiv", "2301.07041").execute(&mut db).await?;
 //!
+//! // Delete it permanently, along with its authors, references and document record
+//! Remove::by_source("arxiv", "2301.07041").purge().execute(&mut db).await?;
+//!
 //! // Preview deletion with dry run
 //! let papers = Remove::by_author("Alice Researcher").dry_run().execute(&mut db).await?;
 //!
@@ -51,6 +57,10 @@ pub struct RemoveOptions {
   /// - Validating removal queries
   /// - Testing removal logic safely
   pub dry_run: bool,
+  /// When true, deletes papers and their related data instead of archiving them.
+  ///
+  /// Purging also applies to papers that are already archived.
+  pub purge:   bool,
 }
 
 /// Instruction for removing papers from the database.
@@ -59,7 +69,7 @@ pub struct RemoveOptions {
 /// paper removal functionality. It handles:
 ///
 /// - Paper identification through queries
-/// - Related data cleanup (authors, files)
+/// - Archiving, or related data cleanup (authors, files) when purging
 /// - Transaction management
 /// - Dry run simulation
 pub struct Remove<'a> {
@@ -137,6 +147,105 @@ impl<'a> Remove<'a> {
   /// ```
   pub fn by_author(name: &'a str) -> Self { Self::from_query(Query::by_author(name)) }
 
+  /// Creates a remove instruction for all papers matching a full-text search.
+  ///
+  /// Matches exactly the papers [`Query::text`] would return for the same input.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The text to search titles and abstracts for
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// let remove = Remove::text("quantum computing");
+  /// ```
+  pub fn text(query: &'a str) -> Self { Self::from_query(Query::text(query)) }
+
+  /// Creates a remove instruction for all papers with an author from an institution.
+  ///
+  /// Performs a partial match on author affiliations, like [`Query::by_affiliation`].
+  ///
+  /// # Arguments
+  ///
+  /// * `affiliation` - The institution name to match
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// let remove = Remove::by_affiliation("Example University");
+  /// ```
+  pub fn by_affiliation(affiliation: &'a str) -> Self {
+    Self::from_query(Query::by_affiliation(affiliation))
+  }
+
+  /// Creates a remove instruction for all papers tagged with a subject keyword.
+  ///
+  /// Keywords must match exactly, ignoring case, like [`Query::by_keyword`].
+  ///
+  /// # Arguments
+  ///
+  /// * `keyword` - The keyword to match
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// let remove = Remove::by_keyword("cs.LG");
+  /// ```
+  pub fn by_keyword(keyword: &'a str) -> Self { Self::from_query(Query::by_keyword(keyword)) }
+
+  /// Creates a remove instruction for all papers written in a language.
+  ///
+  /// The language is an ISO 639-1 code matched like [`Query::by_language`]; papers whose
+  /// language is unknown are never removed.
+  ///
+  /// # Arguments
+  ///
+  /// * `language` - The language code to match
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// let remove = Remove::by_language("de");
+  /// ```
+  pub fn by_language(language: &'a str) -> Self { Self::from_query(Query::by_language(language)) }
+
+  /// Creates a remove instruction for all papers published before a date.
+  ///
+  /// # Arguments
+  ///
+  /// * `date` - The cutoff date for publication
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// # use chrono::{DateTime, Utc};
+  /// let date = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+  /// let remove = Remove::before_date(date);
+  /// ```
+  pub fn before_date(date: DateTime<Utc>) -> Self { Self::from_query(Query::before_date(date)) }
+
+  /// Creates a remove instruction for all papers added at or after a time.
+  ///
+  /// # Arguments
+  ///
+  /// * `date` - The earliest time a removed paper was added
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Remove;
+  /// # use chrono::{DateTime, Utc};
+  /// let date = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+  /// let remove = Remove::added_after(date);
+  /// ```
+  pub fn added_after(date: DateTime<Utc>) -> Self { Self::from_query(Query::added_after(date)) }
+
   /// Enables dry run mode for the remove operation.
   ///
   /// In dry run mode, the operation will:
@@ -167,6 +276,26 @@ impl<'a> Remove<'a> {
     self
   }
 
+  /// Deletes the papers permanently instead of archiving them.
+  ///
+  /// Purged papers cannot be restored. Papers that are already archived are matched too, so
+  /// purging is also how archived papers are cleared out.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, Remove};
+  /// // Permanently delete every archived paper
+  /// let remove = Remove::from_query(Query::list_all().only_archived()).purge();
+  /// ```
+  pub fn purge(mut self) -> Self {
+    self.options.purge = true;
+    if self.query.excludes_archived() {
+      self.query = self.query.include_archived();
+    }
+    self
+  }
+
   /// Builds SQL to retrieve paper IDs for removal.
   ///
   /// Generates the SQL and parameters needed to find database IDs
@@ -178,10 +307,19 @@ impl<'a> Remove<'a> {
     ])
   }
 
+  /// Builds SQL to archive papers, hiding them from queries.
+  fn build_archive_sql(ids: &[i64]) -> String {
+    let ids_str = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    format!(
+      "UPDATE papers SET archived = 1, updated_at = datetime('now') WHERE id IN ({})",
+      ids_str
+    )
+  }
+
   /// Builds SQL to remove papers and all related data.
   ///
   /// Generates cascading DELETE statements to remove papers and their
-  /// associated data (authors, files) in the correct order to maintain
+  /// associated data (authors, references, keywords, files) in the correct order to maintain
   /// referential integrity.
   fn build_remove_sql(ids: &[i64]) -> (String, Vec<Option<String>>) {
     let ids_str = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
@@ -189,6 +327,8 @@ impl<'a> Remove<'a> {
     (
       format!(
         "DELETE FROM authors WHERE paper_id IN ({0});
+                 DELETE FROM paper_references WHERE paper_id IN ({0});
+                 DELETE FROM paper_keywords WHERE paper_id IN ({0});
                  DELETE FROM files WHERE paper_id IN ({0});
                  DELETE FROM papers WHERE id IN ({0});",
         ids_str
@@ -203,6 +343,10 @@ impl DatabaseInstruction for Remove<'_> {
   type Output = Vec<Paper>;
 
   async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    // A dry run only reports what would be removed, so it works on a read-only handle
+    if !self.options.dry_run {
+      db.ensure_writable()?;
+    }
     // Use Query to find the papers to remove
     let papers = self.query.execute(db).await?;
 
@@ -213,7 +357,7 @@ impl DatabaseInstruction for Remove<'_> {
         .conn
         .call(move |conn| {
           let mut ids = Vec::new();
-          let tx = conn.transaction()?;
+          let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
 
           for paper in &papers_clone {
             let (sql, params) = Self::build_paper_ids_sql(paper);
@@ -228,12 +372,16 @@ impl DatabaseInstruction for Remove<'_> {
         .await?;
 
       if !ids.is_empty() {
-        // Remove the papers and their related data
-        let (remove_sql, _) = Self::build_remove_sql(&ids);
+        // Archive the papers, or remove them and their related data when purging
+        let remove_sql = if self.options.purge {
+          Self::build_remove_sql(&ids).0
+        } else {
+          Self::build_archive_sql(&ids)
+        };
 
         db.conn
           .call(move |conn| {
-            let tx = conn.transaction()?;
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
             tx.execute_batch(&remove_sql)?;
             tx.commit()?;
             Ok(())
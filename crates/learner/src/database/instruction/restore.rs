@@ -0,0 +1,104 @@
+//! Restore instruction implementation for bringing archived papers back.
+//!
+//! Papers removed with [`Remove`](super::remove::Remove) are archived rather than deleted,
+//! which hides them from queries. Restoring clears the archived flag so they show up again,
+//! with their authors, references and documents as they were.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{
+//!   database::{Database, Query, Remove, Restore},
+//!   prelude::*,
+//! };
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut db = Database::open("papers.db").await?;
+//!
+//! Remove::by_source("arxiv", "2301.07041").execute(&mut db).await?;
+//! let restored = Restore::by_source("arxiv", "2301.07041").execute(&mut db).await?;
+//! println!("Restored {} papers", restored.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+
+/// Instruction for restoring archived papers.
+///
+/// Only archived papers matching the query are affected; the papers that were restored are
+/// returned.
+pub struct Restore<'a> {
+  /// The query identifying papers to restore, limited to archived papers
+  query: Query<'a>,
+}
+
+impl<'a> Restore<'a> {
+  /// Creates a restore instruction from an existing query.
+  ///
+  /// The query is narrowed to archived papers, so papers that are not archived are never
+  /// returned as restored.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The query that identifies papers to restore
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Query, Restore};
+  /// // Restore archived papers matching a text search
+  /// let restore = Restore::from_query(Query::text("quantum computing"));
+  /// ```
+  pub fn from_query(query: Query<'a>) -> Self { Self { query: query.only_archived() } }
+
+  /// Creates a restore instruction for a specific paper by its source and identifier.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system (arXiv, DOI, etc.)
+  /// * `identifier` - The source-specific identifier
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Restore;
+  /// let restore = Restore::by_source("arxiv", "2301.07041");
+  /// ```
+  pub fn by_source(source: &'a str, identifier: &'a str) -> Self {
+    Self::from_query(Query::by_source(source, identifier))
+  }
+}
+
+#[async_trait]
+impl DatabaseInstruction for Restore<'_> {
+  type Output = Vec<Paper>;
+
+  async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    db.ensure_writable()?;
+    let papers = self.query.execute(db).await?;
+
+    if !papers.is_empty() {
+      let keys: Vec<_> = papers
+        .iter()
+        .map(|paper| (paper.source.clone(), paper.source_identifier.clone()))
+        .collect();
+      db.conn
+        .call(move |conn| {
+          let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+          for (source, identifier) in keys {
+            tx.execute(
+              "UPDATE papers SET archived = 0, updated_at = datetime('now')
+               WHERE source = ?1 AND source_identifier = ?2",
+              [source, identifier],
+            )?;
+          }
+          tx.commit()?;
+          Ok(())
+        })
+        .await?;
+    }
+
+    Ok(papers)
+  }
+}
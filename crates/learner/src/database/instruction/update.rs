@@ -0,0 +1,154 @@
+//! Update instruction implementation for editing stored paper metadata.
+//!
+//! This module provides functionality for replacing the metadata of a paper that is
+//! already in the database. It supports:
+//!
+//! - Rewriting the paper's own columns (title, abstract, dates, links)
+//! - Replacing its author, reference and keyword lists
+//! - Keeping the full-text search index in sync
+//!
+//! Papers are identified by their source and source identifier, which therefore
+//! cannot be changed through an update.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{
+//!   database::{Database, Query, Update},
+//!   prelude::*,
+//! };
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut db = Database::open("papers.db").await?;
+//!
+//! let mut paper = Query::by_source("arxiv", "2301.07041").execute(&mut db).await?.remove(0);
+//! paper.title = "A Corrected Title".to_string();
+//! Update::paper(&paper).execute(&mut db).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::*;
+use crate::database::Add;
+
+/// Instruction for replacing a stored paper's metadata.
+///
+/// This struct implements the [`DatabaseInstruction`] trait to overwrite
+/// everything stored about a paper except its identity and documents. It handles:
+///
+/// - Updating the `papers` row
+/// - Replacing authors, references and keywords
+/// - Transaction management
+pub struct Update<'a> {
+  /// The new metadata, matched to the stored paper by source and identifier
+  paper: &'a Paper,
+}
+
+impl<'a> Update<'a> {
+  /// Creates an instruction that replaces the stored copy of `paper` with `paper`.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper` - The edited paper; its source and identifier select the row to update
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Update;
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let learner = Learner::builder().build().await?;
+  /// let mut paper = learner.retriever.get_paper("2301.07041").await?;
+  /// paper.title = paper.title.trim().to_string();
+  /// let instruction = Update::paper(&paper);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn paper(paper: &'a Paper) -> Self { Self { paper } }
+
+  /// Builds the SQL for rewriting the paper's own columns.
+  pub(super) fn build_paper_sql(paper: &Paper) -> (String, Vec<Option<String>>) {
+    (
+      "UPDATE papers
+         SET title = ?, abstract_text = ?, publication_date = ?,
+             pdf_url = ?, doi = ?, version = ?, primary_category = ?, language = ?,
+             updated_at = datetime('now')
+         WHERE source = ? AND source_identifier = ?"
+        .to_string(),
+      vec![
+        Some(paper.title.clone()),
+        Some(paper.abstract_text.clone()),
+        Some(paper.publication_date.to_iso8601()),
+        paper.pdf_url.clone(),
+        paper.doi.clone(),
+        paper.version.clone(),
+        paper.primary_category.clone(),
+        paper.language.clone(),
+        Some(paper.source.to_string()),
+        Some(paper.source_identifier.clone()),
+      ],
+    )
+  }
+
+  /// Builds the SQL for clearing the paper's authors, references and keywords before reinserting
+  /// them.
+  pub(super) fn build_clear_sql(paper: &Paper) -> Vec<(String, Vec<Option<String>>)> {
+    ["authors", "paper_references", "paper_keywords"]
+      .into_iter()
+      .map(|table| {
+        (
+          format!(
+            "DELETE FROM {table} WHERE paper_id IN (
+               SELECT id FROM papers WHERE source = ? AND source_identifier = ?
+             )"
+          ),
+          vec![Some(paper.source.to_string()), Some(paper.source_identifier.clone())],
+        )
+      })
+      .collect()
+  }
+}
+
+#[async_trait]
+impl DatabaseInstruction for Update<'_> {
+  type Output = Vec<Paper>;
+
+  async fn execute(&self, db: &mut Database) -> Result<Self::Output> {
+    db.ensure_writable()?;
+    let paper = self.paper;
+    let (paper_sql, paper_params) = Self::build_paper_sql(paper);
+    let clear_statements = Self::build_clear_sql(paper);
+    let insert_statements: Vec<_> = paper
+      .authors
+      .iter()
+      .map(|author| Add::build_author_sql(author, paper))
+      .chain(paper.references.iter().map(|doi| Add::build_reference_sql(doi, paper)))
+      .chain(paper.keywords.iter().map(|keyword| Add::build_keyword_sql(keyword, paper)))
+      .collect();
+
+    let updated = db
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if tx.execute(&paper_sql, params_from_iter(paper_params))? == 0 {
+          return Ok(false);
+        }
+
+        for (sql, params) in clear_statements.into_iter().chain(insert_statements) {
+          tx.execute(&sql, params_from_iter(params))?;
+        }
+
+        tx.commit()?;
+        Ok(true)
+      })
+      .await?;
+
+    if !updated {
+      return Err(LearnerError::NotFound {
+        source_name: paper.source.clone(),
+        identifier:  paper.source_identifier.clone(),
+      });
+    }
+    Ok(vec![paper.clone()])
+  }
+}
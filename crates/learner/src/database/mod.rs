@@ -23,6 +23,7 @@
 //!
 //! - [`Query`] - For searching and retrieving papers
 //! - [`Add`] - For adding new papers and documents
+//! - [`Update`] - For editing the metadata of stored papers
 //! - [`Remove`] - For removing papers from the database
 //!
 //! # Examples
@@ -52,6 +53,10 @@
 //! # }
 //! ```
 
+use std::{collections::BTreeSet, sync::Arc};
+
+use rusqlite::OptionalExtension;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_rusqlite::Connection;
 
 use super::*;
@@ -61,12 +66,129 @@ mod instruction;
 #[cfg(test)] mod tests;
 
 pub use self::instruction::{
-  add::Add,
+  add::{Add, Conflict},
+  merge::Merge,
   query::{OrderField, Query, QueryCriteria},
   remove::Remove,
+  restore::Restore,
+  update::Update,
   DatabaseInstruction,
 };
 
+/// Schema migrations applied on top of `migrations/init.sql`, in order.
+///
+/// The number of migrations already applied to a database is tracked in SQLite's
+/// `user_version` pragma, so each entry runs exactly once. Append new migrations to the
+/// end of this list; never edit or reorder ones that have shipped.
+const MIGRATIONS: &[&str] = &[
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0001_author_orcid.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0002_paper_references.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0003_paper_version.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0004_papers_fts_update.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0005_author_name_folded.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0006_paper_archived.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0007_paper_keywords.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0008_paper_primary_category.sql")),
+  include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/migrations/0009_author_name_folded_index.sql"
+  )),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0010_paper_language.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0011_file_uri.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0012_file_sha256.sql")),
+  include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/0013_paper_added_at.sql")),
+];
+
+/// Connection settings applied by [`Database::open_with_options`].
+///
+/// Read from the `[database]` table of `config.toml`:
+///
+/// ```toml
+/// [database]
+/// wal = false            # keep the classic rollback journal
+/// busy_timeout_ms = 10000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseOptions {
+  /// Whether to use SQLite's write-ahead log, which lets readers keep going while another
+  /// connection writes. It keeps `-wal` and `-shm` files next to the database.
+  pub wal:             bool,
+  /// How long a connection waits for another one's lock before failing with
+  /// `database is locked`, in milliseconds
+  pub busy_timeout_ms: u64,
+}
+
+impl Default for DatabaseOptions {
+  fn default() -> Self { Self { wal: true, busy_timeout_ms: 5000 } }
+}
+
+/// How many of the most prolific authors [`Database::aggregate_stats`] reports.
+pub const TOP_AUTHORS: usize = 10;
+
+/// Counts describing the papers in a library, from [`Database::aggregate_stats`].
+///
+/// Archived papers are not counted. Each breakdown is sorted by descending count, except
+/// `by_year` which is in chronological order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryStats {
+  /// Number of papers
+  pub papers:      usize,
+  /// Number of distinct authors, ignoring case and diacritics
+  pub authors:     usize,
+  /// Number of papers with a stored document
+  pub documents:   usize,
+  /// Papers per source, e.g. `("arxiv", 12)`
+  pub by_source:   Vec<(String, usize)>,
+  /// Papers per publication year
+  pub by_year:     Vec<(i32, usize)>,
+  /// The [`TOP_AUTHORS`] authors with the most papers, ties broken by name. Spellings that only
+  /// differ in case or diacritics count as one author, named by the most used spelling.
+  pub top_authors: Vec<(String, usize)>,
+}
+
+/// Why the papers in a [`DuplicateCluster`] look like copies of the same work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DuplicateReason {
+  /// The papers have the same DOI, ignoring case and resolver prefixes
+  Doi,
+  /// The papers have the same title and first author surname, ignoring case, punctuation and
+  /// diacritics
+  TitleAndAuthor,
+}
+
+/// Stored papers that are likely copies of the same work, from [`Database::find_duplicates`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+  /// What the papers have in common
+  pub reason: DuplicateReason,
+  /// The normalized DOI, or title and first author, the papers share
+  pub key:    String,
+  /// The papers, at least two, ordered by source and identifier
+  pub papers: Vec<Paper>,
+}
+
+/// A stored document's row in the `files` table, see [`Database::stored_document`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentRecord {
+  /// Where the document is, recorded or derived from its path for older rows
+  pub uri:    String,
+  /// The key the document is stored under in its backend
+  pub key:    String,
+  /// Hex SHA-256 recorded when the document was stored, if it was
+  pub sha256: Option<String>,
+}
+
+impl DocumentRecord {
+  /// Where the document is on the filesystem, if it has a `file://` URI.
+  pub fn path(&self) -> Option<PathBuf> {
+    Url::parse(&self.uri)
+      .ok()
+      .filter(|url| url.scheme() == "file")
+      .and_then(|url| url.to_file_path().ok())
+  }
+}
+
 /// Main database connection handler for the paper management system.
 ///
 /// The `Database` struct provides the primary interface for interacting with the SQLite
@@ -83,7 +205,16 @@ pub use self::instruction::{
 #[derive(Debug, Clone)]
 pub struct Database {
   /// Active connection to the SQLite database
-  pub conn: Connection,
+  pub conn:  Connection,
+  /// Whether this handle was opened with [`Database::open_readonly`]
+  read_only: bool,
+  /// Bounds the downloads in flight across this handle and its clones, see
+  /// [`Database::set_max_concurrency`]
+  network:   Arc<Semaphore>,
+  /// Where documents are written, see [`Database::set_storage`]
+  storage:   Option<Arc<dyn Storage>>,
+  /// Whether downloads are refused, see [`Database::set_offline`]
+  offline:   bool,
 }
 
 impl Database {
@@ -123,12 +254,46 @@ impl Database {
   /// # }
   /// ```
   pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+    Self::open_with_options(path, &DatabaseOptions::default()).await
+  }
+
+  /// Opens or creates a database like [`Database::open`], with the given connection settings.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, DatabaseOptions};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// // Stick to the rollback journal, e.g. on a network filesystem
+  /// let options = DatabaseOptions { wal: false, ..DatabaseOptions::default() };
+  /// let db = Database::open_with_options("papers.db", &options).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn open_with_options(
+    path: impl AsRef<Path>,
+    options: &DatabaseOptions,
+  ) -> Result<Self> {
     // Create parent directories if needed
     if let Some(parent) = path.as_ref().parent() {
       std::fs::create_dir_all(parent)?;
     }
 
     let conn = Connection::open(path.as_ref()).await?;
+    let (wal, busy_timeout) =
+      (options.wal, std::time::Duration::from_millis(options.busy_timeout_ms));
+    conn
+      .call(move |conn| {
+        conn.busy_timeout(busy_timeout)?;
+        // The journal mode sticks to the file, so switch back explicitly when opting out
+        let mode = if wal { "WAL" } else { "DELETE" };
+        if let Err(e) = conn.query_row(&format!("PRAGMA journal_mode = {mode}"), [], |_| Ok(())) {
+          // Leaving WAL needs the file to ourselves, which another process may prevent
+          warn!("Could not switch the database to journal_mode {mode}: {e}");
+        }
+        Ok(())
+      })
+      .await?;
 
     // Initialize schema
     conn
@@ -139,8 +304,16 @@ impl Database {
         )))?)
       })
       .await?;
+    conn.call(|conn| Ok(Self::migrate(conn)?)).await?;
+    conn.call(|conn| Ok(Self::fold_author_names(conn)?)).await?;
 
-    let db = Self { conn };
+    let db = Self {
+      conn,
+      read_only: false,
+      network: Self::network_semaphore(None),
+      storage: None,
+      offline: false,
+    };
 
     // Check if storage path is set, if not, set default
     if db.get_storage_path().await.is_err() {
@@ -150,6 +323,439 @@ impl Database {
     Ok(db)
   }
 
+  /// Opens an existing database without write access.
+  ///
+  /// The file is opened with SQLite's read-only flag, so several tools can browse the same
+  /// library while another one writes to it. [`Query`] works as usual, while [`Add`],
+  /// [`Remove`], [`Update`], [`Merge`] and [`Restore`] fail with
+  /// [`LearnerError::ReadOnlyDatabase`] before touching the file.
+  ///
+  /// Nothing is created or migrated, so the database must already exist and be up to date.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - The database file does not exist or cannot be opened
+  /// - The database needs migrating, in which case it must be opened with [`Database::open`] once
+  ///   first ([`LearnerError::Config`])
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, Query};
+  /// # use learner::prelude::*;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open_readonly(Database::default_path()).await?;
+  /// let papers = Query::text("lattice").execute(&mut db).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn open_readonly(path: impl AsRef<Path>) -> Result<Self> {
+    let flags = rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY
+      | rusqlite::OpenFlags::SQLITE_OPEN_URI
+      | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    let conn = Connection::open_with_flags(path.as_ref(), flags).await?;
+    let busy_timeout = std::time::Duration::from_millis(DatabaseOptions::default().busy_timeout_ms);
+    conn.call(move |conn| Ok(conn.busy_timeout(busy_timeout)?)).await?;
+
+    let applied: usize =
+      conn.call(|conn| Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)).await?;
+    if applied < MIGRATIONS.len() {
+      return Err(LearnerError::Config(format!(
+        "{} needs migrating before it can be opened read-only",
+        path.as_ref().display()
+      )));
+    }
+
+    Ok(Self {
+      conn,
+      read_only: true,
+      network: Self::network_semaphore(None),
+      storage: None,
+      offline: false,
+    })
+  }
+
+  /// Limits how many downloads this handle and its clones run at once.
+  ///
+  /// [`Add::documents`] fetches the PDFs of many papers concurrently; this caps the number in
+  /// flight at any moment, so large batches stay polite to the servers they hit. The limit
+  /// defaults to [`Config::DEFAULT_MAX_CONCURRENCY`] and is set from
+  /// [`Config::max_concurrency`] by [`LearnerBuilder::build`]. A limit of `0` is treated as `1`.
+  ///
+  /// Clones made before this call keep sharing the previous limit.
+  pub fn set_max_concurrency(&mut self, max_concurrency: usize) {
+    self.network = Self::network_semaphore(Some(max_concurrency));
+  }
+
+  /// A semaphore with `max_concurrency` permits, [`Config::DEFAULT_MAX_CONCURRENCY`] if unset.
+  fn network_semaphore(max_concurrency: Option<usize>) -> Arc<Semaphore> {
+    let permits = max_concurrency.unwrap_or(Config::DEFAULT_MAX_CONCURRENCY).max(1);
+    Arc::new(Semaphore::new(permits))
+  }
+
+  /// Waits until another download may start under [`Database::set_max_concurrency`].
+  ///
+  /// The download counts as in flight until the returned permit is dropped.
+  pub(crate) async fn network_permit(&self) -> OwnedSemaphorePermit {
+    self.network.clone().acquire_owned().await.expect("the network semaphore is never closed")
+  }
+
+  /// Makes instructions that would download documents, like [`Add::documents`] and
+  /// [`Add::complete`], fail with [`LearnerError::Offline`] instead.
+  ///
+  /// Set from [`Config::offline`] by [`LearnerBuilder::build`], so offline mode covers the
+  /// downloads of the database as well as the retrievals of
+  /// [`Retriever`](crate::retriever::Retriever).
+  pub fn set_offline(&mut self, offline: bool) { self.offline = offline; }
+
+  /// Fails with [`LearnerError::Offline`] if this handle may not download documents.
+  pub(crate) fn ensure_online(&self) -> Result<()> {
+    if self.offline {
+      return Err(LearnerError::Offline);
+    }
+    Ok(())
+  }
+
+  /// Returns whether this handle was opened with [`Database::open_readonly`].
+  pub fn is_read_only(&self) -> bool { self.read_only }
+
+  /// Fails with [`LearnerError::ReadOnlyDatabase`] if this handle may not write.
+  pub(crate) fn ensure_writable(&self) -> Result<()> {
+    if self.read_only {
+      return Err(LearnerError::ReadOnlyDatabase);
+    }
+    Ok(())
+  }
+
+  /// Applies any [`MIGRATIONS`] the database has not seen yet.
+  ///
+  /// Each migration runs in its own transaction together with the `user_version` bump, so
+  /// an interrupted upgrade never leaves a half-applied migration behind.
+  fn migrate(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    let applied: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+      debug!("Applying database migration {}", version + 1);
+      let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+      tx.execute_batch(migration)?;
+      tx.pragma_update(None, "user_version", version + 1)?;
+      tx.commit()?;
+    }
+    Ok(())
+  }
+
+  /// Fills in `authors.name_folded` for rows stored before it existed.
+  ///
+  /// The folding is done by [`format::fold_diacritics`] rather than in SQL, so a migration
+  /// cannot backfill the column itself.
+  fn fold_author_names(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    let unfolded = tx
+      .prepare("SELECT id, name FROM authors WHERE name_folded IS NULL")?
+      .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+      .collect::<rusqlite::Result<Vec<_>>>()?;
+    if !unfolded.is_empty() {
+      debug!("Folding {} stored author names", unfolded.len());
+    }
+    for (id, name) in unfolded {
+      tx.execute("UPDATE authors SET name_folded = ?1 WHERE id = ?2", rusqlite::params![
+        format::fold_diacritics(&name),
+        id
+      ])?;
+    }
+    tx.commit()
+  }
+
+  /// Rebuilds the `papers_fts` search index from the `papers` table.
+  ///
+  /// The index is normally kept in sync by triggers, but rows written around them (for
+  /// instance by bulk imports or older schema versions) can leave it stale or missing
+  /// entries. The rebuild runs in a single transaction, so searches see either the old
+  /// index or the complete new one.
+  ///
+  /// # Returns
+  ///
+  /// Returns the number of papers in the rebuilt index.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// let indexed = db.reindex_fts().await?;
+  /// println!("Reindexed {indexed} papers");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn reindex_fts(&self) -> Result<usize> {
+    self.ensure_writable()?;
+    Ok(
+      self
+        .conn
+        .call(|conn| {
+          let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+          tx.execute("INSERT INTO papers_fts(papers_fts) VALUES ('rebuild')", [])?;
+          let indexed: usize = tx.query_row("SELECT COUNT(*) FROM papers", [], |row| row.get(0))?;
+          tx.commit()?;
+          debug!("Rebuilt the search index for {indexed} papers");
+          Ok(indexed)
+        })
+        .await?,
+    )
+  }
+
+  /// Rewrites stored DOIs into the canonical form of [`format::normalize_doi`].
+  ///
+  /// DOIs are normalized as papers are retrieved, but rows stored before that (or written
+  /// around it) can hold resolver URLs or mixed case, which then fail to match. This rewrites
+  /// each paper's DOI, the identifiers of papers from the `doi` source and the DOIs in the
+  /// citation graph, all in one transaction. Values that don't look like a DOI are kept as they
+  /// are. A `doi` paper whose canonical identifier is already taken by another paper is left
+  /// alone, for [`Database::find_duplicates`] to report.
+  ///
+  /// # Returns
+  ///
+  /// Returns the number of papers that changed.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// let changed = db.normalize_dois().await?;
+  /// println!("Normalized the DOIs of {changed} papers");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn normalize_dois(&self) -> Result<usize> {
+    self.ensure_writable()?;
+    Ok(
+      self
+        .conn
+        .call(|conn| {
+          let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+          let mut changed = BTreeSet::new();
+          let rewrite = |value: &str| format::normalize_doi(value).filter(|doi| doi != value);
+
+          let papers: Vec<(i64, String, String, Option<String>)> = tx
+            .prepare("SELECT id, source, source_identifier, doi FROM papers")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+          for (id, source, identifier, doi) in papers {
+            if let Some(doi) = doi.as_deref().and_then(rewrite) {
+              tx.execute("UPDATE papers SET doi = ?1 WHERE id = ?2", (&doi, id))?;
+              changed.insert(id);
+            }
+            let Some(canonical) = rewrite(&identifier).filter(|_| source == "doi") else {
+              continue;
+            };
+            let updated = tx.execute(
+              "UPDATE OR IGNORE papers SET source_identifier = ?1 WHERE id = ?2",
+              (&canonical, id),
+            )?;
+            if updated == 0 {
+              warn!("Not renaming doi {identifier} to {canonical}, which is stored already");
+            } else {
+              changed.insert(id);
+            }
+          }
+
+          let references: Vec<(i64, i64, String)> = tx
+            .prepare("SELECT id, paper_id, doi FROM paper_references")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+          for (id, paper_id, doi) in references {
+            let Some(doi) = rewrite(&doi) else { continue };
+            // A paper citing both spellings keeps only the one reference
+            if tx
+              .execute("UPDATE OR IGNORE paper_references SET doi = ?1 WHERE id = ?2", (&doi, id))?
+              == 0
+            {
+              tx.execute("DELETE FROM paper_references WHERE id = ?1", [id])?;
+            }
+            changed.insert(paper_id);
+          }
+
+          tx.commit()?;
+          debug!("Normalized the DOIs of {} papers", changed.len());
+          Ok(changed.len())
+        })
+        .await?,
+    )
+  }
+
+  /// Copies everything in the write-ahead log into the database file and truncates the log.
+  ///
+  /// Queued operations on this handle finish first, so calling this before exiting leaves the
+  /// database file complete on its own. It is a no-op outside WAL mode and on read-only
+  /// handles. If another connection is mid-read the log may only be partly copied; nothing is
+  /// lost, the rest is copied by a later checkpoint.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// db.checkpoint().await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn checkpoint(&self) -> Result<()> {
+    if self.read_only {
+      return Ok(());
+    }
+    Ok(
+      self
+        .conn
+        .call(|conn| {
+          let (busy, frames): (bool, i64) =
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+              Ok((row.get(0)?, row.get(1)?))
+            })?;
+          if busy {
+            warn!("Database is busy, only part of the write-ahead log was checkpointed");
+          } else {
+            debug!("Checkpointed {frames} write-ahead log frames");
+          }
+          Ok(())
+        })
+        .await?,
+    )
+  }
+
+  /// Counts the papers in the library, broken down by source, publication year and author.
+  ///
+  /// Each breakdown is computed with a single grouped query in one read transaction, so the
+  /// counts are consistent with each other.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// let stats = db.aggregate_stats().await?;
+  /// for (source, count) in &stats.by_source {
+  ///   println!("{source}: {count} of {} papers", stats.papers);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn aggregate_stats(&self) -> Result<LibraryStats> {
+    Ok(
+      self
+        .conn
+        .call(|conn| {
+          let tx = conn.transaction()?;
+          let grouped = |sql: &str| -> rusqlite::Result<Vec<(String, usize)>> {
+            tx.prepare(sql)?.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect()
+          };
+
+          let by_source = grouped(
+            "SELECT source, COUNT(*) FROM papers WHERE archived = 0
+             GROUP BY source ORDER BY COUNT(*) DESC, source",
+          )?;
+          // Publication dates, partial or not, start with the year
+          let by_year = grouped(
+            "SELECT substr(publication_date, 1, 4), COUNT(*) FROM papers WHERE archived = 0
+             GROUP BY 1 ORDER BY 1",
+          )?
+          .into_iter()
+          .filter_map(|(year, count)| Some((year.parse().ok()?, count)))
+          .collect();
+          // Each author is shown by their most used spelling, the latest one on a tie
+          let top_authors = grouped(&format!(
+            "SELECT (SELECT s.name FROM authors s
+                     JOIN papers sp ON sp.id = s.paper_id AND sp.archived = 0
+                     WHERE s.name_folded = a.name_folded
+                     GROUP BY s.name ORDER BY COUNT(*) DESC, MAX(sp.id) DESC LIMIT 1),
+                    COUNT(DISTINCT a.paper_id) FROM authors a
+             JOIN papers p ON p.id = a.paper_id AND p.archived = 0
+             GROUP BY a.name_folded ORDER BY 2 DESC, 1 LIMIT {TOP_AUTHORS}"
+          ))?;
+
+          let papers =
+            tx.query_row("SELECT COUNT(*) FROM papers WHERE archived = 0", [], |row| row.get(0))?;
+          let authors = tx.query_row(
+            "SELECT COUNT(DISTINCT a.name_folded) FROM authors a
+             JOIN papers p ON p.id = a.paper_id AND p.archived = 0",
+            [],
+            |row| row.get(0),
+          )?;
+          let documents = tx.query_row(
+            "SELECT COUNT(DISTINCT f.paper_id) FROM files f
+             JOIN papers p ON p.id = f.paper_id AND p.archived = 0
+             WHERE f.download_status = 'Success'",
+            [],
+            |row| row.get(0),
+          )?;
+
+          Ok(LibraryStats { papers, authors, documents, by_source, by_year, top_authors })
+        })
+        .await?,
+    )
+  }
+
+  /// Groups stored papers that are likely duplicates, without changing anything.
+  ///
+  /// Papers are clustered by their normalized DOI (see [`format::normalize_doi`]), where papers
+  /// from the `doi` source count their identifier as their DOI, and separately by their title
+  /// and first author's surname, ignoring case, punctuation and diacritics. A title cluster is
+  /// left out when a DOI cluster already holds all of its papers. Archived papers are not
+  /// considered. The clusters are meant for reviewing before combining papers with
+  /// [`Merge`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open(Database::default_path()).await?;
+  /// for cluster in db.find_duplicates().await? {
+  ///   println!("{} papers share {}", cluster.papers.len(), cluster.key);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn find_duplicates(&mut self) -> Result<Vec<DuplicateCluster>> {
+    let mut papers = Query::list_all().execute(self).await?;
+    papers
+      .sort_by(|a, b| (&a.source, &a.source_identifier).cmp(&(&b.source, &b.source_identifier)));
+
+    let mut by_doi: BTreeMap<String, Vec<&Paper>> = BTreeMap::new();
+    let mut by_title: BTreeMap<String, Vec<&Paper>> = BTreeMap::new();
+    for paper in &papers {
+      let doi = paper
+        .doi
+        .as_deref()
+        .or((paper.source == "doi").then_some(paper.source_identifier.as_str()));
+      if let Some(doi) = doi.and_then(format::normalize_doi) {
+        by_doi.entry(doi).or_default().push(paper);
+      }
+      if let Some(key) = title_author_key(paper) {
+        by_title.entry(key).or_default().push(paper);
+      }
+    }
+
+    let mut duplicates = clusters(by_doi, DuplicateReason::Doi);
+    let title_clusters: Vec<_> = clusters(by_title, DuplicateReason::TitleAndAuthor)
+      .into_iter()
+      .filter(|cluster| {
+        !duplicates.iter().any(|doi| {
+          cluster
+            .papers
+            .iter()
+            .all(|paper| doi.papers.iter().any(|other| other.same_identity(paper)))
+        })
+      })
+      .collect();
+    duplicates.extend(title_clusters);
+    Ok(duplicates)
+  }
+
   /// Gets the configured storage path for document files.
   ///
   /// The storage path determines where document files (like PDFs) will be saved
@@ -188,6 +794,202 @@ impl Database {
     )
   }
 
+  /// Stores documents added from now on in `storage` instead of the storage path.
+  ///
+  /// Documents already stored stay where they are, and are still found through the URI
+  /// recorded for them (see [`Database::document_uri`]). Clones made before this call keep
+  /// writing to the previous backend.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::{Add, Database}, prelude::*, resource::Paper, storage::MemoryStorage};
+  /// # async fn example(paper: Paper) -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open(Database::default_path()).await?;
+  /// db.set_storage(MemoryStorage::default());
+  /// Add::complete(&paper).execute(&mut db).await?;
+  /// assert!(db.document_uri(&paper).await?.unwrap().starts_with("memory:"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn set_storage(&mut self, storage: impl Storage + 'static) {
+    self.storage = Some(Arc::new(storage));
+  }
+
+  /// The backend documents are written to.
+  ///
+  /// This is the backend given to [`Database::set_storage`], or a [`FileStorage`] in
+  /// [`Database::get_storage_path`] if none was.
+  pub async fn storage(&self) -> Result<Arc<dyn Storage>> {
+    match &self.storage {
+      Some(storage) => Ok(storage.clone()),
+      None => Ok(Arc::new(FileStorage::new(self.get_storage_path().await?))),
+    }
+  }
+
+  /// Returns the URI of the stored document for `paper`, if it has one.
+  ///
+  /// Only successfully stored documents are reported; a paper whose download failed has no
+  /// document. Documents on the filesystem have `file://` URIs, including those stored before
+  /// URIs were recorded; other backends use their own scheme (see [`Storage::uri`]).
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, resource::Paper};
+  /// # async fn example(paper: Paper) -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// if let Some(uri) = db.document_uri(&paper).await? {
+  ///   println!("{} is stored at {uri}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn document_uri(&self, paper: &Paper) -> Result<Option<String>> {
+    Ok(self.stored_document(paper).await?.map(|document| document.uri))
+  }
+
+  /// Checks the stored document for `paper` against the checksum recorded when it was stored.
+  ///
+  /// Returns `None` if the paper has no stored document, or one stored before checksums were
+  /// recorded, and otherwise whether the document's contents still match. Documents on the
+  /// filesystem are read from their recorded path, others from [`Database::storage`].
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] if the document cannot be read, e.g. because it was
+  /// deleted outside of learner.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, resource::Paper};
+  /// # async fn example(paper: Paper) -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// if db.verify_document(&paper).await? == Some(false) {
+  ///   println!("The PDF of {} changed since it was stored", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn verify_document(&self, paper: &Paper) -> Result<Option<bool>> {
+    let Some(document) = self.stored_document(paper).await? else {
+      return Ok(None);
+    };
+    let Some(expected) = &document.sha256 else {
+      return Ok(None);
+    };
+    let contents = self.backend(&document).await?.get(&document.key).await?;
+    Ok(Some(&storage::sha256(&contents) == expected))
+  }
+
+  /// Reads the contents of the stored `document` from the backend holding it.
+  ///
+  /// Documents on the filesystem are read from their recorded path, others through
+  /// [`Database::storage`].
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] with [`std::io::ErrorKind::NotFound`] if the document is
+  /// no longer there, or any error from the backend.
+  pub async fn read_document(&self, document: &DocumentRecord) -> Result<Vec<u8>> {
+    self.backend(document).await?.get(&document.key).await
+  }
+
+  /// Deletes the stored `document` from the backend holding it.
+  ///
+  /// Documents on the filesystem are deleted from their recorded path, others through
+  /// [`Database::storage`]. The `files` record is left alone, so look the document up with
+  /// [`Database::stored_document`] before removing its paper, which drops the record.
+  /// Deleting a document that is already gone succeeds.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::{Database, Remove}, prelude::*, resource::Paper};
+  /// # async fn example(paper: Paper) -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut db = Database::open(Database::default_path()).await?;
+  /// let document = db.stored_document(&paper).await?;
+  /// Remove::by_source(&paper.source, &paper.source_identifier).purge().execute(&mut db).await?;
+  /// if let Some(document) = document {
+  ///   db.delete_document(&document).await?;
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn delete_document(&self, document: &DocumentRecord) -> Result<()> {
+    self.backend(document).await?.delete(&document.key).await
+  }
+
+  /// The backend holding `document`: a [`FileStorage`] at the directory it was recorded in for
+  /// documents on the filesystem, since the storage path may have changed since, or else
+  /// [`Database::storage`].
+  async fn backend(&self, document: &DocumentRecord) -> Result<Arc<dyn Storage>> {
+    let depth = Path::new(&document.key).components().count();
+    match document.path().as_deref().and_then(|path| path.ancestors().nth(depth)) {
+      Some(root) => Ok(Arc::new(FileStorage::new(root))),
+      None => self.storage().await,
+    }
+  }
+
+  /// Returns the `files` record of the successfully stored document for `paper`, if there is
+  /// one.
+  ///
+  /// Only successfully stored documents are reported; a paper whose download failed has no
+  /// document.
+  pub async fn stored_document(&self, paper: &Paper) -> Result<Option<DocumentRecord>> {
+    let (source, identifier) = (paper.source.clone(), paper.source_identifier.clone());
+    Ok(
+      self
+        .conn
+        .call(move |conn| {
+          Ok(
+            conn
+              .prepare_cached(
+                "SELECT f.uri, f.path, f.filename, f.sha256
+                 FROM files f
+                 JOIN papers p ON p.id = f.paper_id
+                 WHERE p.source = ?1 AND p.source_identifier = ?2
+                 AND f.download_status = 'Success'",
+              )?
+              .query_row([source, identifier], |row| {
+                let key: String = row.get(2)?;
+                let uri = match row.get::<_, Option<String>>(0)? {
+                  Some(uri) => uri,
+                  None => FileStorage::new(row.get::<_, String>(1)?).uri(&key),
+                };
+                Ok(DocumentRecord { uri, key, sha256: row.get(3)? })
+              })
+              .optional()?,
+          )
+        })
+        .await?,
+    )
+  }
+
+  /// Returns where the stored document for `paper` is on the filesystem, if it has one there.
+  ///
+  /// Only successfully stored documents are reported; a paper whose download failed has no
+  /// document, and neither does one stored in a backend other than [`FileStorage`] (see
+  /// [`Database::document_uri`]). The path is that recorded when the document was stored, so
+  /// it may no longer exist if the file was moved or deleted outside of learner.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, resource::Paper};
+  /// # async fn example(paper: Paper) -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// if let Some(path) = db.document_path(&paper).await? {
+  ///   println!("{} is stored at {}", paper.title, path.display());
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn document_path(&self, paper: &Paper) -> Result<Option<PathBuf>> {
+    Ok(self.stored_document(paper).await?.and_then(|document| document.path()))
+  }
+
   /// Sets the storage path for document files, validating that the path is usable.
   ///
   /// This method configures where document files (like PDFs) will be stored when
@@ -236,6 +1038,7 @@ impl Database {
   /// # }
   /// ```
   pub async fn set_storage_path(&self, path: impl AsRef<Path>) -> Result<()> {
+    self.ensure_writable()?;
     let original_path_result = self.get_storage_path().await;
     let path = path.as_ref();
 
@@ -384,3 +1187,44 @@ impl Database {
     .join("papers")
   }
 }
+
+/// The groups of more than one paper in `groups`, as clusters sharing `reason`.
+fn clusters(
+  groups: BTreeMap<String, Vec<&Paper>>,
+  reason: DuplicateReason,
+) -> Vec<DuplicateCluster> {
+  groups
+    .into_iter()
+    .filter(|(_, papers)| papers.len() > 1)
+    .map(|(key, papers)| DuplicateCluster {
+      reason,
+      key,
+      papers: papers.into_iter().cloned().collect(),
+    })
+    .collect()
+}
+
+/// The title and first author surname of `paper`, folded for [`Database::find_duplicates`].
+///
+/// Only letters and digits are kept, so punctuation and spacing differences do not matter.
+/// Authors written `Surname, Given` and `Given Surname` yield the same surname. Returns `None`
+/// for a paper without a title.
+fn title_author_key(paper: &Paper) -> Option<String> {
+  let fold = |text: &str| {
+    format::fold_diacritics(text)
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .collect::<Vec<_>>()
+      .join(" ")
+  };
+  let title = fold(&paper.title);
+  if title.is_empty() {
+    return None;
+  }
+  let surname =
+    paper.authors.first().map_or(String::new(), |author| match author.name.split_once(',') {
+      Some((surname, _)) => fold(surname),
+      None => fold(&author.name).rsplit(' ').next().unwrap_or_default().to_string(),
+    });
+  Some(format!("{title} / {surname}"))
+}
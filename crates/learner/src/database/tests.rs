@@ -148,3 +148,388 @@ async fn test_storage_path_readonly() -> Result<()> {
   ));
   Ok(())
 }
+
+#[traced_test]
+#[tokio::test]
+async fn test_migrations_upgrade_existing_database() -> Result<()> {
+  let dir = tempdir()?;
+  let path = dir.path().join("old.db");
+
+  // A database created before any migrations existed
+  let conn = rusqlite::Connection::open(&path)?;
+  conn.execute_batch(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/init.sql")))?;
+  conn.execute(
+    "INSERT INTO papers (title, abstract_text, publication_date, source, source_identifier)
+     VALUES ('Old', '', '2020-01-01T00:00:00+00:00', 'arxiv', '1')",
+    [],
+  )?;
+  conn.execute("INSERT INTO authors (paper_id, name) VALUES (1, 'Old Äuthor')", [])?;
+  drop(conn);
+
+  let db = Database::open(&path).await?;
+  let (version, orcid, name_folded, added_at) = db
+    .conn
+    .call(|conn| {
+      let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+      let (orcid, name_folded): (Option<String>, Option<String>) = conn.query_row(
+        "SELECT orcid, name_folded FROM authors WHERE name = 'Old Äuthor'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+      )?;
+      let added_at: bool = conn.query_row(
+        "SELECT added_at IS created_at FROM papers WHERE title = 'Old'",
+        [],
+        |row| row.get(0),
+      )?;
+      Ok((version, orcid, name_folded, added_at))
+    })
+    .await?;
+  assert_eq!(version, MIGRATIONS.len());
+  assert_eq!(orcid, None);
+  // Names stored before folding existed are folded when the database is opened
+  assert_eq!(name_folded.as_deref(), Some("old author"));
+  // Papers stored before `added_at` existed were added when their row was created
+  assert!(added_at);
+
+  // Reopening does not try to apply the migrations again
+  drop(db);
+  Database::open(&path).await?;
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_open_error_keeps_sqlite_source() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("not_a_database.db");
+  std::fs::write(&path, vec![0xAB; 4096]).unwrap();
+
+  let propagated: Box<dyn std::error::Error> = Database::open(&path).await.unwrap_err().into();
+  let error = propagated.downcast_ref::<LearnerError>().expect("a LearnerError");
+  assert!(matches!(error, LearnerError::AsyncSqlite(_)), "unexpected error: {error:?}");
+
+  let source = std::error::Error::source(error).expect("the wrapped tokio-rusqlite error");
+  assert!(source.downcast_ref::<tokio_rusqlite::Error>().is_some());
+  // The cause is shown once, by the report rather than the wrapping message
+  assert!(!error.to_string().contains(&source.to_string()));
+  assert_eq!(crate::error::report(error).matches(&source.to_string()).count(), 1);
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_reindex_fts_finds_rows_written_around_triggers() -> Result<()> {
+  let (mut db, _path, _dir) = setup_test_db().await;
+
+  // Write a paper without the insert trigger, leaving the search index without it
+  db.conn
+    .call(|conn| {
+      conn.execute_batch(
+        "DROP TRIGGER papers_ai;
+         INSERT INTO papers (title, abstract_text, publication_date, source, source_identifier)
+         VALUES ('Unindexed Quantum Paper', '', '2020-01-01T00:00:00+00:00', 'arxiv', '1');",
+      )?;
+      Ok(())
+    })
+    .await?;
+  assert!(Query::text("quantum").execute(&mut db).await?.is_empty());
+
+  assert_eq!(db.reindex_fts().await?, 1);
+  let found = Query::text("quantum").execute(&mut db).await?;
+  assert_eq!(found.len(), 1);
+  assert_eq!(found[0].title, "Unindexed Quantum Paper");
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_normalize_dois_rewrites_stored_spellings() -> Result<()> {
+  let (mut db, _path, _dir) = setup_test_db().await;
+  let paper = |source: &str, identifier: &str| {
+    Paper::builder()
+      .with_title(format!("Paper {identifier}"))
+      .with_source(source)
+      .with_source_identifier(identifier)
+      .with_publication_date(Utc::now())
+      .build()
+      .unwrap()
+  };
+
+  // Papers stored before DOIs were normalized on retrieval
+  let mut cited = paper("arxiv", "2301.00001");
+  cited.doi = Some("https://doi.org/10.1000/ABC".to_string());
+  cited.references =
+    vec!["doi:10.1000/Ref".to_string(), "10.1000/ref".to_string(), "not a doi".to_string()];
+  let renamed = paper("doi", "HTTPS://DX.DOI.ORG/10.1000/XYZ");
+  let taken = paper("doi", "10.1000/DUP");
+  for paper in [&cited, &renamed, &taken, &paper("doi", "10.1000/dup")] {
+    Add::paper(paper).execute(&mut db).await?;
+  }
+
+  assert_eq!(db.normalize_dois().await?, 2);
+  let stored = Query::by_source("arxiv", "2301.00001").one(&mut db).await?;
+  assert_eq!(stored.doi.as_deref(), Some("10.1000/abc"));
+  assert_eq!(stored.references.len(), 2);
+  assert!(stored.references.contains(&"10.1000/ref".to_string()));
+  assert!(stored.references.contains(&"not a doi".to_string()));
+  assert!(Query::by_source("doi", "10.1000/xyz").one(&mut db).await.is_ok());
+  // The canonical identifier already belongs to another paper, so both stay for merging
+  assert!(Query::by_source("doi", "10.1000/DUP").one(&mut db).await.is_ok());
+
+  // Everything is canonical now
+  assert_eq!(db.normalize_dois().await?, 0);
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_readonly_handle_queries_but_refuses_writes() -> Result<()> {
+  let (mut db, path, dir) = setup_test_db().await;
+  let paper = Paper::builder()
+    .with_title("Read Only Lattices")
+    .with_source("arxiv")
+    .with_source_identifier("2301.00001")
+    .with_publication_date(Utc::now())
+    .build()?;
+  Add::paper(&paper).execute(&mut db).await?;
+
+  let mut readonly = Database::open_readonly(&path).await?;
+  assert!(readonly.is_read_only());
+  assert_eq!(Query::text("lattices").execute(&mut readonly).await?.len(), 1);
+  // A dry run only reads, so it is allowed
+  assert_eq!(
+    Remove::by_source("arxiv", "2301.00001").dry_run().execute(&mut readonly).await?.len(),
+    1
+  );
+
+  let second = Paper { source_identifier: "2301.00002".to_string(), ..paper.clone() };
+  assert!(matches!(
+    Add::paper(&second).execute(&mut readonly).await,
+    Err(LearnerError::ReadOnlyDatabase)
+  ));
+  assert!(matches!(
+    Remove::by_source("arxiv", "2301.00001").execute(&mut readonly).await,
+    Err(LearnerError::ReadOnlyDatabase)
+  ));
+  assert!(matches!(
+    Update::paper(&paper).execute(&mut readonly).await,
+    Err(LearnerError::ReadOnlyDatabase)
+  ));
+  assert!(matches!(
+    readonly.set_storage_path(dir.path()).await,
+    Err(LearnerError::ReadOnlyDatabase)
+  ));
+
+  // Nothing was written through the read-only handle
+  assert_eq!(Query::text("lattices").execute(&mut db).await?.len(), 1);
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_readonly_requires_existing_database() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("missing.db");
+
+  assert!(Database::open_readonly(&path).await.is_err());
+  assert!(!path.exists());
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_wal_is_default_and_opt_out() -> Result<()> {
+  let journal_mode = |db: Database| async move {
+    let mode = db
+      .conn
+      .call(|conn| Ok(conn.query_row("PRAGMA journal_mode", [], |row| row.get::<_, String>(0))?))
+      .await?;
+    // Leaving WAL needs every other connection closed
+    db.conn.close().await?;
+    Ok::<_, LearnerError>(mode)
+  };
+  let (db, path, _dir) = setup_test_db().await;
+  assert_eq!(journal_mode(db).await?, "wal");
+
+  let options = DatabaseOptions { wal: false, ..DatabaseOptions::default() };
+  let db = Database::open_with_options(&path, &options).await?;
+  assert_eq!(journal_mode(db).await?, "delete");
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_checkpoint_flushes_wal_into_database_file() -> Result<()> {
+  let (mut db, path, _dir) = setup_test_db().await;
+  let wal_path = path.with_extension("db-wal");
+  for i in 0..20 {
+    let paper = Paper::builder()
+      .with_title(format!("Checkpointed Paper {i}"))
+      .with_source("arxiv")
+      .with_source_identifier(i.to_string())
+      .with_publication_date(Utc::now())
+      .build()?;
+    Add::paper(&paper).execute(&mut db).await?;
+  }
+  assert!(std::fs::metadata(&wal_path)?.len() > 0);
+
+  db.checkpoint().await?;
+  assert_eq!(std::fs::metadata(&wal_path)?.len(), 0);
+
+  // The database file alone now holds every paper, and still passes an integrity check
+  let mut reopened = Database::open_readonly(&path).await?;
+  let integrity = reopened
+    .conn
+    .call(|conn| Ok(conn.query_row("PRAGMA integrity_check", [], |row| row.get::<_, String>(0))?))
+    .await?;
+  assert_eq!(integrity, "ok");
+  assert_eq!(Query::list_all().execute(&mut reopened).await?.len(), 20);
+  // Read-only handles have nothing to flush
+  reopened.checkpoint().await?;
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_concurrent_access_during_write() -> Result<()> {
+  let (writer, path, _dir) = setup_test_db().await;
+  let mut reader = Database::open(&path).await?;
+  let mut second_writer = Database::open(&path).await?;
+
+  // Hold a write transaction open until told to commit
+  let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+  let (commit_tx, commit_rx) = std::sync::mpsc::channel::<()>();
+  let transaction = tokio::spawn(async move {
+    writer
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        tx.execute(
+          "INSERT INTO papers (title, abstract_text, publication_date, source, source_identifier)
+           VALUES ('Uncommitted Lattice Paper', '', '2020-01-01T00:00:00+00:00', 'arxiv', '1')",
+          [],
+        )?;
+        let _ = locked_tx.send(());
+        commit_rx.recv().ok();
+        tx.commit()?;
+        Ok(())
+      })
+      .await
+  });
+  locked_rx.await.unwrap();
+
+  // Readers see the last committed state instead of failing
+  assert!(Query::text("lattice").execute(&mut reader).await?.is_empty());
+
+  // Another writer waits for the lock instead of failing with `database is locked`
+  let paper = Paper::builder()
+    .with_title("Waiting Lattice Paper")
+    .with_source("arxiv")
+    .with_source_identifier("2")
+    .with_publication_date(Utc::now())
+    .build()?;
+  let add = tokio::spawn(async move { Add::paper(&paper).execute(&mut second_writer).await });
+  tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+  commit_tx.send(()).unwrap();
+  transaction.await.unwrap()?;
+  add.await.unwrap()?;
+
+  assert_eq!(Query::text("lattice").execute(&mut reader).await?.len(), 2);
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_author_queries_use_indexes() -> Result<()> {
+  let (db, _path, _dir) = setup_test_db().await;
+
+  let plan = |sql: &'static str| {
+    let conn = db.conn.clone();
+    async move {
+      conn
+        .call(move |conn| {
+          let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+          let details = stmt
+            .query_map([], |row| row.get::<_, String>(3))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+          Ok(details.join("\n"))
+        })
+        .await
+    }
+  };
+
+  // The `Query::by_author` criteria
+  let by_author = plan(
+    "SELECT DISTINCT p.id
+     FROM papers p
+     JOIN authors a ON p.id = a.paper_id
+     WHERE a.name_folded LIKE '%doe%'",
+  )
+  .await?;
+  // A leading wildcard rules out a search, but the covering index avoids reading whole rows
+  assert!(by_author.contains("USING COVERING INDEX idx_authors_name_folded"), "{by_author}");
+
+  // The batched author fetch for a page of results
+  let authors = plan(
+    "SELECT paper_id, name, affiliation, email, orcid
+     FROM authors
+     WHERE paper_id IN (1, 2, 3)
+     ORDER BY paper_id, id",
+  )
+  .await?;
+  assert!(authors.contains("idx_authors_paper_id"), "{authors}");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_aggregate_stats_groups_by_source_year_and_author() -> Result<()> {
+  use chrono::TimeZone;
+
+  let (mut db, _path, _dir) = setup_test_db().await;
+
+  let paper = |source: &str, identifier: &str, year: i32, authors: &[&str]| {
+    Paper::builder()
+      .with_title(format!("Paper {identifier}"))
+      .with_source(source)
+      .with_source_identifier(identifier)
+      .with_publication_date(Utc.with_ymd_and_hms(year, 6, 1, 0, 0, 0).unwrap())
+      .with_authors(
+        authors
+          .iter()
+          .map(|name| Author {
+            name:        name.to_string(),
+            affiliation: None,
+            email:       None,
+            orcid:       None,
+          })
+          .collect(),
+      )
+      .build()
+      .unwrap()
+  };
+  for paper in [
+    paper("arxiv", "2201.00001", 2022, &["Alice Author", "Bob Writer"]),
+    paper("arxiv", "2301.00002", 2023, &["Alice Author"]),
+    paper("arxiv", "2301.00003", 2023, &["Carol Scribe"]),
+    paper("doi", "10.1000/one", 2022, &["ALICE AUTHOR"]),
+  ] {
+    Add::paper(&paper).execute(&mut db).await?;
+  }
+  // Archived papers are left out
+  Add::paper(&paper("doi", "10.1000/gone", 2021, &["Dana Gone"])).execute(&mut db).await?;
+  Remove::by_source("doi", "10.1000/gone").execute(&mut db).await?;
+
+  let stats = db.aggregate_stats().await?;
+  assert_eq!(stats.papers, 4);
+  assert_eq!(stats.authors, 3);
+  assert_eq!(stats.documents, 0);
+  assert_eq!(stats.by_source, vec![("arxiv".to_string(), 3), ("doi".to_string(), 1)]);
+  assert_eq!(stats.by_year, vec![(2022, 2), (2023, 2)]);
+  // "ALICE AUTHOR" sorts first but "Alice Author" is the more common spelling
+  assert_eq!(&stats.top_authors, &[
+    ("Alice Author".to_string(), 3),
+    ("Bob Writer".to_string(), 1),
+    ("Carol Scribe".to_string(), 1)
+  ]);
+  Ok(())
+}
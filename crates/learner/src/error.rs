@@ -32,6 +32,9 @@ use thiserror::Error;
 /// Error type alias used for the [`learner`] crate.
 pub type Result<T> = core::result::Result<T, LearnerError>;
 
+/// Files in a config directory that failed to load, as `(filename, error)` pairs.
+pub type ConfigErrors = Vec<(String, LearnerError)>;
+
 /// Errors that can occur when working with the learner library.
 ///
 /// This enum provides a comprehensive set of error cases that can occur when:
@@ -41,7 +44,9 @@ pub type Result<T> = core::result::Result<T, LearnerError>;
 /// - Handling file system operations
 ///
 /// Most error variants provide additional context through either custom messages
-/// or wrapped underlying errors.
+/// or wrapped underlying errors. Variants that wrap another error only say what failed and
+/// expose the wrapped error through [`std::error::Error::source`]; use [`report`] to display
+/// the full chain.
 #[derive(Error, Debug)]
 pub enum LearnerError {
   /// The provided paper identifier doesn't match the expected format.
@@ -69,26 +74,104 @@ pub enum LearnerError {
   /// - The server is unreachable
   /// - The request times out
   /// - TLS/SSL errors occur
-  #[error(transparent)]
+  #[error("Network error")]
   Network(#[from] reqwest::Error),
 
+  /// A network request did not complete within its configured timeout.
+  ///
+  /// This occurs when a server accepts the connection but hangs, or cannot
+  /// be reached at all, for longer than the retriever's `timeout_secs`.
+  ///
+  /// The string parameter contains the URL that was being requested.
+  #[error("Request timed out: {0}")]
+  Timeout(String),
+
+  /// A network operation was attempted while offline mode is enabled.
+  ///
+  /// This occurs when the [`Learner`](crate::Learner) was built with offline mode
+  /// (see [`LearnerBuilder::offline`](crate::LearnerBuilder::offline)) and an operation
+  /// needed to reach the network, such as:
+  /// - Retrieving paper metadata from a remote source
+  /// - Downloading a paper's PDF
+  ///
+  /// Local database operations are unaffected by offline mode.
+  #[error("Offline mode is enabled, refusing to access the network")]
+  Offline,
+
   /// The requested paper couldn't be found.
   ///
   /// This occurs when the paper identifier is valid but:
-  /// - The paper doesn't exist in the source repository
-  /// - The paper has been removed or retracted
-  /// - The paper is not publicly accessible
-  #[error("Paper not found")]
-  NotFound,
+  /// - The paper doesn't exist in the local database
+  /// - A single-result lookup such as [`Query::one`](crate::database::Query::one) matched nothing
+  /// - An update targets a paper that was never stored
+  ///
+  /// The fields name the source and identifier that were looked up.
+  #[error("No paper found for {source_name} {identifier}")]
+  NotFound {
+    /// The source system that was searched (e.g., "arxiv")
+    ///
+    /// Not named `source` since `thiserror` reserves that name for the underlying cause.
+    source_name: String,
+    /// The identifier that was searched for
+    identifier:  String,
+  },
+
+  /// A lookup expected exactly one paper but matched several.
+  ///
+  /// This occurs when [`Query::one`](crate::database::Query::one) is used with criteria
+  /// that are not unique, such as a broad text or author search.
+  ///
+  /// The parameter contains the number of papers that matched.
+  #[error("Expected exactly one paper but {0} matched")]
+  Ambiguous(usize),
 
   /// An API returned an error response.
   ///
-  /// This occurs when the external API (arXiv, IACR, DOI) returns
-  /// an error response. The string parameter contains the error
-  /// message from the API for debugging.
+  /// This occurs when the external API (arXiv, IACR, DOI) answers but:
+  /// - Responds with a non-success HTTP status
+  /// - Omits a field the retriever configuration requires
+  /// - Returns a field that cannot be transformed as configured
+  ///
+  /// The string parameter contains the error message for debugging.
   #[error("API error: {0}")]
   ApiError(String),
 
+  /// A PDF URL answered with something other than a PDF, such as an HTML paywall or captcha
+  /// page.
+  ///
+  /// The parameter is the response's `Content-Type`, or `unknown` if it had none.
+  #[error("Expected a PDF but the server sent {0}; the PDF URL may need a login or a browser")]
+  NotPdf(String),
+
+  /// A downloaded document is not the size the server said it would be.
+  ///
+  /// The server's `Content-Length` (or `Content-Range` total, for a resumed download) is
+  /// compared with the bytes actually received, so truncated downloads are not stored.
+  #[error("Incomplete download: expected {expected} bytes but received {received}")]
+  IncompleteDownload {
+    /// The size the server announced
+    expected: u64,
+    /// The size that was received
+    received: u64,
+  },
+
+  /// An API response body could not be parsed.
+  ///
+  /// This occurs when a source responds successfully but the body is not valid as:
+  /// - JSON or XML, depending on the retriever's response format
+  /// - Text in its declared encoding (e.g., malformed UTF-16)
+  /// - A publication date in RFC 3339 format
+  ///
+  /// The `what` field names the content being parsed and `source` is the underlying
+  /// parser error.
+  #[error("Failed to parse {what}")]
+  Deserialize {
+    /// A description of the content that failed to parse (e.g., "JSON response")
+    what:   &'static str,
+    /// The error reported by the parser
+    source: Box<dyn std::error::Error + Send + Sync>,
+  },
+
   /// A SQLite operation failed.
   ///
   /// This wraps errors from the `rusqlite` crate, covering:
@@ -96,14 +179,14 @@ pub enum LearnerError {
   /// - Constraint violations
   /// - Schema errors
   /// - Type conversion errors
-  #[error(transparent)]
+  #[error("Database error")]
   Sqlite(#[from] rusqlite::Error),
 
   /// An async SQLite operation failed.
   ///
   /// This wraps errors from the `tokio-rusqlite` crate, covering
   /// async-specific failures in database operations.
-  #[error(transparent)]
+  #[error("Database error")]
   AsyncSqlite(#[from] tokio_rusqlite::Error),
 
   /// A file system operation failed.
@@ -112,14 +195,14 @@ pub enum LearnerError {
   /// - Creating the database file fails
   /// - Reading/writing to the filesystem fails
   /// - Permission errors occur
-  #[error(transparent)]
+  #[error("I/O error")]
   Path(#[from] std::io::Error),
 
   /// A numeric conversion failed, typically in database operations.
   ///
   /// This occurs when converting between different numeric types,
   /// usually when dealing with database column indices or sizes.
-  #[error(transparent)]
+  #[error("Numeric conversion failed")]
   ColumnOverflow(#[from] std::num::TryFromIntError),
 
   /// PDF parsing and processing errors from the lopdf library.
@@ -136,7 +219,7 @@ pub enum LearnerError {
   /// - Invalid stream encoding
   /// - Type mismatches when accessing PDF objects
   /// - Encrypted PDF files that require passwords
-  #[error(transparent)]
+  #[error("PDF error")]
   Lopdf(#[from] lopdf::Error),
 
   /// A model was not specified for the LLM request.
@@ -163,6 +246,14 @@ pub enum LearnerError {
   #[error("No messages were supplied to send to the LLM.")]
   LLMMissingMessage,
 
+  /// A builder was finished without setting one of its required fields.
+  ///
+  /// The parameter names the missing field, e.g. `source_identifier` when
+  /// [`PaperBuilder::build`](crate::resource::PaperBuilder::build) is called before
+  /// [`with_source_identifier`](crate::resource::PaperBuilder::with_source_identifier).
+  #[error("Missing required field `{0}`")]
+  MissingField(&'static str),
+
   /// Indicates an attempt to add a paper that already exists in the database.
   ///
   /// This error occurs during paper addition operations when the database
@@ -174,6 +265,17 @@ pub enum LearnerError {
   #[error("Tried to add a paper titled \"{0}\" that was already in the database.")]
   DatabaseDuplicatePaper(String),
 
+  /// A write was attempted through a database handle opened with
+  /// [`Database::open_readonly`](crate::database::Database::open_readonly).
+  #[error("The database was opened read-only, refusing to write to it")]
+  ReadOnlyDatabase,
+
+  /// A [`Query`](crate::database::Query) combined its criteria in a way that cannot match
+  /// sensibly, such as an empty group or a negated
+  /// [`QueryCriteria::All`](crate::database::QueryCriteria::All).
+  #[error("Invalid query: {0}")]
+  InvalidQuery(String),
+
   /// Multiple retriever configurations matched an identifier.
   ///
   /// This error occurs when an input identifier matches the patterns of
@@ -212,9 +314,16 @@ pub enum LearnerError {
   /// # Invalid TOML - wrong type
   /// database_path = true  # should be a string
   /// ```
-  #[error(transparent)]
+  #[error("Invalid TOML")]
   TomlDe(#[from] toml::de::Error),
 
+  /// Failed to serialize a value to TOML.
+  ///
+  /// This error occurs when writing configuration files, for example when
+  /// [`Config::save`](crate::Config::save) encounters a value TOML cannot represent.
+  #[error("Failed to write TOML")]
+  TomlSer(#[from] toml::ser::Error),
+
   /// General configuration error.
   ///
   /// This error represents various configuration-related issues that
@@ -248,7 +357,7 @@ pub enum LearnerError {
   /// - Type mismatches during deserialization
   /// - Missing required fields
   /// - Numeric conversion failures
-  #[error(transparent)]
+  #[error("JSON error")]
   SerdeJson(#[from] serde_json::Error),
 
   /// Indicates a resource failed to serialize into a valid structure.
@@ -264,4 +373,49 @@ pub enum LearnerError {
   /// structure that can be properly stored and queried in the database.
   #[error("A resource must serialize into a flat Rust struct or JSON object.")]
   InvalidResource,
+
+  /// A resource's field does not satisfy its definition in a
+  /// [`ResourceConfig`](crate::resource::ResourceConfig), see
+  /// [`ResourceConfig::validate`](crate::resource::ResourceConfig::validate).
+  #[error("Invalid field `{field}`: {reason}")]
+  InvalidField {
+    /// Path of the field, with nested fields joined by `.`
+    field:  String,
+    /// What the value violates
+    reason: String,
+  },
+}
+
+impl LearnerError {
+  /// Creates a [`LearnerError::Deserialize`] for content described by `what`.
+  pub(crate) fn deserialize(
+    what: &'static str,
+    error: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+  ) -> Self {
+    Self::Deserialize { what, source: error.into() }
+  }
+}
+
+/// Renders `error` followed by each of its sources, separated by `: `.
+///
+/// This is how errors are shown to users and recorded in the database, since the message of a
+/// wrapping [`LearnerError`] leaves the details to its source.
+///
+/// # Examples
+///
+/// ```
+/// use learner::error::{report, LearnerError};
+///
+/// let error = LearnerError::from(std::io::Error::other("disk full"));
+/// assert_eq!(error.to_string(), "I/O error");
+/// assert_eq!(report(&error), "I/O error: disk full");
+/// ```
+pub fn report(error: &dyn std::error::Error) -> String {
+  let mut rendered = error.to_string();
+  let mut source = error.source();
+  while let Some(cause) = source {
+    rendered.push_str(&format!(": {cause}"));
+    source = cause.source();
+  }
+  rendered
 }
@@ -20,7 +20,263 @@
 //! // Custom length limit
 //! let formatted = format::format_title(title, Some(20));
 //! assert_eq!(formatted, "this_is_a_very_long");
+//!
+//! // Filesystem-safe names that keep the original text
+//! let sanitized = format::sanitize_filename("2016/260", 50);
+//! assert_eq!(sanitized, "2016_260");
+//!
+//! // One canonical spelling per DOI
+//! let doi = format::normalize_doi("https://doi.org/10.1145/ABC");
+//! assert_eq!(doi.as_deref(), Some("10.1145/abc"));
 //! ```
+//!
+//! [`detect_language`] guesses which language an abstract is written in.
+//!
+//! [`PaperTemplate`] renders a paper as a line of text from a template such as
+//! `"{year} {first_author} — {title}"`, for custom listings and reports, and [`bibtex`]
+//! renders it as a BibTeX entry for citing.
+
+use crate::resource::Paper;
+
+/// Characters that are not allowed in filenames on at least one common platform.
+const RESERVED_FILENAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Letters that carry no combining mark under Unicode decomposition, with their ASCII spelling.
+const FOLDED_LETTERS: &[(char, &str)] = &[
+  ('ß', "ss"),
+  ('æ', "ae"),
+  ('œ', "oe"),
+  ('ø', "o"),
+  ('ł', "l"),
+  ('đ', "d"),
+  ('ð', "d"),
+  ('þ', "th"),
+  ('ı', "i"),
+];
+
+/// Folds case and diacritics away so that e.g. "Müller" and "muller" compare equal.
+///
+/// The text is lowercased and canonically decomposed, then combining marks are dropped and a
+/// few letters that have no decomposition (such as `ß` and `ø`) are spelled out in ASCII.
+/// Other characters, including non-Latin scripts, are kept as they are. This is meant for
+/// matching only; the original text should be kept for display.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// assert_eq!(format::fold_diacritics("Müller"), "muller");
+/// assert_eq!(format::fold_diacritics("Erdős"), format::fold_diacritics("Erdos"));
+/// ```
+pub fn fold_diacritics(text: &str) -> String {
+  const NFD: icu_normalizer::DecomposingNormalizer =
+    icu_normalizer::DecomposingNormalizer::new_nfd();
+
+  let mut folded = String::with_capacity(text.len());
+  for c in NFD.normalize(&text.to_lowercase()).chars() {
+    if let Some((_, ascii)) = FOLDED_LETTERS.iter().find(|(letter, _)| *letter == c) {
+      folded.push_str(ascii);
+    } else if !is_combining_mark(c) {
+      folded.push(c);
+    }
+  }
+  folded
+}
+
+/// Whether `c` is in one of the blocks of combining diacritical marks.
+fn is_combining_mark(c: char) -> bool {
+  matches!(c, '\u{0300}'..='\u{036F}'
+    | '\u{1AB0}'..='\u{1AFF}'
+    | '\u{1DC0}'..='\u{1DFF}'
+    | '\u{20D0}'..='\u{20FF}'
+    | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Resolver and scheme prefixes a DOI may be written with, in lowercase.
+const DOI_PREFIXES: &[&str] =
+  &["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/", "doi:"];
+
+/// Reduces a DOI to its canonical form so different spellings compare equal.
+///
+/// DOIs are case-insensitive, and are often written as resolver URLs or with a `doi:` scheme.
+/// The result is lowercased with any such prefix removed. Returns `None` if what remains does
+/// not look like a DOI (a `10.` prefix followed by a `/` and a suffix).
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// let canonical = Some("10.1145/1327452.1327492".to_string());
+/// assert_eq!(format::normalize_doi("10.1145/1327452.1327492"), canonical);
+/// assert_eq!(format::normalize_doi("https://doi.org/10.1145/1327452.1327492"), canonical);
+/// assert_eq!(format::normalize_doi("not a doi"), None);
+/// ```
+pub fn normalize_doi(doi: &str) -> Option<String> {
+  let doi = doi.trim().to_lowercase();
+  let doi = DOI_PREFIXES
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .map(str::trim_start)
+    .unwrap_or(&doi);
+
+  let (prefix, suffix) = doi.split_once('/')?;
+  (prefix.starts_with("10.") && !suffix.is_empty()).then(|| doi.to_string())
+}
+
+/// Checks that `url` is an absolute `http` or `https` URL with a host, as PDF and landing page
+/// URLs must be to be fetched.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// assert!(format::is_web_url("https://arxiv.org/pdf/2301.07041"));
+/// assert!(!format::is_web_url("arxiv.org/pdf/2301.07041"));
+/// assert!(!format::is_web_url("file:///tmp/paper.pdf"));
+/// ```
+pub fn is_web_url(url: &str) -> bool {
+  reqwest::Url::parse(url.trim())
+    .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+}
+
+/// Frequent function words of the Latin-script languages [`detect_language`] tells apart,
+/// keyed by ISO 639-1 code.
+const LANGUAGE_STOPWORDS: &[(&str, &[&str])] = &[
+  ("en", &[
+    "the", "of", "and", "to", "in", "is", "that", "for", "we", "this", "with", "are", "on", "by",
+    "an", "be", "as", "which", "from", "our", "these", "it", "can", "not",
+  ]),
+  ("de", &[
+    "der", "die", "und", "das", "ist", "nicht", "mit", "von", "den", "ein", "eine", "wir", "auf",
+    "für", "dem", "des", "sich", "zu", "im", "werden", "wird", "auch", "bei", "durch",
+  ]),
+  ("fr", &[
+    "le", "la", "les", "des", "et", "est", "une", "un", "du", "dans", "pour", "nous", "que", "qui",
+    "sur", "par", "au", "avec", "ce", "cette", "sont", "pas", "aux", "ces",
+  ]),
+  ("es", &[
+    "el", "los", "las", "del", "una", "y", "es", "que", "en", "por", "para", "con", "se", "su",
+    "como", "este", "esta", "sobre", "más", "al", "son", "lo", "entre", "sus",
+  ]),
+  ("it", &[
+    "il", "gli", "della", "delle", "dei", "di", "che", "è", "una", "per", "sono", "con", "nel",
+    "nella", "questo", "questa", "anche", "come", "degli", "alla", "tra", "non", "essere", "si",
+  ]),
+  ("pt", &[
+    "os", "as", "do", "da", "dos", "das", "uma", "um", "não", "em", "que", "para", "com", "é",
+    "por", "mais", "como", "este", "esta", "são", "nos", "foi", "ao", "também",
+  ]),
+  ("nl", &[
+    "de", "het", "een", "van", "en", "is", "dat", "niet", "met", "voor", "op", "zijn", "wij",
+    "deze", "ook", "worden", "wordt", "door", "bij", "naar", "aan", "om", "te", "dit",
+  ]),
+];
+
+/// Fewest words [`detect_language`] will guess the language of a Latin-script text from.
+const MIN_LANGUAGE_WORDS: usize = 8;
+
+/// Fewest function words of one language a Latin-script text needs to be tagged with it.
+const MIN_STOPWORD_HITS: usize = 3;
+
+/// Guesses the language of `text`, returning its ISO 639-1 code (e.g. `"en"`).
+///
+/// This is a lightweight heuristic meant for abstracts, not a full language identifier. Text
+/// mostly written in a non-Latin script is tagged by that script (Cyrillic as `ru`, Greek as
+/// `el`, Arabic as `ar`, Hebrew as `he`, Hangul as `ko`, kana as `ja`, and other Han text as
+/// `zh`). Latin-script text is scored against the most frequent function words of English,
+/// German, French, Spanish, Italian, Portuguese and Dutch. Returns `None` for text that is too
+/// short or matches no language clearly.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// let abstract_text = "We propose a new architecture for the translation of text, and we show \
+///                      that it is faster than the models in use today.";
+/// assert_eq!(format::detect_language(abstract_text), Some("en"));
+/// assert_eq!(format::detect_language("Attention"), None);
+/// ```
+pub fn detect_language(text: &str) -> Option<&'static str> {
+  let mut latin = 0;
+  let mut scripts: [(&'static str, usize); 7] =
+    [("ru", 0), ("el", 0), ("ar", 0), ("he", 0), ("ko", 0), ("ja", 0), ("zh", 0)];
+  for c in text.chars().filter(|c| c.is_alphabetic()) {
+    let script = match c {
+      'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => {
+        latin += 1;
+        continue;
+      },
+      '\u{0400}'..='\u{04FF}' => 0,
+      '\u{0370}'..='\u{03FF}' => 1,
+      '\u{0600}'..='\u{06FF}' => 2,
+      '\u{0590}'..='\u{05FF}' => 3,
+      '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => 4,
+      '\u{3040}'..='\u{30FF}' => 5,
+      '\u{4E00}'..='\u{9FFF}' => 6,
+      _ => continue,
+    };
+    scripts[script].1 += 1;
+  }
+
+  // Japanese mixes kana with Han characters, so any real share of kana means Japanese
+  let (_, kana) = scripts[5];
+  if kana > 0 && kana * 5 >= scripts[6].1 {
+    scripts[5].1 += scripts[6].1;
+    scripts[6].1 = 0;
+  }
+  let (code, count) = scripts.iter().copied().max_by_key(|(_, count)| *count)?;
+  if count > latin {
+    return Some(code);
+  }
+
+  let text = text.to_lowercase();
+  let words: Vec<&str> =
+    text.split(|c: char| !c.is_alphabetic()).filter(|word| !word.is_empty()).collect();
+  if words.len() < MIN_LANGUAGE_WORDS {
+    return None;
+  }
+
+  let mut scores: Vec<(&'static str, usize)> = LANGUAGE_STOPWORDS
+    .iter()
+    .map(|(code, stopwords)| (*code, words.iter().filter(|word| stopwords.contains(word)).count()))
+    .collect();
+  scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+  let (code, best) = scores[0];
+  // A clear winner covers a fair share of the words and beats the runner-up comfortably
+  (best >= MIN_STOPWORD_HITS && best * 10 >= words.len() && best > scores[1].1 * 3 / 2)
+    .then_some(code)
+}
+
+/// Makes an arbitrary string safe to use as a single path component.
+///
+/// Path separators, characters reserved on Windows, and control characters are replaced with
+/// underscores. The result is then truncated to at most `max_bytes` bytes, always cutting on a
+/// character boundary so multibyte text is never split mid-codepoint.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// assert_eq!(format::sanitize_filename("a/b\\c", 50), "a_b_c");
+/// // "é" is two bytes, so it is dropped rather than split
+/// assert_eq!(format::sanitize_filename("café", 4), "caf");
+/// ```
+pub fn sanitize_filename(name: &str, max_bytes: usize) -> String {
+  let mut sanitized = String::with_capacity(name.len().min(max_bytes));
+  for c in name.chars() {
+    let c = if c.is_control() || RESERVED_FILENAME_CHARS.contains(&c) { '_' } else { c };
+    if sanitized.len() + c.len_utf8() > max_bytes {
+      break;
+    }
+    sanitized.push(c);
+  }
+  sanitized
+}
 
 /// Formats a title string for use as a filename or identifier.
 ///
@@ -28,13 +284,15 @@
 /// use as filenames or identifiers:
 /// - Converts the text to lowercase
 /// - Replaces whitespace (including multiple spaces) with single underscores
-/// - Truncates to a maximum length while preserving word boundaries
+/// - Truncates to a maximum length (in bytes) while preserving word boundaries
+/// - Passes the result through [`sanitize_filename`], which also cuts overlong single words on a
+///   character boundary
 ///
 /// # Arguments
 ///
 /// * `title` - The input title string to format
-/// * `max_length` - Optional maximum length limit. If `None`, defaults to 50 characters. The
-///   function will truncate at word boundaries to stay within this limit.
+/// * `max_length` - Optional maximum length limit in bytes. If `None`, defaults to 50. The function
+///   will truncate at word boundaries to stay within this limit.
 ///
 /// # Returns
 ///
@@ -72,7 +330,7 @@ pub fn format_title(title: &str, max_length: Option<usize>) -> String {
 
   // If the string is already within length limit, return it
   if formatted.len() <= max_length {
-    return formatted;
+    return sanitize_filename(&formatted, max_length);
   }
 
   // Split into words
@@ -96,11 +354,250 @@ pub fn format_title(title: &str, max_length: Option<usize>) -> String {
     result.push_str(word);
   }
 
-  result
+  // A single word longer than the limit (common for CJK text) is cut instead of dropped
+  if result.is_empty() {
+    return sanitize_filename(&formatted, max_length);
+  }
+
+  sanitize_filename(&result, max_length)
+}
+
+/// Placeholders understood by [`PaperTemplate`].
+///
+/// - `title`, `source`, `identifier`: the paper's title, source and source identifier
+/// - `authors`: every author's name, separated by commas
+/// - `first_author`: the first author's name
+/// - `year`, `date`: the publication year, or date as `YYYY-MM-DD`
+/// - `doi`, `version`, `category`, `pdf_url`: the optional fields of the same name (`category` is
+///   the primary category)
+/// - `keywords`: the keywords, separated by commas
+/// - `url`: the landing page at the paper's source, see [`Paper::source_url`]
+pub const PAPER_PLACEHOLDERS: &[&str] = &[
+  "title",
+  "authors",
+  "first_author",
+  "year",
+  "date",
+  "source",
+  "identifier",
+  "doi",
+  "version",
+  "category",
+  "keywords",
+  "pdf_url",
+  "url",
+];
+
+/// One piece of a parsed [`PaperTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+  /// Text copied to the output as is
+  Literal(String),
+  /// A `{name}` placeholder, replaced by the named field
+  Placeholder(String),
 }
+
+/// A template that renders a [`Paper`] as a line of text.
+///
+/// Placeholders are written `{name}` using the names in [`PAPER_PLACEHOLDERS`], and `{{` and
+/// `}}` stand for literal braces. Fields a paper lacks, such as a missing DOI, render empty, as
+/// do placeholders with unknown names; [`PaperTemplate::unknown_placeholders`] lists the latter
+/// so callers can warn about them.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::{format::PaperTemplate, resource::Paper};
+/// let paper = Paper::builder()
+///   .with_title("Notes on Things")
+///   .with_source("local")
+///   .with_source_identifier("notes")
+///   .build()?;
+///
+/// let template = PaperTemplate::parse("{source}:{identifier} {title}{typo}");
+/// assert_eq!(template.render(&paper), "local:notes Notes on Things");
+/// assert_eq!(template.unknown_placeholders(), ["typo"]);
+/// # Ok::<(), learner::error::LearnerError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperTemplate {
+  /// The literal text and placeholders, in order
+  segments: Vec<Segment>,
+}
+
+impl PaperTemplate {
+  /// Parses a template string.
+  ///
+  /// Parsing never fails: a `{` without a closing `}` and a lone `}` are kept as literal text.
+  pub fn parse(template: &str) -> Self {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+      rest = &rest[c.len_utf8()..];
+      match c {
+        '{' if rest.starts_with('{') => {
+          literal.push('{');
+          rest = &rest[1..];
+        },
+        '}' if rest.starts_with('}') => {
+          literal.push('}');
+          rest = &rest[1..];
+        },
+        '{' => match rest.find('}') {
+          Some(end) => {
+            if !literal.is_empty() {
+              segments.push(Segment::Literal(std::mem::take(&mut literal)));
+            }
+            segments.push(Segment::Placeholder(rest[..end].trim().to_string()));
+            rest = &rest[end + 1..];
+          },
+          None => literal.push('{'),
+        },
+        c => literal.push(c),
+      }
+    }
+    if !literal.is_empty() {
+      segments.push(Segment::Literal(literal));
+    }
+    Self { segments }
+  }
+
+  /// Returns the placeholders that are not in [`PAPER_PLACEHOLDERS`], each once, in the order
+  /// they first appear.
+  pub fn unknown_placeholders(&self) -> Vec<&str> {
+    let mut unknown = Vec::new();
+    for segment in &self.segments {
+      if let Segment::Placeholder(name) = segment {
+        if !PAPER_PLACEHOLDERS.contains(&name.as_str()) && !unknown.contains(&name.as_str()) {
+          unknown.push(name.as_str());
+        }
+      }
+    }
+    unknown
+  }
+
+  /// Renders the template for `paper`.
+  pub fn render(&self, paper: &Paper) -> String {
+    let mut rendered = String::new();
+    for segment in &self.segments {
+      match segment {
+        Segment::Literal(text) => rendered.push_str(text),
+        Segment::Placeholder(name) => rendered.push_str(&placeholder_value(paper, name)),
+      }
+    }
+    rendered
+  }
+}
+
+/// Returns the value a placeholder renders to, empty when the paper lacks the field or the
+/// placeholder is unknown.
+fn placeholder_value(paper: &Paper, name: &str) -> String {
+  let names = || paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ");
+  match name {
+    "title" => paper.title.clone(),
+    "authors" => names(),
+    "first_author" => paper.authors.first().map(|a| a.name.clone()).unwrap_or_default(),
+    "year" => paper.publication_date.year().to_string(),
+    "date" => paper.publication_date.to_string(),
+    "source" => paper.source.clone(),
+    "identifier" => paper.source_identifier.clone(),
+    "doi" => paper.doi.clone().unwrap_or_default(),
+    "version" => paper.version.clone().unwrap_or_default(),
+    "category" => paper.primary_category.clone().unwrap_or_default(),
+    "keywords" => paper.keywords.join(", "),
+    "pdf_url" => paper.pdf_url.clone().unwrap_or_default(),
+    "url" => paper.source_url().map(|url| url.to_string()).unwrap_or_default(),
+    _ => String::new(),
+  }
+}
+
+/// Renders `paper` as a BibTeX `@misc` entry.
+///
+/// The citation key is the first author's last name, the publication year and the first
+/// word of the title, e.g. `vaswani2017attention`. arXiv papers also get `eprint` and
+/// `archivePrefix` fields. Characters with a special meaning to LaTeX are escaped.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::{format, resource::Paper};
+/// let paper = Paper::builder()
+///   .with_title("Notes on Things")
+///   .with_source("local")
+///   .with_source_identifier("notes")
+///   .with_publication_date(chrono::DateTime::UNIX_EPOCH)
+///   .build()?;
+///
+/// assert!(format::bibtex(&paper).starts_with("@misc{1970notes,\n  title = {Notes on Things},"));
+/// # Ok::<(), learner::error::LearnerError>(())
+/// ```
+pub fn bibtex(paper: &Paper) -> String {
+  let key_part = |text: &str| -> String {
+    fold_diacritics(text).chars().filter(char::is_ascii_alphanumeric).collect()
+  };
+  let last_name = paper
+    .authors
+    .first()
+    .and_then(|author| author.name.split_whitespace().last())
+    .map(key_part)
+    .unwrap_or_default();
+  let first_word =
+    paper.title.split_whitespace().map(key_part).find(|word| !word.is_empty()).unwrap_or_default();
+  let year = paper.publication_date.year();
+
+  let mut fields = vec![("title", paper.title.clone())];
+  if !paper.authors.is_empty() {
+    let names: Vec<_> = paper.authors.iter().map(|author| author.name.as_str()).collect();
+    fields.push(("author", names.join(" and ")));
+  }
+  fields.push(("year", year.to_string()));
+  if let Some(doi) = &paper.doi {
+    fields.push(("doi", doi.clone()));
+  }
+  if paper.source == "arxiv" {
+    fields.push(("eprint", paper.source_identifier.clone()));
+    fields.push(("archivePrefix", "arXiv".to_string()));
+  }
+  if let Some(url) = paper.source_url() {
+    fields.push(("url", url.to_string()));
+  }
+
+  let mut entry = format!("@misc{{{last_name}{year}{first_word},\n");
+  for (name, value) in fields {
+    entry.push_str(&format!("  {name} = {{{}}},\n", escape_latex(&value)));
+  }
+  entry.push('}');
+  entry
+}
+
+/// Escapes the ten characters LaTeX treats specially inside a BibTeX field.
+///
+/// Backslash, tilde and caret have no backslash escape, so they become text commands. Each
+/// character is replaced once, so the backslashes added by an escape are never escaped again.
+fn escape_latex(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '\\' => escaped.push_str(r"\textbackslash{}"),
+      '~' => escaped.push_str(r"\textasciitilde{}"),
+      '^' => escaped.push_str(r"\textasciicircum{}"),
+      '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+        escaped.push('\\');
+        escaped.push(c);
+      },
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
 #[cfg(test)]
 mod tests {
+  use chrono::{TimeZone, Utc};
+
   use super::*;
+  use crate::resource::Author;
 
   #[test]
   fn test_format_title() {
@@ -119,4 +616,207 @@ mod tests {
     assert_eq!(format_title("No    Extra    Spaces", None), "no_extra_spaces");
     assert_eq!(format_title("Title with: </weird things\\>", None), "title_with_weird_things");
   }
+
+  #[test]
+  fn test_format_title_unicode() {
+    // Emoji are dropped
+    assert_eq!(format_title("Rocket 🚀 Science", None), "rocket_science");
+
+    // CJK text is kept and cut on a character boundary
+    let formatted = format_title("量子计算的基础研究与应用", Some(10));
+    assert!(formatted.len() <= 10);
+    assert_eq!(formatted, "量子计");
+
+    // Path separators never survive
+    let formatted = format_title("Input/Output: A \\ Study", None);
+    assert!(!formatted.contains('/') && !formatted.contains('\\'));
+  }
+
+  #[test]
+  fn test_normalize_doi() {
+    let canonical = Some("10.1145/1327452.1327492".to_string());
+    for doi in [
+      "10.1145/1327452.1327492",
+      "https://doi.org/10.1145/1327452.1327492",
+      "http://dx.doi.org/10.1145/1327452.1327492",
+      "HTTPS://DOI.ORG/10.1145/1327452.1327492",
+      "doi: 10.1145/1327452.1327492",
+      "  10.1145/1327452.1327492\n",
+    ] {
+      assert_eq!(normalize_doi(doi), canonical, "failed for {doi:?}");
+    }
+    assert_eq!(normalize_doi("10.1000/ABC.Def"), Some("10.1000/abc.def".to_string()));
+
+    assert_eq!(normalize_doi(""), None);
+    assert_eq!(normalize_doi("2301.07041"), None);
+    assert_eq!(normalize_doi("https://doi.org/"), None);
+    assert_eq!(normalize_doi("10.1145/"), None);
+  }
+
+  #[test]
+  fn test_fold_diacritics() {
+    assert_eq!(fold_diacritics("Müller"), "muller");
+    assert_eq!(fold_diacritics("MÜLLER"), "muller");
+    assert_eq!(fold_diacritics("Gödel, Kurt"), "godel, kurt");
+    assert_eq!(fold_diacritics("François Ångström"), "francois angstrom");
+    assert_eq!(fold_diacritics("Paweł Øster Straße"), "pawel oster strasse");
+
+    // Text without diacritics only changes case, and other scripts are left alone
+    assert_eq!(fold_diacritics("Jane Doe"), "jane doe");
+    assert_eq!(fold_diacritics("李华"), "李华");
+  }
+
+  #[test]
+  fn test_detect_language() {
+    let english = "The dominant sequence transduction models are based on complex recurrent or \
+                   convolutional neural networks in an encoder-decoder configuration. We propose \
+                   a new simple network architecture, the Transformer, based solely on attention \
+                   mechanisms.";
+    assert_eq!(detect_language(english), Some("en"));
+
+    let german = "Wir untersuchen die Stabilität der Lösungen und zeigen, dass sich das Verfahren \
+                  auch für große Systeme eignet, die mit den bisherigen Methoden nicht behandelt \
+                  werden können.";
+    assert_eq!(detect_language(german), Some("de"));
+
+    let french = "Nous étudions la stabilité des solutions et nous montrons que la méthode est \
+                  efficace pour les grands systèmes qui ne sont pas traités par les approches \
+                  existantes.";
+    assert_eq!(detect_language(french), Some("fr"));
+
+    assert_eq!(
+      detect_language("Мы изучаем устойчивость решений нелинейных уравнений."),
+      Some("ru")
+    );
+    assert_eq!(detect_language("本文研究了非线性方程解的稳定性。"), Some("zh"));
+    assert_eq!(detect_language("本論文では、非線形方程式の解の安定性を調べる。"), Some("ja"));
+
+    // Too little text to tell
+    assert_eq!(detect_language(""), None);
+    assert_eq!(detect_language("A much longer abstract than the arXiv copy has"), None);
+    assert_eq!(detect_language("Attention Is All You Need"), None);
+  }
+
+  #[test]
+  fn test_sanitize_filename() {
+    assert_eq!(sanitize_filename("a/b\\c", 50), "a_b_c");
+    assert_eq!(sanitize_filename("tab\there\n", 50), "tab_here_");
+    assert_eq!(sanitize_filename("what?<is>|this*", 50), "what__is__this_");
+
+    // Output stays within the byte limit and is valid UTF-8 at every cut
+    let title = "🚀量子 computing/research";
+    for max_bytes in 0..title.len() {
+      let sanitized = sanitize_filename(title, max_bytes);
+      assert!(sanitized.len() <= max_bytes);
+      assert!(title.replace('/', "_").starts_with(&sanitized));
+    }
+  }
+
+  #[test]
+  fn test_paper_template_with_authors() {
+    let author = |name: &str| Author {
+      name:        name.to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    };
+    let paper = Paper::builder()
+      .with_title("Attention Is All You Need")
+      .with_source("arxiv")
+      .with_source_identifier("1706.03762")
+      .with_author(author("Ashish Vaswani"))
+      .with_author(author("Noam Shazeer"))
+      .with_publication_date(Utc.with_ymd_and_hms(2017, 6, 12, 0, 0, 0).unwrap())
+      .build()
+      .unwrap();
+
+    let template = PaperTemplate::parse("{year} {first_author} — {title}");
+    assert_eq!(template.render(&paper), "2017 Ashish Vaswani — Attention Is All You Need");
+    assert!(template.unknown_placeholders().is_empty());
+
+    let template = PaperTemplate::parse("{authors} ({ date }) {url}");
+    assert_eq!(
+      template.render(&paper),
+      "Ashish Vaswani, Noam Shazeer (2017-06-12) https://arxiv.org/abs/1706.03762"
+    );
+  }
+
+  #[test]
+  fn test_paper_template_edge_cases() {
+    let paper = Paper::builder()
+      .with_title("T")
+      .with_source("s")
+      .with_source_identifier("i")
+      .build()
+      .unwrap();
+
+    // Missing fields and unknown placeholders render empty, braces can be escaped
+    let template = PaperTemplate::parse("{{{doi}}}{nope}|{first_author}|{nope}{unclosed");
+    assert_eq!(template.render(&paper), "{}||{unclosed");
+    assert_eq!(template.unknown_placeholders(), ["nope"]);
+  }
+
+  #[test]
+  fn test_bibtex() {
+    let paper = Paper::builder()
+      .with_title("Attention Is All You Need")
+      .with_source("arxiv")
+      .with_source_identifier("1706.03762")
+      .with_doi("10.48550/arXiv.1706.03762")
+      .with_publication_date(Utc.with_ymd_and_hms(2017, 6, 12, 0, 0, 0).unwrap())
+      .with_authors(
+        ["Ashish Vaswani", "Noam Shazeer"]
+          .into_iter()
+          .map(|name| Author {
+            name:        name.to_string(),
+            affiliation: None,
+            email:       None,
+            orcid:       None,
+          })
+          .collect(),
+      )
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      bibtex(&paper),
+      [
+        "@misc{vaswani2017attention,",
+        "  title = {Attention Is All You Need},",
+        "  author = {Ashish Vaswani and Noam Shazeer},",
+        "  year = {2017},",
+        "  doi = {10.48550/arxiv.1706.03762},",
+        "  eprint = {1706.03762},",
+        "  archivePrefix = {arXiv},",
+        "  url = {https://arxiv.org/abs/1706.03762},",
+        "}",
+      ]
+      .join("\n")
+    );
+
+    let paper = Paper::builder()
+      .with_title(r"R&D: 100% of {Things} \ x^2 ~ $y$")
+      .with_source("local")
+      .with_source_identifier("notes")
+      .with_publication_date(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap())
+      .with_author(Author {
+        name:        "Zoë Ångström".to_string(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
+      })
+      .build()
+      .unwrap();
+    assert_eq!(
+      bibtex(&paper),
+      [
+        "@misc{angstrom2020rd,",
+        r"  title = {R\&D: 100\% of \{Things\} \textbackslash{} x\textasciicircum{}2 \textasciitilde{} \$y\$},",
+        "  author = {Zoë Ångström},",
+        "  year = {2020},",
+        "}",
+      ]
+      .join("\n")
+    );
+  }
 }
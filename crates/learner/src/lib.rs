@@ -90,6 +90,11 @@
 //!   - Response parsing and validation
 //!   - Error handling and retry logic
 //!
+//! - [`storage`]: Backends documents are written to
+//!   - Filesystem storage by default
+//!   - In-memory storage for tests
+//!   - HTTP object stores with the `object-store` feature
+//!
 //! - [`retriever`]: Configurable paper retrieval system
 //!   - Automatic source detection
 //!   - XML and JSON response handling
@@ -128,6 +133,17 @@
 //! database_path = "~/.local/share/learner/papers.db"
 //! storage_path = "~/Documents/papers"
 //! retrievers_path = "~/.learner/retrievers"
+//! # Optional, copied citations are BibTeX unless set
+//! citation_format = "{authors} ({year}). {title}. {url}"
+//! # Optional, how many downloads may run at once
+//! max_concurrency = 4
+//! # Optional, sent to APIs by retrievers that don't set their own
+//! user_agent = "learner (mailto:me@example.com)"
+//!
+//! # Optional, see `DatabaseOptions`
+//! [database]
+//! wal = true
+//! busy_timeout_ms = 5000
 //! ```
 //!
 //! ```no_run
@@ -148,8 +164,10 @@
 #![feature(str_from_utf16_endian)]
 
 use std::{
+  collections::BTreeMap,
   fmt::Display,
   path::{Path, PathBuf},
+  time::Duration,
 };
 
 use async_trait::async_trait;
@@ -170,12 +188,15 @@ pub mod format;
 pub mod llm;
 pub mod pdf;
 pub mod resource;
+pub mod storage;
 
 use crate::{
   database::*,
   error::*,
-  resource::{Author, Paper},
+  pdf::PDFContentBuilder,
+  resource::{Author, Paper, PartialDate},
   retriever::*,
+  storage::{FileStorage, ObjectStoreOptions, Storage},
 };
 
 /// ArXiv default configuration
@@ -215,8 +236,10 @@ pub const IACR_CONFIG: &str = include_str!("../config/retrievers/iacr.toml");
 /// ```
 pub mod prelude {
   pub use crate::{
-    database::DatabaseInstruction, error::LearnerError, resource::Resource,
-    retriever::ResponseProcessor,
+    database::DatabaseInstruction,
+    error::LearnerError,
+    resource::Resource,
+    retriever::{ResourceRetriever, ResponseProcessor},
   };
 }
 
@@ -225,6 +248,9 @@ pub mod prelude {
 /// Manages paths for database, document storage, and retriever configurations.
 /// The configuration can be loaded from disk or created programmatically.
 ///
+/// Paths read from TOML or passed to the `with_*` setters have a leading `~` expanded to the
+/// home directory and `$VAR`/`${VAR}` (plus `%VAR%` on Windows) replaced by environment values.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -242,16 +268,78 @@ pub mod prelude {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
   /// The path to store the database.
-  #[serde(default = "Database::default_path")]
+  #[serde(default = "Database::default_path", deserialize_with = "deserialize_expanded_path")]
   pub database_path: PathBuf,
 
   /// The path to store associated documents and files.
-  #[serde(default = "Database::default_storage_path")]
+  #[serde(
+    default = "Database::default_storage_path",
+    deserialize_with = "deserialize_expanded_path"
+  )]
   pub storage_path: PathBuf,
 
   /// The path to load retriever configs from.
-  #[serde(default = "Config::default_retrievers_path")]
+  #[serde(
+    default = "Config::default_retrievers_path",
+    deserialize_with = "deserialize_expanded_path"
+  )]
   pub retrievers_path: PathBuf,
+
+  /// Whether to refuse all network access and only work with the local database.
+  #[serde(default)]
+  pub offline: bool,
+
+  /// Connection settings for the database, from the `[database]` table.
+  #[serde(default)]
+  pub database: DatabaseOptions,
+
+  /// How many downloads may be in flight at once, see [`Database::set_max_concurrency`].
+  #[serde(default = "Config::default_max_concurrency")]
+  pub max_concurrency: usize,
+
+  /// Template for copied citations, see [`format::PaperTemplate`]; BibTeX from
+  /// [`format::bibtex`] when unset.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub citation_format: Option<String>,
+
+  /// `User-Agent` for retriever requests, see [`Retriever::with_user_agent`]; a retriever's own
+  /// [`user_agent`](retriever::RetrieverConfig::user_agent) takes precedence.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub user_agent: Option<String>,
+
+  /// Named libraries that can be selected with [`Config::with_profile`].
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub profiles: BTreeMap<String, Profile>,
+
+  /// Object store to keep documents in instead of `storage_path`, from the `[object_store]`
+  /// table.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub object_store: Option<ObjectStoreOptions>,
+}
+
+/// A named library in the `[profiles]` section of `config.toml`.
+///
+/// Each profile has its own database. Paths it leaves out fall back to the top-level
+/// configuration, so profiles can share document storage or retriever configs if desired.
+///
+/// # Examples
+///
+/// ```toml
+/// [profiles.work]
+/// database_path = "~/work/learner.db"
+/// storage_path = "~/work/papers"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+  /// The path to store this profile's database.
+  pub database_path:   PathBuf,
+  /// The path to store this profile's documents, if different from the top-level one.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub storage_path:    Option<PathBuf>,
+  /// The path to load this profile's retriever configs from, if different from the top-level
+  /// one.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub retrievers_path: Option<PathBuf>,
 }
 
 // TODO: We should really let the database storage path be set prior to opening. We need a slightly
@@ -285,6 +373,92 @@ pub struct Learner {
   pub retriever: Retriever,
 }
 
+/// Where a paper returned by [`Learner::get_paper`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperOrigin {
+  /// The paper was already stored in the local database
+  Database,
+  /// The paper was retrieved from its remote source and then added to the database
+  Network,
+}
+
+/// What [`Learner::import_directory`] did with a single PDF.
+#[derive(Debug)]
+pub enum ImportOutcome {
+  /// A DOI found in the PDF was resolved through the DOI retriever and the paper stored
+  Resolved(Paper),
+  /// No DOI could be resolved, so a minimal paper titled after the file was stored
+  Minimal(Paper),
+  /// The PDF could not be imported
+  Failed(LearnerError),
+}
+
+/// A stored URL checked by [`Learner::verify_links`].
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+  /// The paper the URL belongs to
+  pub paper:  Paper,
+  /// The paper's `pdf_url` or its [`source_url`](Paper::source_url)
+  pub url:    String,
+  /// What the server said about the URL
+  pub status: LinkStatus,
+}
+
+impl LinkCheck {
+  /// Returns whether the URL still resolves.
+  pub fn is_live(&self) -> bool { matches!(self.status, LinkStatus::Live(_)) }
+}
+
+/// Whether a URL checked by [`Learner::verify_links`] still resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkStatus {
+  /// The server answered with a success status, after following redirects
+  Live(u16),
+  /// The server answered with an error status, e.g. `404`
+  Dead(u16),
+  /// The server could not be reached or did not answer in time, with the error and its causes
+  Unreachable(String),
+}
+
+/// How long [`Learner::verify_links`] waits for each URL.
+const LINK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Name of the paper list inside a bundle written by [`Learner::export_library`].
+pub const LIBRARY_FILE: &str = "library.json";
+
+/// Directory inside a library bundle that holds the exported documents, if they were included.
+pub const LIBRARY_DOCUMENTS_DIR: &str = "documents";
+
+/// The contents of [`LIBRARY_FILE`].
+#[derive(Debug, Serialize, Deserialize)]
+struct LibraryBundle {
+  /// Every exported paper
+  papers: Vec<BundledPaper>,
+}
+
+/// A paper in a [`LibraryBundle`] along with a reference to its document.
+#[derive(Debug, Serialize, Deserialize)]
+struct BundledPaper {
+  /// The paper's metadata in the form written by [`Paper::to_json`]
+  #[serde(flatten)]
+  paper:    serde_json::Value,
+  /// Filename of the paper's stored document, present if it was included in the bundle
+  #[serde(default)]
+  document: Option<String>,
+  /// Whether the paper was archived (see [`Remove`]) when exported
+  #[serde(default)]
+  archived: bool,
+}
+
+/// What [`Learner::export_library`] wrote into a bundle.
+#[derive(Debug)]
+pub struct LibraryExport {
+  /// Number of papers exported, archived ones included
+  pub papers:  usize,
+  /// Papers whose stored document could not be read into the bundle, with the reason
+  pub skipped: Vec<(Paper, LearnerError)>,
+}
+
 /// Builder for creating configured Learner instances.
 ///
 /// Provides a flexible way to construct Learner instances with
@@ -310,15 +484,136 @@ pub struct LearnerBuilder {
   config:      Option<Config>,
   /// Path to load configuration from
   config_path: Option<PathBuf>,
+  /// Offline mode override, taking precedence over [`Config::offline`]
+  offline:     Option<bool>,
+  /// Profile to select from the resolved configuration, see [`Config::with_profile`]
+  profile:     Option<String>,
 }
 
 impl Config {
+  /// Environment variable that overrides the configuration directory returned by
+  /// [`Config::default_path`].
+  pub const CONFIG_PATH_ENV: &'static str = "LEARNER_CONFIG";
+  /// Environment variable that overrides [`Config::database_path`].
+  pub const DATABASE_PATH_ENV: &'static str = "LEARNER_DATABASE_PATH";
+  /// Default for [`Config::max_concurrency`].
+  pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+  /// Name of the profile that uses the top-level paths unless `[profiles.default]` is defined.
+  pub const DEFAULT_PROFILE: &'static str = "default";
+  /// Environment variable that overrides [`Config::retrievers_path`].
+  pub const RETRIEVERS_PATH_ENV: &'static str = "LEARNER_RETRIEVERS_PATH";
+  /// Environment variable that overrides [`Config::storage_path`].
+  pub const STORAGE_PATH_ENV: &'static str = "LEARNER_STORAGE_PATH";
+
+  /// Creates a default configuration with environment variable overrides applied.
+  ///
+  /// See [`with_env_overrides`](Config::with_env_overrides) for the variables that are read.
+  pub fn from_env() -> Self { Self::default().with_env_overrides() }
+
+  /// Applies path overrides from the environment on top of this configuration.
+  ///
+  /// The following variables are read, and any that are set (and non-empty) replace the
+  /// corresponding value, with `~` and `$VAR`/`${VAR}` expanded:
+  /// - `LEARNER_DATABASE_PATH` for [`database_path`](Config::database_path)
+  /// - `LEARNER_STORAGE_PATH` for [`storage_path`](Config::storage_path)
+  /// - `LEARNER_RETRIEVERS_PATH` for [`retrievers_path`](Config::retrievers_path)
+  ///
+  /// This is applied automatically whenever configuration is read from a `config.toml`, so the
+  /// environment always wins over the file.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Config;
+  /// std::env::set_var("LEARNER_STORAGE_PATH", "/data/papers");
+  /// let config = Config::default().with_env_overrides();
+  /// assert_eq!(config.storage_path, std::path::PathBuf::from("/data/papers"));
+  /// ```
+  pub fn with_env_overrides(mut self) -> Self {
+    let env_path =
+      |name| std::env::var_os(name).filter(|v| !v.is_empty()).map(|v| expand_path(Path::new(&v)));
+
+    if let Some(path) = env_path(Self::DATABASE_PATH_ENV) {
+      self.database_path = path;
+    }
+    if let Some(path) = env_path(Self::STORAGE_PATH_ENV) {
+      self.storage_path = path;
+    }
+    if let Some(path) = env_path(Self::RETRIEVERS_PATH_ENV) {
+      self.retrievers_path = path;
+    }
+    self
+  }
+
+  /// Switches to the paths of the named profile.
+  ///
+  /// Paths the profile leaves out keep their current values, and the environment overrides of
+  /// [`with_env_overrides`](Config::with_env_overrides) still take precedence. Selecting
+  /// [`Config::DEFAULT_PROFILE`] without defining it keeps the top-level paths.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if no profile with the given name is defined.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::Config;
+  /// let config: Config = toml::from_str(
+  ///   r#"
+  ///   database_path = "/home/learner.db"
+  ///
+  ///   [profiles.work]
+  ///   database_path = "/work/learner.db"
+  ///   "#,
+  /// )?;
+  ///
+  /// let work = config.clone().with_profile("work")?;
+  /// assert_eq!(work.database_path, std::path::PathBuf::from("/work/learner.db"));
+  /// assert!(config.with_profile("play").is_err());
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn with_profile(mut self, name: &str) -> Result<Self> {
+    let Some(profile) = self.profiles.get(name).cloned() else {
+      if name == Self::DEFAULT_PROFILE {
+        return Ok(self);
+      }
+      let available = self.profiles.keys().map(String::as_str).collect::<Vec<_>>().join(", ");
+      return Err(LearnerError::Config(format!(
+        "Unknown profile '{name}' (configured profiles: {})",
+        if available.is_empty() { "none" } else { &available }
+      )));
+    };
+
+    self.database_path = expand_path(&profile.database_path);
+    if let Some(storage_path) = profile.storage_path {
+      self.storage_path = expand_path(&storage_path);
+    }
+    if let Some(retrievers_path) = profile.retrievers_path {
+      self.retrievers_path = expand_path(&retrievers_path);
+    }
+    Ok(self.with_env_overrides())
+  }
+
   /// Returns the default configuration directory path, creating it if needed.
   ///
   /// The default location is:
   /// - Unix: `~/.learner`
   /// - Windows: `%USERPROFILE%\.learner`
   ///
+  /// Setting `LEARNER_CONFIG` (see [`Config::CONFIG_PATH_ENV`]) to a directory uses that
+  /// instead, with `~` and `$VAR`/`${VAR}` expanded, which makes it easy to keep separate
+  /// libraries (e.g. "work" and "personal") side by side.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Config;
+  /// std::env::set_var("LEARNER_CONFIG", "/profiles/work");
+  /// assert_eq!(Config::default_path()?, std::path::PathBuf::from("/profiles/work"));
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  ///
   /// # Errors
   ///
   /// Returns error if:
@@ -326,7 +621,10 @@ impl Config {
   /// - Directory creation fails
   /// - Insufficient permissions
   pub fn default_path() -> Result<PathBuf> {
-    let config_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".learner");
+    let config_dir = match std::env::var_os(Self::CONFIG_PATH_ENV).filter(|v| !v.is_empty()) {
+      Some(dir) => expand_path(Path::new(&dir)),
+      None => dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".learner"),
+    };
 
     std::fs::create_dir_all(&config_dir)?;
     Ok(config_dir)
@@ -340,10 +638,21 @@ impl Config {
     Self::default_path().unwrap_or_else(|_| PathBuf::from(".")).join("retrievers")
   }
 
+  /// Returns [`Config::DEFAULT_MAX_CONCURRENCY`], for deserializing configs that leave
+  /// [`Config::max_concurrency`] out.
+  fn default_max_concurrency() -> usize { Self::DEFAULT_MAX_CONCURRENCY }
+
+  /// The default configuration for a configuration directory other than
+  /// [`Config::default_path`], keeping its retriever configs in `dir/retrievers`.
+  pub fn default_in(dir: impl AsRef<Path>) -> Self {
+    Self { retrievers_path: dir.as_ref().join("retrievers"), ..Self::default() }
+  }
+
   /// Loads existing configuration or creates new with defaults.
   ///
   /// Looks for configuration file at the default path. If not found,
-  /// creates new configuration file with default settings.
+  /// creates new configuration file with default settings. Environment
+  /// overrides are applied on top (see [`with_env_overrides`](Config::with_env_overrides)).
   ///
   /// # Errors
   ///
@@ -351,19 +660,57 @@ impl Config {
   /// - Configuration file exists but cannot be read
   /// - TOML parsing fails
   /// - File creation fails when saving defaults
-  pub fn load() -> Result<Self> {
-    let config_file = Self::default_path()?.join("config.toml");
+  pub fn load() -> Result<Self> { Self::load_from(Self::default_path()?) }
+
+  /// Loads the configuration in `dir`, like [`Config::load`] does for the default path.
+  ///
+  /// A missing `dir/config.toml` is created from [`Config::default_in`].
+  ///
+  /// # Errors
+  ///
+  /// Returns error if the configuration file exists but cannot be read or parsed, or the
+  /// defaults cannot be saved.
+  pub fn load_from(dir: impl AsRef<Path>) -> Result<Self> {
+    let dir = dir.as_ref();
+    let config_file = dir.join("config.toml");
 
     if config_file.exists() {
       let content = std::fs::read_to_string(&config_file)?;
-      toml::from_str(&content).map_err(|e| LearnerError::Config(e.to_string()))
+      Ok(toml::from_str::<Self>(&content)?.with_env_overrides())
     } else {
-      let config = Self::default();
-      config.save()?;
-      Ok(config)
+      let config = Self::default_in(dir);
+      config.save_to(dir)?;
+      Ok(config.with_env_overrides())
     }
   }
 
+  /// Reads the configuration at the default path without writing anything.
+  ///
+  /// Unlike [`Config::load`], a missing `config.toml` is not created: the defaults are used
+  /// instead. Environment overrides are applied on top, as for [`Config::load`]. This is for
+  /// inspecting the configuration, e.g. printing where it lives.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if the configuration file exists but cannot be read or parsed.
+  pub fn read() -> Result<Self> { Self::read_from(Self::default_path()?) }
+
+  /// Reads the configuration in `dir` without writing anything, see [`Config::read`].
+  ///
+  /// # Errors
+  ///
+  /// Returns error if the configuration file exists but cannot be read or parsed.
+  pub fn read_from(dir: impl AsRef<Path>) -> Result<Self> {
+    let dir = dir.as_ref();
+    let config_file = dir.join("config.toml");
+    let config = if config_file.exists() {
+      toml::from_str::<Self>(&std::fs::read_to_string(&config_file)?)?
+    } else {
+      Self::default_in(dir)
+    };
+    Ok(config.with_env_overrides())
+  }
+
   /// Saves current configuration to disk.
   ///
   /// Writes configuration to the default path in TOML format and ensures
@@ -375,11 +722,20 @@ impl Config {
   /// - TOML serialization fails
   /// - File write fails
   /// - Directory creation fails
-  pub fn save(&self) -> Result<()> {
-    let config_str =
-      toml::to_string_pretty(self).map_err(|e| LearnerError::Config(e.to_string()))?;
+  pub fn save(&self) -> Result<()> { self.save_to(Self::default_path()?) }
 
-    let config_file = Self::default_path()?.join("config.toml");
+  /// Saves the configuration as `dir/config.toml`, like [`Config::save`] does for the default
+  /// path.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if serialization fails or a file or directory cannot be written.
+  pub fn save_to(&self, dir: impl AsRef<Path>) -> Result<()> {
+    let config_str = toml::to_string_pretty(self)?;
+
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let config_file = dir.join("config.toml");
     debug!("Initializing config to: {config_file:?}");
     std::fs::write(config_file, config_str)?;
 
@@ -429,7 +785,7 @@ impl Config {
   /// let config = Config::default().with_database_path(&PathBuf::from("~/papers/db.sqlite"));
   /// ```
   pub fn with_database_path(mut self, database_path: &Path) -> Self {
-    self.database_path = database_path.to_path_buf();
+    self.database_path = expand_path(database_path);
     self
   }
 
@@ -439,7 +795,7 @@ impl Config {
   ///
   /// * `retrievers_path` - Directory where retriever TOML configs are stored
   pub fn with_retrievers_path(mut self, retrievers_path: &Path) -> Self {
-    self.retrievers_path = retrievers_path.to_path_buf();
+    self.retrievers_path = expand_path(retrievers_path);
     self
   }
 
@@ -449,9 +805,104 @@ impl Config {
   ///
   /// * `storage_path` - Directory where paper PDFs will be stored
   pub fn with_storage_path(mut self, storage_path: &Path) -> Self {
-    self.storage_path = storage_path.to_path_buf();
+    self.storage_path = expand_path(storage_path);
+    self
+  }
+
+  /// Sets how many downloads may be in flight at once.
+  ///
+  /// # Arguments
+  ///
+  /// * `max_concurrency` - Upper bound on concurrent downloads, at least `1`
+  pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+    self.max_concurrency = max_concurrency;
+    self
+  }
+
+  /// Sets the `User-Agent` retrievers send when their configuration has none.
+  ///
+  /// # Arguments
+  ///
+  /// * `user_agent` - Agent string, ideally with contact details, e.g. `learner (mailto:...)`
+  pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+    self.user_agent = Some(user_agent.into());
     self
   }
+
+  /// Checks that the configured paths are usable before anything is opened.
+  ///
+  /// The retrievers directory must already exist, unless it is the default
+  /// [`Config::default_retrievers_path`], which is created on demand. The database file's
+  /// directory and the storage directory must either exist or be creatable, meaning the
+  /// nearest existing ancestor is a directory.
+  ///
+  /// This runs as part of [`LearnerBuilder::build`], so a misconfigured path is reported at
+  /// startup rather than by whichever operation first touches it.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] naming the offending setting and path.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Config;
+  /// # use std::path::Path;
+  /// let config = Config::default().with_retrievers_path(Path::new("/no/such/retrievers"));
+  /// assert!(config.validate().is_err());
+  /// ```
+  pub fn validate(&self) -> Result<()> {
+    if !self.retrievers_path.is_dir() && self.retrievers_path != Self::default_retrievers_path() {
+      return Err(LearnerError::Config(format!(
+        "retrievers_path {} {}; create it or point `retrievers_path` at a directory of retriever \
+         configs",
+        self.retrievers_path.display(),
+        if self.retrievers_path.exists() { "is not a directory" } else { "does not exist" }
+      )));
+    }
+
+    if self.database_path.is_dir() {
+      return Err(LearnerError::Config(format!(
+        "database_path {} is a directory, expected the path of a database file",
+        self.database_path.display()
+      )));
+    }
+    if let Some(parent) = self.database_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+      check_creatable_dir("database_path", parent)?;
+    }
+    check_creatable_dir("storage_path", &self.storage_path)?;
+
+    if self.max_concurrency == 0 {
+      return Err(LearnerError::Config(
+        "max_concurrency is 0; at least one download must be allowed at a time".to_string(),
+      ));
+    }
+    if let Some(object_store) = &self.object_store {
+      object_store.validate()?;
+    }
+    Ok(())
+  }
+}
+
+/// Checks that `dir` is a directory or could be created as one.
+///
+/// `setting` names the configuration value the directory comes from, for the error message.
+fn check_creatable_dir(setting: &str, dir: &Path) -> Result<()> {
+  let Some(existing) = dir.ancestors().find(|ancestor| ancestor.exists()) else {
+    return Ok(());
+  };
+  if existing.is_dir() {
+    return Ok(());
+  }
+  Err(LearnerError::Config(if existing == dir {
+    format!("{setting} {} is not a directory", dir.display())
+  } else {
+    format!(
+      "{setting} {} cannot be created because {} is not a directory",
+      dir.display(),
+      existing.display()
+    )
+  }))
 }
 
 impl Default for Config {
@@ -460,6 +911,13 @@ impl Default for Config {
       database_path:   Database::default_path(),
       storage_path:    Database::default_storage_path(),
       retrievers_path: Self::default_retrievers_path(),
+      offline:         false,
+      database:        DatabaseOptions::default(),
+      max_concurrency: Self::DEFAULT_MAX_CONCURRENCY,
+      citation_format: None,
+      user_agent:      None,
+      profiles:        BTreeMap::new(),
+      object_store:    None,
     }
   }
 }
@@ -509,31 +967,83 @@ impl LearnerBuilder {
     self
   }
 
+  /// Enables or disables offline mode, overriding [`Config::offline`].
+  ///
+  /// In offline mode any retrieval attempt fails immediately with [`LearnerError::Offline`],
+  /// while local database operations keep working.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().offline(true).build().await?;
+  /// assert!(learner.retriever.get_paper("2301.07041").await.is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn offline(mut self, offline: bool) -> Self {
+    self.offline = Some(offline);
+    self
+  }
+
+  /// Selects a profile from the resolved configuration, see [`Config::with_profile`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().with_profile("work").build().await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+    self.profile = Some(profile.into());
+    self
+  }
+
   /// Builds a new [`Learner`] instance with the configured options.
   ///
   /// This method:
-  /// 1. Resolves configuration from provided sources
-  /// 2. Ensures required directories exist
-  /// 3. Opens database connection
-  /// 4. Initializes paper retriever
+  /// 1. Resolves configuration from provided sources, switching to the selected profile
+  /// 2. Expands and validates its paths (see [`Config::validate`])
+  /// 3. Ensures required directories exist
+  /// 4. Opens database connection
+  /// 5. Initializes paper retriever from the bundled retriever configs (see
+  ///    [`Retriever::with_default_configs`]) with the retrievers directory layered on top, so a
+  ///    user config replaces the bundled one of the same name and leaves the others in place
   ///
   /// # Errors
   ///
   /// Returns error if:
   /// - Configuration loading fails
+  /// - The selected profile is not configured ([`LearnerError::Config`])
+  /// - A configured path is unusable ([`LearnerError::Config`])
   /// - Directory creation fails
   /// - Database initialization fails
   /// - Retriever configuration fails
   pub async fn build(self) -> Result<Learner> {
-    let config = if let Some(config) = self.config {
+    let mut config = if let Some(config) = self.config {
       config
     } else if let Some(path) = self.config_path {
       let config_file = path.join("config.toml");
       let content = std::fs::read_to_string(config_file)?;
-      toml::from_str(&content).map_err(|e| LearnerError::Config(e.to_string()))?
+      toml::from_str::<Config>(&content)?.with_env_overrides()
     } else {
       Config::load()?
     };
+    if let Some(profile) = &self.profile {
+      config = config.with_profile(profile)?;
+    }
+    if let Some(offline) = self.offline {
+      config.offline = offline;
+    }
+    // Paths set directly on the struct have not been through the expanding setters
+    config.database_path = expand_path(&config.database_path);
+    config.storage_path = expand_path(&config.storage_path);
+    config.retrievers_path = expand_path(&config.retrievers_path);
+    config.validate()?;
 
     // Ensure paths exist
     std::fs::create_dir_all(&config.retrievers_path)?;
@@ -542,10 +1052,22 @@ impl LearnerBuilder {
     }
     std::fs::create_dir_all(&config.storage_path)?;
 
-    let database = Database::open(&config.database_path).await?;
+    let mut database = Database::open_with_options(&config.database_path, &config.database).await?;
     database.set_storage_path(&config.storage_path).await?;
+    #[cfg(feature = "object-store")]
+    if let Some(object_store) = &config.object_store {
+      database.set_storage(object_store.storage()?);
+    }
+    database.set_max_concurrency(config.max_concurrency);
+    database.set_offline(config.offline);
 
-    let retriever = Retriever::new().with_config_dir(&config.retrievers_path)?;
+    let mut retriever = Retriever::new()
+      .with_default_configs()?
+      .with_config_dir(&config.retrievers_path)?
+      .with_offline(config.offline);
+    if let Some(user_agent) = &config.user_agent {
+      retriever = retriever.with_user_agent(user_agent);
+    }
 
     Ok(Learner { config, database, retriever })
   }
@@ -699,12 +1221,735 @@ impl Learner {
   /// # }
   /// ```
   pub async fn init() -> Result<Self> { Self::with_config(Config::init()?).await }
+
+  /// Gets a paper, preferring the local database over the network.
+  ///
+  /// The input is sanitized into a source and identifier and looked up with
+  /// [`Query::by_source`]. Only on a miss is the paper retrieved from its remote source, in which
+  /// case it is also added to the database so later calls are served locally.
+  ///
+  /// # Arguments
+  ///
+  /// * `input` - Paper identifier or URL
+  ///
+  /// # Returns
+  ///
+  /// The paper together with a [`PaperOrigin`] saying whether it was found locally or fetched.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - The input does not match exactly one configured retriever
+  /// - The database lookup or insertion fails
+  /// - Retrieval from the remote source fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{Learner, PaperOrigin};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  ///
+  /// // The first call fetches and stores the paper, the second is served from the database
+  /// let (paper, origin) = learner.get_paper("2301.07041").await?;
+  /// let (_, origin) = learner.get_paper("2301.07041").await?;
+  /// assert_eq!(origin, PaperOrigin::Database);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_paper(&mut self, input: &str) -> Result<(Paper, PaperOrigin)> {
+    let (source, identifier) = self.retriever.sanitize_identifier(input)?;
+
+    if let Some(paper) =
+      Query::by_source(&source, &identifier).execute(&mut self.database).await?.into_iter().next()
+    {
+      debug!("Found {source} paper {identifier} in the database");
+      return Ok((paper, PaperOrigin::Database));
+    }
+
+    let paper = self.retriever.get_paper(input).await?;
+    Add::paper(&paper).execute(&mut self.database).await?;
+    Ok((paper, PaperOrigin::Network))
+  }
+
+  /// Checks whether the source has a newer version of `paper` than the one given.
+  ///
+  /// The paper is re-fetched from the retriever for its source and the reported version is
+  /// compared against [`Paper::version`]. Nothing is written to the database; callers decide
+  /// whether to replace the stored copy.
+  ///
+  /// Repeated checks through the same retriever are conditional requests, so a source that
+  /// answers `304 Not Modified` is not downloaded again.
+  ///
+  /// # Returns
+  ///
+  /// The newer version (e.g. `"v3"`) if one is available, or `None` if the paper is up to
+  /// date or the source does not report versions.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
+  /// - No retriever is configured for the paper's source ([`LearnerError::InvalidSource`])
+  /// - Retrieval from the remote source fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  /// let (paper, _) = learner.get_paper("2301.07041").await?;
+  /// if let Some(version) = learner.check_for_updates(&paper).await? {
+  ///   println!("{} has a newer version: {version}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn check_for_updates(&self, paper: &Paper) -> Result<Option<String>> {
+    let latest = self.retriever.fetch_paper(&paper.source, &paper.source_identifier).await?;
+    let newer = match (&latest.version, &paper.version) {
+      (Some(latest), Some(current)) => version_number(latest) > version_number(current),
+      (Some(_), None) => true,
+      (None, _) => false,
+    };
+    Ok(if newer { latest.version } else { None })
+  }
+
+  /// Fetches the abstract of `paper` from its source and stores it in place of the current one.
+  ///
+  /// Only the abstract is requested and replaced, so this is the way to fill in an abstract
+  /// that was stored empty or cut short without re-adding the paper. The rest of the stored
+  /// paper is kept; its language is guessed from the new abstract if it was unknown.
+  ///
+  /// # Returns
+  ///
+  /// The updated paper, or `None` if the source has no abstract for it or it matches the one
+  /// already stored.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
+  /// - No retriever is configured for the paper's source ([`LearnerError::InvalidSource`])
+  /// - The paper is not in the database ([`LearnerError::NotFound`])
+  /// - Retrieval from the remote source or the database update fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  /// let (paper, _) = learner.get_paper("2301.07041").await?;
+  /// if let Some(paper) = learner.refresh_abstract(&paper).await? {
+  ///   println!("{}", paper.abstract_text);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn refresh_abstract(&mut self, paper: &Paper) -> Result<Option<Paper>> {
+    if self.retriever.is_offline() {
+      return Err(LearnerError::Offline);
+    }
+    if !self.retriever.sources().contains(&paper.source.as_str()) {
+      return Err(LearnerError::InvalidSource(paper.source.clone()));
+    }
+
+    let mut stored = Query::by_paper(paper).one(&mut self.database).await?;
+    let Some(abstract_text) =
+      self.retriever.fetch_abstract(&paper.source, &paper.source_identifier).await?
+    else {
+      debug!("{} {} has no abstract at its source", paper.source, paper.source_identifier);
+      return Ok(None);
+    };
+    if abstract_text == stored.abstract_text.trim() {
+      return Ok(None);
+    }
+
+    stored.language =
+      stored.language.or_else(|| format::detect_language(&abstract_text).map(String::from));
+    stored.abstract_text = abstract_text;
+    Update::paper(&stored).execute(&mut self.database).await?;
+    Ok(Some(stored))
+  }
+
+  /// Searches a remote source for papers matching a keyword query.
+  ///
+  /// Uses the source's [`search_endpoint_template`](RetrieverConfig::search_endpoint_template),
+  /// so only sources configured for searching (like the bundled `arxiv` and `doi` retrievers)
+  /// can be searched. Results are parsed with the source's field maps and are not stored.
+  /// Programmatic retrievers search with [`ResourceRetriever::search`], see
+  /// [`Retriever::search`].
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - Name of the source to search, e.g. `"arxiv"`
+  /// * `query` - Free-text keywords
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
+  /// - No retriever is configured for the source ([`LearnerError::InvalidSource`])
+  /// - The source does not support searching ([`LearnerError::Config`])
+  /// - The search request fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::new().await?;
+  /// for paper in learner.search_online("arxiv", "attention transformer").await? {
+  ///   println!("{}: {}", paper.source_identifier, paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_online(&self, source: &str, query: &str) -> Result<Vec<Paper>> {
+    self.retriever.search(source, query).await
+  }
+
+  /// Checks that the stored URLs of every paper still resolve.
+  ///
+  /// Each paper's `pdf_url` and [`source_url`](Paper::source_url) is requested with `HEAD`, or
+  /// with `GET` when the server does not allow `HEAD`. Requests run concurrently, at most
+  /// [`Config::max_concurrency`] at a time. Nothing is stored or changed, so dead links are
+  /// only reported.
+  ///
+  /// # Returns
+  ///
+  /// A [`LinkCheck`] for every URL, in the order of the papers by source.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
+  /// - The database cannot be read
+  ///
+  /// Failed requests are reported as [`LinkStatus::Unreachable`] instead.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::new().await?;
+  /// for check in learner.verify_links().await? {
+  ///   if !check.is_live() {
+  ///     println!("{}: {} is dead ({:?})", check.paper.title, check.url, check.status);
+  ///   }
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn verify_links(&self) -> Result<Vec<LinkCheck>> {
+    if self.retriever.is_offline() {
+      return Err(LearnerError::Offline);
+    }
+    let papers =
+      Query::list_all().order_by(OrderField::Source).execute(&mut self.database.clone()).await?;
+    let links = papers.into_iter().flat_map(|paper| {
+      let mut urls: Vec<String> =
+        paper.pdf_url.iter().filter(|url| !url.trim().is_empty()).cloned().collect();
+      if let Some(url) = paper.source_url().map(String::from) {
+        if !urls.contains(&url) {
+          urls.push(url);
+        }
+      }
+      urls.into_iter().map(move |url| (paper.clone(), url))
+    });
+    let checks = futures::future::join_all(links.map(|(paper, url)| async move {
+      let _permit = self.database.network_permit().await;
+      let status = check_link(&self.retriever, &url).await;
+      LinkCheck { paper, url, status }
+    }))
+    .await;
+    Ok(checks)
+  }
+
+  /// Counts the papers in the library, with per-source, per-year and top author breakdowns.
+  ///
+  /// See [`Database::aggregate_stats`] for what is counted.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::new().await?;
+  /// let stats = learner.stats().await?;
+  /// println!("{} papers by {} authors", stats.papers, stats.authors);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn stats(&self) -> Result<LibraryStats> { self.database.aggregate_stats().await }
+
+  /// Imports every PDF in `dir` into the library, storing each file as a paper's document.
+  ///
+  /// Each PDF is analyzed with [`PDFContentBuilder`]. When it mentions a DOI (see
+  /// [`PDFContent::doi`](pdf::PDFContent::doi)) the metadata is retrieved through the
+  /// retriever for the `doi` source. Otherwise, or when offline or the retrieval fails, a
+  /// minimal paper from the `local` source is stored, titled by the file stem and identified by
+  /// the SHA-256 of its contents.
+  ///
+  /// Files are processed in name order and a failure on one does not stop the others. The
+  /// directory is not searched recursively.
+  ///
+  /// # Returns
+  ///
+  /// Every PDF path found, paired with the [`ImportOutcome`] for that file.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if the directory cannot be read. Failures for individual files are
+  /// reported as [`ImportOutcome::Failed`] instead.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{error::report, ImportOutcome, Learner};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  /// for (path, outcome) in learner.import_directory("downloads/").await? {
+  ///   match outcome {
+  ///     ImportOutcome::Resolved(paper) => println!("{}: {}", path.display(), paper.title),
+  ///     ImportOutcome::Minimal(_) => println!("{}: stored without metadata", path.display()),
+  ///     ImportOutcome::Failed(e) => println!("{}: {}", path.display(), report(&e)),
+  ///   }
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn import_directory(
+    &mut self,
+    dir: impl AsRef<Path>,
+  ) -> Result<Vec<(PathBuf, ImportOutcome)>> {
+    let mut pdfs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+      let path = entry?.path();
+      if path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")) {
+        pdfs.push(path);
+      }
+    }
+    pdfs.sort();
+
+    let mut report = Vec::with_capacity(pdfs.len());
+    for path in pdfs {
+      let outcome = match self.import_pdf(&path).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+          warn!("Failed to import {path:?}: {}", error::report(&e));
+          ImportOutcome::Failed(e)
+        },
+      };
+      report.push((path, outcome));
+    }
+    Ok(report)
+  }
+
+  /// Exports the whole library into a portable bundle at `dest`.
+  ///
+  /// The bundle is a directory holding [`LIBRARY_FILE`], a JSON list of every paper's metadata,
+  /// including archived papers and whether they are archived. With `include_documents`, the
+  /// stored documents are read through whichever [`Storage`] holds them into its
+  /// [`LIBRARY_DOCUMENTS_DIR`], and their filenames are listed, so that
+  /// [`Learner::import_library`] can restore them on another machine. `dest` is created if
+  /// needed, and an existing bundle there is overwritten.
+  ///
+  /// # Returns
+  ///
+  /// The number of papers exported, along with every paper whose document could not be read
+  /// and was left out of the bundle.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if the database cannot be read or the bundle cannot be written. A stored
+  /// document that cannot be read is skipped with a warning and reported in
+  /// [`LibraryExport::skipped`] instead.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  /// let exported = learner.export_library("backup/", true).await?;
+  /// println!("Exported {} papers", exported.papers);
+  /// for (paper, error) in &exported.skipped {
+  ///   println!("Left out the document of {}: {}", paper.title, learner::error::report(error));
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn export_library(
+    &mut self,
+    dest: impl AsRef<Path>,
+    include_documents: bool,
+  ) -> Result<LibraryExport> {
+    let dest = dest.as_ref();
+    let documents_dir = dest.join(LIBRARY_DOCUMENTS_DIR);
+    std::fs::create_dir_all(if include_documents { &documents_dir } else { dest })?;
+
+    let archived = Query::list_all().only_archived().execute(&mut self.database).await?;
+    let all = Query::list_all().include_archived().order_by(OrderField::Source);
+    let mut papers = Vec::new();
+    let mut skipped = Vec::new();
+    for paper in all.execute(&mut self.database).await? {
+      let mut document = None;
+      if include_documents {
+        if let Some(record) = self.database.stored_document(&paper).await? {
+          let filename = Path::new(&record.key)
+            .file_name()
+            .map_or_else(|| paper.filename(), PathBuf::from)
+            .to_string_lossy()
+            .to_string();
+          let copied = match self.database.read_document(&record).await {
+            Ok(contents) =>
+              std::fs::write(documents_dir.join(&filename), contents).map_err(Into::into),
+            Err(e) => Err(e),
+          };
+          match copied {
+            Ok(()) => document = Some(filename),
+            Err(e) => {
+              warn!(
+                "Skipping document {} for {}: {}",
+                record.uri,
+                paper.source_identifier,
+                report(&e)
+              );
+              skipped.push((paper.clone(), e));
+            },
+          }
+        }
+      }
+      let archived = archived.iter().any(|other| other.same_identity(&paper));
+      papers.push(BundledPaper { paper: paper.to_json_value()?, document, archived });
+    }
+
+    let exported = papers.len();
+    std::fs::write(
+      dest.join(LIBRARY_FILE),
+      serde_json::to_string_pretty(&LibraryBundle { papers })?,
+    )?;
+    Ok(LibraryExport { papers: exported, skipped })
+  }
+
+  /// Merges a bundle written by [`Learner::export_library`] into this library.
+  ///
+  /// Papers already in the database (by [`Paper::identity`]), archived or not, are skipped and
+  /// left unchanged. Every other paper is added, together with its document when the bundle
+  /// includes it, and archived again if it was archived when exported.
+  ///
+  /// # Returns
+  ///
+  /// The papers that were added.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if [`LIBRARY_FILE`] cannot be read or parsed, or a paper cannot be stored.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut learner = Learner::new().await?;
+  /// let added = learner.import_library("backup/").await?;
+  /// println!("Imported {} new papers", added.len());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn import_library(&mut self, src: impl AsRef<Path>) -> Result<Vec<Paper>> {
+    let src = src.as_ref();
+    let bundle: LibraryBundle = serde_json::from_slice(&std::fs::read(src.join(LIBRARY_FILE))?)
+      .map_err(|e| LearnerError::deserialize("library bundle", e))?;
+
+    // Papers with a document are added one by one, the rest in a single batch
+    let mut added = Vec::new();
+    let mut metadata_only = Vec::new();
+    let mut archived = Vec::new();
+    for BundledPaper { paper, document, archived: was_archived } in bundle.papers {
+      let paper = Paper::from_json_value(paper)?;
+      if !Query::by_paper(&paper).include_archived().execute(&mut self.database).await?.is_empty() {
+        debug!("Skipping {} {}, it is already stored", paper.source, paper.source_identifier);
+        continue;
+      }
+      if was_archived {
+        archived.push(paper.clone());
+      }
+      let document = document
+        .map(|filename| src.join(LIBRARY_DOCUMENTS_DIR).join(filename))
+        .filter(|path| path.is_file());
+      let Some(path) = document else {
+        metadata_only.push(paper);
+        continue;
+      };
+      Add::local_document(&paper, &path).execute(&mut self.database).await?;
+      added.push(paper);
+    }
+    added.extend(
+      Add::papers(&metadata_only).on_conflict(Conflict::Skip).execute(&mut self.database).await?,
+    );
+    for paper in
+      archived.iter().filter(|paper| added.iter().any(|other| other.same_identity(paper)))
+    {
+      Remove::by_source(&paper.source, &paper.source_identifier)
+        .execute(&mut self.database)
+        .await?;
+    }
+    Ok(added)
+  }
+
+  /// Imports a single PDF for [`Learner::import_directory`].
+  async fn import_pdf(&mut self, path: &Path) -> Result<ImportOutcome> {
+    let content = PDFContentBuilder::new().path(path).analyze()?;
+    let doi = content.doi();
+
+    let resolved = match &doi {
+      Some(doi) if !self.retriever.is_offline() && self.retriever.sources().contains(&"doi") =>
+        match self.retriever.get_paper_for_source("doi", doi).await {
+          Ok(paper) => Some(paper),
+          Err(e) => {
+            warn!("Could not resolve DOI {doi} found in {path:?}: {}", report(&e));
+            None
+          },
+        },
+      _ => None,
+    };
+
+    let resolved_paper = resolved.is_some();
+    let paper = match resolved {
+      Some(paper) => paper,
+      None => {
+        // Keyed by content so same-named files from different directories don't collide
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let mut builder = Paper::builder()
+          .with_title(stem)
+          .with_source("local")
+          .with_source_identifier(storage::sha256(&std::fs::read(path)?));
+        if let Ok(modified) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+          builder = builder.with_publication_date(DateTime::<Utc>::from(modified));
+        }
+        if let Some(doi) = doi {
+          builder = builder.with_doi(doi);
+        }
+        builder.build()?
+      },
+    };
+
+    Add::local_document(&paper, path).execute(&mut self.database).await?;
+    Ok(if resolved_paper { ImportOutcome::Resolved(paper) } else { ImportOutcome::Minimal(paper) })
+  }
+}
+
+/// Parses the numeric part of a version string like `v2`, for ordering versions.
+fn version_number(version: &str) -> Option<u32> {
+  version.trim_start_matches(['v', 'V']).parse().ok()
+}
+
+/// Requests `url` for [`Learner::verify_links`] through the retriever's client, retrying with
+/// `GET` if `HEAD` is not allowed.
+async fn check_link(retriever: &Retriever, url: &str) -> LinkStatus {
+  let send = |method: reqwest::Method| retriever.request(method, url).timeout(LINK_TIMEOUT).send();
+  let mut response = send(reqwest::Method::HEAD).await;
+  if let Ok(head) = &response {
+    if matches!(head.status().as_u16(), 405 | 501) {
+      response = send(reqwest::Method::GET).await;
+    }
+  }
+  match response {
+    Ok(response) if response.status().is_success() => LinkStatus::Live(response.status().as_u16()),
+    Ok(response) => LinkStatus::Dead(response.status().as_u16()),
+    Err(e) => LinkStatus::Unreachable(error::report(&e)),
+  }
+}
+
+/// Parses every `.toml` file in `dir` with `parse`, in filename order.
+///
+/// A file that cannot be read or parsed does not stop the others: it is returned as a
+/// `(filename, error)` pair next to the values that did load. Only a missing or unlistable
+/// directory is an error.
+fn load_toml_dir<T>(
+  dir: &Path,
+  parse: impl Fn(&str) -> Result<T>,
+) -> Result<(Vec<T>, ConfigErrors)> {
+  if !dir.is_dir() {
+    return Err(LearnerError::Path(std::io::Error::new(
+      std::io::ErrorKind::NotFound,
+      "Config directory not found",
+    )));
+  }
+
+  let mut paths = std::fs::read_dir(dir)?
+    .map(|entry| entry.map(|entry| entry.path()))
+    .collect::<std::io::Result<Vec<_>>>()?;
+  paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+  paths.sort();
+
+  let mut loaded = Vec::new();
+  let mut errors = Vec::new();
+  for path in paths {
+    match std::fs::read_to_string(&path).map_err(LearnerError::from).and_then(|s| parse(&s)) {
+      Ok(value) => loaded.push(value),
+      Err(error) => {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        errors.push((file, error));
+      },
+    }
+  }
+  Ok((loaded, errors))
+}
+
+/// Expands a leading `~` to the home directory and `$VAR`/`${VAR}` to environment values.
+///
+/// On Windows, `%VAR%` is expanded as well. Unset variables are left as written, and non-UTF-8
+/// paths are returned unchanged.
+fn expand_path(path: &Path) -> PathBuf {
+  lazy_static! {
+    static ref ENV_VAR: Regex = Regex::new(if cfg!(windows) {
+      r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)|%([A-Za-z_][A-Za-z0-9_()]*)%"
+    } else {
+      r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)"
+    })
+    .unwrap();
+  }
+
+  let Some(raw) = path.to_str() else {
+    return path.to_path_buf();
+  };
+
+  let expanded = ENV_VAR.replace_all(raw, |caps: &regex::Captures| {
+    let name = caps.iter().skip(1).flatten().next().map_or("", |m| m.as_str());
+    std::env::var(name).unwrap_or_else(|_| caps[0].to_string())
+  });
+
+  match expanded.strip_prefix('~') {
+    Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => match dirs::home_dir() {
+      Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+      None => PathBuf::from(expanded.as_ref()),
+    },
+    _ => PathBuf::from(expanded.as_ref()),
+  }
+}
+
+/// Deserializes a path and applies [`expand_path`] to it.
+fn deserialize_expanded_path<'de, D>(deserializer: D) -> std::result::Result<PathBuf, D::Error>
+where D: serde::Deserializer<'de> {
+  PathBuf::deserialize(deserializer).map(|path| expand_path(&path))
 }
 
 #[cfg(test)]
 mod tests {
+  use serial_test::serial;
+
   use super::*;
 
+  #[test]
+  #[serial]
+  fn test_env_overrides_file_values() {
+    let config: Config = toml::from_str(
+      r#"
+      database_path = "/file/learner.db"
+      storage_path = "/file/papers"
+      retrievers_path = "/file/retrievers"
+      "#,
+    )
+    .unwrap();
+
+    std::env::set_var(Config::STORAGE_PATH_ENV, "/env/papers");
+    std::env::remove_var(Config::DATABASE_PATH_ENV);
+    std::env::remove_var(Config::RETRIEVERS_PATH_ENV);
+    let config = config.with_env_overrides();
+    std::env::remove_var(Config::STORAGE_PATH_ENV);
+
+    assert_eq!(config.storage_path, PathBuf::from("/env/papers"));
+    assert_eq!(config.database_path, PathBuf::from("/file/learner.db"));
+    assert_eq!(config.retrievers_path, PathBuf::from("/file/retrievers"));
+  }
+
+  #[test]
+  #[serial]
+  fn test_config_load_from_dir() {
+    let dir = tempdir().unwrap();
+
+    // Reading leaves a missing config alone, loading creates it
+    let read = Config::read_from(dir.path()).unwrap();
+    assert_eq!(read.retrievers_path, dir.path().join("retrievers"));
+    assert!(!dir.path().join("config.toml").exists());
+
+    let loaded = Config::load_from(dir.path()).unwrap();
+    assert_eq!(loaded.retrievers_path, dir.path().join("retrievers"));
+    assert!(dir.path().join("config.toml").exists());
+    assert!(dir.path().join("retrievers").is_dir());
+
+    let saved = loaded.with_database_path(Path::new("/saved/learner.db"));
+    saved.save_to(dir.path()).unwrap();
+    assert_eq!(Config::read_from(dir.path()).unwrap().database_path, saved.database_path);
+  }
+
+  #[test]
+  #[serial]
+  fn test_config_env_overrides_default_path() {
+    let dir = tempdir().unwrap();
+    let profile = dir.path().join("work");
+
+    std::env::set_var(Config::CONFIG_PATH_ENV, &profile);
+    let path = Config::default_path().unwrap();
+    std::env::remove_var(Config::CONFIG_PATH_ENV);
+
+    assert_eq!(path, profile);
+    assert!(profile.is_dir());
+  }
+
+  #[test]
+  fn test_expand_path() {
+    let home = dirs::home_dir().unwrap();
+
+    assert_eq!(expand_path(Path::new("~")), home);
+    assert_eq!(expand_path(Path::new("~/papers")), home.join("papers"));
+    // Only a leading `~/` refers to the home directory
+    assert_eq!(expand_path(Path::new("~user/papers")), PathBuf::from("~user/papers"));
+
+    let home_var = std::env::var("HOME").unwrap();
+    assert_eq!(expand_path(Path::new("$HOME/papers")), PathBuf::from(&home_var).join("papers"));
+    assert_eq!(expand_path(Path::new("${HOME}/papers")), PathBuf::from(&home_var).join("papers"));
+    assert_eq!(
+      expand_path(Path::new("$LEARNER_TEST_SURELY_UNSET_VAR/papers")),
+      PathBuf::from("$LEARNER_TEST_SURELY_UNSET_VAR/papers")
+    );
+
+    assert_eq!(
+      expand_path(Path::new("/plain/path/db.sqlite")),
+      PathBuf::from("/plain/path/db.sqlite")
+    );
+  }
+
+  #[test]
+  fn test_config_paths_are_expanded() {
+    let home = dirs::home_dir().unwrap();
+
+    let config: Config = toml::from_str(
+      r#"
+      database_path = "~/.local/share/learner/learner.db"
+      storage_path = "/plain/papers"
+      "#,
+    )
+    .unwrap();
+    assert_eq!(config.database_path, home.join(".local/share/learner/learner.db"));
+    assert_eq!(config.storage_path, PathBuf::from("/plain/papers"));
+
+    let config = Config::default()
+      .with_storage_path(Path::new("~/papers"))
+      .with_retrievers_path(Path::new("${HOME}/retrievers"));
+    assert_eq!(config.storage_path, home.join("papers"));
+    assert_eq!(
+      config.retrievers_path,
+      PathBuf::from(std::env::var("HOME").unwrap()).join("retrievers")
+    );
+  }
+
   #[tokio::test]
   async fn test_learner_creation() {
     let config_dir = tempdir().unwrap();
@@ -714,6 +1959,7 @@ mod tests {
       .with_database_path(&database_dir.path().join("learner.db"))
       .with_retrievers_path(&config_dir.path().join("config/retrievers/"))
       .with_storage_path(storage_dir.path());
+    std::fs::create_dir_all(&config.retrievers_path).unwrap();
     let learner =
       Learner::builder().with_path(config_dir.path()).with_config(config).build().await.unwrap();
 
@@ -721,4 +1967,61 @@ mod tests {
     assert_eq!(learner.config.database_path, database_dir.path().join("learner.db"));
     assert_eq!(learner.database.get_storage_path().await.unwrap(), storage_dir.path());
   }
+
+  #[tokio::test]
+  async fn test_build_rejects_missing_retrievers_dir() {
+    let dir = tempdir().unwrap();
+    let missing = dir.path().join("no-such-retrievers");
+    let config = Config::default()
+      .with_database_path(&dir.path().join("learner.db"))
+      .with_retrievers_path(&missing)
+      .with_storage_path(&dir.path().join("papers"));
+
+    let Err(LearnerError::Config(message)) = Learner::builder().with_config(config).build().await
+    else {
+      panic!("expected a configuration error");
+    };
+    assert!(message.contains("retrievers_path"), "{message}");
+    assert!(message.contains(&missing.display().to_string()), "{message}");
+    assert!(message.contains("does not exist"), "{message}");
+    // Nothing was created for the rejected configuration
+    assert!(!dir.path().join("learner.db").exists());
+  }
+
+  #[test]
+  fn test_validate_rejects_uncreatable_dirs() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("file");
+    std::fs::write(&file, "").unwrap();
+    let config = Config::default().with_retrievers_path(dir.path());
+
+    let error = config.clone().with_storage_path(&file.join("papers")).validate().unwrap_err();
+    assert!(error.to_string().contains("storage_path"), "{error}");
+    assert!(error.to_string().contains("is not a directory"), "{error}");
+
+    let error = config.clone().with_database_path(dir.path()).validate().unwrap_err();
+    assert!(error.to_string().contains("is a directory"), "{error}");
+
+    let config = config
+      .with_database_path(&dir.path().join("db/learner.db"))
+      .with_storage_path(&dir.path().join("papers"));
+    assert!(config.validate().is_ok());
+  }
+
+  #[test]
+  fn test_validate_checks_object_store() {
+    let dir = tempdir().unwrap();
+    let mut config = Config::default().with_retrievers_path(dir.path());
+    config.object_store = Some(toml::from_str("url = \"not a url\"").unwrap());
+    let error = config.validate().unwrap_err();
+    assert!(error.to_string().contains("object_store url not a url is invalid"), "{error}");
+
+    config.object_store = Some(toml::from_str("url = \"https://papers.example.com/\"").unwrap());
+    if cfg!(feature = "object-store") {
+      assert!(config.validate().is_ok());
+    } else {
+      let error = config.validate().unwrap_err();
+      assert!(error.to_string().contains("`object-store` feature"), "{error}");
+    }
+  }
 }
@@ -139,6 +139,43 @@ pub struct PageContent {
   pub text:        String,
 }
 
+impl PDFContent {
+  /// Finds the first DOI mentioned in the document.
+  ///
+  /// The metadata fields are searched before the page text, so a DOI embedded by the publisher
+  /// takes precedence over one printed in the body. Trailing punctuation is not part of the
+  /// returned DOI, which is normalized with [`format::normalize_doi`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::pdf::PDFContentBuilder;
+  /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let content = PDFContentBuilder::new().path("paper.pdf").analyze()?;
+  /// if let Some(doi) = content.doi() {
+  ///   println!("DOI: {}", doi);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn doi(&self) -> Option<String> {
+    lazy_static! {
+      static ref DOI_REGEX: Regex = Regex::new(r#"10\.\d{4,9}/[^\s"<>]+"#).unwrap();
+    };
+
+    let metadata = [&self.metadata.title, &self.metadata.subject, &self.metadata.keywords];
+    metadata
+      .into_iter()
+      .flatten()
+      .map(String::as_str)
+      .chain(self.pages.iter().map(|page| page.text.as_str()))
+      .find_map(|text| DOI_REGEX.find(text))
+      .and_then(|doi| {
+        format::normalize_doi(doi.as_str().trim_end_matches(['.', ',', ';', ')', ']']))
+      })
+  }
+}
+
 impl PDFContentBuilder {
   /// Creates a new builder instance with default settings.
   ///
@@ -334,6 +371,13 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_pdf_doi_detection() {
+    let content =
+      PDFContentBuilder::new().path(PathBuf::from("tests/.data/doi_paper.pdf")).analyze().unwrap();
+    assert_eq!(content.doi().as_deref(), Some("10.1000/imported.42"));
+  }
+
   #[test]
   fn test_pdf_page_extraction() {
     let content =
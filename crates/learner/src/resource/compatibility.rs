@@ -0,0 +1,310 @@
+//! Backward compatibility checks between versions of a [`ResourceConfig`].
+//!
+//! A resource configuration's field definitions (see [`ResourceConfig::to_json_schema`]) decide
+//! which stored resources are valid. Changing them can leave resources that were valid under
+//! the older definitions invalid, which [`ResourceConfig::is_backward_compatible`] detects.
+
+use std::fmt;
+
+use super::*;
+
+/// A change to a [`ResourceConfig`] that can make resources valid under the older configuration
+/// invalid, as found by [`ResourceConfig::is_backward_compatible`].
+///
+/// Fields are named by their path, with nested fields joined by `.` and array elements written
+/// as `[]`, e.g. `committee[].name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakage {
+  /// A field of the older configuration no longer exists
+  Removed(String),
+  /// A field is required that was optional or did not exist
+  NewlyRequired(String),
+  /// A field's type changed
+  TypeChanged {
+    /// Path of the field
+    field: String,
+    /// The older type, `any` if it had none
+    from:  String,
+    /// The newer type
+    to:    String,
+  },
+  /// A constraint on a field became stricter
+  Tightened {
+    /// Path of the field
+    field:      String,
+    /// The constraint, e.g. `max` or `pattern`
+    constraint: String,
+  },
+}
+
+impl fmt::Display for Breakage {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Removed(field) => write!(f, "`{field}` was removed"),
+      Self::NewlyRequired(field) => write!(f, "`{field}` is now required"),
+      Self::TypeChanged { field, from, to } =>
+        write!(f, "`{field}` changed type from `{from}` to `{to}`"),
+      Self::Tightened { field, constraint } => write!(f, "`{field}` has a stricter `{constraint}`"),
+    }
+  }
+}
+
+impl ResourceConfig {
+  /// Lists the changes from `older` that could invalidate resources stored under it.
+  ///
+  /// Removed fields, fields that became required (also only under a new `required_if`), changed
+  /// types and tightened constraints are reported: a raised `min`, a lowered `max`, a new or
+  /// changed `pattern` or `format`, and an `enum` that lost values or was added. Nested `fields`
+  /// and array `items` are compared the same way. Everything else, like new optional fields or
+  /// looser bounds, is compatible.
+  ///
+  /// # Returns
+  ///
+  /// Every [`Breakage`] found, so an empty list means the change is backward compatible.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::{Breakage, ResourceConfig};
+  /// let older: ResourceConfig =
+  ///   toml::from_str("type_name = \"note\"\n[fields]\ntitle = { type = \"string\" }")?;
+  /// let newer: ResourceConfig = toml::from_str(
+  ///   "type_name = \"note\"\n[fields]\ntitle = { type = \"string\", required = true }",
+  /// )?;
+  /// assert_eq!(newer.is_backward_compatible(&older), vec![Breakage::NewlyRequired(
+  ///   "title".to_string()
+  /// )]);
+  /// assert!(older.is_backward_compatible(&newer).is_empty());
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn is_backward_compatible(&self, older: &ResourceConfig) -> Vec<Breakage> {
+    let mut breakages = Vec::new();
+    compare_fields("", &self.fields, &older.fields, &mut breakages);
+    breakages
+  }
+}
+
+/// Compares the field definitions under `prefix`, recording what `newer` breaks.
+fn compare_fields(
+  prefix: &str,
+  newer: &Map<String, Value>,
+  older: &Map<String, Value>,
+  breakages: &mut Vec<Breakage>,
+) {
+  let path =
+    |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{prefix}.{name}") };
+  for name in older.keys().filter(|name| !newer.contains_key(*name)) {
+    breakages.push(Breakage::Removed(path(name)));
+  }
+  for (name, definition) in newer {
+    let older = older.get(name);
+    if is_required(definition) && !older.is_some_and(is_required) {
+      breakages.push(Breakage::NewlyRequired(path(name)));
+    } else if !is_required(definition)
+      && definition.get("required_if").is_some()
+      && older.and_then(|older| older.get("required_if")) != definition.get("required_if")
+    {
+      breakages.push(Breakage::Tightened {
+        field:      path(name),
+        constraint: "required_if".to_string(),
+      });
+    }
+    if let Some(older) = older {
+      compare_definition(&path(name), definition, older, breakages);
+    }
+  }
+}
+
+/// Compares a single field's definitions, recording what `newer` breaks.
+fn compare_definition(field: &str, newer: &Value, older: &Value, breakages: &mut Vec<Breakage>) {
+  let new_type = newer.get("type").and_then(Value::as_str);
+  let old_type = older.get("type").and_then(Value::as_str);
+  // Every integer is a number, so only the reverse narrows the type
+  let widened = new_type == Some("number") && old_type == Some("integer");
+  if let Some(to) = new_type.filter(|to| Some(*to) != old_type && !widened) {
+    let from = old_type.unwrap_or("any").to_string();
+    breakages.push(Breakage::TypeChanged { field: field.to_string(), from, to: to.to_string() });
+    return;
+  }
+
+  let mut tightened = |constraint: &str| {
+    breakages.push(Breakage::Tightened {
+      field:      field.to_string(),
+      constraint: constraint.to_string(),
+    })
+  };
+  let bound = |definition: &Value, key| definition.get(key).and_then(Value::as_f64);
+  match (bound(newer, "min"), bound(older, "min")) {
+    (Some(new), Some(old)) if new > old => tightened("min"),
+    (Some(_), None) => tightened("min"),
+    _ => {},
+  }
+  match (bound(newer, "max"), bound(older, "max")) {
+    (Some(new), Some(old)) if new < old => tightened("max"),
+    (Some(_), None) => tightened("max"),
+    _ => {},
+  }
+  for key in ["pattern", "format"] {
+    if newer.get(key).is_some_and(|new| older.get(key) != Some(new)) {
+      tightened(key);
+    }
+  }
+  if let Some(Value::Array(allowed)) = newer.get("enum") {
+    let narrowed = match older.get("enum") {
+      Some(Value::Array(before)) => before.iter().any(|value| !allowed.contains(value)),
+      _ => true,
+    };
+    if narrowed {
+      tightened("enum");
+    }
+  }
+
+  if let Some(items) = newer.get("items") {
+    let older_items = older.get("items").cloned().unwrap_or(Value::Null);
+    compare_definition(
+      &format!("{field}[]"),
+      &definition(items),
+      &definition(&older_items),
+      breakages,
+    );
+  }
+  if let Some(Value::Object(fields)) = newer.get("fields") {
+    let empty = Map::new();
+    let older_fields = match older.get("fields") {
+      Some(Value::Object(fields)) => fields,
+      _ => &empty,
+    };
+    compare_fields(field, fields, older_fields, breakages);
+  }
+}
+
+/// Whether a field definition has `required = true`.
+fn is_required(definition: &Value) -> bool {
+  definition.get("required").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Expands an `items` type name like `"string"` into the definition `{ type = "string" }`.
+fn definition(items: &Value) -> Value {
+  match items {
+    Value::String(item_type) => serde_json::json!({ "type": item_type }),
+    items => items.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn config(fields: &str) -> ResourceConfig {
+    toml::from_str(&format!("type_name = \"thesis\"\n[fields]\n{fields}")).unwrap()
+  }
+
+  fn thesis() -> ResourceConfig {
+    toml::from_str(include_str!("../../config/resources/thesis.toml")).unwrap()
+  }
+
+  #[test]
+  fn test_new_required_field_breaks() {
+    let older = thesis();
+    let mut newer = older.clone();
+    newer
+      .fields
+      .insert("advisor".into(), serde_json::json!({ "type": "string", "required": true }));
+
+    assert_eq!(newer.is_backward_compatible(&older), vec![Breakage::NewlyRequired(
+      "advisor".to_string()
+    )]);
+  }
+
+  #[test]
+  fn test_new_optional_field_is_compatible() {
+    let older = thesis();
+    let mut newer = older.clone();
+    newer.fields.insert("advisor".into(), serde_json::json!({ "type": "string" }));
+
+    assert!(newer.is_backward_compatible(&older).is_empty());
+    // Removing it again is what breaks
+    assert_eq!(older.is_backward_compatible(&newer), vec![Breakage::Removed(
+      "advisor".to_string()
+    )]);
+  }
+
+  #[test]
+  fn test_tightened_constraints_break() {
+    let older = config(
+      r#"
+      title  = { type = "string", max = 200 }
+      pages  = { type = "integer", min = 1 }
+      status = { type = "string", enum = ["draft", "final"] }
+      year   = { type = "string" }
+      tags   = { type = "array", items = "string" }
+      "#,
+    );
+    let newer = config(
+      r#"
+      title  = { type = "string", max = 100, pattern = "^[A-Z]" }
+      pages  = { type = "integer", min = 0 }
+      status = { type = "string", enum = ["final"] }
+      year   = { type = "integer" }
+      tags   = { type = "array", items = { type = "string", max = 20 } }
+      "#,
+    );
+
+    let mut breakages: Vec<_> =
+      newer.is_backward_compatible(&older).iter().map(ToString::to_string).collect();
+    breakages.sort();
+    assert_eq!(breakages, [
+      "`status` has a stricter `enum`",
+      "`tags[]` has a stricter `max`",
+      "`title` has a stricter `max`",
+      "`title` has a stricter `pattern`",
+      "`year` changed type from `string` to `integer`",
+    ]);
+    // Going back loosens all of those, but raises the minimum number of pages
+    let breakages: Vec<_> =
+      older.is_backward_compatible(&newer).iter().map(ToString::to_string).collect();
+    assert_eq!(breakages, [
+      "`pages` has a stricter `min`",
+      "`year` changed type from `integer` to `string`",
+    ]);
+  }
+
+  #[test]
+  fn test_new_conditional_requirement_breaks() {
+    let older = config(r#"pdf_url = { type = "string" }"#);
+    let newer = config(r#"pdf_url = { type = "string", required_if = ["has_pdf", true] }"#);
+
+    assert_eq!(newer.is_backward_compatible(&older), vec![Breakage::Tightened {
+      field:      "pdf_url".to_string(),
+      constraint: "required_if".to_string(),
+    }]);
+    assert!(older.is_backward_compatible(&newer).is_empty());
+  }
+
+  #[test]
+  fn test_integer_to_number_widens() {
+    let older = config(r#"pages = { type = "integer" }"#);
+    let newer = config(r#"pages = { type = "number" }"#);
+
+    assert!(newer.is_backward_compatible(&older).is_empty());
+    assert_eq!(older.is_backward_compatible(&newer), vec![Breakage::TypeChanged {
+      field: "pages".to_string(),
+      from:  "number".to_string(),
+      to:    "integer".to_string(),
+    }]);
+  }
+
+  #[test]
+  fn test_nested_fields_are_compared() {
+    let older = config(
+      r#"committee = { type = "array", items = { type = "object", fields = { name = { type = "string" } } } }"#,
+    );
+    let newer = config(
+      r#"committee = { type = "array", items = { type = "object", fields = { name = { type = "string", required = true } } } }"#,
+    );
+
+    assert_eq!(newer.is_backward_compatible(&older), vec![Breakage::NewlyRequired(
+      "committee[].name".to_string()
+    )]);
+  }
+}
@@ -4,7 +4,8 @@
 //! and research resources. It defines:
 //!
 //! - A [`Resource`] trait that all resource types must implement
-//! - A flexible [`ResourceConfig`] for runtime-configured resource types
+//! - A flexible [`ResourceConfig`] for runtime-configured resource types, whose values are held in
+//!   a [`Record`]
 //! - Common utility types and functions for resource management
 //!
 //! The design allows for both statically defined resource types (like papers and books)
@@ -42,10 +43,15 @@ use serde_json::{Map, Value};
 
 use super::*;
 
+mod compatibility;
 mod paper;
+mod record;
 mod shared;
+mod validation;
 
+pub use compatibility::*;
 pub use paper::*;
+pub use record::*;
 pub use shared::*;
 
 /// Core trait that defines the behavior of a resource in the system.
@@ -127,6 +133,172 @@ pub struct ResourceConfig {
   pub fields:    Map<String, Value>,
 }
 
+/// Value types a field definition may declare, as named by JSON Schema.
+const SCHEMA_TYPES: [&str; 6] = ["string", "number", "integer", "boolean", "array", "object"];
+
+impl ResourceConfig {
+  /// Parses a configuration from a TOML string, such as `config/resources/thesis.toml`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::TomlDe`] if the TOML is malformed or doesn't describe a valid
+  /// configuration.
+  pub fn from_toml_str(toml_str: &str) -> Result<Self> { Ok(toml::from_str(toml_str)?) }
+
+  /// Loads every `.toml` file in a directory, collecting failures instead of stopping at the
+  /// first one.
+  ///
+  /// The valid configurations are returned in filename order, and each file that cannot be read
+  /// or parsed is returned alongside them as a `(filename, error)` pair, as
+  /// [`Retriever::load_config_dir`](crate::retriever::Retriever::load_config_dir) does for
+  /// retriever configs.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error only if the directory itself is missing or cannot be listed.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{error::report, resource::ResourceConfig};
+  /// let (configs, errors) = ResourceConfig::load_dir("config/resources")?;
+  /// for (file, error) in &errors {
+  ///   eprintln!("{file}: {}", report(error));
+  /// }
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn load_dir(dir: impl AsRef<Path>) -> Result<(Vec<Self>, ConfigErrors)> {
+    load_toml_dir(dir.as_ref(), Self::from_toml_str)
+  }
+
+  /// Describes the resources this configuration defines as a [JSON Schema] (draft 2020-12).
+  ///
+  /// Each of [`fields`](ResourceConfig::fields) is read as a field definition, the way
+  /// `config/resources/thesis.toml` is written:
+  ///
+  /// - `type` is one of the JSON Schema types (`string`, `number`, `integer`, `boolean`, `array` or
+  ///   `object`)
+  /// - `required = true` lists the field as required, and `required_if = [sibling, value]` only
+  ///   while the sibling field holds that value
+  /// - `min` and `max` bound a string's length in characters, an array's number of items or a
+  ///   number's value
+  /// - `pattern`, `enum`, `format`, `default` and `description` are passed through
+  /// - `items` describes array elements, either as a type name or as a nested definition
+  /// - `fields` holds the nested definitions of an `object` field
+  /// - `coerce = true` lets [`ResourceConfig::coerce`] convert strings into the field's type
+  ///
+  /// A field whose value is not a definition (e.g. sample data) is described by its JSON type.
+  ///
+  /// [JSON Schema]: https://json-schema.org
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if a definition declares an unknown type.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::ResourceConfig;
+  /// let config: ResourceConfig = toml::from_str(
+  ///   r#"
+  ///   type_name = "note"
+  ///
+  ///   [fields]
+  ///   title = { type = "string", required = true, max = 200 }
+  ///   "#,
+  /// )?;
+  /// let schema = config.to_json_schema()?;
+  /// assert_eq!(schema["properties"]["title"]["maxLength"], 200);
+  /// assert_eq!(schema["required"][0], "title");
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn to_json_schema(&self) -> Result<Value> {
+    let mut schema = object_schema(&self.fields)?;
+    schema.insert("$schema".into(), "https://json-schema.org/draft/2020-12/schema".into());
+    schema.insert("title".into(), self.type_name.clone().into());
+    Ok(Value::Object(schema))
+  }
+}
+
+/// Builds the schema of an object whose properties are the field definitions in `fields`.
+fn object_schema(fields: &Map<String, Value>) -> Result<Map<String, Value>> {
+  let mut properties = Map::new();
+  let mut required = Vec::new();
+  for (name, definition) in fields {
+    if definition.get("required").and_then(Value::as_bool).unwrap_or(false) {
+      required.push(Value::String(name.clone()));
+    }
+    properties.insert(name.clone(), Value::Object(field_schema(name, definition)?));
+  }
+
+  let mut schema = Map::new();
+  schema.insert("type".into(), "object".into());
+  schema.insert("properties".into(), Value::Object(properties));
+  if !required.is_empty() {
+    schema.insert("required".into(), Value::Array(required));
+  }
+  Ok(schema)
+}
+
+/// Builds the schema of the field `name` from its definition, see
+/// [`ResourceConfig::to_json_schema`].
+fn field_schema(name: &str, definition: &Value) -> Result<Map<String, Value>> {
+  let mut schema = Map::new();
+  let Some(field_type) = definition.get("type").and_then(Value::as_str) else {
+    // Not a definition, so only the type of the value is known
+    let value_type = match definition {
+      Value::Null => "null",
+      Value::Bool(_) => "boolean",
+      Value::Number(n) if n.is_f64() => "number",
+      Value::Number(_) => "integer",
+      Value::String(_) => "string",
+      Value::Array(_) => "array",
+      Value::Object(_) => "object",
+    };
+    schema.insert("type".into(), value_type.into());
+    return Ok(schema);
+  };
+  if !SCHEMA_TYPES.contains(&field_type) {
+    return Err(LearnerError::Config(format!(
+      "Field `{name}` has unknown type `{field_type}`, expected one of: {}",
+      SCHEMA_TYPES.join(", ")
+    )));
+  }
+  schema.insert("type".into(), field_type.into());
+
+  for key in ["description", "format", "pattern", "enum", "default"] {
+    if let Some(value) = definition.get(key) {
+      schema.insert(key.into(), value.clone());
+    }
+  }
+  let (min, max) = match field_type {
+    "string" => ("minLength", "maxLength"),
+    "array" => ("minItems", "maxItems"),
+    "object" => ("minProperties", "maxProperties"),
+    _ => ("minimum", "maximum"),
+  };
+  for (key, bound) in [("min", min), ("max", max)] {
+    if let Some(value) = definition.get(key) {
+      schema.insert(bound.into(), value.clone());
+    }
+  }
+
+  match definition.get("items") {
+    Some(Value::String(item_type)) => {
+      let items = field_schema(name, &serde_json::json!({ "type": item_type }))?;
+      schema.insert("items".into(), Value::Object(items));
+    },
+    Some(items) => {
+      schema.insert("items".into(), Value::Object(field_schema(name, items)?));
+    },
+    None => {},
+  }
+  if let Some(Value::Object(fields)) = definition.get("fields") {
+    schema.extend(object_schema(fields)?);
+  }
+  Ok(schema)
+}
+
 impl Resource for ResourceConfig {
   fn resource_type(&self) -> String { self.type_name.clone() }
 
@@ -192,4 +364,89 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_thesis_json_schema_snapshot() -> Result<()> {
+    let config: ResourceConfig =
+      toml::from_str(include_str!("../../config/resources/thesis.toml"))?;
+    let expected: Value =
+      serde_json::from_str(include_str!("../../tests/.data/thesis.schema.json"))?;
+
+    assert_eq!(config.to_json_schema()?, expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_json_schema_rules() -> Result<()> {
+    let config: ResourceConfig = toml::from_str(
+      r#"
+      type_name = "dataset"
+
+      [fields]
+      name    = { type = "string", required = true, min = 1, max = 80, pattern = "^[a-z-]+$" }
+      license = { type = "string", enum = ["MIT", "CC-BY-4.0"] }
+      rows    = { type = "integer", min = 0 }
+      tags    = { type = "array", items = "string", max = 10 }
+      sample  = 3.5
+
+      [fields.maintainer]
+      type   = "object"
+      fields = { email = { type = "string", format = "email", required = true } }
+
+      [fields.columns]
+      type  = "array"
+      items = { type = "object", fields = { label = { type = "string", required = true } } }
+      "#,
+    )?;
+    let schema = config.to_json_schema()?;
+
+    assert_eq!(schema["title"], "dataset");
+    assert_eq!(schema["required"], json!(["name"]));
+    let properties = &schema["properties"];
+    assert_eq!(
+      properties["name"],
+      json!({ "type": "string", "minLength": 1, "maxLength": 80, "pattern": "^[a-z-]+$" })
+    );
+    assert_eq!(properties["license"]["enum"], json!(["MIT", "CC-BY-4.0"]));
+    assert_eq!(properties["rows"], json!({ "type": "integer", "minimum": 0 }));
+    assert_eq!(
+      properties["tags"],
+      json!({ "type": "array", "items": { "type": "string" }, "maxItems": 10 })
+    );
+    assert_eq!(properties["sample"], json!({ "type": "number" }));
+    assert_eq!(
+      properties["maintainer"],
+      json!({
+        "type": "object",
+        "properties": { "email": { "type": "string", "format": "email" } },
+        "required": ["email"]
+      })
+    );
+    assert_eq!(properties["columns"]["items"]["required"], json!(["label"]));
+
+    // Types must be ones JSON Schema knows
+    let config: ResourceConfig =
+      toml::from_str("type_name = \"bad\"\n[fields]\nwhen = { type = \"date\" }")?;
+    assert!(matches!(config.to_json_schema(), Err(LearnerError::Config(_))));
+    Ok(())
+  }
+
+  #[test]
+  fn test_load_dir_collects_errors() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::copy("config/resources/book.toml", dir.path().join("book.toml"))?;
+    std::fs::copy("config/resources/thesis.toml", dir.path().join("thesis.toml"))?;
+    std::fs::write(dir.path().join("broken.toml"), "type_name = \"broken\"\n[fields")?;
+    std::fs::write(dir.path().join("notes.txt"), "not a config")?;
+
+    let (configs, errors) = ResourceConfig::load_dir(dir.path())?;
+    let names: Vec<_> = configs.iter().map(|config| config.type_name.as_str()).collect();
+    assert_eq!(names, ["book", "thesis"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "broken.toml");
+    assert!(matches!(errors[0].1, LearnerError::TomlDe(_)));
+
+    assert!(ResourceConfig::load_dir(dir.path().join("missing")).is_err());
+    Ok(())
+  }
 }
@@ -42,8 +42,14 @@
 //! # }
 //! ```
 
+use tokio::io::AsyncWriteExt;
+
 use super::*;
 
+/// Layout version of the JSON written by [`Paper::to_json`], stored in its `schema_version`
+/// field and raised whenever older readers could no longer make sense of it.
+pub const PAPER_JSON_VERSION: u64 = 1;
+
 /// Complete representation of an academic paper with metadata.
 ///
 /// This struct serves as the core data type for paper management, containing
@@ -90,8 +96,8 @@ pub struct Paper {
   pub authors:           Vec<Author>,
   /// Full abstract or summary text
   pub abstract_text:     String,
-  /// Publication or last update timestamp
-  pub publication_date:  DateTime<Utc>,
+  /// Publication or last update date, which may be known only to the year or month
+  pub publication_date:  PartialDate,
   /// Source repository or system (arXiv, DOI, IACR, etc.)
   pub source:            String,
   /// Source-specific paper identifier
@@ -100,19 +106,213 @@ pub struct Paper {
   pub pdf_url:           Option<String>,
   /// Optional DOI reference
   pub doi:               Option<String>,
+  /// DOIs of the works this paper cites, when the source provides them
+  #[serde(default)]
+  pub references:        Vec<String>,
+  /// Subject keywords or categories (e.g. arXiv's `cs.LG`), when the source provides them
+  #[serde(default)]
+  pub keywords:          Vec<String>,
+  /// Source-reported version (e.g. `v2` on arXiv), for sources that version papers
+  #[serde(default)]
+  pub version:           Option<String>,
+  /// Primary subject category (e.g. arXiv's `cs.LG`), for sources that assign one
+  #[serde(default)]
+  pub primary_category:  Option<String>,
+  /// ISO 639-1 code of the language the paper is written in, e.g. `en`
+  ///
+  /// Guessed from the abstract with [`format::detect_language`] when the paper is added, unless
+  /// already set.
+  #[serde(default)]
+  pub language:          Option<String>,
+}
+
+/// Builder for constructing a [`Paper`] by hand, e.g. when importing from local files.
+///
+/// The title, source and source identifier are required, and [`PaperBuilder::build`] fails
+/// with [`LearnerError::MissingField`] if any is unset. Everything else is optional: the
+/// publication date defaults to the time of building and the remaining fields to empty.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::resource::Paper;
+/// let paper = Paper::builder()
+///   .with_title("Notes on Things")
+///   .with_source("local")
+///   .with_source_identifier("notes")
+///   .with_doi("https://doi.org/10.1000/NOTES")
+///   .build()?;
+/// assert_eq!(paper.doi.as_deref(), Some("10.1000/notes"));
+/// assert_eq!(paper.identity(), ("local", "notes"));
+/// # Ok::<(), learner::error::LearnerError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PaperBuilder {
+  /// The paper's title, required
+  title:             Option<String>,
+  /// Source the paper is stored under, required
+  source:            Option<String>,
+  /// Identifier within the source, required
+  source_identifier: Option<String>,
+  /// Authors in order
+  authors:           Vec<Author>,
+  /// Abstract text
+  abstract_text:     String,
+  /// Publication date, defaulting to now when built
+  publication_date:  Option<PartialDate>,
+  /// URL of the PDF
+  pdf_url:           Option<String>,
+  /// The paper's DOI
+  doi:               Option<String>,
+  /// DOIs of cited works
+  references:        Vec<String>,
+  /// Subject keywords
+  keywords:          Vec<String>,
+  /// Source-reported version
+  version:           Option<String>,
+  /// Primary subject category
+  primary_category:  Option<String>,
+  /// ISO 639-1 language code
+  language:          Option<String>,
+}
+
+impl PaperBuilder {
+  /// Creates a builder with no fields set, equivalent to [`Paper::builder`].
+  pub fn new() -> Self { Self::default() }
+
+  /// Sets the title (required).
+  pub fn with_title(mut self, title: impl Into<String>) -> Self {
+    self.title = Some(title.into());
+    self
+  }
+
+  /// Sets the source, e.g. `"arxiv"` (required).
+  pub fn with_source(mut self, source: impl Into<String>) -> Self {
+    self.source = Some(source.into());
+    self
+  }
+
+  /// Sets the source-specific identifier (required).
+  pub fn with_source_identifier(mut self, source_identifier: impl Into<String>) -> Self {
+    self.source_identifier = Some(source_identifier.into());
+    self
+  }
+
+  /// Appends an author after any already added.
+  pub fn with_author(mut self, author: Author) -> Self {
+    self.authors.push(author);
+    self
+  }
+
+  /// Replaces the list of authors.
+  pub fn with_authors(mut self, authors: Vec<Author>) -> Self {
+    self.authors = authors;
+    self
+  }
+
+  /// Sets the abstract.
+  pub fn with_abstract(mut self, abstract_text: impl Into<String>) -> Self {
+    self.abstract_text = abstract_text.into();
+    self
+  }
+
+  /// Sets the publication date, either a full [`DateTime`] or a [`PartialDate`].
+  pub fn with_publication_date(mut self, publication_date: impl Into<PartialDate>) -> Self {
+    self.publication_date = Some(publication_date.into());
+    self
+  }
+
+  /// Sets the URL of the paper's PDF.
+  pub fn with_pdf_url(mut self, pdf_url: impl Into<String>) -> Self {
+    self.pdf_url = Some(pdf_url.into());
+    self
+  }
+
+  /// Sets the DOI, normalized with [`format::normalize_doi`] when it looks like one.
+  pub fn with_doi(mut self, doi: impl Into<String>) -> Self {
+    let doi = doi.into();
+    self.doi = Some(format::normalize_doi(&doi).unwrap_or(doi));
+    self
+  }
+
+  /// Sets the DOIs of the works the paper cites.
+  pub fn with_references(mut self, references: Vec<String>) -> Self {
+    self.references = references;
+    self
+  }
+
+  /// Sets the subject keywords or categories, e.g. `"cs.LG"`.
+  pub fn with_keywords(mut self, keywords: Vec<String>) -> Self {
+    self.keywords = keywords;
+    self
+  }
+
+  /// Sets the source-reported version, e.g. `"v2"`.
+  pub fn with_version(mut self, version: impl Into<String>) -> Self {
+    self.version = Some(version.into());
+    self
+  }
+
+  /// Sets the primary subject category, e.g. `"cs.LG"`.
+  pub fn with_primary_category(mut self, category: impl Into<String>) -> Self {
+    self.primary_category = Some(category.into());
+    self
+  }
+
+  /// Sets the ISO 639-1 code of the paper's language, e.g. `"de"`, instead of having it
+  /// guessed from the abstract when the paper is added.
+  pub fn with_language(mut self, language: impl Into<String>) -> Self {
+    self.language = Some(language.into());
+    self
+  }
+
+  /// Builds the paper.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::MissingField`] naming the first of the title, source and source
+  /// identifier that was not set.
+  pub fn build(self) -> Result<Paper> {
+    Ok(Paper {
+      title:             self.title.ok_or(LearnerError::MissingField("title"))?,
+      source:            self.source.ok_or(LearnerError::MissingField("source"))?,
+      source_identifier: self
+        .source_identifier
+        .ok_or(LearnerError::MissingField("source_identifier"))?,
+      authors:           self.authors,
+      abstract_text:     self.abstract_text,
+      publication_date:  self.publication_date.unwrap_or_else(|| Utc::now().into()),
+      pdf_url:           self.pdf_url,
+      doi:               self.doi,
+      references:        self.references,
+      keywords:          self.keywords,
+      version:           self.version,
+      primary_category:  self.primary_category,
+      language:          self.language,
+    })
+  }
 }
 
 impl Paper {
+  /// Returns a [`PaperBuilder`] for constructing a paper by hand.
+  pub fn builder() -> PaperBuilder { PaperBuilder::new() }
+
   /// Downloads the paper's PDF to the specified directory.
   ///
   /// This method handles the retrieval and storage of the paper's PDF
   /// document, if available. It will:
   ///
   /// 1. Check for PDF availability
-  /// 2. Download the document
-  /// 3. Store it with a formatted filename
+  /// 2. Download the document into `.partial/<filename>.part` in `dir`
+  /// 3. Rename it to a formatted filename once complete
   /// 4. Handle network and storage errors
   ///
+  /// An interrupted download leaves the `.part` file behind when the server advertises
+  /// `Accept-Ranges: bytes` and identifies the document with an `ETag` or `Last-Modified`
+  /// header. The next call resumes it with a range request instead of starting over, and gets
+  /// the whole document again if it changed in the meantime. Other servers get a fresh
+  /// download.
+  ///
   /// # Arguments
   ///
   /// * `dir` - Target directory for PDF storage
@@ -138,31 +338,317 @@ impl Paper {
   /// # }
   /// ```
   pub async fn download_pdf(&self, dir: &Path) -> Result<PathBuf> {
+    self.download_pdf_to(&FileStorage::new(dir)).await.map(PathBuf::from)
+  }
+
+  /// Downloads the paper's PDF into a [`Storage`] backend.
+  ///
+  /// The document is stored under the same name [`Paper::download_pdf`] would give the file,
+  /// including the numeric suffix used when a different document already holds the name.
+  ///
+  /// # Returns
+  ///
+  /// The key the document was stored under.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{Learner, storage::{MemoryStorage, Storage}};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("2301.07041").await?;
+  /// let storage = MemoryStorage::default();
+  /// let key = paper.download_pdf_to(&storage).await?;
+  /// println!("PDF stored at: {}", storage.uri(&key));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn download_pdf_to(&self, storage: &dyn Storage) -> Result<String> {
+    self.store_download(storage).await.map(|document| document.key)
+  }
+
+  /// Downloads the paper's PDF like [`Paper::download_pdf_to`], also returning its checksum.
+  pub(crate) async fn store_download(&self, storage: &dyn Storage) -> Result<StoredDocument> {
     let Some(pdf_url) = &self.pdf_url else {
       return Err(LearnerError::ApiError("No PDF URL available".into()));
     };
 
-    let response = reqwest::get(pdf_url).await?;
+    let staging_dir = storage.staging_dir();
+    tokio::fs::create_dir_all(&staging_dir).await?;
+    let part = staging_dir.join(format!("{}.part", self.filename().to_string_lossy()));
+    self.fetch_pdf(pdf_url, &part).await?;
 
-    // Check the status code of the response
-    if response.status().is_success() {
-      let bytes = response.bytes().await?;
-      let path = dir.join(self.filename());
-      debug!("Writing PDF to path: {path:?}");
-      std::fs::write(path, bytes)?;
-      Ok(self.filename())
-    } else {
+    let bytes = tokio::fs::read(&part).await?;
+    let key = self.available_key(storage, &bytes).await?;
+    storage.put_file(&key, &part).await?;
+    Ok(StoredDocument { key, sha256: storage::sha256(&bytes) })
+  }
+
+  /// Downloads `pdf_url` into the partial download file `part`, resuming it if possible.
+  ///
+  /// When `part` already holds the start of the document, only the rest is requested with an
+  /// HTTP range request. The `ETag` (or `Last-Modified` date) the document was first sent with
+  /// is kept beside `part` and sent as `If-Range`, so a server whose document changed since
+  /// sends all of it instead. Whole documents, also from servers that do not support ranges,
+  /// replace what `part` held. A partial response for any other range than the one requested
+  /// discards `part` and retries once without a range, and fails if it comes again. If the
+  /// transfer is interrupted, `part` is kept for the next
+  /// attempt when the server advertised `Accept-Ranges: bytes` and a validator, and removed
+  /// otherwise.
+  ///
+  /// The response must be declared `application/pdf` or start like a PDF (`%PDF-`), or this
+  /// fails with [`LearnerError::NotPdf`] before anything is kept. The document must end up the
+  /// size the server announced, or this fails with
+  /// [`LearnerError::IncompleteDownload`] (removing `part` if it grew too large to resume).
+  async fn fetch_pdf(&self, pdf_url: &str, part: &Path) -> Result<()> {
+    let validator_path = part.with_extension("validator");
+    let validator = tokio::fs::read_to_string(&validator_path).await.ok();
+    // Without a validator there is no telling whether `part` is still a prefix of the document
+    let resume_from = match &validator {
+      Some(_) => tokio::fs::metadata(part).await.map_or(0, |metadata| metadata.len()),
+      None => 0,
+    };
+    let mut request = DOWNLOAD_CLIENT.get(pdf_url);
+    if let Some(validator) = validator.as_ref().filter(|_| resume_from > 0) {
+      debug!("Resuming download of {pdf_url} from byte {resume_from}");
+      request = request
+        .header(reqwest::header::RANGE, format!("bytes={resume_from}-"))
+        .header(reqwest::header::IF_RANGE, validator);
+    }
+    let mut response = request.send().await?;
+
+    let resumed = resume_from > 0
+      && response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+      && content_range_start(&response) == Some(resume_from);
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+      // The partial file is not a prefix of the document any more, start over
+      discard_partial(part).await?;
+      return Box::pin(self.fetch_pdf(pdf_url, part)).await;
+    }
+    if !response.status().is_success() {
       // Handle non-successful status codes
       trace!("{} pdf_url response: {response:?}", self.source);
-      Err(LearnerError::ApiError(format!("Failed to download PDF: {}", response.status())))
+      return Err(LearnerError::ApiError(format!("Failed to download PDF: {}", response.status())));
+    }
+    if response.status() == reqwest::StatusCode::PARTIAL_CONTENT && !resumed {
+      // A part of the document other than the one asked for cannot be stored as it is
+      let range = response.headers().get(reqwest::header::CONTENT_RANGE).cloned();
+      discard_partial(part).await?;
+      if resume_from > 0 {
+        debug!("{pdf_url} sent the wrong range ({range:?}), downloading it again");
+        return Box::pin(self.fetch_pdf(pdf_url, part)).await;
+      }
+      return Err(LearnerError::ApiError(format!(
+        "Failed to download PDF: unrequested partial content ({range:?})"
+      )));
+    }
+    if resume_from > 0 && !resumed {
+      debug!("{pdf_url} changed or does not support resuming, downloading it again");
+    }
+
+    // A fresh download is resumable against the validator it was sent with
+    let validator = if resumed { validator } else { response_validator(&response) };
+    match &validator {
+      Some(validator) if !resumed => tokio::fs::write(&validator_path, validator).await?,
+      Some(_) => (),
+      None => remove_if_exists(&validator_path).await?,
+    }
+    let resumable = validator.is_some()
+      && (resumed
+        || response
+          .headers()
+          .get(reqwest::header::ACCEPT_RANGES)
+          .and_then(|value| value.to_str().ok())
+          .is_some_and(|value| value.eq_ignore_ascii_case("bytes")));
+    let mut received = if resumed { resume_from } else { 0 };
+    let expected = if resumed {
+      content_range_total(&response).or(response.content_length().map(|len| received + len))
+    } else {
+      response.content_length()
+    };
+    let mut file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .write(true)
+      .append(resumed)
+      .truncate(!resumed)
+      .open(part)
+      .await?;
+    // Only the start of the document can be sniffed, so resumed downloads were checked earlier.
+    // Undeclared documents are held back until enough of them arrived to sniff.
+    let mut unsniffed = match (!resumed).then(|| pdf_content_type(&response)) {
+      Some(Err(content_type)) => Some((content_type, Vec::new())),
+      _ => None,
+    };
+    let interrupted = loop {
+      let chunk = match response.chunk().await {
+        Ok(chunk) => chunk,
+        Err(e) => break Some(e),
+      };
+      let sniffable = match (&mut unsniffed, &chunk) {
+        (Some((_, start)), Some(chunk)) => {
+          start.extend_from_slice(chunk);
+          start.len() >= PDF_SNIFF_LEN
+        },
+        // All of the document arrived, however short (an empty body is no PDF)
+        (Some(_), None) => true,
+        (None, _) => false,
+      };
+      if sniffable {
+        let (content_type, start) = unsniffed.take().expect("only sniffable while held back");
+        if !looks_like_pdf(&start) {
+          drop(file);
+          discard_partial(part).await?;
+          return Err(LearnerError::NotPdf(content_type));
+        }
+        file.write_all(&start).await?;
+        received += start.len() as u64;
+      } else if let (None, Some(chunk)) = (&unsniffed, &chunk) {
+        file.write_all(chunk).await?;
+        received += chunk.len() as u64;
+      }
+      if chunk.is_none() {
+        break None;
+      }
+    };
+    file.flush().await?;
+    drop(file);
+
+    let complete = expected.is_none_or(|expected| received == expected);
+    if interrupted.is_none() && complete {
+      remove_if_exists(&validator_path).await?;
+      return Ok(());
+    }
+    // Keep what arrived for a later range request, unless it cannot be a prefix of the document
+    if !resumable || expected.is_some_and(|expected| received > expected) {
+      discard_partial(part).await?;
+    }
+    match (expected, interrupted) {
+      (Some(expected), _) if received != expected =>
+        Err(LearnerError::IncompleteDownload { expected, received }),
+      (_, Some(e)) => Err(e.into()),
+      _ => unreachable!("a complete, uninterrupted download returned above"),
+    }
+  }
+
+  /// Returns the source and source identifier, which together identify the paper.
+  ///
+  /// This is the same pair the database treats as unique, so it stays stable while other
+  /// metadata (title, abstract, authors) is edited or refreshed.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::Learner;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("2301.07041").await?;
+  /// assert_eq!(paper.identity(), ("arxiv", "2301.07041"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn identity(&self) -> (&str, &str) { (&self.source, &self.source_identifier) }
+
+  /// Whether `other` is the same paper as this one, see [`Paper::identity`].
+  ///
+  /// Unlike `==`, which compares every field, this ignores metadata differences such as an
+  /// edited title, so it is the right test for finding a paper in a list.
+  pub fn same_identity(&self, other: &Paper) -> bool { self.identity() == other.identity() }
+
+  /// Serializes the paper to its canonical JSON form.
+  ///
+  /// The JSON object has one key per field of [`Paper`], authors included, plus a
+  /// `schema_version` set to [`PAPER_JSON_VERSION`]. Publication dates are RFC 3339
+  /// timestamps, or `YYYY`/`YYYY-MM` when only partly known.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::Paper;
+  /// let paper = Paper::builder()
+  ///   .with_title("Notes on Things")
+  ///   .with_source("local")
+  ///   .with_source_identifier("notes")
+  ///   .build()?;
+  /// assert_eq!(Paper::from_json(&paper.to_json()?)?, paper);
+  /// # Ok::<(), learner::error::LearnerError>(())
+  /// ```
+  pub fn to_json(&self) -> Result<String> { Ok(serde_json::to_string(&self.to_json_value()?)?) }
+
+  /// Reads a paper back from the JSON written by [`Paper::to_json`].
+  ///
+  /// Fields this version does not know are ignored, so JSON from newer releases that only
+  /// added fields still reads. A missing `schema_version` is taken to be the current one.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the JSON is malformed, lacks a required field,
+  /// or has a `schema_version` newer than [`PAPER_JSON_VERSION`].
+  pub fn from_json(json: &str) -> Result<Self> {
+    Self::from_json_value(
+      serde_json::from_str(json).map_err(|e| LearnerError::deserialize("paper JSON", e))?,
+    )
+  }
+
+  /// The canonical JSON form of [`Paper::to_json`] as a value, for embedding in larger
+  /// documents such as a library bundle.
+  pub(crate) fn to_json_value(&self) -> Result<Value> {
+    let mut json = serde_json::to_value(self)?;
+    if let Value::Object(fields) = &mut json {
+      fields.insert("schema_version".to_string(), PAPER_JSON_VERSION.into());
+    }
+    Ok(json)
+  }
+
+  /// Reads a paper from a value produced by [`Paper::to_json_value`], see [`Paper::from_json`].
+  pub(crate) fn from_json_value(mut json: Value) -> Result<Self> {
+    if let Some(version) = json.as_object_mut().and_then(|fields| fields.remove("schema_version")) {
+      if !version.as_u64().is_some_and(|version| version <= PAPER_JSON_VERSION) {
+        return Err(LearnerError::deserialize(
+          "paper JSON",
+          format!("unsupported schema_version {version}, expected at most {PAPER_JSON_VERSION}"),
+        ));
+      }
     }
+    serde_json::from_value(json).map_err(|e| LearnerError::deserialize("paper JSON", e))
+  }
+
+  /// Copies a PDF already on disk into a [`Storage`] backend as this paper's document.
+  ///
+  /// The copy is named like a downloaded document (see [`Paper::download_pdf_to`]), including
+  /// the numeric suffix used when a different document already holds the name.
+  ///
+  /// # Returns
+  ///
+  /// The key the document was stored under.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] if the file cannot be read, or the backend's error if it
+  /// cannot be stored.
+  pub async fn copy_pdf_to(&self, storage: &dyn Storage, from: &Path) -> Result<String> {
+    self.store_copy(storage, from).await.map(|document| document.key)
+  }
+
+  /// Copies a PDF like [`Paper::copy_pdf_to`], also returning its checksum.
+  pub(crate) async fn store_copy(
+    &self,
+    storage: &dyn Storage,
+    from: &Path,
+  ) -> Result<StoredDocument> {
+    let bytes = tokio::fs::read(from).await?;
+    let key = self.available_key(storage, &bytes).await?;
+    debug!("Copying PDF from {from:?} to {}", storage.uri(&key));
+    storage.put(&key, &bytes).await?;
+    Ok(StoredDocument { key, sha256: storage::sha256(&bytes) })
   }
 
   /// Generates a standardized filename for the paper's PDF.
   ///
-  /// Creates a filesystem-safe filename based on the paper's title,
-  /// suitable for PDF storage. The filename is:
-  /// - Truncated to a reasonable length
+  /// Creates a filesystem-safe filename based on the paper's source, identifier, and title,
+  /// suitable for PDF storage (e.g. `arxiv-2301.07041-some_title.pdf`). The filename is:
+  /// - Prefixed with the source and identifier, so distinct papers never share a name
+  /// - Truncated to a reasonable length, with identifiers over 50 bytes shortened and suffixed with
+  ///   the first 8 hex digits of their SHA-256 so they stay distinct
   /// - Cleaned of problematic characters
   /// - Suffixed with ".pdf"
   ///
@@ -185,10 +671,418 @@ impl Paper {
   /// ```
   pub fn filename(&self) -> PathBuf {
     let formatted_title = format::format_title(&self.title, Some(50));
-    PathBuf::from(format!("{}.pdf", formatted_title))
+    let mut identifier = format::sanitize_filename(&self.source_identifier, usize::MAX);
+    if identifier.len() > 50 {
+      let hash = storage::sha256(self.source_identifier.as_bytes());
+      identifier = format!("{}_{}", format::sanitize_filename(&identifier, 41), &hash[..8]);
+    }
+    PathBuf::from(format!("{}-{}-{}.pdf", self.source.to_lowercase(), identifier, formatted_title))
+  }
+
+  /// Returns the canonical landing page for the paper at its source.
+  ///
+  /// Known sources are arXiv (`https://arxiv.org/abs/{id}`), DOI (`https://doi.org/{id}`) and
+  /// IACR (`https://eprint.iacr.org/{id}`). Returns `None` for any other source.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{Learner, resource::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let learner = Learner::builder().build().await?;
+  /// let paper = learner.retriever.get_paper("2301.07041").await?;
+  /// assert_eq!(paper.source_url().unwrap().as_str(), "https://arxiv.org/abs/2301.07041");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn source_url(&self) -> Option<Url> {
+    let base = match self.source.to_lowercase().as_str() {
+      "arxiv" => "https://arxiv.org/abs/",
+      "doi" => "https://doi.org/",
+      "iacr" => "https://eprint.iacr.org/",
+      _ => return None,
+    };
+    Url::parse(&format!("{base}{}", self.source_identifier)).ok()
+  }
+
+  /// Returns the abstract for display, with whitespace collapsed and at most `max_chars`
+  /// characters long.
+  ///
+  /// Runs of spaces and line breaks become single spaces. Longer abstracts are cut at the
+  /// last word boundary that fits and end in `…`, which counts towards `max_chars`; a single
+  /// word longer than the limit is cut mid-word.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::Paper;
+  /// let paper = Paper::builder()
+  ///   .with_title("Attention")
+  ///   .with_source("arxiv")
+  ///   .with_source_identifier("1706.03762")
+  ///   .with_abstract("The dominant sequence\n  transduction models")
+  ///   .build()?;
+  /// assert_eq!(paper.abstract_summary(30), "The dominant sequence…");
+  /// assert_eq!(paper.abstract_summary(100), "The dominant sequence transduction models");
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn abstract_summary(&self, max_chars: usize) -> String {
+    let text = self.abstract_text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+      return text;
+    }
+
+    if max_chars == 0 {
+      return String::new();
+    }
+
+    // Leave room for the ellipsis, and back up to a word boundary unless the cut already is one
+    let cut = text.char_indices().nth(max_chars - 1).map_or(text.len(), |(i, _)| i);
+    let fitted = if text[cut..].starts_with(' ') {
+      &text[..cut]
+    } else {
+      text[..cut].rfind(' ').map_or(&text[..cut], |space| &text[..space])
+    };
+    format!("{fitted}…")
+  }
+
+  /// Finds a key in `storage` that can hold `contents` without clobbering another document.
+  ///
+  /// Uses [`Paper::filename`] unless a different document is already stored there, in which
+  /// case a numeric suffix is appended until a free (or identical) key is found.
+  async fn available_key(&self, storage: &dyn Storage, contents: &[u8]) -> Result<String> {
+    let filename = self.filename();
+    let stem = filename.file_stem().unwrap_or_default().to_string_lossy().to_string();
+
+    let mut candidate = filename.to_string_lossy().to_string();
+    let mut suffix = 1;
+    loop {
+      if !storage.exists(&candidate).await? || storage.get(&candidate).await? == contents {
+        return Ok(candidate);
+      }
+      suffix += 1;
+      warn!(
+        "{} already holds a different document, trying suffix {suffix}",
+        storage.uri(&candidate)
+      );
+      candidate = format!("{stem}-{suffix}.pdf");
+    }
+  }
+}
+
+/// A document [`Paper`] wrote into a [`Storage`] backend.
+pub(crate) struct StoredDocument {
+  /// The key the document was stored under
+  pub(crate) key:    String,
+  /// Hex SHA-256 of the document's contents, see [`storage::sha256`]
+  pub(crate) sha256: String,
+}
+
+/// The total size of the document a `206 Partial Content` response is part of, from its
+/// `Content-Range` header.
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+  let range = response.headers().get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+  range.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// `Ok` if `response` declares itself a PDF, otherwise `Err` with the content type it declares.
+fn pdf_content_type(response: &reqwest::Response) -> std::result::Result<(), String> {
+  let content_type = response
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or("unknown");
+  let essence = content_type.split(';').next().unwrap_or_default().trim();
+  if essence.eq_ignore_ascii_case("application/pdf") {
+    Ok(())
+  } else {
+    Err(content_type.to_string())
+  }
+}
+
+/// How much of the start of a document [`looks_like_pdf`] looks at.
+const PDF_SNIFF_LEN: usize = 1024;
+
+/// Whether `start`, the first bytes of a document, holds the `%PDF-` header.
+///
+/// Readers accept the header anywhere in the first kilobyte, so this does too.
+fn looks_like_pdf(start: &[u8]) -> bool {
+  start[..start.len().min(PDF_SNIFF_LEN)].windows(5).any(|window| window == b"%PDF-")
+}
+
+lazy_static! {
+  /// Client shared by every PDF download, so connections to a host are reused.
+  ///
+  /// There is no limit on the whole request, which large documents on slow links would hit,
+  /// only on connecting and on the wait for each part of the body.
+  static ref DOWNLOAD_CLIENT: reqwest::Client = reqwest::Client::builder()
+    .connect_timeout(std::time::Duration::from_secs(30))
+    .read_timeout(std::time::Duration::from_secs(60))
+    .build()
+    .expect("the download client uses no settings that can fail");
+}
+
+/// The validator to resume a download of `response`'s document against: its `ETag`, or its
+/// `Last-Modified` date when it has none. Weak `ETag`s cannot be used with `If-Range`.
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+  let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok());
+  header(reqwest::header::ETAG)
+    .filter(|etag| !etag.starts_with("W/"))
+    .or_else(|| header(reqwest::header::LAST_MODIFIED))
+    .map(String::from)
+}
+
+/// Removes the partial download `part` and the validator kept beside it.
+async fn discard_partial(part: &Path) -> Result<()> {
+  remove_if_exists(part).await?;
+  remove_if_exists(&part.with_extension("validator")).await
+}
+
+/// Removes the file at `path`, if there is one.
+async fn remove_if_exists(path: &Path) -> Result<()> {
+  match tokio::fs::remove_file(path).await {
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    removed => Ok(removed?),
   }
 }
 
+/// The first byte of a `206 Partial Content` response, from its `Content-Range` header.
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+  let range = response.headers().get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+  range.strip_prefix("bytes ")?.split_once('-')?.0.trim().parse().ok()
+}
+
 impl Resource for Paper {
   fn resource_type(&self) -> String { "paper".to_string() }
 }
+
+#[cfg(test)]
+mod tests {
+  use chrono::TimeZone;
+
+  use super::*;
+  use crate::storage::MemoryStorage;
+
+  fn paper_with(title: &str, source_identifier: &str) -> Paper {
+    Paper::builder()
+      .with_title(title)
+      .with_source("arxiv")
+      .with_source_identifier(source_identifier)
+      .with_publication_date(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())
+      .build()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_json_round_trip() {
+    let mut paper = paper_with("Round Trip", "2301.00001");
+    paper.authors = vec![
+      Author {
+        name:        "Jane Doe".to_string(),
+        affiliation: Some("Test University".to_string()),
+        email:       Some("jane@test.edu".to_string()),
+        orcid:       Some("0000-0002-1825-0097".to_string()),
+      },
+      Author {
+        name:        "Zoë Ångström".to_string(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
+      },
+    ];
+    paper.abstract_text = "Lossless, \"quoted\" and\nmultiline".to_string();
+    paper.doi = Some("10.1000/round.trip".to_string());
+    paper.references = vec!["10.1000/cited".to_string()];
+    paper.keywords = vec!["cs.LG".to_string()];
+    paper.version = Some("v2".to_string());
+    paper.primary_category = Some("cs.LG".to_string());
+    paper.language = Some("en".to_string());
+
+    let json = paper.to_json().unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap()["schema_version"], 1);
+    assert_eq!(Paper::from_json(&json).unwrap(), paper);
+
+    let partial = Paper { publication_date: PartialDate::YearMonth(2023, 4), ..paper };
+    assert_eq!(Paper::from_json(&partial.to_json().unwrap()).unwrap(), partial);
+  }
+
+  #[test]
+  fn test_from_json_ignores_unknown_fields() {
+    let paper = paper_with("Forward Compatible", "2301.00002");
+    let mut json: Value = serde_json::from_str(&paper.to_json().unwrap()).unwrap();
+    json["added_in_a_later_release"] = "whatever".into();
+    assert_eq!(Paper::from_json(&json.to_string()).unwrap(), paper);
+
+    json["schema_version"] = (PAPER_JSON_VERSION + 1).into();
+    assert!(matches!(Paper::from_json(&json.to_string()), Err(LearnerError::Deserialize { .. })));
+  }
+
+  #[test]
+  fn test_builder_matches_literal() {
+    let date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let author = Author {
+      name:        "Jane Doe".to_string(),
+      affiliation: Some("Test University".to_string()),
+      email:       None,
+      orcid:       None,
+    };
+    let literal = Paper {
+      title:             "Some Title".to_string(),
+      authors:           vec![author.clone()],
+      abstract_text:     "An abstract".to_string(),
+      publication_date:  date.into(),
+      source:            "doi".to_string(),
+      source_identifier: "10.1000/abc".to_string(),
+      pdf_url:           Some("https://example.com/abc.pdf".to_string()),
+      doi:               Some("10.1000/abc".to_string()),
+      references:        vec!["10.1000/cited".to_string()],
+      keywords:          vec!["cs.DL".to_string()],
+      version:           Some("v2".to_string()),
+      primary_category:  Some("cs.DL".to_string()),
+      language:          Some("en".to_string()),
+    };
+
+    let built = Paper::builder()
+      .with_title("Some Title")
+      .with_author(author)
+      .with_abstract("An abstract")
+      .with_publication_date(date)
+      .with_source("doi")
+      .with_source_identifier("10.1000/abc")
+      .with_pdf_url("https://example.com/abc.pdf")
+      .with_doi("https://doi.org/10.1000/ABC")
+      .with_references(vec!["10.1000/cited".to_string()])
+      .with_keywords(vec!["cs.DL".to_string()])
+      .with_version("v2")
+      .with_primary_category("cs.DL")
+      .with_language("en")
+      .build()
+      .unwrap();
+    assert_eq!(built, literal);
+  }
+
+  #[test]
+  fn test_abstract_summary_truncates_at_word_boundary() {
+    let mut paper = paper_with("Summaries", "1");
+    paper.abstract_text = "We study   lattice\nproblems in depth".to_string();
+
+    assert_eq!(paper.abstract_summary(20), "We study lattice…");
+    assert_eq!(paper.abstract_summary(17), "We study lattice…");
+    assert!(paper.abstract_summary(20).chars().count() <= 20);
+    // A first word longer than the limit is cut mid-word
+    assert_eq!(paper.abstract_summary(2), "W…");
+  }
+
+  #[test]
+  fn test_abstract_summary_keeps_short_abstract() {
+    let mut paper = paper_with("Summaries", "1");
+    paper.abstract_text = "Short and sweet".to_string();
+    assert_eq!(paper.abstract_summary(80), "Short and sweet");
+    assert_eq!(paper.abstract_summary(15), "Short and sweet");
+  }
+
+  #[test]
+  fn test_builder_requires_identity_and_title() {
+    let complete =
+      Paper::builder().with_title("T").with_source("arxiv").with_source_identifier("1");
+    let paper = complete.clone().build().unwrap();
+    assert!(paper.authors.is_empty() && paper.abstract_text.is_empty());
+    assert!(paper.publication_date.earliest() <= Utc::now());
+
+    for (builder, field) in [
+      (PaperBuilder { title: None, ..complete.clone() }, "title"),
+      (PaperBuilder { source: None, ..complete.clone() }, "source"),
+      (PaperBuilder { source_identifier: None, ..complete }, "source_identifier"),
+    ] {
+      assert!(
+        matches!(builder.build(), Err(LearnerError::MissingField(missing)) if missing == field)
+      );
+    }
+  }
+
+  #[test]
+  fn test_filename_includes_source_and_identifier() {
+    let paper = paper_with("Some Title", "2301.07041");
+    assert_eq!(paper.filename(), PathBuf::from("arxiv-2301.07041-some_title.pdf"));
+
+    let paper = Paper { source: "iacr".to_string(), ..paper_with("Some Title", "2016/260") };
+    assert_eq!(paper.filename(), PathBuf::from("iacr-2016_260-some_title.pdf"));
+  }
+
+  #[test]
+  fn test_filename_hashes_truncated_identifiers() {
+    let prefix = "10.1000/".to_string() + &"x".repeat(60);
+    let first = paper_with("Some Title", &format!("{prefix}.1"));
+    let second = paper_with("Some Title", &format!("{prefix}.2"));
+    assert_ne!(first.filename(), second.filename());
+
+    let filename = first.filename().to_string_lossy().to_string();
+    let identifier = filename.strip_prefix("arxiv-").unwrap().strip_suffix("-some_title.pdf");
+    assert_eq!(identifier.unwrap().len(), 50);
+    assert!(identifier.unwrap().starts_with("10.1000_xxx"));
+  }
+
+  #[test]
+  fn test_same_identity() {
+    let paper = paper_with("Some Title", "2301.07041");
+    assert_eq!(paper.identity(), ("arxiv", "2301.07041"));
+
+    // Metadata differences don't change identity
+    let edited = Paper { abstract_text: "Edited".to_string(), ..paper_with("Other", "2301.07041") };
+    assert!(paper.same_identity(&edited));
+    assert_ne!(paper, edited);
+
+    // The same identifier from another source is a different paper
+    let other_source =
+      Paper { source: "iacr".to_string(), ..paper_with("Some Title", "2301.07041") };
+    assert!(!paper.same_identity(&other_source));
+    assert!(!paper.same_identity(&paper_with("Some Title", "2301.07042")));
+  }
+
+  #[test]
+  fn test_source_url() {
+    let paper = paper_with("Some Title", "2301.07041");
+    assert_eq!(paper.source_url().unwrap().as_str(), "https://arxiv.org/abs/2301.07041");
+
+    let paper =
+      Paper { source: "doi".to_string(), ..paper_with("Some Title", "10.1145/1327452.1327492") };
+    assert_eq!(paper.source_url().unwrap().as_str(), "https://doi.org/10.1145/1327452.1327492");
+
+    let paper = Paper { source: "iacr".to_string(), ..paper_with("Some Title", "2016/260") };
+    assert_eq!(paper.source_url().unwrap().as_str(), "https://eprint.iacr.org/2016/260");
+
+    let paper = Paper { source: "mock".to_string(), ..paper_with("Some Title", "1") };
+    assert_eq!(paper.source_url(), None);
+  }
+
+  #[test]
+  fn test_filename_no_collision_for_identical_slugs() {
+    let first = paper_with("Zero-Knowledge Proofs!", "2301.00001");
+    let second = paper_with("Zero-Knowledge: Proofs", "2301.00002");
+    assert_eq!(
+      format::format_title(&first.title, Some(50)),
+      format::format_title(&second.title, Some(50))
+    );
+    assert_ne!(first.filename(), second.filename());
+  }
+
+  #[tokio::test]
+  async fn test_available_key_disambiguates_existing_document() {
+    let storage = MemoryStorage::default();
+    let paper = paper_with("Some Title", "2301.07041");
+    let filename = paper.filename().to_string_lossy().to_string();
+
+    // Free target name is used as-is
+    assert_eq!(paper.available_key(&storage, b"one").await.unwrap(), filename);
+
+    // Identical content at the target is reused
+    storage.put(&filename, b"one").await.unwrap();
+    assert_eq!(paper.available_key(&storage, b"one").await.unwrap(), filename);
+
+    // Different content at the target gets a suffixed name
+    assert_eq!(
+      paper.available_key(&storage, b"two").await.unwrap(),
+      "arxiv-2301.07041-some_title-2.pdf"
+    );
+  }
+}
@@ -0,0 +1,318 @@
+//! Values of a runtime-configured resource and typed access to them.
+//!
+//! A [`ResourceConfig`] defines a resource type, while a [`Record`] holds the values of one
+//! resource of that type as JSON. Reading a value by hand means chaining
+//! `values.get("title").and_then(Value::as_str)` and losing why it failed. The accessors of a
+//! [`Record`] return a [`LearnerError::InvalidField`] that says whether the field was missing
+//! or held another type.
+
+use super::{validation::*, *};
+
+/// The values of one resource of a runtime-configured type.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::resource::{Record, ResourceConfig};
+/// # use serde_json::json;
+/// let config: ResourceConfig = toml::from_str(
+///   "type_name = \"thesis\"\n[fields]\npages = { type = \"integer\", required = true }",
+/// )?;
+/// let values = json!({ "pages": 120 }).as_object().unwrap().clone();
+/// let thesis = Record::new(&config, values)?;
+/// assert_eq!(thesis.type_name, "thesis");
+/// assert_eq!(thesis.get_i64("pages")?, 120);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+  /// The [`ResourceConfig::type_name`] of the resource type
+  pub type_name: String,
+  /// Map of field names to their values
+  pub values:    Map<String, Value>,
+}
+
+impl Record {
+  /// Creates a record of the type `config` defines, once `values` pass its definitions.
+  ///
+  /// Values are checked as they are given; run them through [`ResourceConfig::coerce`] first
+  /// to accept numbers and booleans sent as strings.
+  ///
+  /// # Errors
+  ///
+  /// Fails like [`ResourceConfig::validate`] if `values` do not match the definitions.
+  pub fn new(config: &ResourceConfig, values: Map<String, Value>) -> Result<Self> {
+    config.validate(&values)?;
+    Ok(Self { type_name: config.type_name.clone(), values })
+  }
+
+  /// Reads a string field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing or not a string.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::Record;
+  /// # use serde_json::json;
+  /// let thesis: Record = serde_json::from_value(json!({
+  ///   "type_name": "thesis",
+  ///   "values": { "university": "Tech University", "pages": 120 },
+  /// }))?;
+  /// assert_eq!(thesis.get_str("university")?, "Tech University");
+  /// assert!(thesis.get_str("pages").is_err());
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn get_str(&self, name: &str) -> Result<&str> {
+    let value = self.field(name)?;
+    value.as_str().ok_or_else(|| mismatch(name, "string", value))
+  }
+
+  /// Reads a whole-number field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing or not an integer that fits
+  /// an `i64`.
+  pub fn get_i64(&self, name: &str) -> Result<i64> {
+    let value = self.field(name)?;
+    value.as_i64().ok_or_else(|| mismatch(name, "integer", value))
+  }
+
+  /// Reads a number field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing or not a number.
+  pub fn get_f64(&self, name: &str) -> Result<f64> {
+    let value = self.field(name)?;
+    value.as_f64().ok_or_else(|| mismatch(name, "number", value))
+  }
+
+  /// Reads a boolean field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing or not a boolean.
+  pub fn get_bool(&self, name: &str) -> Result<bool> {
+    let value = self.field(name)?;
+    value.as_bool().ok_or_else(|| mismatch(name, "boolean", value))
+  }
+
+  /// Reads an array field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing or not an array.
+  pub fn get_array(&self, name: &str) -> Result<&[Value]> {
+    let value = self.field(name)?;
+    value.as_array().map(Vec::as_slice).ok_or_else(|| mismatch(name, "array", value))
+  }
+
+  /// Reads an RFC 3339 timestamp, such as a thesis's `defense_date`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the field is missing, not a string or not an
+  /// RFC 3339 timestamp.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::Record;
+  /// # use serde_json::json;
+  /// let thesis: Record = serde_json::from_value(json!({
+  ///   "type_name": "thesis",
+  ///   "values": { "defense_date": "2024-06-15T14:00:00+02:00" },
+  /// }))?;
+  /// assert_eq!(thesis.get_datetime("defense_date")?.to_rfc3339(), "2024-06-15T12:00:00+00:00");
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn get_datetime(&self, name: &str) -> Result<DateTime<Utc>> {
+    let text = self.get_str(name)?;
+    DateTime::parse_from_rfc3339(text)
+      .map(|datetime| datetime.with_timezone(&Utc))
+      .map_err(|e| invalid(name, format!("`{text}` is not an RFC 3339 timestamp: {e}")))
+  }
+
+  /// Reads the `title` field.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if the resource has no string `title`.
+  pub fn title(&self) -> Result<&str> { self.get_str("title") }
+
+  /// Reads the resource's authors.
+  ///
+  /// They are taken from `authors`, as a [`Paper`] names them, or else from `author`, as
+  /// `config/resources/thesis.toml` does. Each entry is either a name or an [`Author`] object.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidField`] if neither field exists, or if it is not an array of
+  /// names and authors.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::Record;
+  /// # use serde_json::json;
+  /// let thesis: Record = serde_json::from_value(json!({
+  ///   "type_name": "thesis",
+  ///   "values": {
+  ///     "author": ["Alice Researcher", { "name": "Bob Scientist", "affiliation": "MIT" }],
+  ///   },
+  /// }))?;
+  /// let authors = thesis.authors()?;
+  /// assert_eq!(authors[0].name, "Alice Researcher");
+  /// assert_eq!(authors[1].affiliation.as_deref(), Some("MIT"));
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn authors(&self) -> Result<Vec<Author>> {
+    let name = if self.values.contains_key("authors") { "authors" } else { "author" };
+    self
+      .get_array(name)?
+      .iter()
+      .enumerate()
+      .map(|(index, author)| match author {
+        Value::String(author) => Ok(Author {
+          name:        author.clone(),
+          affiliation: None,
+          email:       None,
+          orcid:       None,
+        }),
+        author => serde_json::from_value(author.clone()).map_err(|e| {
+          invalid(&format!("{name}[{index}]"), format!("expected a name or an author: {e}"))
+        }),
+      })
+      .collect()
+  }
+
+  /// Looks up the value of the field `name`.
+  fn field(&self, name: &str) -> Result<&Value> {
+    self.values.get(name).ok_or_else(|| invalid(name, "the field is missing"))
+  }
+}
+
+impl Resource for Record {
+  fn resource_type(&self) -> String { self.type_name.clone() }
+
+  fn fields(&self) -> Result<Map<String, Value>> { Ok(self.values.clone()) }
+}
+
+/// Builds the error for a `field` holding `value` rather than the `expected` type.
+fn mismatch(field: &str, expected: &str, value: &Value) -> LearnerError {
+  invalid(field, format!("expected {expected}, found {}", type_of(value)))
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn thesis() -> Record {
+    serde_json::from_value(json!({
+      "type_name": "thesis",
+      "values": {
+        "title": "Understanding Quantum Computing Effects",
+        "author": ["Alice Researcher", { "name": "Bob Scientist", "email": "bob@tech.edu" }],
+        "university": "Tech University",
+        "defense_date": "2024-06-15T14:00:00Z",
+        "pages": 120,
+        "rating": 4.5,
+        "published": false,
+        "keywords": ["quantum computing", "decoherence"],
+      },
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_typed_accessors() -> Result<()> {
+    let thesis = thesis();
+
+    assert_eq!(thesis.title()?, "Understanding Quantum Computing Effects");
+    assert_eq!(thesis.get_str("university")?, "Tech University");
+    assert_eq!(thesis.get_i64("pages")?, 120);
+    assert_eq!(thesis.get_f64("rating")?, 4.5);
+    assert!(!thesis.get_bool("published")?);
+    assert_eq!(thesis.get_array("keywords")?, [json!("quantum computing"), json!("decoherence")]);
+    assert_eq!(
+      thesis.get_datetime("defense_date")?,
+      "2024-06-15T14:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+
+    let authors = thesis.authors()?;
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[0].name, "Alice Researcher");
+    assert_eq!(authors[1].email.as_deref(), Some("bob@tech.edu"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_accessor_errors() {
+    let thesis = thesis();
+
+    let error = thesis.get_str("department").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `department`: the field is missing");
+    let error = thesis.get_str("pages").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `pages`: expected string, found number");
+    let error = thesis.get_i64("rating").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `rating`: expected integer, found number");
+    assert!(thesis.get_datetime("university").is_err());
+
+    let mut untitled = thesis;
+    untitled.values.insert("title".into(), json!(["Understanding"]));
+    untitled.values.insert("author".into(), json!(["Alice Researcher", 7]));
+    let error = untitled.title().unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `title`: expected string, found array");
+    let error = untitled.authors().unwrap_err();
+    assert!(error
+      .to_string()
+      .starts_with("Invalid field `author[1]`: expected a name or an author"));
+  }
+
+  #[test]
+  fn test_new_validates_values() -> Result<()> {
+    let config: ResourceConfig =
+      toml::from_str(include_str!("../../config/resources/thesis.toml"))?;
+    let values = json!({
+      "title": "Understanding Quantum Computing Effects",
+      "author": ["Alice Researcher"],
+      "university": "Tech University",
+      "defense_date": "2024-06-15T14:00:00Z",
+    });
+    let record = Record::new(&config, values.as_object().unwrap().clone())?;
+    assert_eq!(record.resource_type(), "thesis");
+    assert_eq!(record.fields()?, record.values);
+
+    let mut untitled = record.values;
+    untitled.remove("title");
+    let error = Record::new(&config, untitled).unwrap_err();
+    assert!(matches!(error, LearnerError::InvalidField { ref field, .. } if field == "title"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_paper_authors() -> Result<()> {
+    let paper = Paper::builder()
+      .with_title("Attention Is All You Need")
+      .with_source("arxiv")
+      .with_source_identifier("1706.03762")
+      .with_author(Author {
+        name:        "Ashish Vaswani".to_string(),
+        affiliation: Some("Google Brain".to_string()),
+        email:       None,
+        orcid:       None,
+      })
+      .build()?;
+    let record = Record { type_name: paper.resource_type(), values: paper.fields()? };
+
+    assert_eq!(record.title()?, "Attention Is All You Need");
+    assert_eq!(record.authors()?, paper.authors);
+    Ok(())
+  }
+}
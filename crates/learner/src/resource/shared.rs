@@ -5,6 +5,8 @@
 //! and reduce duplication in how we represent common academic concepts like
 //! authorship, publication details, and citations.
 
+use chrono::{Datelike, TimeZone};
+
 use super::*;
 
 /// Author information for academic papers.
@@ -22,6 +24,7 @@ use super::*;
 ///   name:        "Alice Researcher".to_string(),
 ///   affiliation: Some("Example University".to_string()),
 ///   email:       Some("alice@example.edu".to_string()),
+///   orcid:       Some("0000-0002-1825-0097".to_string()),
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,4 +35,216 @@ pub struct Author {
   pub affiliation: Option<String>,
   /// Optional contact email
   pub email:       Option<String>,
+  /// Optional [ORCID](https://orcid.org) iD in its bare `0000-0000-0000-0000` form
+  #[serde(default)]
+  pub orcid:       Option<String>,
+}
+
+/// A publication date known to the year, the month, or in full.
+///
+/// Sources often report less than a full date (Crossref frequently gives only a year, or a
+/// year and month), and filling in the rest with January 1st would be misleading when sorting
+/// and displaying papers. Partial dates are kept as they are instead: they display and store as
+/// `2023` or `2023-05`, while full dates store as RFC 3339 timestamps, so dates stored before
+/// partial dates existed still load.
+///
+/// Dates order by the earliest instant they could refer to, with less precise dates first, so
+/// `2023` sorts before `2023-01`, which sorts before any full date in January 2023.
+///
+/// # Examples
+///
+/// ```
+/// use learner::resource::PartialDate;
+///
+/// let year: PartialDate = "2023".parse()?;
+/// let month: PartialDate = "2023-05".parse()?;
+/// let full: PartialDate = "2023-05-17T12:00:00Z".parse()?;
+///
+/// assert_eq!(year, PartialDate::Year(2023));
+/// assert_eq!(month.to_string(), "2023-05");
+/// assert_eq!(full.to_string(), "2023-05-17");
+/// assert!(year < month && month < full);
+/// # Ok::<(), learner::error::LearnerError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartialDate {
+  /// Only the year is known
+  Year(i32),
+  /// The year and month (1 to 12) are known
+  YearMonth(i32, u32),
+  /// The full date and time are known
+  Full(DateTime<Utc>),
+}
+
+impl PartialDate {
+  /// Returns the year.
+  pub fn year(&self) -> i32 {
+    match self {
+      Self::Year(year) | Self::YearMonth(year, _) => *year,
+      Self::Full(date) => date.year(),
+    }
+  }
+
+  /// Returns the month (1 to 12), unless only the year is known.
+  pub fn month(&self) -> Option<u32> {
+    match self {
+      Self::Year(_) => None,
+      Self::YearMonth(_, month) => Some(*month),
+      Self::Full(date) => Some(date.month()),
+    }
+  }
+
+  /// Returns whether only the year, or only the year and month, are known.
+  pub fn is_partial(&self) -> bool { !matches!(self, Self::Full(_)) }
+
+  /// Returns the earliest instant the date could refer to, e.g. midnight UTC on January 1st
+  /// for a bare year.
+  ///
+  /// This is what partial dates are compared by, including against full dates in filters such
+  /// as "published before".
+  pub fn earliest(&self) -> DateTime<Utc> {
+    let first_of = |year: i32, month: u32| {
+      Utc.with_ymd_and_hms(year, month.clamp(1, 12), 1, 0, 0, 0).single().unwrap_or_default()
+    };
+    match self {
+      Self::Year(year) => first_of(*year, 1),
+      Self::YearMonth(year, month) => first_of(*year, *month),
+      Self::Full(date) => *date,
+    }
+  }
+
+  /// Returns the lossless text form used for storage: `YYYY`, `YYYY-MM` or an RFC 3339
+  /// timestamp.
+  ///
+  /// Compared as text, these sort the same way the dates themselves do.
+  pub fn to_iso8601(&self) -> String {
+    match self {
+      Self::Year(year) => format!("{year:04}"),
+      Self::YearMonth(year, month) => format!("{year:04}-{month:02}"),
+      Self::Full(date) => date.to_rfc3339(),
+    }
+  }
+
+  /// Ranks how much of the date is known, for ordering dates with the same earliest instant.
+  fn precision(&self) -> u8 {
+    match self {
+      Self::Year(_) => 0,
+      Self::YearMonth(..) => 1,
+      Self::Full(_) => 2,
+    }
+  }
+}
+
+impl From<DateTime<Utc>> for PartialDate {
+  fn from(date: DateTime<Utc>) -> Self { Self::Full(date) }
+}
+
+impl Ord for PartialDate {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    self.earliest().cmp(&other.earliest()).then(self.precision().cmp(&other.precision()))
+  }
+}
+
+impl PartialOrd for PartialDate {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl std::fmt::Display for PartialDate {
+  /// Shows as much of the date as is known, as `YYYY`, `YYYY-MM` or `YYYY-MM-DD`.
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Full(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+      partial => f.write_str(&partial.to_iso8601()),
+    }
+  }
+}
+
+impl std::str::FromStr for PartialDate {
+  type Err = LearnerError;
+
+  /// Parses `YYYY`, `YYYY-MM`, `YYYY-MM-DD` (as midnight UTC) or an RFC 3339 timestamp.
+  fn from_str(s: &str) -> Result<Self> {
+    lazy_static! {
+      static ref PARTIAL_DATE: Regex = Regex::new(r"^(\d{4})(?:-(\d{1,2}))?$").unwrap();
+    };
+
+    let s = s.trim();
+    if let Some(captures) = PARTIAL_DATE.captures(s) {
+      let year =
+        captures[1].parse().map_err(|e| LearnerError::deserialize("publication date", e))?;
+      return match captures.get(2).map(|month| month.as_str().parse()) {
+        None => Ok(Self::Year(year)),
+        Some(Ok(month @ 1..=12)) => Ok(Self::YearMonth(year, month)),
+        Some(_) =>
+          Err(LearnerError::deserialize("publication date", format!("invalid month in '{s}'"))),
+      };
+    }
+
+    match chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+      Ok(date) => Ok(Self::Full(date.and_time(chrono::NaiveTime::MIN).and_utc())),
+      Err(_) => DateTime::parse_from_rfc3339(s)
+        .map(|date| Self::Full(date.with_timezone(&Utc)))
+        .map_err(|e| LearnerError::deserialize("publication date", e)),
+    }
+  }
+}
+
+impl Serialize for PartialDate {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_iso8601())
+  }
+}
+
+impl<'de> Deserialize<'de> for PartialDate {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> std::result::Result<Self, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_partial_date_parsing() {
+    assert_eq!("2023".parse::<PartialDate>().unwrap(), PartialDate::Year(2023));
+    assert_eq!("2023-5".parse::<PartialDate>().unwrap(), PartialDate::YearMonth(2023, 5));
+    assert_eq!("2023-05".parse::<PartialDate>().unwrap(), PartialDate::YearMonth(2023, 5));
+    assert!("2023-13".parse::<PartialDate>().is_err());
+    assert!("May 2023".parse::<PartialDate>().is_err());
+
+    // Full dates, including those stored before partial dates existed
+    let midnight = Utc.with_ymd_and_hms(2023, 5, 17, 0, 0, 0).unwrap();
+    assert_eq!("2023-05-17".parse::<PartialDate>().unwrap(), PartialDate::Full(midnight));
+    assert_eq!(
+      "2023-05-17T00:00:00+00:00".parse::<PartialDate>().unwrap(),
+      PartialDate::Full(midnight)
+    );
+  }
+
+  #[test]
+  fn test_partial_date_round_trips_and_orders() {
+    let dates = [
+      PartialDate::Year(2022),
+      PartialDate::Year(2023),
+      PartialDate::YearMonth(2023, 1),
+      PartialDate::Full(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+      PartialDate::YearMonth(2023, 2),
+    ];
+    assert!(dates.windows(2).all(|pair| pair[0] < pair[1]));
+    // The stored text sorts the same way
+    assert!(dates.windows(2).all(|pair| pair[0].to_iso8601() < pair[1].to_iso8601()));
+
+    for date in dates {
+      let json = serde_json::to_string(&date).unwrap();
+      assert_eq!(serde_json::from_str::<PartialDate>(&json).unwrap(), date);
+    }
+    assert_eq!(PartialDate::Year(2023).to_string(), "2023");
+    assert_eq!(
+      PartialDate::YearMonth(2023, 1).earliest().to_rfc3339(),
+      "2023-01-01T00:00:00+00:00"
+    );
+  }
 }
@@ -0,0 +1,514 @@
+//! Checking resources against the field definitions of a [`ResourceConfig`].
+//!
+//! The definitions are read the same way as for [`ResourceConfig::to_json_schema`]:
+//! [`ResourceConfig::validate`] checks that a resource has every required field and that each
+//! value has its field's `type`, and [`ResourceConfig::coerce`] first converts values from
+//! sources that send everything as strings, for fields that opt in with `coerce = true`.
+
+use super::*;
+
+impl ResourceConfig {
+  /// Checks `resource` against the field definitions of this configuration.
+  ///
+  /// Every field with `required = true` must be present, as must a field with
+  /// `required_if = [sibling, value]` whenever its sibling field holds that value. Every present
+  /// field must hold a value of its `type`, where an `integer` is a number without a fractional
+  /// part, and stay within its `min` and `max`. These bound a number's value, a string's length
+  /// counted in characters (Unicode scalar values) rather than bytes, so `max = 10` allows ten
+  /// emoji, and an array's number of elements. A string with a `format` of `email`, `uri` or
+  /// `isbn` (ISBN-10 or ISBN-13, with its check digit) must be one; other formats are only
+  /// described in the [JSON Schema](ResourceConfig::to_json_schema). Array `items` and the nested
+  /// `fields` of objects are checked the same way. Fields without a definition are not checked.
+  ///
+  /// # Errors
+  ///
+  /// - [`LearnerError::InvalidField`] for the first field that fails its definition
+  /// - [`LearnerError::Config`] if a definition declares an unknown type or a malformed
+  ///   `required_if`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::ResourceConfig;
+  /// # use serde_json::json;
+  /// let config: ResourceConfig = toml::from_str(
+  ///   "type_name = \"note\"\n[fields]\ntitle = { type = \"string\", required = true }",
+  /// )?;
+  /// let note = json!({ "title": "Reading list" });
+  /// assert!(config.validate(note.as_object().unwrap()).is_ok());
+  ///
+  /// let untitled = json!({ "title": 7 });
+  /// assert!(config.validate(untitled.as_object().unwrap()).is_err());
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn validate(&self, resource: &Map<String, Value>) -> Result<()> {
+    validate_fields("", &self.fields, resource)
+  }
+
+  /// Converts the string values of coercing fields into their field's type.
+  ///
+  /// APIs often send numbers and booleans as strings (`"2023"`, `"true"`). Fields defined with
+  /// `coerce = true` accept those: a `number` or `integer` field has a numeric string parsed into
+  /// a number, and a `boolean` field has `"true"` or `"false"` turned into a boolean. Values
+  /// that do not parse, and every value of a field without `coerce`, are kept as they are so
+  /// that [`validate`](ResourceConfig::validate) reports them.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::resource::ResourceConfig;
+  /// # use serde_json::json;
+  /// let config: ResourceConfig = toml::from_str(
+  ///   "type_name = \"note\"\n[fields]\nyear = { type = \"number\", coerce = true }",
+  /// )?;
+  /// let note = json!({ "year": "2023" });
+  /// let note = config.coerce(note.as_object().unwrap().clone());
+  /// assert_eq!(note["year"], 2023);
+  /// assert!(config.validate(&note).is_ok());
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn coerce(&self, resource: Map<String, Value>) -> Map<String, Value> {
+    coerce_fields(&self.fields, resource)
+  }
+}
+
+/// Checks the fields under `prefix` of `resource` against their `definitions`.
+fn validate_fields(
+  prefix: &str,
+  definitions: &Map<String, Value>,
+  resource: &Map<String, Value>,
+) -> Result<()> {
+  for (name, definition) in definitions {
+    let field = if prefix.is_empty() { name.clone() } else { format!("{prefix}.{name}") };
+    match resource.get(name) {
+      Some(value) => validate_value(&field, definition, value)?,
+      None if definition.get("required").and_then(Value::as_bool).unwrap_or(false) =>
+        return Err(invalid(&field, "the field is required")),
+      None =>
+        if let Some((sibling, value)) = required_if(&field, definition)? {
+          if resource.get(sibling) == Some(value) {
+            return Err(invalid(
+              &field,
+              format!("the field is required when `{sibling}` is {value}"),
+            ));
+          }
+        },
+    }
+  }
+  Ok(())
+}
+
+/// Reads the `required_if = [sibling, value]` condition of a definition, if it has one.
+///
+/// # Errors
+///
+/// Returns [`LearnerError::Config`] if the condition is not a sibling name and a value.
+fn required_if<'a>(field: &str, definition: &'a Value) -> Result<Option<(&'a str, &'a Value)>> {
+  let Some(condition) = definition.get("required_if") else {
+    return Ok(None);
+  };
+  match condition.as_array().map(Vec::as_slice) {
+    Some([Value::String(sibling), value]) => Ok(Some((sibling, value))),
+    _ => Err(LearnerError::Config(format!(
+      "Field `{field}` has a `required_if` that is not a field name and a value"
+    ))),
+  }
+}
+
+/// Checks a single value of `field` against its definition.
+fn validate_value(field: &str, definition: &Value, value: &Value) -> Result<()> {
+  let Some(field_type) = definition.get("type").and_then(Value::as_str) else {
+    return Ok(());
+  };
+  let matches = match field_type {
+    "string" => value.is_string(),
+    "number" => value.is_number(),
+    "integer" => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+    "boolean" => value.is_boolean(),
+    "array" => value.is_array(),
+    "object" => value.is_object(),
+    _ =>
+      return Err(LearnerError::Config(format!(
+        "Field `{field}` has unknown type `{field_type}`, expected one of: {}",
+        SCHEMA_TYPES.join(", ")
+      ))),
+  };
+  if !matches {
+    // A number only fails an integer field by being fractional, so show which one it was
+    let found = match value {
+      Value::Number(number) if field_type == "integer" => number.to_string(),
+      value => type_of(value).to_string(),
+    };
+    return Err(invalid(field, format!("expected {field_type}, found {found}")));
+  }
+
+  let measured = match value {
+    Value::String(text) => Some((text.chars().count() as f64, " characters")),
+    Value::Array(values) => Some((values.len() as f64, " elements")),
+    Value::Number(number) => number.as_f64().map(|number| (number, "")),
+    _ => None,
+  };
+  if let Some((measured, unit)) = measured {
+    let bound = |key| definition.get(key).and_then(Value::as_f64);
+    if let Some(min) = bound("min").filter(|min| measured < *min) {
+      return Err(invalid(field, format!("expected at least {min}{unit}, found {measured}")));
+    }
+    if let Some(max) = bound("max").filter(|max| measured > *max) {
+      return Err(invalid(field, format!("expected at most {max}{unit}, found {measured}")));
+    }
+  }
+
+  if let (Some(format), Value::String(text)) =
+    (definition.get("format").and_then(Value::as_str), value)
+  {
+    if !has_format(format, text) {
+      return Err(invalid(field, format!("`{text}` is not a valid {format}")));
+    }
+  }
+
+  if let (Some(items), Value::Array(values)) = (definition.get("items"), value) {
+    let items = match items {
+      Value::String(item_type) => serde_json::json!({ "type": item_type }),
+      items => items.clone(),
+    };
+    for (index, value) in values.iter().enumerate() {
+      validate_value(&format!("{field}[{index}]"), &items, value)?;
+    }
+  }
+  if let (Some(Value::Object(fields)), Value::Object(values)) = (definition.get("fields"), value) {
+    validate_fields(field, fields, values)?;
+  }
+  Ok(())
+}
+
+/// Whether `text` is written in the named `format`, which holds for formats without a check.
+fn has_format(format: &str, text: &str) -> bool {
+  lazy_static! {
+    static ref EMAIL: Regex = Regex::new(r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)+$").unwrap();
+  }
+
+  match format {
+    "email" => EMAIL.is_match(text),
+    "uri" => Url::parse(text).is_ok(),
+    "isbn" => is_isbn(text),
+    _ => true,
+  }
+}
+
+/// Whether `text` is an ISBN-10 or ISBN-13 with a correct check digit, ignoring hyphens and
+/// spaces.
+fn is_isbn(text: &str) -> bool {
+  let digits: Vec<char> = text.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+  let value = |c: &char| c.to_digit(10);
+  match digits.as_slice() {
+    [body @ .., check] if digits.len() == 10 => {
+      let check = if matches!(check, 'X' | 'x') { Some(10) } else { value(check) };
+      let body: Option<Vec<u32>> = body.iter().map(value).collect();
+      let (Some(body), Some(check)) = (body, check) else {
+        return false;
+      };
+      let sum: u32 = body.iter().zip((2..=10).rev()).map(|(digit, weight)| digit * weight).sum();
+      (sum + check).is_multiple_of(11)
+    },
+    _ if digits.len() == 13 => {
+      let Some(digits) = digits.iter().map(value).collect::<Option<Vec<u32>>>() else {
+        return false;
+      };
+      let sum: u32 =
+        digits.iter().zip([1, 3].into_iter().cycle()).map(|(digit, weight)| digit * weight).sum();
+      sum.is_multiple_of(10)
+    },
+    _ => false,
+  }
+}
+
+/// Coerces the values in `resource` whose definitions in `definitions` allow it.
+fn coerce_fields(
+  definitions: &Map<String, Value>,
+  mut resource: Map<String, Value>,
+) -> Map<String, Value> {
+  for (name, definition) in definitions {
+    if let Some(value) = resource.get_mut(name) {
+      *value = coerce_value(definition, value.take());
+    }
+  }
+  resource
+}
+
+/// Coerces `value` into the type of its definition, if the definition has `coerce = true`.
+fn coerce_value(definition: &Value, value: Value) -> Value {
+  match value {
+    Value::String(text) if definition.get("coerce").and_then(Value::as_bool).unwrap_or(false) => {
+      let coerced = match definition.get("type").and_then(Value::as_str) {
+        Some("number" | "integer") =>
+          text.trim().parse::<serde_json::Number>().ok().map(Value::Number),
+        Some("boolean") => match text.trim() {
+          "true" => Some(Value::Bool(true)),
+          "false" => Some(Value::Bool(false)),
+          _ => None,
+        },
+        _ => None,
+      };
+      coerced.unwrap_or(Value::String(text))
+    },
+    Value::Object(values) => match definition.get("fields") {
+      Some(Value::Object(fields)) => Value::Object(coerce_fields(fields, values)),
+      _ => Value::Object(values),
+    },
+    Value::Array(values) => match definition.get("items") {
+      Some(Value::Object(items)) => {
+        let items = Value::Object(items.clone());
+        Value::Array(values.into_iter().map(|value| coerce_value(&items, value)).collect())
+      },
+      _ => Value::Array(values),
+    },
+    value => value,
+  }
+}
+
+/// Builds the [`LearnerError::InvalidField`] for `field`.
+pub(super) fn invalid(field: &str, reason: impl Into<String>) -> LearnerError {
+  LearnerError::InvalidField { field: field.to_string(), reason: reason.into() }
+}
+
+/// Names the JSON type of `value` for error messages.
+pub(super) fn type_of(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn config(fields: &str) -> ResourceConfig {
+    toml::from_str(&format!("type_name = \"record\"\n[fields]\n{fields}")).unwrap()
+  }
+
+  fn resource(value: Value) -> Map<String, Value> { value.as_object().unwrap().clone() }
+
+  #[test]
+  fn test_thesis_validates() -> Result<()> {
+    let config: ResourceConfig =
+      toml::from_str(include_str!("../../config/resources/thesis.toml"))?;
+    let thesis = resource(json!({
+      "title": "Understanding Quantum Computing Effects",
+      "author": ["Alice Researcher"],
+      "university": "Tech University",
+    }));
+    config.validate(&thesis)?;
+
+    let mut missing = thesis.clone();
+    missing.remove("university");
+    let error = config.validate(&missing).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `university`: the field is required");
+
+    let mut mistyped = thesis;
+    mistyped.insert("author".into(), json!(["Alice Researcher", 42]));
+    let error = config.validate(&mistyped).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `author[1]`: expected string, found number");
+    Ok(())
+  }
+
+  #[test]
+  fn test_required_if_sibling_matches() {
+    let config = config(
+      r#"
+      has_pdf = { type = "boolean" }
+      pdf_url = { type = "string", required_if = ["has_pdf", true] }
+      "#,
+    );
+
+    let error = config.validate(&resource(json!({ "has_pdf": true }))).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Invalid field `pdf_url`: the field is required when `has_pdf` is true"
+    );
+    let with_pdf =
+      resource(json!({ "has_pdf": true, "pdf_url": "https://arxiv.org/pdf/2301.07041" }));
+    assert!(config.validate(&with_pdf).is_ok());
+  }
+
+  #[test]
+  fn test_required_if_sibling_differs() {
+    let config = config(
+      r#"
+      has_pdf = { type = "boolean" }
+      pdf_url = { type = "string", required_if = ["has_pdf", true] }
+      "#,
+    );
+
+    assert!(config.validate(&resource(json!({ "has_pdf": false }))).is_ok());
+    assert!(config.validate(&resource(json!({}))).is_ok());
+
+    let malformed = self::config(r#"pdf_url = { type = "string", required_if = "has_pdf" }"#);
+    assert!(matches!(malformed.validate(&resource(json!({}))), Err(LearnerError::Config(_))));
+  }
+
+  #[test]
+  fn test_length_counts_characters() {
+    let config = config(
+      r#"
+      title = { type = "string", min = 2, max = 10 }
+      tags  = { type = "array", items = "string", max = 2 }
+      "#,
+    );
+
+    // 4 characters, but 16 bytes
+    let emoji = "🦀🦀🦀🦀";
+    assert!(emoji.len() > 10);
+    assert!(config.validate(&resource(json!({ "title": emoji }))).is_ok());
+
+    let error = config.validate(&resource(json!({ "title": "ž" }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `title`: expected at least 2 characters, found 1");
+    let error = config.validate(&resource(json!({ "title": "ü".repeat(11) }))).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Invalid field `title`: expected at most 10 characters, found 11"
+    );
+    let error = config.validate(&resource(json!({ "tags": ["a", "b", "c"] }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `tags`: expected at most 2 elements, found 3");
+  }
+
+  #[test]
+  fn test_integer_rejects_fractions() {
+    let config = config(
+      r#"
+      year  = { type = "integer", min = 1900, max = 2100 }
+      score = { type = "number", max = 10 }
+      "#,
+    );
+
+    assert!(config.validate(&resource(json!({ "year": 2023, "score": 7.5 }))).is_ok());
+    assert!(config.validate(&resource(json!({ "year": 2023.0 }))).is_ok());
+
+    let error = config.validate(&resource(json!({ "year": 2023.5 }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `year`: expected integer, found 2023.5");
+    let error = config.validate(&resource(json!({ "year": 1850 }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `year`: expected at least 1900, found 1850");
+    let error = config.validate(&resource(json!({ "score": 10.5 }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `score`: expected at most 10, found 10.5");
+  }
+
+  #[test]
+  fn test_integer_coercion() {
+    let config = config(r#"pages = { type = "integer", coerce = true }"#);
+
+    let coerced = config.coerce(resource(json!({ "pages": "2023" })));
+    assert_eq!(coerced["pages"], json!(2023));
+    assert!(config.validate(&coerced).is_ok());
+
+    let fractional = config.coerce(resource(json!({ "pages": "2023.5" })));
+    assert!(config.validate(&fractional).is_err());
+  }
+
+  #[test]
+  fn test_formats() {
+    let config = config(
+      r#"
+      email = { type = "string", format = "email" }
+      url   = { type = "string", format = "uri" }
+      isbn  = { type = "string", format = "isbn" }
+      date  = { type = "string", format = "date-time" }
+      "#,
+    );
+    let valid =
+      |field: &str, value: &str| config.validate(&resource(json!({ field: value }))).is_ok();
+
+    assert!(valid("email", "alice@tech.edu"));
+    assert!(valid("email", "alice.researcher+thesis@cs.tech.edu"));
+    assert!(!valid("email", "alice"));
+    assert!(!valid("email", "alice@localhost"));
+    assert!(!valid("email", "alice smith@tech.edu"));
+
+    assert!(valid("url", "https://arxiv.org/abs/2301.07041"));
+    assert!(valid("url", "doi:10.1145/1327452.1327492"));
+    assert!(!valid("url", "arxiv.org/abs/2301.07041"));
+
+    assert!(valid("isbn", "978-0-262-03384-8"));
+    assert!(valid("isbn", "0-262-03384-4"));
+    assert!(valid("isbn", "0 8044 2957 X"));
+    assert!(!valid("isbn", "978-0-262-03384-9"));
+    assert!(!valid("isbn", "0-262-03384-5"));
+    assert!(!valid("isbn", "978-0-262"));
+
+    // Formats without a built-in check are accepted
+    assert!(valid("date", "next tuesday"));
+
+    let error = config.validate(&resource(json!({ "isbn": "12345" }))).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `isbn`: `12345` is not a valid isbn");
+  }
+
+  #[test]
+  fn test_book_validates() -> Result<()> {
+    let config: ResourceConfig = toml::from_str(include_str!("../../config/resources/book.toml"))?;
+    let book = config.coerce(resource(json!({
+      "title": "Introduction to Algorithms",
+      "author": ["Thomas H. Cormen", "Charles E. Leiserson"],
+      "isbn": "978-0-262-03384-8",
+      "edition": "3",
+      "year": "2009",
+    })));
+    config.validate(&book)?;
+    assert_eq!(book["year"], json!(2009));
+
+    let mut misprinted = book;
+    misprinted.insert("isbn".into(), json!("978-0-262-03384-0"));
+    assert!(config.validate(&misprinted).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_numeric_string_coerces_only_when_allowed() {
+    let config = config(
+      r#"
+      pages = { type = "number", coerce = true }
+      year  = { type = "number" }
+      "#,
+    );
+
+    let coerced = config.coerce(resource(json!({ "pages": "42" })));
+    assert_eq!(coerced["pages"], json!(42));
+    assert!(config.validate(&coerced).is_ok());
+
+    let strict = config.coerce(resource(json!({ "year": "42" })));
+    assert_eq!(strict["year"], json!("42"));
+    let error = config.validate(&strict).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid field `year`: expected number, found string");
+  }
+
+  #[test]
+  fn test_coercion_of_booleans_and_nested_fields() {
+    let config = config(
+      r#"
+      open  = { type = "boolean", coerce = true }
+      venue = { type = "object", fields = { volume = { type = "number", coerce = true } } }
+      pages = { type = "array", items = { type = "integer", coerce = true } }
+      "#,
+    );
+
+    let coerced = config.coerce(resource(json!({
+      "open": "true",
+      "venue": { "volume": " 3.5 " },
+      "pages": ["12", 13],
+    })));
+    assert_eq!(coerced["open"], json!(true));
+    assert_eq!(coerced["venue"]["volume"], json!(3.5));
+    assert_eq!(coerced["pages"], json!([12, 13]));
+    assert!(config.validate(&coerced).is_ok());
+
+    // Strings that do not parse are left for validation to report
+    let unparsed = config.coerce(resource(json!({ "open": "yes", "venue": { "volume": "iii" } })));
+    assert_eq!(unparsed["open"], json!("yes"));
+    assert_eq!(unparsed["venue"]["volume"], json!("iii"));
+    assert!(config.validate(&unparsed).is_err());
+  }
+}
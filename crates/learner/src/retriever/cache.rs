@@ -0,0 +1,146 @@
+//! Responses kept for conditional revalidation, see [`RetrieverConfig`].
+//!
+//! The cache is bounded both in entries and in the bytes of the bodies it holds, and evicts
+//! the least recently used responses to stay within them.
+
+use super::*;
+
+/// Most responses a [`ResponseCache`] keeps.
+const MAX_ENTRIES: usize = 256;
+
+/// Most bytes of response bodies a [`ResponseCache`] keeps.
+const MAX_BYTES: usize = 32 * 1024 * 1024;
+
+/// Responses with an `ETag` or `Last-Modified` validator, keyed by request URL.
+///
+/// Clones share the same entries.
+#[derive(Debug, Clone)]
+pub(super) struct ResponseCache {
+  /// The cached responses
+  entries:   Arc<Mutex<Entries>>,
+  /// Most responses to keep
+  max_count: usize,
+  /// Most bytes of bodies to keep
+  max_bytes: usize,
+}
+
+impl Default for ResponseCache {
+  fn default() -> Self { Self::with_limits(MAX_ENTRIES, MAX_BYTES) }
+}
+
+/// The contents of a [`ResponseCache`].
+#[derive(Debug, Default)]
+struct Entries {
+  /// Each response, with the tick it was last used at
+  responses: HashMap<String, (CachedResponse, u64)>,
+  /// Advances with every use, ordering the responses by recency
+  tick:      u64,
+  /// Bytes of bodies held
+  bytes:     usize,
+}
+
+/// A response body kept so a later request for the same URL can be revalidated.
+///
+/// Requests for a cached URL send `If-None-Match`/`If-Modified-Since`, and a
+/// `304 Not Modified` answer reuses [`body`](CachedResponse::body) instead of downloading it again.
+#[derive(Debug, Clone)]
+pub(super) struct CachedResponse {
+  /// `ETag` header of the cached response
+  pub(super) etag:          Option<String>,
+  /// `Last-Modified` header of the cached response
+  pub(super) last_modified: Option<String>,
+  /// `Content-Type` header of the cached response, used to decode the body
+  pub(super) content_type:  Option<String>,
+  /// Raw response body
+  pub(super) body:          Vec<u8>,
+}
+
+impl ResponseCache {
+  /// Creates an empty cache holding at most `max_count` responses and `max_bytes` of bodies.
+  fn with_limits(max_count: usize, max_bytes: usize) -> Self {
+    Self { entries: Arc::default(), max_count, max_bytes }
+  }
+
+  /// Returns the response cached for `url`, marking it as recently used.
+  pub(super) fn get(&self, url: &str) -> Option<CachedResponse> {
+    let mut entries = self.entries.lock().unwrap();
+    entries.tick += 1;
+    let tick = entries.tick;
+    let (response, used) = entries.responses.get_mut(url)?;
+    *used = tick;
+    Some(response.clone())
+  }
+
+  /// Caches `response` for `url`, evicting the least recently used responses while over the
+  /// limits. Bodies too large to fit at all are not cached.
+  pub(super) fn insert(&self, url: &str, response: CachedResponse) {
+    let mut entries = self.entries.lock().unwrap();
+    if let Some((replaced, _)) = entries.responses.remove(url) {
+      entries.bytes -= replaced.body.len();
+    }
+    if response.body.len() > self.max_bytes {
+      return;
+    }
+    while entries.responses.len() >= self.max_count
+      || entries.bytes + response.body.len() > self.max_bytes
+    {
+      let Some(oldest) =
+        entries.responses.iter().min_by_key(|(_, (_, used))| *used).map(|(url, _)| url.clone())
+      else {
+        break;
+      };
+      let (evicted, _) = entries.responses.remove(&oldest).expect("the oldest entry exists");
+      entries.bytes -= evicted.body.len();
+    }
+    entries.tick += 1;
+    let tick = entries.tick;
+    entries.bytes += response.body.len();
+    entries.responses.insert(url.to_string(), (response, tick));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn response(body: &[u8]) -> CachedResponse {
+    CachedResponse {
+      etag:          Some("\"v1\"".to_string()),
+      last_modified: None,
+      content_type:  None,
+      body:          body.to_vec(),
+    }
+  }
+
+  #[test]
+  fn test_evicts_least_recently_used_entry() {
+    let cache = ResponseCache::with_limits(2, 1024);
+    cache.insert("a", response(b"a"));
+    cache.insert("b", response(b"b"));
+    // Reading `a` makes `b` the least recently used
+    assert!(cache.get("a").is_some());
+    cache.insert("c", response(b"c"));
+
+    assert!(cache.get("b").is_none());
+    assert_eq!(cache.get("a").unwrap().body, b"a");
+    assert_eq!(cache.get("c").unwrap().body, b"c");
+  }
+
+  #[test]
+  fn test_evicts_to_stay_within_bytes() {
+    let cache = ResponseCache::with_limits(10, 8);
+    cache.insert("a", response(b"aaaa"));
+    cache.insert("b", response(b"bbbb"));
+    cache.insert("c", response(b"cc"));
+    assert!(cache.get("a").is_none());
+    assert!(cache.get("b").is_some());
+    assert_eq!(cache.entries.lock().unwrap().bytes, 6);
+
+    // Replacing an entry frees its old body, and a body over the limit is not kept
+    cache.insert("b", response(b"b"));
+    assert_eq!(cache.entries.lock().unwrap().bytes, 3);
+    cache.insert("d", response(b"ddddddddd"));
+    assert!(cache.get("d").is_none());
+    assert!(cache.get("c").is_some());
+  }
+}
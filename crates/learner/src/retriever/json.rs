@@ -15,6 +15,8 @@
 //! abstract = { path = "message/abstract" }
 //! publication_date = { path = "message/published-print/date-parts/0" }
 //! authors = { path = "message/author" }
+//! references = { path = "message/reference" }
+//! keywords = { path = "message/subject" }
 //! ```
 
 use serde_json::Value;
@@ -34,6 +36,7 @@ use super::*;
 /// let config = JsonConfig {
 ///   field_maps: HashMap::from([("title".to_string(), FieldMap {
 ///     path:      "message/title/0".to_string(),
+///     fallback:  None,
 ///     transform: None,
 ///   })]),
 /// };
@@ -68,18 +71,27 @@ impl ResponseProcessor for JsonConfig {
   /// - JSON parsing fails
   /// - Required fields are missing
   /// - Field values are invalid or cannot be transformed
-  async fn process_response(&self, data: &[u8]) -> Result<Paper> {
-    let json: Value = serde_json::from_slice(data)
-      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+  async fn process_response(&self, data: &[u8]) -> Result<Paper> { self.process(data, false) }
+}
+
+impl JsonConfig {
+  /// Parses a response for a single paper, or one result of a search when `search_result` is
+  /// set.
+  ///
+  /// Search results often leave out the abstract, so for them it is optional and defaults to
+  /// an empty string.
+  pub(crate) fn process(&self, data: &[u8], search_result: bool) -> Result<Paper> {
+    let json: Value =
+      serde_json::from_slice(data).map_err(|e| LearnerError::deserialize("JSON response", e))?;
 
     trace!("Processing JSON response: {}", serde_json::to_string_pretty(&json).unwrap());
 
     let title = self.extract_field(&json, "title")?;
-    let abstract_text = self.extract_field(&json, "abstract")?;
-    let publication_date =
-      chrono::DateTime::parse_from_rfc3339(&self.extract_field(&json, "publication_date")?)
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| LearnerError::ApiError(format!("Invalid date format: {}", e)))?;
+    let abstract_text = match self.field_maps.get("abstract") {
+      Some(map) if search_result && self.get_by_path(&json, &map.path).is_none() => String::new(),
+      _ => self.extract_field(&json, "abstract")?,
+    };
+    let publication_date = self.extract_date(&json)?;
 
     let authors = if let Some(map) = self.field_maps.get("authors") {
       self.extract_authors(&json, map)?
@@ -101,7 +113,40 @@ impl ResponseProcessor for JsonConfig {
       .field_maps
       .get("doi")
       .and_then(|map| self.get_by_path(&json, &map.path))
-      .map(String::from);
+      .map(|doi| format::normalize_doi(&doi).unwrap_or(doi));
+
+    let version = self.field_maps.get("version").and_then(|map| {
+      self.get_by_path(&json, &map.path).map(|version| match &map.transform {
+        Some(transform) => apply_transform(&version, transform).unwrap_or(version),
+        None => version,
+      })
+    });
+
+    let primary_category = self.field_maps.get("primary_category").and_then(|map| {
+      self.get_by_path(&json, &map.path).map(|category| match &map.transform {
+        Some(transform) => apply_transform(&category, transform).unwrap_or(category),
+        None => category,
+      })
+    });
+
+    let references = self
+      .field_maps
+      .get("references")
+      .map(|map| self.extract_references(&json, map))
+      .unwrap_or_default();
+
+    let keywords = self
+      .field_maps
+      .get("keywords")
+      .map(|map| self.extract_keywords(&json, map))
+      .unwrap_or_default();
+
+    // Only used by search results, which carry their own identifier
+    let source_identifier = self
+      .field_maps
+      .get("identifier")
+      .and_then(|map| self.get_by_path(&json, &map.path))
+      .unwrap_or_default();
 
     Ok(Paper {
       title,
@@ -109,14 +154,68 @@ impl ResponseProcessor for JsonConfig {
       abstract_text,
       publication_date,
       source: String::new(),
-      source_identifier: String::new(),
+      source_identifier,
       pdf_url,
       doi,
+      references,
+      keywords,
+      version,
+      primary_category,
+      language: None,
     })
   }
-}
 
-impl JsonConfig {
+  /// Extracts a single mapped field from a response, without parsing the rest of the paper.
+  ///
+  /// Returns `None` if the field has no mapping or the response has no content for it.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the response is not JSON, or the error of a
+  /// failing transform.
+  pub(crate) fn extract_one(&self, data: &[u8], field: &str) -> Result<Option<String>> {
+    let Some(map) = self.field_maps.get(field) else {
+      return Ok(None);
+    };
+    let json: Value =
+      serde_json::from_slice(data).map_err(|e| LearnerError::deserialize("JSON response", e))?;
+    match (self.get_by_path(&json, &map.path), &map.transform) {
+      (Some(value), Some(transform)) => apply_transform(&value, transform).map(Some),
+      (value, None) => Ok(value),
+      (None, _) => Ok(None),
+    }
+  }
+
+  /// Splits a search response into one response per result.
+  ///
+  /// The array at `path` holds the results, and each one is wrapped in the objects leading to
+  /// it, so with `message/items` every item becomes `{"message": item}` and is read with the
+  /// same field maps as a single-paper `message`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the response is not JSON, or
+  /// [`LearnerError::ApiError`] if there is no array at `path`.
+  pub(crate) fn split_results(&self, data: &[u8], path: &str) -> Result<Vec<Vec<u8>>> {
+    let json: Value =
+      serde_json::from_slice(data).map_err(|e| LearnerError::deserialize("JSON response", e))?;
+    let Some(Value::Array(items)) = get_path_value(&json, path) else {
+      return Err(LearnerError::ApiError(format!("No search results found at {path}")));
+    };
+    let parents: Vec<&str> = path.split('/').collect();
+    let parents = &parents[..parents.len() - 1];
+
+    items
+      .iter()
+      .map(|item| {
+        let wrapped = parents.iter().rev().fold(item.clone(), |value, key| {
+          Value::Object(serde_json::Map::from_iter([(key.to_string(), value)]))
+        });
+        serde_json::to_vec(&wrapped).map_err(|e| LearnerError::deserialize("JSON response", e))
+      })
+      .collect()
+  }
+
   /// Extracts a single field value using configured mapping.
   ///
   /// # Errors
@@ -131,8 +230,9 @@ impl JsonConfig {
       .get(field)
       .ok_or_else(|| LearnerError::ApiError(format!("Missing field mapping for {}", field)))?;
 
-    let value = self
-      .get_by_path(json, &map.path)
+    let value = map
+      .paths()
+      .find_map(|path| self.get_by_path(json, path))
       .ok_or_else(|| LearnerError::ApiError(format!("No content found for {}", field)))?;
 
     if let Some(transform) = &map.transform {
@@ -142,6 +242,43 @@ impl JsonConfig {
     }
   }
 
+  /// Extracts the publication date, keeping only as much of it as the source gives.
+  ///
+  /// Besides date strings, this accepts Crossref-style `date-parts` such as `[2008]`,
+  /// `[2008, 1]` or `[2008, 1, 15]`. An unknown date such as `[null]` moves on to the
+  /// mapping's [`FieldMap::fallback`].
+  fn extract_date(&self, json: &Value) -> Result<PartialDate> {
+    let map = self
+      .field_maps
+      .get("publication_date")
+      .ok_or_else(|| LearnerError::ApiError("Missing field mapping for publication_date".into()))?;
+
+    for path in map.paths() {
+      let date = match get_path_value(json, path) {
+        Some(Value::Array(parts)) => {
+          // Crossref reports unknown dates as `[null]`, which falls through to the fallback
+          let parts = parts.iter().map(Value::as_u64).collect::<Option<Vec<_>>>();
+          match parts.as_deref() {
+            Some([year]) => format!("{year:04}"),
+            Some([year, month]) => format!("{year:04}-{month:02}"),
+            Some([year, month, day, ..]) => format!("{year:04}-{month:02}-{day:02}"),
+            _ => continue,
+          }
+        },
+        Some(_) => match self.get_by_path(json, path) {
+          Some(date) => match &map.transform {
+            Some(transform) => apply_transform(&date, transform)?,
+            None => date,
+          },
+          None => continue,
+        },
+        None => continue,
+      };
+      return date.parse();
+    }
+    Err(LearnerError::ApiError("No content found for publication_date".into()))
+  }
+
   /// Retrieves a value from JSON using slash-separated path.
   ///
   /// Supports both object key and array index access:
@@ -150,17 +287,7 @@ impl JsonConfig {
   ///
   /// Handles string, array, and number values with appropriate conversion.
   fn get_by_path(&self, json: &Value, path: &str) -> Option<String> {
-    let mut current = json;
-
-    for part in path.split('/') {
-      current = if let Ok(index) = part.parse::<usize>() {
-        // Handle numeric indices for arrays
-        current.as_array()?.get(index)?
-      } else {
-        // Handle regular object keys
-        current.get(part)?
-      };
-    }
+    let current = get_path_value(json, path)?;
 
     match current {
       Value::String(s) => Some(s.clone()),
@@ -170,10 +297,49 @@ impl JsonConfig {
     }
   }
 
+  /// Extracts the DOIs of cited works from JSON.
+  ///
+  /// Expects an array at the configured path whose entries are either DOI strings or
+  /// objects with a `DOI` key, as in Crossref's `reference` list. DOIs are normalized
+  /// with [`format::normalize_doi`], and entries without one (e.g. unstructured citations) are
+  /// skipped.
+  fn extract_references(&self, json: &Value, map: &FieldMap) -> Vec<String> {
+    let Some(Value::Array(arr)) = get_path_value(json, &map.path) else {
+      return Vec::new();
+    };
+
+    let mut references: Vec<String> = Vec::new();
+    for entry in arr {
+      let doi = match entry {
+        Value::String(doi) => Some(doi.as_str()),
+        _ => entry.get("DOI").and_then(|doi| doi.as_str()),
+      };
+      if let Some(doi) = doi.and_then(format::normalize_doi) {
+        if !references.contains(&doi) {
+          references.push(doi);
+        }
+      }
+    }
+    references
+  }
+
+  /// Extracts subject keywords from JSON.
+  ///
+  /// The configured path may hold an array of strings, as in Crossref's `subject` list, or a
+  /// single string.
+  fn extract_keywords(&self, json: &Value, map: &FieldMap) -> Vec<String> {
+    let values = match get_path_value(json, &map.path) {
+      Some(Value::Array(arr)) => arr.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+      Some(Value::String(keyword)) => vec![keyword.clone()],
+      _ => Vec::new(),
+    };
+    collect_keywords(values, map)
+  }
+
   /// Extracts and processes author information from JSON.
   ///
   /// Handles author objects with given/family name fields and optional
-  /// affiliation information. Expects authors as an array matching the
+  /// affiliation and `ORCID` information. Expects authors as an array matching the
   /// configured path.
   ///
   /// # Errors
@@ -201,7 +367,9 @@ impl JsonConfig {
             .and_then(|n| n.as_str())
             .map(String::from);
 
-          Some(Author { name, affiliation, email: None })
+          let orcid = author.get("ORCID").and_then(|o| o.as_str()).and_then(normalize_orcid);
+
+          Some(Author { name, affiliation, email: None, orcid })
         })
         .collect()
     } else {
@@ -219,11 +387,47 @@ impl JsonConfig {
 /// Helper function to navigate JSON structure using path.
 ///
 /// Similar to get_by_path but returns raw JSON Value instead of
-/// converted string.
+/// converted string. Numeric path parts index into arrays.
 fn get_path_value<'a>(json: &'a Value, path: &str) -> Option<&'a Value> {
   let mut current = json;
   for part in path.split('/') {
-    current = current.get(part)?;
+    current = match (part.parse::<usize>(), current) {
+      (Ok(index), Value::Array(values)) => values.get(index)?,
+      _ => current.get(part)?,
+    };
   }
   Some(current)
 }
+
+/// Reduces an ORCID iD to its canonical `0000-0002-1825-0097` form, accepting values like
+/// `http://orcid.org/0000-0002-1825-0097` as returned by Crossref, with or without dashes.
+///
+/// Returns `None` if the value does not look like an ORCID iD.
+pub(super) fn normalize_orcid(value: &str) -> Option<String> {
+  let id = value.trim().trim_end_matches('/').rsplit('/').next()?.to_uppercase();
+  let digits: Vec<char> = id.chars().filter(|c| *c != '-').collect();
+  let valid = digits.len() == 16
+    && digits[..15].iter().all(char::is_ascii_digit)
+    && (digits[15].is_ascii_digit() || digits[15] == 'X');
+  valid.then(|| digits.chunks(4).map(String::from_iter).collect::<Vec<_>>().join("-"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalize_orcid() {
+    for value in [
+      "0000-0002-1825-0097",
+      "0000000218250097",
+      "http://orcid.org/0000-0002-1825-0097",
+      "https://orcid.org/000000021825-0097/",
+    ] {
+      assert_eq!(normalize_orcid(value).as_deref(), Some("0000-0002-1825-0097"), "{value}");
+    }
+    assert_eq!(normalize_orcid("0000-0002-1694-233x").as_deref(), Some("0000-0002-1694-233X"));
+    assert_eq!(normalize_orcid("0000-0002-1825"), None);
+    assert_eq!(normalize_orcid("not an orcid"), None);
+  }
+}
@@ -12,6 +12,7 @@
 //! - [`RetrieverConfig`]: Configuration for specific paper sources
 //! - [`ResponseFormat`]: Format-specific parsing logic (XML/JSON)
 //! - [`ResponseProcessor`]: Trait for processing API responses
+//! - [`ResourceRetriever`]: Trait for retrievers implemented in Rust rather than TOML
 //!
 //! # Features
 //!
@@ -64,13 +65,21 @@
 //! # }
 //! ```
 
-use std::collections::HashMap;
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  fmt::Debug,
+  sync::{Arc, Mutex},
+};
 
 use super::*;
 
+mod cache;
 pub mod json;
 pub mod xml;
 
+use cache::{CachedResponse, ResponseCache};
+
 /// Main entry point for paper retrieval operations.
 ///
 /// The `Retriever` struct manages a collection of paper source configurations and
@@ -92,13 +101,55 @@ pub mod xml;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Retriever {
   /// The collection of configurations used for this [`Retriever`].
-  configs: HashMap<String, RetrieverConfig>,
+  configs:    HashMap<String, RetrieverConfig>,
+  /// Programmatic retrievers added with [`Retriever::with_retriever`], keyed by name.
+  custom:     HashMap<String, Arc<dyn ResourceRetriever>>,
+  /// Whether network access is disabled, making every retrieval fail with
+  /// [`LearnerError::Offline`].
+  offline:    bool,
+  /// HTTP client shared by every configuration so connections and TLS sessions are pooled.
+  client:     reqwest::Client,
+  /// Responses kept for conditional revalidation, shared by every configuration.
+  cache:      ResponseCache,
+  /// `User-Agent` sent by configurations that don't set their own
+  /// [`user_agent`](RetrieverConfig::user_agent).
+  user_agent: Option<String>,
+}
+
+impl Default for Retriever {
+  fn default() -> Self {
+    Self {
+      configs:    HashMap::new(),
+      custom:     HashMap::new(),
+      offline:    false,
+      client:     http_client(),
+      cache:      ResponseCache::default(),
+      user_agent: None,
+    }
+  }
 }
 
 impl Retriever {
+  /// Typical arXiv, DOI, and IACR inputs used by [`Retriever::check_overlaps`] to spot
+  /// configurations whose patterns claim the same identifiers.
+  pub const DEFAULT_OVERLAP_SAMPLES: &'static [&'static str] = &[
+    "2301.07041",
+    "2301.07041v2",
+    "https://arxiv.org/abs/2301.07041",
+    "https://arxiv.org/pdf/2301.07041",
+    "math.AG/0601001",
+    "https://arxiv.org/abs/math/0404443",
+    "10.1145/1327452.1327492",
+    "https://doi.org/10.1145/1327452.1327492",
+    "10.48550/arXiv.2301.07041",
+    "2016/260",
+    "https://eprint.iacr.org/2016/260",
+    "https://eprint.iacr.org/2016/260.pdf",
+  ];
+
   /// Checks whether the retreivers map is empty.
   ///
   /// This is useful for handling the case where no retreivers are specified and
@@ -119,7 +170,138 @@ impl Retriever {
   /// # Ok(())
   /// # }
   /// ```
-  pub fn is_empty(&self) -> bool { self.configs.is_empty() }
+  pub fn is_empty(&self) -> bool { self.configs.is_empty() && self.custom.is_empty() }
+
+  /// Returns the names of all loaded retriever configurations and programmatic retrievers,
+  /// sorted alphabetically.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::Retriever;
+  /// let retriever = Retriever::new().with_config_dir("config/retrievers")?;
+  /// for name in retriever.names() {
+  ///   println!("{name}");
+  /// }
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn names(&self) -> Vec<&str> {
+    let mut names: Vec<_> =
+      self.configs.keys().chain(self.custom.keys()).map(String::as_str).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+  }
+
+  /// Reports sample inputs that match more than one loaded configuration at the same priority.
+  ///
+  /// Overlapping patterns only surface as [`LearnerError::AmbiguousIdentifier`] once a user hits
+  /// them, so this can be run at startup (e.g. with [`Retriever::DEFAULT_OVERLAP_SAMPLES`]) to
+  /// catch misconfigurations early. Overlaps settled by a higher
+  /// [`priority`](RetrieverConfig::priority) are not reported.
+  ///
+  /// # Returns
+  ///
+  /// A list of `(sample, config_names)` pairs, one for each ambiguous sample, with the tied
+  /// configuration names sorted alphabetically.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::Retriever;
+  /// let retriever = Retriever::new().with_config_dir("config/retrievers")?;
+  /// for (sample, names) in retriever.check_overlaps(Retriever::DEFAULT_OVERLAP_SAMPLES) {
+  ///   println!("{sample} is claimed by: {}", names.join(", "));
+  /// }
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn check_overlaps(&self, samples: &[&str]) -> Vec<(String, Vec<String>)> {
+    samples
+      .iter()
+      .filter_map(|sample| {
+        let names: Vec<String> =
+          top_priority(self.matching(sample)).iter().map(|c| c.name().to_string()).collect();
+        (names.len() > 1).then(|| (sample.to_string(), names))
+      })
+      .collect()
+  }
+
+  /// Enables or disables offline mode.
+  ///
+  /// While offline, [`Retriever::get_paper`] fails immediately with [`LearnerError::Offline`]
+  /// instead of attempting a request.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::retriever::Retriever;
+  /// let retriever = Retriever::new().with_offline(true);
+  /// assert!(retriever.is_offline());
+  /// ```
+  pub fn with_offline(mut self, offline: bool) -> Self {
+    self.offline = offline;
+    self
+  }
+
+  /// Returns whether offline mode is enabled.
+  pub fn is_offline(&self) -> bool { self.offline }
+
+  /// Sets the default `User-Agent` header for requests made through this retriever.
+  ///
+  /// Applies to configurations loaded before and after this call, except those with their own
+  /// [`user_agent`](RetrieverConfig::user_agent). Without either, reqwest's default agent is sent.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::retriever::Retriever;
+  /// let retriever = Retriever::new().with_user_agent("learner/0.1 (mailto:me@example.com)");
+  /// ```
+  pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+    let user_agent = user_agent.into();
+    for config in self.configs.values_mut() {
+      config.default_user_agent = Some(user_agent.clone());
+    }
+    self.user_agent = Some(user_agent);
+    self
+  }
+
+  /// Starts a request outside any configuration on the shared HTTP client, sending the
+  /// default `User-Agent` set with [`Retriever::with_user_agent`].
+  pub(crate) fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+    let request = self.client.request(method, url);
+    match &self.user_agent {
+      Some(user_agent) => request.header(reqwest::header::USER_AGENT, user_agent),
+      None => request,
+    }
+  }
+
+  /// Returns the configuration with the given name, if one is loaded.
+  pub fn config(&self, name: &str) -> Option<&RetrieverConfig> { self.configs.get(name) }
+
+  /// Returns a loaded configuration providing papers for `source`, if any.
+  ///
+  /// When several configurations share a source, the one with the
+  /// alphabetically first name is returned so the choice is stable.
+  pub fn config_for_source(&self, source: &str) -> Option<&RetrieverConfig> {
+    self.names().into_iter().filter_map(|name| self.config(name)).find(|c| c.source == source)
+  }
+
+  /// Returns the distinct paper sources (e.g. "arxiv") provided by the loaded configurations
+  /// and programmatic retrievers, sorted alphabetically.
+  ///
+  /// Useful for validating user supplied source filters against what can actually be stored.
+  pub fn sources(&self) -> Vec<&str> {
+    let mut sources: Vec<_> = self
+      .configs
+      .values()
+      .map(|config| config.source.as_str())
+      .chain(self.custom.values().map(|retriever| retriever.source()))
+      .collect();
+    sources.sort_unstable();
+    sources.dedup();
+    sources
+  }
 }
 
 /// Configuration for a specific paper source retriever.
@@ -147,25 +329,96 @@ impl Retriever {
 /// abstract = { path = "entry/summary" }
 /// publication_date = { path = "entry/published" }
 /// authors = { path = "entry/author/name" }
+///
+/// # Alternative endpoints, selected with `Retriever::get_paper_from(input, "oai")`
+/// [endpoints.oai]
+/// template = "http://export.arxiv.org/oai2?verb=GetRecord&identifier=oai:arXiv.org:{identifier}&metadataPrefix=oai_dc"
+///
+/// [endpoints.oai.response_format]
+/// type = "xml"
+/// strip_namespaces = true
+///
+/// [endpoints.oai.response_format.field_maps]
+/// title = { path = "OAI-PMH/GetRecord/record/metadata/dc/title" }
+/// # ...
 /// ```
 #[derive(Debug, Clone, Deserialize)]
 pub struct RetrieverConfig {
   /// Name of this retriever configuration
-  pub name:              String,
+  pub name: String,
   /// Base URL for API requests
-  pub base_url:          String,
+  pub base_url: String,
   /// Regex pattern for matching and extracting paper identifiers
+  ///
+  /// The first capture group is the canonical identifier. An optional named group
+  /// `version` captures a version suffix (e.g. `v2`) that is not part of the identifier.
   #[serde(deserialize_with = "deserialize_regex")]
-  pub pattern:           Regex,
+  pub pattern: Regex,
   /// Source identifier for papers from this retriever
-  pub source:            String,
+  pub source: String,
   /// Template for constructing API endpoint URLs
   pub endpoint_template: String,
+  /// Alternative endpoints by name, e.g. a richer record than the default endpoint's, selected
+  /// at retrieval time with [`Retriever::get_paper_from`]
+  #[serde(default)]
+  pub endpoints: HashMap<String, Endpoint>,
+  /// Template for keyword search URLs, with the URL-encoded query in place of `{query}`, for
+  /// sources that can be searched (see [`RetrieverConfig::search`])
+  #[serde(default)]
+  pub search_endpoint_template: Option<String>,
+  /// Path to each result in a search response, e.g. `feed/entry` for XML, or `message/items`
+  /// for a JSON array whose items look like the single-paper response's `message`
+  #[serde(default)]
+  pub search_results_path: Option<String>,
+  /// Precedence over other retrievers whose patterns match the same input; the highest wins
+  /// instead of the input being ambiguous (defaults to 0)
+  #[serde(default)]
+  pub priority: i32,
   /// Format and parsing configuration for API responses
-  pub response_format:   ResponseFormat,
+  pub response_format: ResponseFormat,
   /// Optional HTTP headers for API requests
   #[serde(default)]
-  pub headers:           HashMap<String, String>,
+  pub headers: HashMap<String, String>,
+  /// `User-Agent` header for API requests, overriding the [`Retriever`]'s default and any
+  /// `User-Agent` entry in [`headers`](RetrieverConfig::headers)
+  #[serde(default)]
+  pub user_agent: Option<String>,
+  /// Request timeout for API requests, in seconds (defaults to 30)
+  #[serde(default = "RetrieverConfig::default_timeout_secs")]
+  pub timeout_secs: u64,
+  /// Largest response body accepted from the API, in bytes (defaults to 8 MiB)
+  #[serde(default = "RetrieverConfig::default_max_response_bytes")]
+  pub max_response_bytes: u64,
+  /// HTTP client used for requests, shared with the owning [`Retriever`] once added to one
+  #[serde(skip, default = "http_client")]
+  client: reqwest::Client,
+  /// `User-Agent` inherited from the owning [`Retriever`], see [`Retriever::with_user_agent`]
+  #[serde(skip)]
+  default_user_agent: Option<String>,
+  /// Response cache, shared with the owning [`Retriever`] once added to one
+  #[serde(skip)]
+  cache: ResponseCache,
+}
+
+/// A named alternative to a retriever's default endpoint.
+///
+/// The endpoint's responses are parsed with the retriever's
+/// [`response_format`](RetrieverConfig::response_format) unless it has its own.
+///
+/// # Examples
+///
+/// ```toml
+/// [endpoints.full]
+/// template = "https://api.example.org/records/{identifier}?detail=full"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Endpoint {
+  /// Template for constructing the endpoint's URLs, with the identifier in place of
+  /// `{identifier}`
+  pub template:        String,
+  /// Format of the endpoint's responses, when it differs from the retriever's
+  #[serde(default)]
+  pub response_format: Option<ResponseFormat>,
 }
 
 /// Available response format handlers.
@@ -204,6 +457,49 @@ pub enum ResponseFormat {
   Json(json::JsonConfig),
 }
 
+impl ResponseFormat {
+  /// Returns the processor that parses responses in this format.
+  fn processor(&self) -> &dyn ResponseProcessor {
+    match self {
+      Self::Xml(config) => config,
+      Self::Json(config) => config,
+    }
+  }
+
+  /// Whether `field` has a mapping, i.e. whether responses can provide it at all.
+  fn maps_field(&self, field: &str) -> bool {
+    match self {
+      Self::Xml(config) => config.field_maps.contains_key(field),
+      Self::Json(config) => config.field_maps.contains_key(field),
+    }
+  }
+
+  /// Extracts the single mapped `field` from a response, if the source provides it.
+  fn extract_one(&self, data: &[u8], field: &str) -> Result<Option<String>> {
+    match self {
+      Self::Xml(config) => config.extract_one(data, field),
+      Self::Json(config) => config.extract_one(data, field),
+    }
+  }
+
+  /// Parses one result of a search, split off by [`ResponseFormat::split_results`].
+  fn process_search_result(&self, data: &[u8]) -> Result<Paper> {
+    match self {
+      Self::Xml(config) => config.process(data, true),
+      Self::Json(config) => config.process(data, true),
+    }
+  }
+
+  /// Splits a search response into one response per result, each shaped like a response for a
+  /// single paper so the same field maps apply.
+  fn split_results(&self, data: &[u8], path: &str) -> Result<Vec<Vec<u8>>> {
+    match self {
+      Self::Xml(config) => config.split_results(data, path),
+      Self::Json(config) => config.split_results(data, path),
+    }
+  }
+}
+
 /// Field mapping configuration.
 ///
 /// Defines how to extract and transform specific fields from API responses.
@@ -214,16 +510,30 @@ pub enum ResponseFormat {
 /// [field_maps.title]
 /// path = "entry/title"
 /// transform = { type = "replace", pattern = "\\s+", replacement = " " }
+///
+/// [field_maps.publication_date]
+/// path = "message/issued/date-parts/0"
+/// fallback = "message/created/date-time"
 /// ```
 #[derive(Debug, Clone, Deserialize)]
 pub struct FieldMap {
   /// Path to field in response (e.g., JSON path or XPath)
   pub path:      String,
+  /// Optional path to read instead when `path` holds no content
+  #[serde(default)]
+  pub fallback:  Option<String>,
   /// Optional transformation to apply to extracted value
   #[serde(default)]
   pub transform: Option<Transform>,
 }
 
+impl FieldMap {
+  /// The paths to try in order: [`FieldMap::path`], then [`FieldMap::fallback`] if set.
+  pub fn paths(&self) -> impl Iterator<Item = &str> {
+    std::iter::once(self.path.as_str()).chain(self.fallback.as_deref())
+  }
+}
+
 /// Available field value transformations.
 ///
 /// Transformations that can be applied to extracted field values
@@ -240,6 +550,15 @@ pub struct FieldMap {
 ///
 /// # Construct full URL
 /// transform = { type = "url", base = "https://example.com/", suffix = ".pdf" }
+///
+/// # Build one author per `creator`, with the parallel `affiliation` elements (XML only)
+/// [field_maps.authors]
+/// path = "record"
+///
+/// [field_maps.authors.transform]
+/// type    = "Compose"
+/// format  = { type = "ArrayOfObjects" }
+/// sources = [{ key = "name", path = "creator" }, { key = "affiliation", path = "affiliation" }]
 /// ```
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
@@ -252,6 +571,9 @@ pub enum Transform {
     replacement: String,
   },
   /// Convert between date formats
+  ///
+  /// Dates whose `from_format` has no day, such as `%Y` or `%Y-%m`, become a partial
+  /// `YYYY` or `YYYY-MM` date instead of being formatted with `to_format`.
   Date {
     /// Source date format string using chrono syntax (e.g., "%Y-%m-%d")
     from_format: String,
@@ -265,6 +587,62 @@ pub enum Transform {
     /// Optional suffix to append to the URL (e.g., ".pdf")
     suffix: Option<String>,
   },
+  /// Build a value from several parts of an XML response
+  ///
+  /// Source paths are relative to the field map's `path`. Unlike the other transforms this
+  /// reads the whole response rather than one value, so JSON responses reject it.
+  Compose {
+    /// Values to combine, in order
+    sources: Vec<Source>,
+    /// How the values are combined
+    format:  ComposeFormat,
+  },
+}
+
+/// A value read by a [`Transform::Compose`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Source {
+  /// A field of a composed object holding the values at `path`, e.g.
+  /// `{ key = "name", path = "creator" }`
+  KeyValue {
+    /// Field name in the object
+    key:  String,
+    /// Path to the values, relative to the field map's path
+    path: String,
+  },
+  /// Every value at a path, relative to the field map's path, e.g. `{ path = "creator" }`
+  Path {
+    /// Path to the values
+    path: String,
+  },
+  /// Fixed text, e.g. `{ literal = "name" }`
+  Literal {
+    /// The text itself
+    literal: String,
+  },
+}
+
+/// How a [`Transform::Compose`] combines its sources.
+///
+/// In the object formats a [`Source::KeyValue`] names its own field, so
+/// `[{ key = "name", path = "creator" }]` builds `{ "name": <creator> }`. Other sources are
+/// taken in key, value pairs, so `[{ literal = "name" }, { path = "creator" }]` builds the same
+/// object, and a path source can supply keys as well as values.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum ComposeFormat {
+  /// Concatenate every value of every source, separated by `delimiter`
+  Join {
+    /// Text placed between values (defaults to none)
+    #[serde(default)]
+    delimiter: String,
+  },
+  /// A single object from the first value of each source
+  Object,
+  /// One object per value of the longest path source, where the n-th object takes the
+  /// n-th value of each path; literals are the same in every object
+  ArrayOfObjects,
 }
 
 /// Trait for processing API responses into Paper objects.
@@ -276,16 +654,20 @@ pub enum Transform {
 ///
 /// # Examples
 ///
-/// ```no_run
+/// ```
 /// # use learner::{retriever::ResponseProcessor, resource::Paper};
 /// # use learner::error::LearnerError;
-/// struct CustomProcessor;
+/// /// Reads responses whose whole body is the paper's title.
+/// struct TitleProcessor;
 ///
 /// #[async_trait::async_trait]
-/// impl ResponseProcessor for CustomProcessor {
+/// impl ResponseProcessor for TitleProcessor {
 ///   async fn process_response(&self, data: &[u8]) -> Result<Paper, LearnerError> {
-///     // Parse response data and construct Paper
-///     todo!()
+///     Paper::builder()
+///       .with_title(String::from_utf8_lossy(data).trim())
+///       .with_source("example")
+///       .with_source_identifier("1")
+///       .build()
 ///   }
 /// }
 /// ```
@@ -305,6 +687,94 @@ pub trait ResponseProcessor: Send + Sync {
   async fn process_response(&self, data: &[u8]) -> Result<Paper>;
 }
 
+/// Trait for paper retrievers implemented in code instead of a TOML configuration.
+///
+/// Some APIs don't fit the field mapping of [`RetrieverConfig`]. Implementing this trait and
+/// registering the type with [`Retriever::with_retriever`] lets such a source take part in
+/// [`Retriever::get_paper`], [`Retriever::sanitize_identifier`] and [`Retriever::search`]
+/// alongside the configured ones, including ambiguity detection. Lookups of stored papers by
+/// their source, like [`Learner::check_for_updates`](crate::Learner::check_for_updates), use
+/// [`ResourceRetriever::fetch`].
+///
+/// # Examples
+///
+/// ```
+/// # use learner::{retriever::{ResourceRetriever, Retriever}, resource::Paper};
+/// # use learner::error::LearnerError;
+/// #[derive(Debug)]
+/// struct InternalApi;
+///
+/// #[async_trait::async_trait]
+/// impl ResourceRetriever for InternalApi {
+///   fn name(&self) -> &str { "internal" }
+///
+///   fn source(&self) -> &str { "internal" }
+///
+///   fn matches(&self, input: &str) -> bool { input.starts_with("INT-") }
+///
+///   async fn retrieve(&self, input: &str) -> Result<Paper, LearnerError> {
+///     // A real retriever would call its API here
+///     Paper::builder()
+///       .with_title(format!("Internal report {input}"))
+///       .with_source(self.source())
+///       .with_source_identifier(input)
+///       .build()
+///   }
+/// }
+///
+/// let retriever = Retriever::new().with_retriever(InternalApi);
+/// let (source, identifier) = retriever.sanitize_identifier("INT-42")?;
+/// assert_eq!((source.as_str(), identifier.as_str()), ("internal", "INT-42"));
+/// # Ok::<(), LearnerError>(())
+/// ```
+#[async_trait]
+pub trait ResourceRetriever: Debug + Send + Sync {
+  /// Name of this retriever, unique among the retrievers of a [`Retriever`].
+  fn name(&self) -> &str;
+
+  /// Source stored on papers from this retriever (e.g. "arxiv").
+  fn source(&self) -> &str;
+
+  /// Returns whether this retriever handles the given identifier or URL.
+  fn matches(&self, input: &str) -> bool;
+
+  /// Extracts the identifier papers are stored under from a matching input.
+  ///
+  /// Defaults to the trimmed input.
+  ///
+  /// # Errors
+  ///
+  /// Should return [`LearnerError::InvalidIdentifier`] if no identifier can be extracted.
+  fn identifier(&self, input: &str) -> Result<String> { Ok(input.trim().to_string()) }
+
+  /// Precedence over other retrievers matching the same input; the highest wins.
+  ///
+  /// Defaults to 0, like [`RetrieverConfig::priority`].
+  fn priority(&self) -> i32 { 0 }
+
+  /// Retrieves the paper for a matching input.
+  ///
+  /// The returned paper's `source` and `source_identifier` are overwritten with
+  /// [`ResourceRetriever::source`] and [`ResourceRetriever::identifier`] by
+  /// [`Retriever::get_paper`].
+  async fn retrieve(&self, input: &str) -> Result<Paper>;
+
+  /// Retrieves the paper stored under `identifier`, as extracted by
+  /// [`ResourceRetriever::identifier`].
+  ///
+  /// Defaults to [`ResourceRetriever::retrieve`], which suits retrievers whose identifiers are
+  /// also inputs they match. The returned paper's `source` and `source_identifier` are
+  /// overwritten like for [`ResourceRetriever::retrieve`].
+  async fn fetch(&self, identifier: &str) -> Result<Paper> { self.retrieve(identifier).await }
+
+  /// Searches the source for papers matching a keyword query.
+  ///
+  /// Defaults to a [`LearnerError::Config`] error, for sources that cannot be searched.
+  async fn search(&self, _query: &str) -> Result<Vec<Paper>> {
+    Err(LearnerError::Config(format!("{} does not support searching", self.name())))
+  }
+}
+
 impl Retriever {
   /// Creates a new empty retriever with no configurations.
   ///
@@ -334,8 +804,28 @@ impl Retriever {
   /// let retriever = Retriever::new().with_config(config);
   /// # }
   /// ```
-  pub fn with_config(mut self, config: RetrieverConfig) {
-    self.configs.insert(config.name.clone(), config);
+  pub fn with_config(mut self, config: RetrieverConfig) { self.insert_config(config); }
+
+  /// Adds a programmatic retriever to this instance.
+  ///
+  /// Retrievers are keyed by [`ResourceRetriever::name`], so adding one with the same name as
+  /// an earlier programmatic retriever replaces it.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::{ResourceRetriever, Retriever};
+  /// # fn example(custom: impl ResourceRetriever + 'static) -> Result<(), Box<dyn std::error::Error>> {
+  /// let retriever = Retriever::new().with_default_configs()?.with_retriever(custom);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_retriever(mut self, retriever: impl ResourceRetriever + 'static) -> Self {
+    let name = retriever.name().to_string();
+    if self.custom.insert(name.clone(), Arc::new(retriever)).is_some() {
+      debug!("Retriever '{name}' overridden by a later retriever");
+    }
+    self
   }
 
   /// Adds a retriever configuration from a TOML string.
@@ -369,11 +859,42 @@ impl Retriever {
   /// # Ok::<(), Box<dyn std::error::Error>>(())
   /// ```
   pub fn with_config_str(mut self, toml_str: &str) -> Result<Self> {
-    let config: RetrieverConfig = toml::from_str(toml_str)?;
-    self.configs.insert(config.name.clone(), config);
+    self.insert_config(RetrieverConfig::from_toml_str(toml_str)?);
     Ok(self)
   }
 
+  /// Adds the bundled retriever configurations ([`ARXIV_CONFIG`], [`DOI_CONFIG`], and
+  /// [`IACR_CONFIG`]).
+  ///
+  /// Configurations are keyed by name and later additions replace earlier ones, so loading the
+  /// defaults first and a user directory afterwards lets a user file (e.g. a custom `arxiv.toml`)
+  /// override the bundled retriever of the same name.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::Retriever;
+  /// let retriever =
+  ///   Retriever::new().with_default_configs()?.with_config_dir("~/.learner/retrievers")?;
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn with_default_configs(self) -> Result<Self> {
+    self.with_config_str(ARXIV_CONFIG)?.with_config_str(DOI_CONFIG)?.with_config_str(IACR_CONFIG)
+  }
+
+  /// Inserts a configuration, replacing (and logging) any existing one with the same name.
+  ///
+  /// The configuration is switched over to this retriever's shared HTTP client and response
+  /// cache.
+  fn insert_config(&mut self, mut config: RetrieverConfig) {
+    config.client = self.client.clone();
+    config.cache = self.cache.clone();
+    config.default_user_agent = self.user_agent.clone();
+    if let Some(previous) = self.configs.insert(config.name.clone(), config) {
+      debug!("Retriever config '{}' overridden by a later configuration", previous.name);
+    }
+  }
+
   /// Adds a retriever configuration from a TOML file.
   ///
   /// # Arguments
@@ -440,6 +961,36 @@ impl Retriever {
     Ok(retriever)
   }
 
+  /// Loads every `.toml` file in a directory, collecting failures instead of stopping at the
+  /// first one.
+  ///
+  /// Unlike [`Retriever::with_config_dir`], a file that cannot be read or parsed does not abort
+  /// the load: the valid configurations are added and each failure is returned alongside the
+  /// retriever as a `(filename, error)` pair, in filename order.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error only if the directory itself is missing or cannot be listed.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{error::report, retriever::Retriever};
+  /// let (retriever, errors) = Retriever::new().load_config_dir("~/.learner/retrievers")?;
+  /// for (file, error) in &errors {
+  ///   eprintln!("{file}: {}", report(error));
+  /// }
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn load_config_dir(self, dir: impl AsRef<Path>) -> Result<(Self, ConfigErrors)> {
+    let (configs, errors) = load_toml_dir(dir.as_ref(), RetrieverConfig::from_toml_str)?;
+    let mut retriever = self;
+    for config in configs {
+      retriever.insert_config(config);
+    }
+    Ok((retriever, errors))
+  }
+
   /// Attempts to retrieve a paper using any matching configuration.
   ///
   /// This method tries to match the input against all configured retrievers
@@ -458,6 +1009,7 @@ impl Retriever {
   /// # Errors
   ///
   /// This method will return an error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
   /// - No configuration matches the input format
   /// - Multiple configurations match ambiguously
   /// - Paper retrieval fails
@@ -476,24 +1028,191 @@ impl Retriever {
   /// # }
   /// ```
   pub async fn get_paper(&self, input: &str) -> Result<Paper> {
-    let mut matches = Vec::new();
+    self.get_paper_at(input, None).await
+  }
 
-    // Find all configs that match the input
-    for config in self.configs.values() {
-      if config.pattern.is_match(input) {
-        matches.push(config);
-      }
+  /// Retrieves a paper from the named alternative endpoint of the matching retriever, see
+  /// [`RetrieverConfig::endpoints`].
+  ///
+  /// This works like [`Retriever::get_paper`], but requests the endpoint's template instead of
+  /// the retriever's [`endpoint_template`](RetrieverConfig::endpoint_template), so a source's
+  /// richer record can be chosen per retrieval.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if the matching retriever has no endpoint named
+  /// `endpoint`, and otherwise fails like [`Retriever::get_paper`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::Retriever;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let retriever = Retriever::new().with_default_configs()?;
+  /// let paper = retriever.get_paper_from("2301.07041", "oai").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_paper_from(&self, input: &str, endpoint: &str) -> Result<Paper> {
+    self.get_paper_at(input, Some(endpoint)).await
+  }
+
+  /// Retrieves a paper from the named endpoint, or the default one for `None`.
+  async fn get_paper_at(&self, input: &str, endpoint: Option<&str>) -> Result<Paper> {
+    if self.offline {
+      return Err(LearnerError::Offline);
     }
 
-    match matches.len() {
-      0 => Err(LearnerError::InvalidIdentifier),
-      1 => matches[0].retrieve_paper(input).await,
-      _ => Err(LearnerError::AmbiguousIdentifier(
-        matches.into_iter().map(|c| c.name.clone()).collect(),
-      )),
+    match self.resolve(input)? {
+      Candidate::Config(config) => config.retrieve_paper_at(input, endpoint).await,
+      Candidate::Custom(retriever) => {
+        // Programmatic retrievers have a single way of retrieving papers
+        if let Some(endpoint) = endpoint {
+          return Err(LearnerError::Config(format!(
+            "{} has no endpoint named {endpoint:?}",
+            retriever.source()
+          )));
+        }
+        let identifier = retriever.identifier(input)?;
+        let paper = retriever.retrieve(input).await?;
+        Ok(stamp(paper, retriever, identifier))
+      },
+    }
+  }
+
+  /// Retrieves a paper for `input` from the retriever providing `source`, whether or not the
+  /// input would resolve to it on its own.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Offline`] in offline mode, [`LearnerError::InvalidSource`] if no
+  /// retriever provides `source`, and otherwise fails like [`Retriever::get_paper`].
+  pub(crate) async fn get_paper_for_source(&self, source: &str, input: &str) -> Result<Paper> {
+    if self.offline {
+      return Err(LearnerError::Offline);
+    }
+    match self.for_source(source)? {
+      Candidate::Config(config) => config.retrieve_paper(input).await,
+      Candidate::Custom(retriever) => {
+        let identifier = retriever.identifier(input)?;
+        let paper = retriever.retrieve(input).await?;
+        Ok(stamp(paper, retriever, identifier))
+      },
+    }
+  }
+
+  /// Fetches the paper stored as `identifier` from the retriever providing `source`.
+  ///
+  /// # Errors
+  ///
+  /// Fails like [`Retriever::get_paper_for_source`].
+  pub(crate) async fn fetch_paper(&self, source: &str, identifier: &str) -> Result<Paper> {
+    if self.offline {
+      return Err(LearnerError::Offline);
+    }
+    match self.for_source(source)? {
+      Candidate::Config(config) => config.fetch_paper(identifier).await,
+      Candidate::Custom(retriever) => {
+        let paper = retriever.fetch(identifier).await?;
+        Ok(stamp(paper, retriever, identifier.to_string()))
+      },
+    }
+  }
+
+  /// Fetches only the abstract of the paper stored as `identifier` from the retriever
+  /// providing `source`, or `None` if it is blank or not provided.
+  ///
+  /// Configured retrievers request just the abstract, see [`RetrieverConfig::fetch_field`];
+  /// programmatic ones fetch the whole paper.
+  ///
+  /// # Errors
+  ///
+  /// Fails like [`Retriever::get_paper_for_source`].
+  pub(crate) async fn fetch_abstract(
+    &self,
+    source: &str,
+    identifier: &str,
+  ) -> Result<Option<String>> {
+    if self.offline {
+      return Err(LearnerError::Offline);
+    }
+    match self.for_source(source)? {
+      Candidate::Config(config) => config.fetch_field(identifier, "abstract").await,
+      Candidate::Custom(retriever) => {
+        let abstract_text = retriever.fetch(identifier).await?.abstract_text.trim().to_string();
+        Ok(Some(abstract_text).filter(|text| !text.is_empty()))
+      },
     }
   }
 
+  /// Searches the retriever providing `source` for papers matching a keyword query.
+  ///
+  /// Configured retrievers search with [`RetrieverConfig::search`] and programmatic ones with
+  /// [`ResourceRetriever::search`]. Results are not stored.
+  ///
+  /// # Errors
+  ///
+  /// Returns error if:
+  /// - Offline mode is enabled ([`LearnerError::Offline`])
+  /// - No retriever provides `source` ([`LearnerError::InvalidSource`])
+  /// - The source does not support searching ([`LearnerError::Config`])
+  /// - The search request fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::retriever::Retriever;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let retriever = Retriever::new().with_default_configs()?;
+  /// for paper in retriever.search("arxiv", "attention transformer").await? {
+  ///   println!("{}: {}", paper.source_identifier, paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, source: &str, query: &str) -> Result<Vec<Paper>> {
+    if self.offline {
+      return Err(LearnerError::Offline);
+    }
+    match self.for_source(source)? {
+      Candidate::Config(config) => config.search(query).await,
+      Candidate::Custom(retriever) => Ok(
+        retriever
+          .search(query)
+          .await?
+          .into_iter()
+          .map(|paper| {
+            let identifier = paper.source_identifier.clone();
+            stamp(paper, retriever, identifier)
+          })
+          .collect(),
+      ),
+    }
+  }
+
+  /// Returns the retriever providing papers for `source`.
+  ///
+  /// Configurations are preferred, as by [`Retriever::config_for_source`], over programmatic
+  /// retrievers, which are tried in name order.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidSource`] if no retriever provides `source`.
+  fn for_source(&self, source: &str) -> Result<Candidate<'_>> {
+    self
+      .config_for_source(source)
+      .map(Candidate::Config)
+      .or_else(|| {
+        self
+          .custom
+          .values()
+          .filter(|retriever| retriever.source() == source)
+          .min_by(|a, b| a.name().cmp(b.name()))
+          .map(|retriever| Candidate::Custom(retriever.as_ref()))
+      })
+      .ok_or_else(|| LearnerError::InvalidSource(source.to_string()))
+  }
+
   /// Sanitizes and normalizes a paper identifier using configured retrieval patterns.
   ///
   /// This function processes an input string (which could be a URL, DOI, arXiv ID, etc.)
@@ -545,39 +1264,135 @@ impl Retriever {
   /// - The input matches a pattern but the identifier extraction fails
   ///
   /// Will return `LearnerError::AmbiguousIdentifier` if:
-  /// - The input matches multiple source patterns
-  /// - Includes the list of matching sources in the error
+  /// - The input matches multiple source patterns with the same, highest priority
+  /// - Includes the list of tied retriever names in the error
   ///
   /// # Implementation Notes
   ///
   /// The function:
-  /// 1. Checks the input against all configured source patterns
-  /// 2. Attempts to extract identifiers from all matching patterns
-  /// 3. Validates that exactly one pattern matched
-  /// 4. Returns the normalized source and identifier
+  /// 1. Checks the input against all configured source patterns and programmatic retrievers
+  /// 2. Keeps the matches with the highest [`priority`](RetrieverConfig::priority)
+  /// 3. Validates that exactly one match remains
+  /// 4. Extracts and returns its normalized source and identifier
   ///
   /// The matching process uses regex patterns defined in the retriever configuration
   /// files, allowing for flexible addition of new paper sources.
   pub fn sanitize_identifier(&self, input: &str) -> Result<(String, String)> {
-    let mut matches = Vec::new();
-
-    for config in self.configs.values() {
-      if config.pattern.is_match(input) {
-        matches.push((config.source.clone(), config.extract_identifier(input)?.to_string()));
-      }
+    match self.resolve(input)? {
+      Candidate::Config(config) => Ok((config.source.clone(), config.canonical_identifier(input)?)),
+      Candidate::Custom(retriever) =>
+        Ok((retriever.source().to_string(), retriever.identifier(input)?)),
     }
+  }
 
-    match matches.len() {
+  /// Returns every configuration and programmatic retriever claiming `input`, ordered by
+  /// descending priority and then by name.
+  fn matching(&self, input: &str) -> Vec<Candidate<'_>> {
+    let mut candidates: Vec<_> = self
+      .configs
+      .values()
+      .filter(|config| config.pattern.is_match(input))
+      .map(Candidate::Config)
+      .chain(
+        self
+          .custom
+          .values()
+          .filter(|retriever| retriever.matches(input))
+          .map(|retriever| Candidate::Custom(retriever.as_ref())),
+      )
+      .collect();
+    candidates.sort_by(|a, b| b.priority().cmp(&a.priority()).then_with(|| a.name().cmp(b.name())));
+    candidates
+  }
+
+  /// Picks the retriever that handles `input`: the only match with the highest priority.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidIdentifier`] if nothing matches, or
+  /// [`LearnerError::AmbiguousIdentifier`] with the tied names if several matches share the
+  /// highest priority.
+  fn resolve(&self, input: &str) -> Result<Candidate<'_>> {
+    let mut top = top_priority(self.matching(input));
+    match top.len() {
       0 => Err(LearnerError::InvalidIdentifier),
-      1 => Ok(matches.remove(0)),
+      1 => Ok(top.remove(0)),
       _ => Err(LearnerError::AmbiguousIdentifier(
-        matches.into_iter().map(|(source, _)| source).collect(),
+        top.iter().map(|candidate| candidate.name().to_string()).collect(),
       )),
     }
   }
 }
 
+/// A configuration or programmatic retriever claiming an input.
+enum Candidate<'a> {
+  /// A TOML-configured retriever
+  Config(&'a RetrieverConfig),
+  /// A retriever added with [`Retriever::with_retriever`]
+  Custom(&'a dyn ResourceRetriever),
+}
+
+impl Candidate<'_> {
+  /// Name of the retriever.
+  fn name(&self) -> &str {
+    match self {
+      Self::Config(config) => &config.name,
+      Self::Custom(retriever) => retriever.name(),
+    }
+  }
+
+  /// Precedence of the retriever when several match the same input.
+  fn priority(&self) -> i32 {
+    match self {
+      Self::Config(config) => config.priority,
+      Self::Custom(retriever) => retriever.priority(),
+    }
+  }
+}
+
+/// Builds the HTTP client retrievers send their requests with.
+///
+/// Connecting is limited to [`RetrieverConfig::default_timeout_secs`] for every configuration,
+/// since the client is shared; each request is limited by its configuration's
+/// [`timeout_secs`](RetrieverConfig::timeout_secs) on top.
+fn http_client() -> reqwest::Client {
+  reqwest::Client::builder()
+    .connect_timeout(std::time::Duration::from_secs(RetrieverConfig::default_timeout_secs()))
+    .build()
+    .expect("the retriever client uses no settings that can fail")
+}
+
+/// Marks `paper` as coming from the programmatic `retriever` under `identifier`.
+fn stamp(mut paper: Paper, retriever: &dyn ResourceRetriever, identifier: String) -> Paper {
+  paper.source = retriever.source().to_string();
+  paper.source_identifier = identifier;
+  paper
+}
+
+/// Keeps the leading candidates that share the highest priority, expecting them ordered as by
+/// [`Retriever::matching`].
+fn top_priority(mut candidates: Vec<Candidate<'_>>) -> Vec<Candidate<'_>> {
+  if let Some(highest) = candidates.first().map(Candidate::priority) {
+    candidates.retain(|candidate| candidate.priority() == highest);
+  }
+  candidates
+}
+
 impl RetrieverConfig {
+  /// Returns the default request timeout in seconds.
+  pub const fn default_timeout_secs() -> u64 { 30 }
+
+  /// Returns the default limit on response body size, in bytes.
+  pub const fn default_max_response_bytes() -> u64 { 8 * 1024 * 1024 }
+
+  /// Parses a configuration from a TOML string.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::TomlDe`] if the TOML is malformed or doesn't describe a valid
+  /// configuration.
+  pub fn from_toml_str(toml_str: &str) -> Result<Self> { Ok(toml::from_str(toml_str)?) }
+
   /// Extracts the canonical identifier from an input string.
   ///
   /// Uses the configured regex pattern to extract the standardized
@@ -601,6 +1416,41 @@ impl RetrieverConfig {
       .ok_or(LearnerError::InvalidIdentifier)
   }
 
+  /// Extracts the identifier from an input string in the form it is stored under.
+  ///
+  /// This is [`RetrieverConfig::extract_identifier`] plus source-specific normalization: DOIs
+  /// are passed through [`format::normalize_doi`] so that differently cased or prefixed inputs
+  /// map to the same paper.
+  fn canonical_identifier(&self, input: &str) -> Result<String> {
+    let identifier = self.extract_identifier(input)?;
+    if self.source == "doi" {
+      return format::normalize_doi(identifier).ok_or(LearnerError::InvalidIdentifier);
+    }
+    Ok(identifier.to_string())
+  }
+
+  /// Extracts the version suffix from an input string, if the pattern captures one.
+  ///
+  /// Versions are read from the pattern's named `version` group, so they never end up in
+  /// the identifier returned by [`RetrieverConfig::extract_identifier`].
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use learner::retriever::Retriever;
+  /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let retriever = Retriever::new().with_default_configs()?;
+  /// let arxiv = retriever.config("arxiv").unwrap();
+  /// assert_eq!(arxiv.extract_identifier("2301.07041v2")?, "2301.07041");
+  /// assert_eq!(arxiv.extract_version("2301.07041v2"), Some("v2"));
+  /// assert_eq!(arxiv.extract_version("2301.07041"), None);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn extract_version<'a>(&self, input: &'a str) -> Option<&'a str> {
+    self.pattern.captures(input).and_then(|cap| cap.name("version")).map(|m| m.as_str())
+  }
+
   /// Retrieves a paper using this configuration.
   ///
   /// This method:
@@ -623,38 +1473,314 @@ impl RetrieverConfig {
   ///
   /// This method will return an error if:
   /// - The identifier cannot be extracted
-  /// - The HTTP request fails
-  /// - The response cannot be parsed
+  /// - The server cannot be reached ([`LearnerError::Network`])
+  /// - The server does not respond within [`timeout_secs`](RetrieverConfig::timeout_secs)
+  ///   ([`LearnerError::Timeout`])
+  /// - The server responds with an error status ([`LearnerError::ApiError`]); a `304 Not Modified`
+  ///   for a response cached by an earlier request is treated as a cache hit
+  /// - The response body is larger than [`max_response_bytes`](RetrieverConfig::max_response_bytes)
+  ///   ([`LearnerError::ApiError`])
+  /// - The response body is not valid in its declared text encoding or format
+  ///   ([`LearnerError::Deserialize`])
+  /// - The response lacks a required field ([`LearnerError::ApiError`])
   pub async fn retrieve_paper(&self, input: &str) -> Result<Paper> {
-    let identifier = self.extract_identifier(input)?;
+    self.retrieve_paper_at(input, None).await
+  }
+
+  /// Retrieves a paper from the named alternative [`endpoint`](RetrieverConfig::endpoints).
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if there is no endpoint named `endpoint`, and otherwise
+  /// fails like [`RetrieverConfig::retrieve_paper`].
+  pub async fn retrieve_paper_from(&self, input: &str, endpoint: &str) -> Result<Paper> {
+    self.retrieve_paper_at(input, Some(endpoint)).await
+  }
+
+  /// Retrieves a paper from the named endpoint, or the default one for `None`.
+  async fn retrieve_paper_at(&self, input: &str, endpoint: Option<&str>) -> Result<Paper> {
+    let identifier = self.canonical_identifier(input)?;
+    let mut paper = self.fetch_paper_at(&identifier, endpoint).await?;
+    // Prefer the version the source reports, which is the latest one it has
+    paper.version = paper.version.or_else(|| self.extract_version(input).map(String::from));
+    Ok(paper)
+  }
+
+  /// Fetches and parses the paper for an already extracted canonical identifier.
+  ///
+  /// Unlike [`RetrieverConfig::retrieve_paper`], the input is not matched against the
+  /// configured pattern, so stored `source_identifier`s can be fetched directly.
+  pub(crate) async fn fetch_paper(&self, identifier: &str) -> Result<Paper> {
+    self.fetch_paper_at(identifier, None).await
+  }
+
+  /// Fetches and parses the paper from the named endpoint, or the default one for `None`.
+  async fn fetch_paper_at(&self, identifier: &str, endpoint: Option<&str>) -> Result<Paper> {
+    let (template, response_format) = self.endpoint(endpoint)?;
+    let url = template.replace("{identifier}", identifier);
+    let data = self.fetch(&url).await?;
+
+    let mut paper = response_format.processor().process_response(&data).await?;
+    paper.source = self.source.clone();
+    paper.source_identifier = identifier.to_string();
+    paper.version = paper.version.filter(|version| !version.is_empty());
+    Ok(paper)
+  }
+
+  /// Fetches a single field of the paper for an already extracted canonical identifier.
+  ///
+  /// Unlike [`RetrieverConfig::fetch_paper`], the rest of the response does not need to parse,
+  /// so a field can be fetched from sources whose responses lack other required fields.
+  /// Returns `None` if the source does not provide the field or it is blank; no request is
+  /// made when there is no mapping for `field` at all.
+  pub(crate) async fn fetch_field(&self, identifier: &str, field: &str) -> Result<Option<String>> {
+    if !self.response_format.maps_field(field) {
+      return Ok(None);
+    }
     let url = self.endpoint_template.replace("{identifier}", identifier);
+    let data = self.fetch(&url).await?;
+    Ok(
+      self
+        .response_format
+        .extract_one(&data, field)?
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty()),
+    )
+  }
+
+  /// Returns the URL template and response format of the named endpoint, or of the default
+  /// endpoint for `None`.
+  fn endpoint(&self, endpoint: Option<&str>) -> Result<(&str, &ResponseFormat)> {
+    let Some(name) = endpoint else {
+      return Ok((&self.endpoint_template, &self.response_format));
+    };
+    let endpoint = self.endpoints.get(name).ok_or_else(|| {
+      LearnerError::Config(format!("{} has no endpoint named {name:?}", self.name))
+    })?;
+    Ok((&endpoint.template, endpoint.response_format.as_ref().unwrap_or(&self.response_format)))
+  }
+
+  /// Searches the source for papers matching `query`, using the
+  /// [`search_endpoint_template`](RetrieverConfig::search_endpoint_template).
+  ///
+  /// Each result in the response, found at
+  /// [`search_results_path`](RetrieverConfig::search_results_path), is parsed with the same
+  /// field maps as a single paper, except that a missing abstract is left empty. Results are
+  /// identified by their `identifier` field map, or by their DOI when there is none. Results
+  /// that cannot be parsed or identified are skipped.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if the retriever has no search endpoint or results path,
+  /// and otherwise fails like [`RetrieverConfig::retrieve_paper`] for the request itself.
+  pub async fn search(&self, query: &str) -> Result<Vec<Paper>> {
+    let (Some(template), Some(results_path)) =
+      (&self.search_endpoint_template, &self.search_results_path)
+    else {
+      return Err(LearnerError::Config(format!("{} does not support searching", self.name)));
+    };
+    let url = template.replace("{query}", &encode_query(query.trim()));
+    let data = self.fetch(&url).await?;
+
+    let mut papers = Vec::new();
+    for result in self.response_format.split_results(&data, results_path)? {
+      let mut paper = match self.response_format.process_search_result(&result) {
+        Ok(paper) => paper,
+        Err(e) => {
+          debug!("Skipping {} search result: {}", self.name, report(&e));
+          continue;
+        },
+      };
+      let raw = match std::mem::take(&mut paper.source_identifier) {
+        identifier if !identifier.is_empty() => identifier,
+        _ => paper.doi.clone().unwrap_or_default(),
+      };
+      let Ok(identifier) = self.canonical_identifier(&raw) else {
+        debug!("Skipping {} search result without a usable identifier: {raw:?}", self.name);
+        continue;
+      };
+      paper.source = self.source.clone();
+      paper.source_identifier = identifier;
+      paper.version = paper
+        .version
+        .filter(|version| !version.is_empty())
+        .or_else(|| self.extract_version(&raw).map(String::from));
+      papers.push(paper);
+    }
+    Ok(papers)
+  }
 
+  /// Requests `url` and returns the decoded response body.
+  ///
+  /// Responses with an `ETag` or `Last-Modified` validator are cached, and requesting the
+  /// same URL again revalidates them. The cache keeps the most recently used responses, up to
+  /// 256 of them and 32 MiB of bodies.
+  async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
     debug!("Fetching from {} via: {}", self.name, url);
 
-    let client = reqwest::Client::new();
-    let mut request = client.get(&url);
+    let mut request =
+      self.client.get(url).timeout(std::time::Duration::from_secs(self.timeout_secs));
 
-    // Add any configured headers
+    // Add any configured headers, letting `user_agent` replace a `User-Agent` entry
+    let user_agent = self.user_agent.as_ref().or(self.default_user_agent.as_ref());
     for (key, value) in &self.headers {
-      request = request.header(key, value);
+      if user_agent.is_none() || !key.eq_ignore_ascii_case("user-agent") {
+        request = request.header(key, value);
+      }
+    }
+    if let Some(user_agent) = user_agent {
+      request = request.header(reqwest::header::USER_AGENT, user_agent);
+    }
+
+    // Revalidate a cached response rather than downloading it again
+    let cached = self.cache.get(url);
+    if let Some(cached) = &cached {
+      if let Some(etag) = &cached.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+      }
+      if let Some(last_modified) = &cached.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+      }
     }
 
-    let response = request.send().await?;
-    let data = response.bytes().await?;
+    let response = request.send().await.map_err(|e| request_error(e, url))?;
+    let (content_type, data) = match cached {
+      Some(cached) if response.status() == reqwest::StatusCode::NOT_MODIFIED => {
+        debug!("{} not modified, using cached response", url);
+        (cached.content_type, cached.body)
+      },
+      _ => {
+        if !response.status().is_success() {
+          return Err(LearnerError::ApiError(format!("{} returned {}", url, response.status())));
+        }
+        let header = |name| {
+          response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+        };
+        let content_type = header(reqwest::header::CONTENT_TYPE);
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        let body = self.read_body(response, url).await?;
+        if etag.is_some() || last_modified.is_some() {
+          self.cache.insert(url, CachedResponse {
+            etag,
+            last_modified,
+            content_type: content_type.clone(),
+            body: body.clone(),
+          });
+        }
+        (content_type, body)
+      },
+    };
+    let data = decode_body(&data, content_type.as_deref())?.into_owned();
 
     trace!("{} response: {}", self.name, String::from_utf8_lossy(&data));
+    Ok(data)
+  }
 
-    let response_processor = match &self.response_format {
-      ResponseFormat::Xml(config) => config as &dyn ResponseProcessor,
-      ResponseFormat::Json(config) => config as &dyn ResponseProcessor,
+  /// Reads a response body, giving up once it exceeds
+  /// [`max_response_bytes`](RetrieverConfig::max_response_bytes).
+  ///
+  /// The body is read chunk by chunk, so an oversized response is rejected after at most one
+  /// chunk past the limit rather than after being buffered whole.
+  async fn read_body(&self, mut response: reqwest::Response, url: &str) -> Result<Vec<u8>> {
+    let too_large = || {
+      LearnerError::ApiError(format!("{url} response exceeds {} bytes", self.max_response_bytes))
     };
-    let mut paper = response_processor.process_response(&data).await?;
-    paper.source = self.source.clone();
-    paper.source_identifier = identifier.to_string();
-    Ok(paper)
+    if response.content_length().is_some_and(|length| length > self.max_response_bytes) {
+      return Err(too_large());
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| request_error(e, url))? {
+      if (data.len() + chunk.len()) as u64 > self.max_response_bytes {
+        return Err(too_large());
+      }
+      data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+  }
+}
+
+/// Percent-encodes a search query for use in a URL, leaving only unreserved characters as is.
+fn encode_query(query: &str) -> String {
+  query
+    .bytes()
+    .map(|b| match b {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' =>
+        (b as char).to_string(),
+      _ => format!("%{b:02X}"),
+    })
+    .collect()
+}
+
+/// Converts a request failure into a [`LearnerError`], keeping timeouts distinct.
+fn request_error(error: reqwest::Error, url: &str) -> LearnerError {
+  if error.is_timeout() {
+    LearnerError::Timeout(url.to_string())
+  } else {
+    LearnerError::Network(error)
   }
 }
 
+/// Decodes a response body into UTF-8 before it is handed to a [`ResponseProcessor`].
+///
+/// A byte order mark takes precedence; otherwise the `charset` parameter of the
+/// `Content-Type` header is consulted. UTF-16 without a BOM is read as big-endian, as
+/// RFC 2781 specifies. Anything else is passed through as UTF-8, minus any UTF-8 BOM.
+///
+/// # Errors
+///
+/// Returns [`LearnerError::Deserialize`] if a UTF-16 body is malformed.
+fn decode_body<'a>(data: &'a [u8], content_type: Option<&str>) -> Result<Cow<'a, [u8]>> {
+  let charset = content_type
+    .and_then(|value| {
+      value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"'))
+      })
+    })
+    .map(str::to_ascii_lowercase);
+
+  // `Some(true)` for UTF-16LE, `Some(false)` for UTF-16BE, `None` for UTF-8
+  let (little_endian, body) = match data {
+    [0xEF, 0xBB, 0xBF, rest @ ..] => (None, rest),
+    [0xFF, 0xFE, rest @ ..] => (Some(true), rest),
+    [0xFE, 0xFF, rest @ ..] => (Some(false), rest),
+    _ => match charset.as_deref() {
+      Some("utf-16le") => (Some(true), data),
+      Some("utf-16be" | "utf-16") => (Some(false), data),
+      _ => (None, data),
+    },
+  };
+  let Some(little_endian) = little_endian else {
+    return Ok(Cow::Borrowed(body));
+  };
+
+  let decoded = if little_endian { String::from_utf16le(body) } else { String::from_utf16be(body) };
+  decoded
+    .map(|text| Cow::Owned(text.into_bytes()))
+    .map_err(|e| LearnerError::deserialize("UTF-16 response body", e))
+}
+
+/// Cleans up extracted subject keywords for a [`Paper`].
+///
+/// Each value is transformed if the mapping asks for it, then trimmed. Empty values and
+/// repeats (ignoring case) are dropped, keeping the order they were found in.
+fn collect_keywords(values: impl IntoIterator<Item = String>, map: &FieldMap) -> Vec<String> {
+  let mut keywords: Vec<String> = Vec::new();
+  for value in values {
+    let value = match &map.transform {
+      Some(transform) => apply_transform(&value, transform).unwrap_or(value),
+      None => value,
+    };
+    let keyword = value.trim();
+    if !keyword.is_empty() && !keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)) {
+      keywords.push(keyword.to_string());
+    }
+  }
+  keywords
+}
+
 /// Custom deserializer for converting string patterns into Regex objects.
 ///
 /// Used with serde's derive functionality to automatically deserialize
@@ -682,16 +1808,33 @@ where D: serde::Deserializer<'de> {
 /// - Regex pattern is invalid
 /// - Date parsing fails
 /// - Date format is invalid
+/// - The transform is a [`Transform::Compose`], which XML responses apply themselves
 fn apply_transform(value: &str, transform: &Transform) -> Result<String> {
   match transform {
     Transform::Replace { pattern, replacement } => Regex::new(pattern)
       .map_err(|e| LearnerError::ApiError(format!("Invalid regex: {}", e)))
       .map(|re| re.replace_all(value, replacement.as_str()).into_owned()),
-    Transform::Date { from_format, to_format } =>
-      chrono::NaiveDateTime::parse_from_str(value, from_format)
-        .map_err(|e| LearnerError::ApiError(format!("Invalid date: {}", e)))
-        .map(|dt| dt.format(to_format).to_string()),
+    Transform::Date { from_format, to_format } => {
+      let invalid = |e: chrono::ParseError| LearnerError::ApiError(format!("Invalid date: {}", e));
+      let mut parsed = chrono::format::Parsed::new();
+      chrono::format::parse(&mut parsed, value, chrono::format::StrftimeItems::new(from_format))
+        .map_err(invalid)?;
+      match (parsed.year(), parsed.month(), parsed.day()) {
+        // Without a day there is nothing to fill in `to_format` with, so the date stays partial
+        (Some(year), None, None) => Ok(format!("{year:04}")),
+        (Some(year), Some(month), None) => Ok(format!("{year:04}-{month:02}")),
+        _ => match parsed.to_naive_datetime_with_offset(0) {
+          Ok(datetime) => Ok(datetime.format(to_format).to_string()),
+          Err(_) => parsed
+            .to_naive_date()
+            .map(|date| date.and_time(chrono::NaiveTime::MIN).format(to_format).to_string())
+            .map_err(invalid),
+        },
+      }
+    },
     Transform::Url { base, suffix } =>
       Ok(format!("{}{}", base.replace("{value}", value), suffix.as_deref().unwrap_or(""))),
+    Transform::Compose { .. } =>
+      Err(LearnerError::Config("Compose transforms are only supported for XML".to_string())),
   }
 }
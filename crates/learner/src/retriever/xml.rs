@@ -9,16 +9,26 @@
 //! ```toml
 //! [response_format]
 //! type = "xml"
-//! strip_namespaces = true
+//! strip_namespaces = true  # or ["oai_dc", "soap"], or { keep = ["dc"] }
 //!
 //! [response_format.field_maps]
 //! title = { path = "entry/title" }
 //! abstract = { path = "entry/summary" }
 //! publication_date = { path = "entry/published" }
 //! authors = { path = "entry/author/name" }
+//! pdf_url = { path = "entry/link/@href" }
 //! ```
+//!
+//! Attribute values are addressed by appending `@name` to the element's path, so
+//! `<link href="..."/>` inside `<entry>` is reachable as `entry/link/@href`.
+//!
+//! Repeated elements can be combined with a `Compose` transform, e.g. pairing the n-th
+//! `creator` of a flat record with its n-th `affiliation` to build structured authors.
 
-use quick_xml::{events::Event, Reader};
+use quick_xml::{
+  events::{BytesStart, Event},
+  Reader,
+};
 
 use super::*;
 
@@ -32,23 +42,78 @@ use super::*;
 /// ```no_run
 /// # use std::collections::HashMap;
 /// # use learner::retriever::{xml::XmlConfig, FieldMap};
+/// # use learner::retriever::xml::StripNamespaces;
 /// let config = XmlConfig {
-///   strip_namespaces: true,
+///   strip_namespaces: StripNamespaces::All(true),
 ///   field_maps:       HashMap::from([("title".to_string(), FieldMap {
 ///     path:      "entry/title".to_string(),
+///     fallback:  None,
 ///     transform: None,
 ///   })]),
 /// };
 /// ```
 #[derive(Debug, Clone, Deserialize)]
 pub struct XmlConfig {
-  /// Whether to remove XML namespace declarations and prefixes
+  /// Which XML namespace declarations and prefixes to remove
   #[serde(default)]
-  pub strip_namespaces: bool,
+  pub strip_namespaces: StripNamespaces,
   /// XML path mappings for paper metadata fields
   pub field_maps:       HashMap<String, FieldMap>,
 }
 
+/// Selects which namespace prefixes are removed before path-based extraction.
+///
+/// Deserializes from a bool for backward compatibility, from a list of prefixes to
+/// strip, or from a `keep` table listing the only prefixes to preserve.
+///
+/// # Examples
+///
+/// ```toml
+/// # Strip every prefix
+/// strip_namespaces = true
+///
+/// # Strip only the SOAP and OAI envelope prefixes
+/// strip_namespaces = ["soap", "oai_dc"]
+///
+/// # Strip everything except Dublin Core, so `dc:title` stays distinct
+/// strip_namespaces = { keep = ["dc"] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum StripNamespaces {
+  /// Strip every namespace prefix (`true`) or none at all (`false`)
+  All(bool),
+  /// Strip only the listed prefixes
+  Only(Vec<String>),
+  /// Strip every prefix except the listed ones
+  Except {
+    /// Prefixes to preserve
+    keep: Vec<String>,
+  },
+}
+
+impl Default for StripNamespaces {
+  fn default() -> Self { Self::All(false) }
+}
+
+impl StripNamespaces {
+  /// Returns whether any namespace stripping is configured.
+  pub fn is_enabled(&self) -> bool { !matches!(self, Self::All(false)) }
+
+  /// Returns whether the given namespace prefix should be removed.
+  ///
+  /// # Arguments
+  ///
+  /// * `prefix` - Namespace prefix without the trailing colon, e.g. `"dc"`
+  pub fn strips(&self, prefix: &str) -> bool {
+    match self {
+      Self::All(strip) => *strip,
+      Self::Only(prefixes) => prefixes.iter().any(|p| p == prefix),
+      Self::Except { keep } => !keep.iter().any(|p| p == prefix),
+    }
+  }
+}
+
 #[async_trait]
 impl ResponseProcessor for XmlConfig {
   /// Processes an XML API response into a Paper object.
@@ -72,14 +137,25 @@ impl ResponseProcessor for XmlConfig {
   /// - XML parsing fails
   /// - Required fields are missing
   /// - Field values are invalid or cannot be transformed
-  async fn process_response(&self, data: &[u8]) -> Result<Paper> {
-    let xml = if self.strip_namespaces {
-      strip_xml_namespaces(&String::from_utf8_lossy(data))
+  async fn process_response(&self, data: &[u8]) -> Result<Paper> { self.process(data, false) }
+}
+
+impl XmlConfig {
+  /// Parses a response for a single paper, or one result of a search when `search_result` is
+  /// set.
+  ///
+  /// Search results often leave out the abstract, so for them it is optional and defaults to
+  /// an empty string.
+  pub(crate) fn process(&self, data: &[u8], search_result: bool) -> Result<Paper> {
+    let xml = if self.strip_namespaces.is_enabled() {
+      strip_xml_namespaces(&String::from_utf8_lossy(data), &self.strip_namespaces)
     } else {
       String::from_utf8_lossy(data).to_string()
     };
 
     let content = self.extract_content(&xml)?;
+    // Scalar fields take the first value when an element repeats
+    let first = |path: &str| content.get(path).and_then(|values| values.first());
 
     // Helper function to extract and transform field
     let get_field = |name: &str| -> Result<String> {
@@ -88,8 +164,19 @@ impl ResponseProcessor for XmlConfig {
         .get(name)
         .ok_or_else(|| LearnerError::ApiError(format!("Missing field mapping for {}", name)))?;
 
-      let value = content
-        .get(&map.path)
+      if let Some(Transform::Compose { sources, format }) = &map.transform {
+        return match compose(&content, &map.path, sources, format)? {
+          Composed::Text(text) if !text.is_empty() => Ok(text),
+          Composed::Text(_) =>
+            Err(LearnerError::ApiError(format!("No content found for {}", name))),
+          Composed::Objects(_) =>
+            Err(LearnerError::Config(format!("Composed objects can't be used as the {}", name))),
+        };
+      }
+
+      let value = map
+        .paths()
+        .find_map(first)
         .ok_or_else(|| LearnerError::ApiError(format!("No content found for {}", name)))?;
 
       if let Some(transform) = &map.transform {
@@ -100,45 +187,69 @@ impl ResponseProcessor for XmlConfig {
     };
 
     let title = get_field("title")?;
-    let abstract_text = get_field("abstract")?;
-    let publication_date = chrono::DateTime::parse_from_rfc3339(&get_field("publication_date")?)
-      .map(|dt| dt.with_timezone(&Utc))
-      .map_err(|e| LearnerError::ApiError(format!("Invalid date format: {}", e)))?;
+    let abstract_text = match self.field_maps.get("abstract") {
+      Some(map) if search_result && first(&map.path).is_none() => String::new(),
+      _ => get_field("abstract")?,
+    };
+    let publication_date: PartialDate = get_field("publication_date")?.parse()?;
 
     // Extract authors
     let authors = if let Some(map) = self.field_maps.get("authors") {
-      let names: Vec<Author> = content
-        .get(&map.path)
-        .map(|s| {
-          s.split(';')
-            .map(|name| Author {
-              name:        name.trim().to_string(),
-              affiliation: None,
-              email:       None,
-            })
-            .collect()
-        })
-        .unwrap_or_default();
-      if names.is_empty() {
+      let authors: Vec<Author> = match &map.transform {
+        // Composed objects become authors with whichever fields they name, while composed
+        // text is read as a `;`-separated list of names
+        Some(Transform::Compose { sources, format }) =>
+          match compose(&content, &map.path, sources, format)? {
+            Composed::Text(names) => authors_from_names([names.as_str()]),
+            Composed::Objects(objects) => objects.into_iter().filter_map(author_from).collect(),
+          },
+        // Every repeated element is an author, and each may hold a `;`-separated list
+        _ => authors_from_names(content.get(&map.path).into_iter().flatten().map(String::as_str)),
+      };
+      if authors.is_empty() {
         return Err(LearnerError::ApiError("No authors found".to_string()));
       }
-      names
+      authors
     } else {
       return Err(LearnerError::ApiError("Missing authors mapping".to_string()));
     };
 
-    // Optional fields
-    let pdf_url = self.field_maps.get("pdf_url").and_then(|map| {
-      content.get(&map.path).map(|url| {
-        if let Some(transform) = &map.transform {
-          apply_transform(url, transform).ok().unwrap_or_else(|| url.clone())
-        } else {
-          url.clone()
-        }
-      })
-    });
+    // Optional fields keep their untransformed value if the transform fails
+    let optional = |name: &str| -> Option<String> {
+      let map = self.field_maps.get(name)?;
+      match &map.transform {
+        Some(Transform::Compose { sources, format }) =>
+          match compose(&content, &map.path, sources, format) {
+            Ok(Composed::Text(text)) if !text.is_empty() => Some(text),
+            _ => None,
+          },
+        Some(transform) => first(&map.path)
+          .map(|value| apply_transform(value, transform).unwrap_or_else(|_| value.clone())),
+        None => first(&map.path).cloned(),
+      }
+    };
+
+    let pdf_url = optional("pdf_url");
+
+    let doi = self
+      .field_maps
+      .get("doi")
+      .and_then(|map| first(&map.path))
+      .map(|doi| format::normalize_doi(doi).unwrap_or_else(|| doi.clone()));
+
+    let version = optional("version");
+
+    let primary_category = optional("primary_category");
+
+    // Every repeated element (or attribute) is a keyword
+    let keywords = self
+      .field_maps
+      .get("keywords")
+      .map(|map| collect_keywords(content.get(&map.path).cloned().unwrap_or_default(), map))
+      .unwrap_or_default();
 
-    let doi = self.field_maps.get("doi").and_then(|map| content.get(&map.path)).map(String::from);
+    // Only used by search results, which carry their own identifier
+    let source_identifier = optional("identifier").unwrap_or_default();
 
     Ok(Paper {
       title,
@@ -146,18 +257,111 @@ impl ResponseProcessor for XmlConfig {
       abstract_text,
       publication_date,
       source: String::new(),
-      source_identifier: String::new(),
+      source_identifier,
       pdf_url,
       doi,
+      references: Vec::new(),
+      keywords,
+      version,
+      primary_category,
+      language: None,
     })
   }
-}
 
-impl XmlConfig {
+  /// Extracts a single mapped field from a response, without parsing the rest of the paper.
+  ///
+  /// Like the scalar fields of a full paper, the first value is taken when the element
+  /// repeats. Returns `None` if the field has no mapping or the response has no content for it.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the XML is malformed, or the error of a failing
+  /// transform.
+  pub(crate) fn extract_one(&self, data: &[u8], field: &str) -> Result<Option<String>> {
+    let Some(map) = self.field_maps.get(field) else {
+      return Ok(None);
+    };
+    let xml = if self.strip_namespaces.is_enabled() {
+      strip_xml_namespaces(&String::from_utf8_lossy(data), &self.strip_namespaces)
+    } else {
+      String::from_utf8_lossy(data).to_string()
+    };
+    let content = self.extract_content(&xml)?;
+    match (content.get(&map.path).and_then(|values| values.first()), &map.transform) {
+      (_, Some(Transform::Compose { sources, format })) =>
+        match compose(&content, &map.path, sources, format)? {
+          Composed::Text(text) => Ok(Some(text).filter(|text| !text.is_empty())),
+          Composed::Objects(_) =>
+            Err(LearnerError::Config(format!("Composed objects can't be used as the {}", field))),
+        },
+      (Some(value), Some(transform)) => apply_transform(value, transform).map(Some),
+      (value, None) => Ok(value.cloned()),
+      (None, _) => Ok(None),
+    }
+  }
+
+  /// Splits a search response into one response per result.
+  ///
+  /// Every element at `path` is a result, and each one is wrapped in the elements leading to
+  /// it, so with `feed/entry` every entry becomes `<feed><entry>...</entry></feed>` and is read
+  /// with the same field maps as a single-paper response.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the XML is malformed.
+  pub(crate) fn split_results(&self, data: &[u8], path: &str) -> Result<Vec<Vec<u8>>> {
+    let xml = if self.strip_namespaces.is_enabled() {
+      strip_xml_namespaces(&String::from_utf8_lossy(data), &self.strip_namespaces)
+    } else {
+      String::from_utf8_lossy(data).to_string()
+    };
+    let parents: Vec<&str> = path.split('/').collect();
+    let parents = &parents[..parents.len() - 1];
+    let open: String = parents.iter().map(|name| format!("<{name}>")).collect();
+    let close: String = parents.iter().rev().map(|name| format!("</{name}>")).collect();
+
+    let mut reader = Reader::from_str(&xml);
+    let mut results = Vec::new();
+    let mut path_stack = Vec::new();
+    let mut result_start = None;
+    let mut buf = Vec::new();
+
+    loop {
+      let position = reader.buffer_position() as usize;
+      let event = reader
+        .read_event_into(&mut buf)
+        .map_err(|e| LearnerError::deserialize("XML response", e))?;
+      match event {
+        Event::Start(e) => {
+          path_stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+          if result_start.is_none() && path_stack.join("/") == path {
+            result_start = Some((position, path_stack.len()));
+          }
+        },
+        Event::End(_) => {
+          if let Some((start, depth)) = result_start {
+            if path_stack.len() == depth {
+              let end = reader.buffer_position() as usize;
+              results.push(format!("{open}{}{close}", &xml[start..end]).into_bytes());
+              result_start = None;
+            }
+          }
+          path_stack.pop();
+        },
+        Event::Eof => break,
+        _ => (),
+      }
+      buf.clear();
+    }
+
+    Ok(results)
+  }
+
   /// Extracts field values from XML content using path-based navigation.
   ///
   /// Builds a map of path -> value pairs by walking the XML tree and
-  /// tracking element paths. Handles nested elements and text content.
+  /// tracking element paths. Handles nested elements, text content, and
+  /// attributes, which are stored under `<element path>/@<attribute>`.
   ///
   /// # Arguments
   ///
@@ -165,23 +369,38 @@ impl XmlConfig {
   ///
   /// # Returns
   ///
-  /// Returns a HashMap mapping XML paths to their text content.
-  fn extract_content(&self, xml: &str) -> Result<HashMap<String, String>> {
+  /// Returns a HashMap mapping XML paths to their text content, in document
+  /// order when the same path occurs more than once.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Deserialize`] if the XML is malformed.
+  fn extract_content(&self, xml: &str) -> Result<HashMap<String, Vec<String>>> {
     let mut reader = Reader::from_str(xml);
     let mut content = HashMap::new();
     let mut path_stack = Vec::new();
     let mut buf = Vec::new();
 
-    while let Ok(event) = reader.read_event_into(&mut buf) {
+    loop {
+      let event = reader
+        .read_event_into(&mut buf)
+        .map_err(|e| LearnerError::deserialize("XML response", e))?;
       match event {
         Event::Start(e) => {
           path_stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+          extract_attributes(&e, &path_stack.join("/"), &mut content)?;
+        },
+        Event::Empty(e) => {
+          let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+          let path =
+            if path_stack.is_empty() { name } else { format!("{}/{}", path_stack.join("/"), name) };
+          extract_attributes(&e, &path, &mut content)?;
         },
         Event::Text(e) =>
           if let Ok(text) = e.unescape() {
             let text = text.trim();
             if !text.is_empty() {
-              content.insert(path_stack.join("/"), text.to_string());
+              content.entry(path_stack.join("/")).or_default().push(text.to_string());
             }
           },
         Event::End(_) => {
@@ -197,21 +416,199 @@ impl XmlConfig {
   }
 }
 
+/// The result of a [`Transform::Compose`].
+enum Composed {
+  /// Text from [`ComposeFormat::Join`]
+  Text(String),
+  /// Objects of field name -> value, a single one for [`ComposeFormat::Object`]
+  Objects(Vec<HashMap<String, String>>),
+}
+
+/// Combines the values of `sources` as described by `format`.
+///
+/// # Arguments
+///
+/// * `content` - Map of extracted path -> values pairs
+/// * `base` - Path of the field map, which source paths are relative to
+/// * `sources` - Values to combine, in order
+/// * `format` - How the values are combined
+///
+/// # Errors
+///
+/// Returns [`LearnerError::Config`] if an object format is given a key without a value, which
+/// only [`Source::KeyValue`] sources avoid by naming their own field.
+fn compose(
+  content: &HashMap<String, Vec<String>>,
+  base: &str,
+  sources: &[Source],
+  format: &ComposeFormat,
+) -> Result<Composed> {
+  let values = |source: &Source| -> Vec<String> {
+    match source {
+      Source::KeyValue { path, .. } | Source::Path { path } => {
+        let path = match (base, path.as_str()) {
+          ("", path) | (path, "") => path.to_string(),
+          (base, path) => format!("{base}/{path}"),
+        };
+        content.get(&path).cloned().unwrap_or_default()
+      },
+      Source::Literal { literal } => vec![literal.clone()],
+    }
+  };
+
+  if let ComposeFormat::Join { delimiter } = format {
+    return Ok(Composed::Text(sources.iter().flat_map(values).collect::<Vec<_>>().join(delimiter)));
+  }
+
+  // Each source resolves to whether it is fixed text, and its values
+  let resolve = |source: &Source| (matches!(source, Source::Literal { .. }), values(source));
+  // Key-value sources are whole fields, while the others pair up as key then value
+  let mut fields = Vec::new();
+  let mut pairs = Vec::new();
+  for source in sources {
+    match source {
+      Source::KeyValue { key, .. } => fields.push(((true, vec![key.clone()]), resolve(source))),
+      _ => pairs.push(resolve(source)),
+    }
+  }
+  if !pairs.len().is_multiple_of(2) {
+    return Err(LearnerError::Config(
+      "Composed objects need a key and a value source for every field".to_string(),
+    ));
+  }
+  let mut pairs = pairs.into_iter();
+  while let (Some(key), Some(value)) = (pairs.next(), pairs.next()) {
+    fields.push((key, value));
+  }
+
+  // Literals hold the same value for every object, paths their n-th value
+  let value = |(literal, values): &(bool, Vec<String>), index: usize| {
+    values.get(if *literal { 0 } else { index }).cloned()
+  };
+  let object = |index: usize| -> HashMap<String, String> {
+    fields
+      .iter()
+      .filter_map(|(key, field)| Some((value(key, index)?, value(field, index)?)))
+      .collect()
+  };
+
+  let count = match format {
+    ComposeFormat::Object => 1,
+    _ => fields
+      .iter()
+      .flat_map(|(key, value)| [key, value])
+      .filter(|(literal, _)| !literal)
+      .map(|(_, values)| values.len())
+      .max()
+      .unwrap_or(0),
+  };
+  Ok(Composed::Objects((0..count).map(object).filter(|object| !object.is_empty()).collect()))
+}
+
+/// Builds authors from names, splitting each value on `;` since a single element may list
+/// several of them.
+fn authors_from_names<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<Author> {
+  values
+    .into_iter()
+    .flat_map(|s| s.split(';'))
+    .map(str::trim)
+    .filter(|name| !name.is_empty())
+    .map(|name| Author {
+      name:        name.to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    })
+    .collect()
+}
+
+/// Builds an author from a composed object's `name`, `affiliation`, `email` and `orcid`
+/// fields.
+///
+/// Returns `None` if the object has no name.
+fn author_from(mut object: HashMap<String, String>) -> Option<Author> {
+  let name = object.remove("name").filter(|name| !name.trim().is_empty())?;
+  Some(Author {
+    name:        name.trim().to_string(),
+    affiliation: object.remove("affiliation"),
+    email:       object.remove("email"),
+    orcid:       object.remove("orcid").as_deref().and_then(json::normalize_orcid),
+  })
+}
+
+/// Records the attributes of an element as `<path>/@<attribute>` entries.
+///
+/// # Arguments
+///
+/// * `element` - The start or empty element carrying the attributes
+/// * `path` - Slash-separated path of the element itself
+/// * `content` - Map of extracted path -> value pairs to insert into
+///
+/// # Errors
+///
+/// Returns [`LearnerError::Deserialize`] if an attribute is malformed.
+fn extract_attributes(
+  element: &BytesStart,
+  path: &str,
+  content: &mut HashMap<String, Vec<String>>,
+) -> Result<()> {
+  for attribute in element.attributes() {
+    let attribute = attribute.map_err(|e| LearnerError::deserialize("XML attribute", e))?;
+    let value =
+      attribute.unescape_value().map_err(|e| LearnerError::deserialize("XML attribute", e))?;
+    let value = value.trim();
+    if !value.is_empty() {
+      let key = String::from_utf8_lossy(attribute.key.as_ref());
+      content.entry(format!("{path}/@{key}")).or_default().push(value.to_string());
+    }
+  }
+  Ok(())
+}
+
 /// Removes XML namespace declarations and prefixes from content.
 ///
-/// Strips both namespace declarations (xmlns attributes) and namespace
-/// prefixes from element names for simpler path-based access.
+/// Strips namespace declarations (xmlns attributes) and prefixes from element
+/// and attribute names for simpler path-based access. Only prefixes selected by
+/// `stripping` are removed; the default namespace declaration is always dropped.
+/// Text content, comments, and processing instructions are left untouched.
 ///
 /// # Arguments
 ///
 /// * `xml` - Raw XML content
+/// * `stripping` - Which prefixes to remove
 ///
 /// # Returns
 ///
-/// XML content with namespaces removed
-fn strip_xml_namespaces(xml: &str) -> String {
-  let re = regex::Regex::new(r#"xmlns(?::\w+)?="[^"]*""#).unwrap();
-  let mut result = re.replace_all(xml, "").to_string();
-  result = result.replace("oai_dc:", "").replace("dc:", "");
-  result
+/// XML content with the selected namespaces removed
+fn strip_xml_namespaces(xml: &str, stripping: &StripNamespaces) -> String {
+  let tag = regex::Regex::new(r"<[^!?][^>]*>").unwrap();
+  let declaration =
+    regex::Regex::new(r#"\s+xmlns(?::([\w.-]+))?\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap();
+  let element = regex::Regex::new(r"^(</?)([A-Za-z_][\w.-]*):").unwrap();
+  let attribute = regex::Regex::new(r"(\s)([A-Za-z_][\w.-]*):([\w.-]+\s*=)").unwrap();
+
+  tag
+    .replace_all(xml, |tag_caps: &regex::Captures| {
+      let tag = declaration.replace_all(&tag_caps[0], |caps: &regex::Captures| match caps.get(1) {
+        Some(prefix) if !stripping.strips(prefix.as_str()) => caps[0].to_string(),
+        _ => String::new(),
+      });
+      let tag = element.replace(&tag, |caps: &regex::Captures| {
+        if stripping.strips(&caps[2]) {
+          caps[1].to_string()
+        } else {
+          caps[0].to_string()
+        }
+      });
+      attribute
+        .replace_all(&tag, |caps: &regex::Captures| {
+          if &caps[2] != "xmlns" && stripping.strips(&caps[2]) {
+            format!("{}{}", &caps[1], &caps[3])
+          } else {
+            caps[0].to_string()
+          }
+        })
+        .into_owned()
+    })
+    .into_owned()
 }
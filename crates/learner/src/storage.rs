@@ -0,0 +1,414 @@
+//! Backends for where paper documents are kept.
+//!
+//! Documents are stored under a key, normally the paper's [`Paper::filename`], in a
+//! [`Storage`] backend. Each backend also names where a key lives as a URI, which is what the
+//! database records for a stored document (see [`Database::document_uri`]).
+//!
+//! The available backends are:
+//!
+//! - [`FileStorage`] - A directory on the local filesystem, the default, rooted at the configured
+//!   storage path
+//! - [`MemoryStorage`] - A map held in memory, for tests and throwaway libraries
+//! - `HttpStorage` - An object store spoken to over plain HTTP, with the `object-store` feature,
+//!   selected by the `[object_store]` table of the configuration (see [`ObjectStoreOptions`])
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{storage::MemoryStorage, Learner};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut learner = Learner::builder().build().await?;
+//!
+//! // Keep downloaded documents in memory instead of the storage directory
+//! learner.database.set_storage(MemoryStorage::default());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, fmt::Debug, io, sync::Mutex};
+
+use super::*;
+
+/// A place documents can be written to and read back from by key.
+///
+/// Keys are relative names such as `arxiv-2301.07041-some_title.pdf`, without a leading `/`.
+/// Implementations must be cheap to share, since the database holds one behind an
+/// [`Arc`](std::sync::Arc).
+#[async_trait]
+pub trait Storage: Debug + Send + Sync {
+  /// Stores `contents` under `key`, replacing anything stored there before.
+  async fn put(&self, key: &str, contents: &[u8]) -> Result<()>;
+
+  /// Reads back the contents stored under `key`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] with [`io::ErrorKind::NotFound`] if nothing is stored under
+  /// `key`.
+  async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+  /// Returns whether anything is stored under `key`.
+  async fn exists(&self, key: &str) -> Result<bool>;
+
+  /// Removes whatever is stored under `key`. Deleting a key that holds nothing is not an error.
+  async fn delete(&self, key: &str) -> Result<()>;
+
+  /// The URI naming where `key` is stored, e.g. `file:///home/me/papers/paper.pdf`.
+  fn uri(&self, key: &str) -> String;
+
+  /// A local directory where downloads into this backend are assembled, so an interrupted one
+  /// can be resumed. Defaults to a `learner-downloads` directory in the system's temporary
+  /// directory.
+  fn staging_dir(&self) -> PathBuf { std::env::temp_dir().join("learner-downloads") }
+
+  /// Stores the contents of the local file at `path` under `key`, consuming the file.
+  ///
+  /// The default reads the file, [`put`](Storage::put)s it and removes it.
+  async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+    self.put(key, &tokio::fs::read(path).await?).await?;
+    Ok(tokio::fs::remove_file(path).await?)
+  }
+}
+
+/// The hex-encoded SHA-256 digest of `contents`, as recorded for stored documents.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::storage::sha256;
+/// assert_eq!(sha256(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+/// ```
+pub fn sha256(contents: &[u8]) -> String {
+  ring::digest::digest(&ring::digest::SHA256, contents)
+    .as_ref()
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect()
+}
+
+/// The error [`Storage::get`] returns for a key that holds nothing.
+fn not_found(uri: &str) -> LearnerError {
+  LearnerError::Path(io::Error::new(io::ErrorKind::NotFound, format!("nothing stored at {uri}")))
+}
+
+/// Stores documents as files in a directory, the default [`Storage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStorage {
+  /// Directory the keys are relative to
+  root: PathBuf,
+}
+
+impl FileStorage {
+  /// Creates a backend storing documents in `root`, which is created on the first
+  /// [`Storage::put`] if needed.
+  pub fn new(root: impl Into<PathBuf>) -> Self { Self { root: root.into() } }
+
+  /// The directory documents are stored in.
+  pub fn root(&self) -> &Path { &self.root }
+
+  /// The path of the file holding `key`.
+  pub fn path(&self, key: &str) -> PathBuf { self.root.join(key) }
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+  async fn put(&self, key: &str, contents: &[u8]) -> Result<()> {
+    let path = self.path(key);
+    if let Some(parent) = path.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+    debug!("Writing document to path: {path:?}");
+    Ok(tokio::fs::write(path, contents).await?)
+  }
+
+  async fn get(&self, key: &str) -> Result<Vec<u8>> {
+    match tokio::fs::read(self.path(key)).await {
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Err(not_found(&self.uri(key))),
+      read => Ok(read?),
+    }
+  }
+
+  async fn exists(&self, key: &str) -> Result<bool> {
+    Ok(tokio::fs::try_exists(self.path(key)).await?)
+  }
+
+  async fn delete(&self, key: &str) -> Result<()> {
+    match tokio::fs::remove_file(self.path(key)).await {
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+      removed => Ok(removed?),
+    }
+  }
+
+  /// A `.partial` directory in the root, on the same filesystem so finished downloads can be
+  /// renamed into place.
+  fn staging_dir(&self) -> PathBuf { self.root.join(".partial") }
+
+  /// Renames the file into place, so a document is never seen half written.
+  async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+    let target = self.path(key);
+    if let Some(parent) = target.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+    debug!("Moving document from {path:?} to {target:?}");
+    Ok(tokio::fs::rename(path, target).await?)
+  }
+
+  fn uri(&self, key: &str) -> String {
+    let path = self.path(key);
+    Url::from_file_path(&path).map_or_else(|()| format!("file://{}", path.display()), String::from)
+  }
+}
+
+/// Keeps documents in memory, losing them when the last handle is dropped.
+///
+/// URIs have the form `memory:<key>`.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+  /// Stored documents by key
+  documents: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+  /// The keys currently stored, in no particular order.
+  pub fn keys(&self) -> Vec<String> {
+    self.documents.lock().expect("memory storage lock poisoned").keys().cloned().collect()
+  }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+  async fn put(&self, key: &str, contents: &[u8]) -> Result<()> {
+    self
+      .documents
+      .lock()
+      .expect("memory storage lock poisoned")
+      .insert(key.to_string(), contents.to_vec());
+    Ok(())
+  }
+
+  async fn get(&self, key: &str) -> Result<Vec<u8>> {
+    self
+      .documents
+      .lock()
+      .expect("memory storage lock poisoned")
+      .get(key)
+      .cloned()
+      .ok_or_else(|| not_found(&self.uri(key)))
+  }
+
+  async fn exists(&self, key: &str) -> Result<bool> {
+    Ok(self.documents.lock().expect("memory storage lock poisoned").contains_key(key))
+  }
+
+  async fn delete(&self, key: &str) -> Result<()> {
+    self.documents.lock().expect("memory storage lock poisoned").remove(key);
+    Ok(())
+  }
+
+  fn uri(&self, key: &str) -> String { format!("memory:{key}") }
+}
+
+/// An object store to keep documents in instead of the storage path, from the
+/// `[object_store]` table of `config.toml`:
+///
+/// ```toml
+/// [object_store]
+/// url = "https://papers.example.com/bucket/"
+/// token = "secret"  # sent as a bearer token, if the store needs one
+/// ```
+///
+/// Selecting a store requires the `object-store` feature, which provides `HttpStorage`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectStoreOptions {
+  /// URL the documents are stored under, which should end in `/`
+  pub url:   String,
+  /// Bearer token to authenticate requests with
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token: Option<String>,
+}
+
+impl ObjectStoreOptions {
+  /// Checks that the store can be used: `url` must be a URL and the `object-store` feature
+  /// enabled.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] describing the problem.
+  pub fn validate(&self) -> Result<()> {
+    self.parsed_url()?;
+    if cfg!(feature = "object-store") {
+      Ok(())
+    } else {
+      Err(LearnerError::Config(
+        "object_store is set, but learner was built without the `object-store` feature".to_string(),
+      ))
+    }
+  }
+
+  /// The backend these options select.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Config`] if `url` is not a URL.
+  #[cfg(feature = "object-store")]
+  pub fn storage(&self) -> Result<HttpStorage> {
+    let storage = HttpStorage::new(self.parsed_url()?);
+    Ok(match &self.token {
+      Some(token) => storage.with_bearer_token(token),
+      None => storage,
+    })
+  }
+
+  /// Parses `url`.
+  fn parsed_url(&self) -> Result<Url> {
+    Url::parse(&self.url)
+      .map_err(|e| LearnerError::Config(format!("object_store url {} is invalid: {e}", self.url)))
+  }
+}
+
+/// Stores documents in an object store that takes plain HTTP `PUT`, `GET`, `HEAD` and `DELETE`
+/// requests, such as an S3-compatible bucket behind a proxy that signs requests, or one that
+/// accepts a bearer token.
+///
+/// Each key is stored at `base_url` joined with the key, so `base_url` should end in `/`.
+/// Requests are not signed with AWS Signature Version 4, so buckets that require it need a
+/// signing proxy in front of them.
+#[cfg(feature = "object-store")]
+#[derive(Debug, Clone)]
+pub struct HttpStorage {
+  /// URL the keys are resolved against
+  base_url: Url,
+  /// Sent as `Authorization: Bearer <token>` when set
+  token:    Option<String>,
+  /// Client reused across requests
+  client:   reqwest::Client,
+}
+
+#[cfg(feature = "object-store")]
+impl HttpStorage {
+  /// Creates a backend storing documents under `base_url`.
+  pub fn new(base_url: Url) -> Self {
+    Self { base_url, token: None, client: reqwest::Client::new() }
+  }
+
+  /// Authenticates every request with the bearer `token`.
+  pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+    self.token = Some(token.into());
+    self
+  }
+
+  /// The URL `key` is stored at.
+  fn url(&self, key: &str) -> Result<Url> {
+    self
+      .base_url
+      .join(key)
+      .map_err(|e| LearnerError::Config(format!("invalid storage key {key}: {e}")))
+  }
+
+  /// Sends a `method` request for `key`, with the bearer token if one is set.
+  async fn send(
+    &self,
+    method: reqwest::Method,
+    key: &str,
+    body: Option<Vec<u8>>,
+  ) -> Result<reqwest::Response> {
+    let mut request = self.client.request(method, self.url(key)?);
+    if let Some(token) = &self.token {
+      request = request.bearer_auth(token);
+    }
+    if let Some(body) = body {
+      request = request.body(body);
+    }
+    Ok(request.send().await?)
+  }
+
+  /// Fails with [`LearnerError::ApiError`] unless `response` succeeded.
+  fn check(response: reqwest::Response, action: &str, key: &str) -> Result<reqwest::Response> {
+    if response.status().is_success() {
+      Ok(response)
+    } else {
+      Err(LearnerError::ApiError(format!("Failed to {action} {key}: {}", response.status())))
+    }
+  }
+}
+
+#[cfg(feature = "object-store")]
+#[async_trait]
+impl Storage for HttpStorage {
+  async fn put(&self, key: &str, contents: &[u8]) -> Result<()> {
+    let response = self.send(reqwest::Method::PUT, key, Some(contents.to_vec())).await?;
+    Self::check(response, "store", key).map(|_| ())
+  }
+
+  async fn get(&self, key: &str) -> Result<Vec<u8>> {
+    let response = self.send(reqwest::Method::GET, key, None).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+      return Err(not_found(&self.uri(key)));
+    }
+    Ok(Self::check(response, "read", key)?.bytes().await?.to_vec())
+  }
+
+  async fn exists(&self, key: &str) -> Result<bool> {
+    let response = self.send(reqwest::Method::HEAD, key, None).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+      return Ok(false);
+    }
+    Self::check(response, "look up", key).map(|_| true)
+  }
+
+  async fn delete(&self, key: &str) -> Result<()> {
+    let response = self.send(reqwest::Method::DELETE, key, None).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+      return Ok(());
+    }
+    Self::check(response, "delete", key).map(|_| ())
+  }
+
+  fn uri(&self, key: &str) -> String {
+    self.url(key).map_or_else(|_| format!("{}{key}", self.base_url), String::from)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Runs the same round trip against any backend.
+  async fn round_trip(storage: &dyn Storage) {
+    assert!(!storage.exists("paper.pdf").await.unwrap());
+    assert!(matches!(
+      storage.get("paper.pdf").await,
+      Err(LearnerError::Path(e)) if e.kind() == io::ErrorKind::NotFound
+    ));
+
+    storage.put("paper.pdf", b"%PDF-1.4").await.unwrap();
+    assert!(storage.exists("paper.pdf").await.unwrap());
+    assert_eq!(storage.get("paper.pdf").await.unwrap(), b"%PDF-1.4");
+
+    storage.put("paper.pdf", b"%PDF-1.7").await.unwrap();
+    assert_eq!(storage.get("paper.pdf").await.unwrap(), b"%PDF-1.7");
+
+    storage.delete("paper.pdf").await.unwrap();
+    assert!(!storage.exists("paper.pdf").await.unwrap());
+    storage.delete("paper.pdf").await.unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_file_storage_round_trip() {
+    let dir = tempdir().unwrap();
+    let storage = FileStorage::new(dir.path().join("papers"));
+    round_trip(&storage).await;
+
+    let uri = storage.uri("paper.pdf");
+    assert!(uri.starts_with("file://"));
+    assert_eq!(Url::parse(&uri).unwrap().to_file_path().unwrap(), storage.path("paper.pdf"));
+  }
+
+  #[tokio::test]
+  async fn test_memory_storage_round_trip() {
+    let storage = MemoryStorage::default();
+    round_trip(&storage).await;
+    assert_eq!(storage.uri("paper.pdf"), "memory:paper.pdf");
+  }
+}
@@ -9,13 +9,14 @@ use learner::{
   llm::{LlamaRequest, Model},
   pdf::PDFContentBuilder,
   prelude::*,
-  resource::{Author, Paper},
+  resource::{Author, Paper, PartialDate},
   Config, Learner,
 };
 use tempfile::{tempdir, TempDir};
 use tracing_test::traced_test;
 
 mod llm;
+mod mock;
 mod workflows;
 
 pub type TestResult<T> = Result<T, Box<dyn Error>>;
@@ -0,0 +1,322 @@
+//! A minimal HTTP/1.1 server for exercising network code paths without leaving the machine.
+
+// Not every test uses every knob of the mock server.
+#![allow(dead_code)]
+
+use std::{
+  collections::HashMap,
+  net::SocketAddr,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use tokio::{
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+};
+
+/// A request received by the [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+  pub method:  String,
+  pub path:    String,
+  /// Header names are lowercased
+  pub headers: HashMap<String, String>,
+  pub body:    Vec<u8>,
+}
+
+/// The response the [`MockServer`] should send back.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+  pub status:  u16,
+  pub headers: Vec<(String, String)>,
+  pub body:    Vec<u8>,
+  /// Delay before the response is written
+  pub delay:   Option<Duration>,
+  /// Close the connection once the response is written, e.g. to cut a body short
+  pub close:   bool,
+  /// Write the body this many bytes at a time, pausing in between so each arrives separately
+  pub chunk:   Option<usize>,
+}
+
+impl MockResponse {
+  pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+    Self {
+      status:  200,
+      headers: Vec::new(),
+      body:    body.into(),
+      delay:   None,
+      close:   false,
+      chunk:   None,
+    }
+  }
+
+  pub fn status(status: u16) -> Self { Self { status, ..Self::ok(Vec::new()) } }
+
+  pub fn with_header(mut self, name: &str, value: &str) -> Self {
+    self.headers.push((name.to_string(), value.to_string()));
+    self
+  }
+
+  pub fn with_delay(mut self, delay: Duration) -> Self {
+    self.delay = Some(delay);
+    self
+  }
+
+  /// Sends the body `size` bytes at a time, like a slow server would.
+  pub fn in_chunks_of(mut self, size: usize) -> Self {
+    self.chunk = Some(size);
+    self
+  }
+
+  /// Closes the connection after this response, so a `Content-Length` longer than the body
+  /// looks like an interrupted transfer.
+  pub fn closing(mut self) -> Self {
+    self.close = true;
+    self
+  }
+}
+
+/// Handle to a running mock server; the server stops when the test's runtime shuts down.
+pub struct MockServer {
+  addr:          SocketAddr,
+  requests:      Arc<Mutex<Vec<MockRequest>>>,
+  connections:   Arc<AtomicUsize>,
+  /// Requests received but not yet answered, and the most there have been at once
+  in_flight:     Arc<AtomicUsize>,
+  max_in_flight: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+  /// Starts a server answering every request with `handler`.
+  pub async fn start<F>(handler: F) -> Self
+  where F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let connections = Arc::new(AtomicUsize::new(0));
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let handler = Arc::new(handler);
+
+    let (reqs, conns) = (requests.clone(), connections.clone());
+    let (active, most) = (in_flight.clone(), max_in_flight.clone());
+    tokio::spawn(async move {
+      while let Ok((stream, _)) = listener.accept().await {
+        conns.fetch_add(1, Ordering::SeqCst);
+        let (handler, reqs) = (handler.clone(), reqs.clone());
+        let (active, most) = (active.clone(), most.clone());
+        tokio::spawn(async move {
+          let mut stream = BufReader::new(stream);
+          while let Some(request) = read_request(&mut stream).await {
+            most.fetch_max(active.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            reqs.lock().unwrap().push(request.clone());
+            let response = handler(&request);
+            if let Some(delay) = response.delay {
+              tokio::time::sleep(delay).await;
+            }
+            let written = write_response(stream.get_mut(), &response).await;
+            active.fetch_sub(1, Ordering::SeqCst);
+            if written.is_err() || response.close {
+              break;
+            }
+          }
+        });
+      }
+    });
+
+    Self { addr, requests, connections, in_flight, max_in_flight }
+  }
+
+  /// Starts a server that always answers with the same response.
+  pub async fn respond_with(response: MockResponse) -> Self {
+    Self::start(move |_| response.clone()).await
+  }
+
+  /// Full URL for `path` on this server.
+  pub fn url(&self, path: &str) -> String { format!("http://{}{}", self.addr, path) }
+
+  /// All requests received so far.
+  pub fn requests(&self) -> Vec<MockRequest> { self.requests.lock().unwrap().clone() }
+
+  /// Number of TCP connections accepted so far.
+  pub fn connections(&self) -> usize { self.connections.load(Ordering::SeqCst) }
+
+  /// Number of requests currently being answered.
+  pub fn in_flight(&self) -> usize { self.in_flight.load(Ordering::SeqCst) }
+
+  /// The most requests that were being answered at the same time so far.
+  pub fn max_in_flight(&self) -> usize { self.max_in_flight.load(Ordering::SeqCst) }
+}
+
+/// Reads one request (headers and any body) off the stream, or `None` once it is closed.
+async fn read_request<R: AsyncBufReadExt + Unpin>(stream: &mut R) -> Option<MockRequest> {
+  let mut line = String::new();
+  if stream.read_line(&mut line).await.ok()? == 0 {
+    return None;
+  }
+  let mut parts = line.split_whitespace();
+  let method = parts.next()?.to_string();
+  let path = parts.next()?.to_string();
+
+  let mut headers = HashMap::new();
+  loop {
+    let mut line = String::new();
+    stream.read_line(&mut line).await.ok()?;
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+    if let Some((name, value)) = line.split_once(':') {
+      headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+    }
+  }
+
+  let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+  let mut body = vec![0; length];
+  stream.read_exact(&mut body).await.ok()?;
+
+  Some(MockRequest { method, path, headers, body })
+}
+
+/// Writes `response`, adding a `Content-Length` header unless the response sets one itself.
+async fn write_response<W: AsyncWriteExt + Unpin>(
+  stream: &mut W,
+  response: &MockResponse,
+) -> std::io::Result<()> {
+  let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+  if !response.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-length")) {
+    head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+  }
+  for (name, value) in &response.headers {
+    head.push_str(&format!("{name}: {value}\r\n"));
+  }
+  head.push_str("\r\n");
+
+  stream.write_all(head.as_bytes()).await?;
+  match response.chunk {
+    Some(size) =>
+      for chunk in response.body.chunks(size) {
+        stream.write_all(chunk).await?;
+        stream.flush().await?;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+      },
+    None => stream.write_all(&response.body).await?,
+  }
+  stream.flush().await
+}
+
+/// TOML for a JSON retriever named `mock` that fetches `/works/<n>` from `server` for inputs of
+/// the form `mock:<n>`.
+pub fn mock_retriever_toml(server: &MockServer) -> String {
+  format!(
+    r#"
+name              = "mock"
+base_url          = "{base}"
+endpoint_template = "{base}/works/{{identifier}}"
+pattern           = "^mock:(\\d+)$"
+source            = "mock"
+
+[response_format]
+type = "json"
+
+[response_format.field_maps.title]
+path = "message/title"
+
+[response_format.field_maps.abstract]
+path = "message/abstract"
+
+[response_format.field_maps.authors]
+path = "message/author"
+
+[response_format.field_maps.publication_date]
+path = "message/created/date-time"
+
+[response_format.field_maps.pdf_url]
+path = "message/link/0/URL"
+
+[response_format.field_maps.references]
+path = "message/reference"
+"#,
+    base = server.url("")
+  )
+}
+
+/// A response body understood by [`mock_retriever_toml`].
+pub fn mock_paper_json(title: &str) -> String {
+  serde_json::json!({
+    "message": {
+      "title": [title],
+      "abstract": "An abstract served by the mock server.",
+      "author": [{
+        "given": "Ada",
+        "family": "Lovelace",
+        "ORCID": "http://orcid.org/0000-0002-1825-0097"
+      }],
+      "created": { "date-time": "2023-01-01T00:00:00Z" },
+      "link": [{ "URL": "http://127.0.0.1:9/unused.pdf" }],
+      "reference": [
+        { "key": "ref1", "DOI": "10.1000/cited.1" },
+        { "key": "ref2", "unstructured": "A citation without a DOI." }
+      ]
+    }
+  })
+  .to_string()
+}
+
+/// A TOML retriever config that parses arXiv-style Atom XML from `server`.
+pub fn mock_xml_retriever_toml(server: &MockServer) -> String {
+  format!(
+    r#"
+name              = "mock_xml"
+base_url          = "{base}"
+endpoint_template = "{base}/query/{{identifier}}"
+pattern           = "^mockxml:(\\d+)(?P<version>v\\d+)?$"
+source            = "mock_xml"
+
+[response_format]
+strip_namespaces = true
+type             = "xml"
+
+[response_format.field_maps.title]
+path = "feed/entry/title"
+
+[response_format.field_maps.abstract]
+path = "feed/entry/summary"
+
+[response_format.field_maps.authors]
+path = "feed/entry/author/name"
+
+[response_format.field_maps.publication_date]
+path = "feed/entry/published"
+
+[response_format.field_maps.version]
+path = "feed/entry/id"
+
+[response_format.field_maps.version.transform]
+pattern     = "^.*?(v\\d+)?$"
+replacement = "$1"
+type        = "Replace"
+"#,
+    base = server.url("")
+  )
+}
+
+/// A response body understood by [`mock_xml_retriever_toml`], reporting version `v2`.
+pub fn mock_paper_xml(title: &str) -> String {
+  format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/2301.00001v2</id>
+    <title>{title}</title>
+    <summary>An abstract served by the mock server.</summary>
+    <author><name>Ada Lovelace</name></author>
+    <published>2023-01-01T00:00:00Z</published>
+  </entry>
+</feed>"#
+  )
+}
@@ -1,6 +1,12 @@
 use std::fs::read_to_string;
 
-use learner::retriever::{ResponseFormat, RetrieverConfig, Transform};
+use learner::{
+  error::LearnerError,
+  resource::PartialDate,
+  retriever::{
+    xml::StripNamespaces, ResponseFormat, ResponseProcessor, Retriever, RetrieverConfig, Transform,
+  },
+};
 
 #[test]
 fn test_arxiv_config_deserialization() {
@@ -33,7 +39,7 @@ fn test_arxiv_config_deserialization() {
   // Verify response format
 
   if let ResponseFormat::Xml(config) = &retriever.response_format {
-    assert!(config.strip_namespaces);
+    assert_eq!(config.strip_namespaces, StripNamespaces::All(true));
 
     // Verify field mappings
     let field_maps = &config.field_maps;
@@ -118,6 +124,94 @@ fn test_doi_config_deserialization() {
   }
 }
 
+#[tokio::test]
+async fn test_crossref_partial_dates() {
+  let format: ResponseFormat = toml::from_str(
+    r#"
+type = "json"
+
+[field_maps.title]
+path = "message/title/0"
+
+[field_maps.abstract]
+path = "message/abstract"
+
+[field_maps.authors]
+path = "message/author"
+
+[field_maps.publication_date]
+path = "message/issued/date-parts/0"
+"#,
+  )
+  .unwrap();
+  let ResponseFormat::Json(config) = format else { panic!("Expected JSON format") };
+
+  let response = |date_parts: &str| {
+    format!(
+      r#"{{"message": {{
+        "title": ["Partially Dated"],
+        "abstract": "",
+        "author": [{{"given": "Ada", "family": "Lovelace"}}],
+        "issued": {{"date-parts": [{date_parts}]}}
+      }}}}"#
+    )
+  };
+
+  let paper = config.process_response(response("[2008]").as_bytes()).await.unwrap();
+  assert_eq!(paper.publication_date, PartialDate::Year(2008));
+  let paper = config.process_response(response("[2008, 1]").as_bytes()).await.unwrap();
+  assert_eq!(paper.publication_date, PartialDate::YearMonth(2008, 1));
+  let paper = config.process_response(response("[2008, 1, 15]").as_bytes()).await.unwrap();
+  assert_eq!(paper.publication_date.to_iso8601(), "2008-01-15T00:00:00+00:00");
+  assert!(config.process_response(response("[null]").as_bytes()).await.is_err());
+}
+
+#[test]
+fn test_doi_identifiers_are_normalized() {
+  let retriever = Retriever::new().with_default_configs().unwrap();
+
+  for input in [
+    "10.1145/1327452.1327492",
+    "https://doi.org/10.1145/1327452.1327492",
+    "HTTPS://DOI.ORG/10.1145/1327452.1327492",
+    "http://dx.doi.org/10.1145/1327452.1327492",
+    "doi:10.1145/1327452.1327492",
+  ] {
+    assert_eq!(
+      retriever.sanitize_identifier(input).unwrap(),
+      ("doi".to_string(), "10.1145/1327452.1327492".to_string()),
+      "failed for {input}"
+    );
+  }
+
+  // DOIs are case-insensitive, so an uppercased suffix is the same paper
+  assert_eq!(
+    retriever.sanitize_identifier("10.1145/ABC.DEF").unwrap(),
+    ("doi".to_string(), "10.1145/abc.def".to_string())
+  );
+}
+
+#[test]
+fn test_arxiv_version_parsing() {
+  let retriever = Retriever::new().with_default_configs().unwrap();
+  let arxiv = retriever.config("arxiv").unwrap();
+
+  for input in
+    ["2301.07041v2", "https://arxiv.org/abs/2301.07041v2", "https://arxiv.org/pdf/2301.07041v2.pdf"]
+  {
+    assert_eq!(arxiv.extract_identifier(input).unwrap(), "2301.07041");
+    assert_eq!(arxiv.extract_version(input), Some("v2"));
+  }
+  assert_eq!(arxiv.extract_version("2301.07041"), None);
+  assert_eq!(arxiv.extract_version("math.AG/0601001v3"), Some("v3"));
+
+  // The stored identifier stays version-less, so every version maps to the same paper
+  assert_eq!(
+    retriever.sanitize_identifier("2301.07041v2").unwrap(),
+    ("arxiv".to_string(), "2301.07041".to_string())
+  );
+}
+
 #[test]
 fn test_iacr_config_deserialization() {
   let config_str =
@@ -159,7 +253,7 @@ fn test_iacr_config_deserialization() {
 
   // Verify response format
   if let ResponseFormat::Xml(config) = &retriever.response_format {
-    assert!(config.strip_namespaces);
+    assert_eq!(config.strip_namespaces, StripNamespaces::All(true));
 
     // Verify field mappings
     let field_maps = &config.field_maps;
@@ -182,3 +276,369 @@ fn test_iacr_config_deserialization() {
   // Verify headers
   assert_eq!(retriever.headers.get("Accept").unwrap(), "application/xml");
 }
+
+#[test]
+fn test_list_shipped_configs() {
+  let retriever = Retriever::new().with_config_dir("config/retrievers").unwrap();
+
+  assert_eq!(retriever.names(), vec!["arxiv", "doi", "iacr"]);
+  assert_eq!(retriever.sources(), vec!["arxiv", "doi", "iacr"]);
+  assert_eq!(retriever.config("arxiv").unwrap().base_url, "http://export.arxiv.org");
+  assert!(retriever.config("nonexistent").is_none());
+}
+
+#[test]
+fn test_user_config_overrides_bundled() {
+  let dir = tempfile::tempdir().unwrap();
+  let custom = read_to_string("config/retrievers/arxiv.toml")
+    .unwrap()
+    .replace("http://export.arxiv.org", "http://arxiv.mirror.example.org");
+  std::fs::write(dir.path().join("arxiv.toml"), custom).unwrap();
+
+  let retriever =
+    Retriever::new().with_default_configs().unwrap().with_config_dir(dir.path()).unwrap();
+
+  assert_eq!(retriever.names(), vec!["arxiv", "doi", "iacr"]);
+  let arxiv = retriever.config("arxiv").unwrap();
+  assert_eq!(arxiv.base_url, "http://arxiv.mirror.example.org");
+  assert!(arxiv.endpoint_template.starts_with("http://arxiv.mirror.example.org"));
+  assert_eq!(retriever.config("doi").unwrap().base_url, "https://api.crossref.org/works");
+}
+
+#[test]
+fn test_load_config_dir_collects_errors() {
+  let dir = tempfile::tempdir().unwrap();
+  std::fs::copy("config/retrievers/arxiv.toml", dir.path().join("arxiv.toml")).unwrap();
+  std::fs::write(dir.path().join("broken.toml"), "name = \"broken\"\nbase_url = ").unwrap();
+  std::fs::write(dir.path().join("notes.txt"), "not a config").unwrap();
+
+  let (retriever, errors) = Retriever::new().load_config_dir(dir.path()).unwrap();
+
+  assert_eq!(retriever.names(), vec!["arxiv"]);
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].0, "broken.toml");
+  assert!(matches!(errors[0].1, LearnerError::TomlDe(_)));
+
+  assert!(Retriever::new().with_config_dir(dir.path()).is_err());
+}
+
+#[test]
+fn test_check_overlaps() {
+  let retriever = Retriever::new().with_config_dir("config/retrievers").unwrap();
+  assert!(retriever.check_overlaps(Retriever::DEFAULT_OVERLAP_SAMPLES).is_empty());
+
+  let overlapping = read_to_string("config/retrievers/arxiv.toml")
+    .unwrap()
+    .replace("name                     = \"arxiv\"", "name                     = \"arxiv_mirror\"");
+  let retriever = retriever.with_config_str(&overlapping).unwrap();
+
+  let overlaps = retriever.check_overlaps(&["2301.07041", "10.1145/1327452.1327492"]);
+  assert_eq!(overlaps, vec![("2301.07041".to_string(), vec![
+    "arxiv".to_string(),
+    "arxiv_mirror".to_string()
+  ])]);
+}
+
+#[test]
+fn test_priority_settles_overlaps() {
+  let retriever = Retriever::new().with_config_dir("config/retrievers").unwrap();
+  let mirror = read_to_string("config/retrievers/arxiv.toml")
+    .unwrap()
+    .replace("name                     = \"arxiv\"", "name                     = \"arxiv_mirror\"")
+    .replace("source                   = \"arxiv\"", "source                   = \"arxiv_mirror\"");
+
+  // At the same priority the overlap is ambiguous
+  let tied = retriever.clone().with_config_str(&mirror).unwrap();
+  assert!(matches!(
+    tied.sanitize_identifier("2301.07041"),
+    Err(LearnerError::AmbiguousIdentifier(names)) if names == ["arxiv", "arxiv_mirror"]
+  ));
+
+  // A higher priority wins, and the overlap is no longer reported
+  let preferred = retriever.with_config_str(&format!("priority = 1\n{mirror}")).unwrap();
+  assert_eq!(
+    preferred.sanitize_identifier("2301.07041").unwrap(),
+    ("arxiv_mirror".to_string(), "2301.07041".to_string())
+  );
+  assert_eq!(preferred.sanitize_identifier("10.1145/1327452.1327492").unwrap().0, "doi");
+  assert!(preferred.check_overlaps(Retriever::DEFAULT_OVERLAP_SAMPLES).is_empty());
+}
+
+#[tokio::test]
+async fn test_xml_attributes_are_addressable() {
+  let format: ResponseFormat = toml::from_str(
+    r#"
+type             = "xml"
+strip_namespaces = true
+
+[field_maps.title]
+path = "feed/entry/title"
+
+[field_maps.abstract]
+path = "feed/entry/summary"
+
+[field_maps.authors]
+path = "feed/entry/author/name"
+
+[field_maps.publication_date]
+path = "feed/entry/published"
+
+[field_maps.pdf_url]
+path = "feed/entry/link/@href"
+
+[field_maps.version]
+path = "feed/entry/@version"
+
+[field_maps.keywords]
+path = "feed/entry/category/@term"
+"#,
+  )
+  .unwrap();
+  let ResponseFormat::Xml(config) = format else { panic!("Expected XML format") };
+
+  let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry version="v3">
+    <title>Attribute Paper</title>
+    <summary>Abstract</summary>
+    <author><name>Ada Lovelace</name></author>
+    <author><name>Charles Babbage</name></author>
+    <published>2023-01-01T00:00:00Z</published>
+    <link href="https://example.org/paper.pdf?a=1&amp;b=2" rel="related"/>
+    <category term="cs.LG"/>
+    <category term="cs.AI"/>
+    <category term="CS.lg"/>
+  </entry>
+</feed>"#;
+
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.title, "Attribute Paper");
+  let names: Vec<_> = paper.authors.iter().map(|a| a.name.as_str()).collect();
+  assert_eq!(names, ["Ada Lovelace", "Charles Babbage"]);
+  assert_eq!(paper.pdf_url.as_deref(), Some("https://example.org/paper.pdf?a=1&b=2"));
+  assert_eq!(paper.version.as_deref(), Some("v3"));
+  // Every category is kept once, ignoring case
+  assert_eq!(paper.keywords, ["cs.LG", "cs.AI"]);
+}
+
+#[tokio::test]
+async fn test_selective_namespace_stripping() {
+  let format: ResponseFormat = toml::from_str(
+    r#"
+type             = "xml"
+strip_namespaces = { keep = ["dc"] }
+
+[field_maps.title]
+path = "envelope/record/dc:title"
+
+[field_maps.abstract]
+path = "envelope/record/dc:description"
+
+[field_maps.authors]
+path = "envelope/record/dc:creator"
+
+[field_maps.publication_date]
+path = "envelope/record/published"
+
+[field_maps.pdf_url]
+path = "envelope/record/link/@href"
+"#,
+  )
+  .unwrap();
+  let ResponseFormat::Xml(config) = format else { panic!("Expected XML format") };
+  assert_eq!(config.strip_namespaces, StripNamespaces::Except { keep: vec!["dc".to_string()] });
+
+  let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<soap:envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <soap:record>
+    <dc:title>Dublin Core Paper</dc:title>
+    <title>Envelope title that must not win</title>
+    <dc:description>soap: prefixes in text stay intact</dc:description>
+    <dc:creator>Ada Lovelace</dc:creator>
+    <soap:published>2023-01-01T00:00:00Z</soap:published>
+    <soap:link soap:href="https://example.org/paper.pdf"/>
+  </soap:record>
+</soap:envelope>"#;
+
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.title, "Dublin Core Paper");
+  assert_eq!(paper.abstract_text, "soap: prefixes in text stay intact");
+  assert_eq!(paper.authors[0].name, "Ada Lovelace");
+  assert_eq!(paper.pdf_url.as_deref(), Some("https://example.org/paper.pdf"));
+
+  // The list form strips only the named prefixes
+  let only: StripNamespaces = serde_json::from_str(r#"["soap"]"#).unwrap();
+  assert!(only.strips("soap"));
+  assert!(!only.strips("dc"));
+  let none: StripNamespaces = serde_json::from_str("false").unwrap();
+  assert!(!none.is_enabled());
+}
+
+/// Field maps for the DataCite record in `fixtures/datacite_1706_03762.xml`, followed by the
+/// `authors` map under test.
+fn datacite_config(authors: &str) -> learner::retriever::xml::XmlConfig {
+  let format: ResponseFormat = toml::from_str(&format!(
+    r#"
+type             = "xml"
+strip_namespaces = true
+
+[field_maps.title]
+path = "OAI-PMH/GetRecord/record/metadata/oai_datacite/payload/resource/titles/title"
+
+[field_maps.abstract]
+path = "OAI-PMH/GetRecord/record/metadata/oai_datacite/payload/resource/descriptions/description"
+
+[field_maps.publication_date]
+path = "OAI-PMH/GetRecord/record/metadata/oai_datacite/payload/resource/dates/date"
+
+[field_maps.pdf_url]
+path = "OAI-PMH/GetRecord/record/metadata/oai_datacite/payload/resource"
+
+[field_maps.pdf_url.transform]
+type    = "Compose"
+format  = {{ type = "Join" }}
+sources = [
+  {{ literal = "https://arxiv.org/pdf/" }},
+  {{ path = "alternateIdentifiers/alternateIdentifier" }},
+  {{ literal = ".pdf" }},
+]
+
+[field_maps.authors]
+path = "OAI-PMH/GetRecord/record/metadata/oai_datacite/payload/resource/creators/creator"
+
+{authors}
+"#
+  ))
+  .unwrap();
+  let ResponseFormat::Xml(config) = format else { panic!("Expected XML format") };
+  config
+}
+
+#[tokio::test]
+async fn test_compose_authors_from_parallel_elements() {
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "ArrayOfObjects" }
+sources = [
+  { literal = "name" }, { path = "creatorName" },
+  { literal = "affiliation" }, { path = "affiliation" },
+]
+"#,
+  );
+  let xml = include_str!("../fixtures/datacite_1706_03762.xml");
+
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.title, "Attention Is All You Need");
+  assert_eq!(paper.publication_date.to_iso8601(), "2017-06-12T00:00:00+00:00");
+  // Joined from literals and the arXiv identifier
+  assert_eq!(paper.pdf_url.as_deref(), Some("https://arxiv.org/pdf/1706.03762.pdf"));
+
+  // The n-th name is paired with the n-th affiliation
+  assert_eq!(paper.authors.len(), 8);
+  assert_eq!(paper.authors[0].name, "Vaswani, Ashish");
+  assert_eq!(paper.authors[0].affiliation.as_deref(), Some("Google Brain"));
+  assert_eq!(paper.authors[5].name, "Gomez, Aidan N.");
+  assert_eq!(paper.authors[5].affiliation.as_deref(), Some("University of Toronto"));
+  assert!(paper.authors.iter().all(|author| author.email.is_none()));
+}
+
+#[tokio::test]
+async fn test_compose_object_and_join_formats() {
+  let xml = include_str!("../fixtures/datacite_1706_03762.xml");
+
+  // A single object takes the first value of each path
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "Object" }
+sources = [
+  { literal = "name" }, { path = "creatorName" },
+  { literal = "affiliation" }, { path = "affiliation" },
+]
+"#,
+  );
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.authors.len(), 1);
+  assert_eq!(paper.authors[0].name, "Vaswani, Ashish");
+  assert_eq!(paper.authors[0].affiliation.as_deref(), Some("Google Brain"));
+
+  // Joined text is read as a `;`-separated list of names
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "Join", delimiter = "; " }
+sources = [{ path = "creatorName" }, { literal = "Anonymous" }]
+"#,
+  );
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  let names: Vec<_> = paper.authors.iter().map(|a| a.name.as_str()).collect();
+  assert_eq!(names.len(), 9);
+  assert_eq!(names[1], "Shazeer, Noam");
+  assert_eq!(names[8], "Anonymous");
+  assert!(paper.authors.iter().all(|author| author.affiliation.is_none()));
+
+  // Object formats need a value for every key
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "ArrayOfObjects" }
+sources = [{ literal = "name" }, { path = "creatorName" }, { literal = "affiliation" }]
+"#,
+  );
+  let err = config.process_response(xml.as_bytes()).await.unwrap_err();
+  assert!(matches!(err, LearnerError::Config(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_compose_key_value_sources() {
+  let xml = include_str!("../fixtures/datacite_1706_03762.xml");
+
+  // Each key-value source names its own field
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "Object" }
+sources = [{ key = "name", path = "creatorName" }, { key = "affiliation", path = "affiliation" }]
+"#,
+  );
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.authors.len(), 1);
+  assert_eq!(paper.authors[0].name, "Vaswani, Ashish");
+  assert_eq!(paper.authors[0].affiliation.as_deref(), Some("Google Brain"));
+
+  // They mix with key, value pairs of other sources, which set the email of every author here
+  let config = datacite_config(
+    r#"
+[field_maps.authors.transform]
+type    = "Compose"
+format  = { type = "ArrayOfObjects" }
+sources = [
+  { key = "name", path = "creatorName" },
+  { literal = "email" }, { literal = "authors@example.org" },
+  { key = "affiliation", path = "affiliation" },
+]
+"#,
+  );
+  let paper = config.process_response(xml.as_bytes()).await.unwrap();
+  assert_eq!(paper.authors.len(), 8);
+  assert_eq!(paper.authors[7].name, "Polosukhin, Illia");
+  assert_eq!(paper.authors[7].affiliation.as_deref(), Some("Google Research"));
+  assert!(paper.authors.iter().all(|a| a.email.as_deref() == Some("authors@example.org")));
+}
+
+#[test]
+fn test_arxiv_offers_oai_endpoint() {
+  let retriever = Retriever::new().with_default_configs().unwrap();
+  let arxiv = retriever.config("arxiv").unwrap();
+  let oai = &arxiv.endpoints["oai"];
+  assert!(oai.template.contains("verb=GetRecord"));
+  assert!(oai.template.contains("{identifier}"));
+  assert!(oai.response_format.is_some());
+}
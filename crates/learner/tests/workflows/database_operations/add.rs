@@ -53,8 +53,14 @@ mod basic_operations {
         name:        "Test Author 1".into(),
         affiliation: Some("University 1".into()),
         email:       Some("email1@test.com".into()),
+        orcid:       None,
+      },
+      Author {
+        name:        "Test Author 2".into(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
       },
-      Author { name: "Test Author 2".into(), affiliation: None, email: None },
     ];
 
     Add::paper(&paper).execute(&mut learner.database).await?;
@@ -166,10 +172,179 @@ mod document_operations {
   }
 }
 
+/// Tests for storing documents through a `Storage` backend other than the filesystem
+mod storage_backends {
+  use learner::storage::MemoryStorage;
+
+  use super::*;
+  use crate::mock::{MockResponse, MockServer};
+
+  /// A test paper whose PDF is served by `server`.
+  fn paper_served_by(server: &MockServer, source_identifier: &str) -> Paper {
+    let mut paper = create_test_paper();
+    paper.source_identifier = source_identifier.to_string();
+    paper.pdf_url = Some(server.url(&format!("/pdf/{source_identifier}.pdf")));
+    paper
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_complete_writes_through_storage() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, strg_dir) = create_test_learner().await;
+    let server = MockServer::respond_with(MockResponse::ok("%PDF-1.4 memory")).await;
+    learner.database.set_storage(MemoryStorage::default());
+
+    let paper = paper_served_by(&server, "2301.00001");
+    Add::complete(&paper).execute(&mut learner.database).await?;
+
+    // The document went to the backend and its URI was recorded, nothing was written to disk
+    let key = paper.filename().to_string_lossy().to_string();
+    let storage = learner.database.storage().await?;
+    assert_eq!(storage.get(&key).await?, b"%PDF-1.4 memory");
+    assert_eq!(learner.database.document_uri(&paper).await?, Some(format!("memory:{key}")));
+    assert_eq!(learner.database.document_path(&paper).await?, None);
+    assert_eq!(std::fs::read_dir(strg_dir.path())?.count(), 0);
+    assert!(Query::missing_documents().execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_documents_and_local_document_write_through_storage() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let server = MockServer::respond_with(MockResponse::ok("%PDF-1.4 downloaded")).await;
+    learner.database.set_storage(MemoryStorage::default());
+
+    let downloaded = paper_served_by(&server, "2301.00002");
+    Add::paper(&downloaded).execute(&mut learner.database).await?;
+    assert_eq!(Add::documents(Query::list_all()).execute(&mut learner.database).await?.len(), 1);
+
+    let local = create_second_test_paper();
+    Add::local_document(&local, Path::new("tests/.data/test_paper.pdf"))
+      .execute(&mut learner.database)
+      .await?;
+
+    let storage = learner.database.storage().await?;
+    for paper in [&downloaded, &local] {
+      let uri = learner.database.document_uri(paper).await?.unwrap();
+      let key = uri.strip_prefix("memory:").unwrap();
+      assert!(storage.exists(key).await?, "{uri} should be stored");
+    }
+    assert_eq!(
+      storage.get(&local.filename().to_string_lossy()).await?,
+      std::fs::read("tests/.data/test_paper.pdf")?
+    );
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_filesystem_documents_record_file_uri() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+    Add::local_document(&paper, Path::new("tests/.data/test_paper.pdf"))
+      .execute(&mut learner.database)
+      .await?;
+
+    let path = strg_dir.path().join(paper.filename());
+    let uri = learner.database.document_uri(&paper).await?.unwrap();
+    assert!(uri.starts_with("file://"), "{uri} should be a file URI");
+    assert_eq!(learner.database.document_path(&paper).await?, Some(path));
+
+    Ok(())
+  }
+}
+
+/// Tests for bounding concurrent downloads
+mod concurrency {
+  use std::time::Duration;
+
+  use super::*;
+  use crate::mock::{MockResponse, MockServer};
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_documents_respects_max_concurrency() -> TestResult<()> {
+    let server =
+      MockServer::respond_with(MockResponse::ok("%PDF-1.4").with_delay(Duration::from_millis(100)))
+        .await;
+    let config_dir = tempdir()?;
+    let database_dir = tempdir()?;
+    let storage_dir = tempdir()?;
+    let config = Config::default()
+      .with_database_path(&database_dir.path().join("learner.db"))
+      .with_retrievers_path(Path::new("config/retrievers/"))
+      .with_storage_path(storage_dir.path())
+      .with_max_concurrency(2);
+    let mut learner =
+      Learner::builder().with_path(config_dir.path()).with_config(config).build().await?;
+
+    for i in 0..6 {
+      let mut paper = create_test_paper();
+      paper.source_identifier = format!("2301.0000{i}");
+      paper.pdf_url = Some(server.url(&format!("/pdf/{i}.pdf")));
+      Add::paper(&paper).execute(&mut learner.database).await?;
+    }
+
+    let added = Add::documents(Query::list_all()).execute(&mut learner.database).await?;
+    assert_eq!(added.len(), 6);
+    assert_eq!(server.requests().len(), 6);
+    // Downloads overlap, but never more than the configured limit
+    assert_eq!(server.max_in_flight(), 2);
+    assert!(Query::missing_documents().execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_zero_max_concurrency_is_rejected() {
+    let storage_dir = tempdir().unwrap();
+    let config = Config::default()
+      .with_database_path(&storage_dir.path().join("learner.db"))
+      .with_retrievers_path(storage_dir.path())
+      .with_storage_path(storage_dir.path())
+      .with_max_concurrency(0);
+    assert!(matches!(config.validate(), Err(LearnerError::Config(_))));
+  }
+}
+
 /// Edge case tests
 mod edge_cases {
   use super::*;
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_complete_records_failed_download() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+
+    // The test paper's PDF URL is a known 404
+    let result = Add::complete(&paper).execute(&mut learner.database).await;
+    assert!(result.is_err());
+
+    // Paper metadata is kept and the failure is recorded
+    let stored = Query::by_source(&paper.source, &paper.source_identifier)
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(stored.len(), 1);
+
+    let (status, error_message) = learner
+      .database
+      .conn
+      .call(|conn| {
+        Ok(conn.query_row("SELECT download_status, error_message FROM files", [], |row| {
+          Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?)
+      })
+      .await?;
+    assert_eq!(status, "Failed");
+    assert!(error_message.is_some_and(|msg| !msg.is_empty()));
+
+    Ok(())
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_add_paper_with_special_characters() -> TestResult<()> {
@@ -214,3 +389,174 @@ mod edge_cases {
     Ok(())
   }
 }
+
+/// Tests for adding a paper that is already stored
+mod conflict_resolution {
+  use learner::database::Conflict;
+
+  use super::*;
+
+  /// Stores a sparse copy of the test paper and returns it along with a fuller copy
+  async fn store_sparse(learner: &mut Learner) -> TestResult<(Paper, Paper)> {
+    let full = Paper {
+      title: "Full Title".to_string(),
+      references: vec!["10.1000/cited.a".to_string()],
+      version: Some("v2".to_string()),
+      ..create_test_paper()
+    };
+    let sparse = Paper {
+      abstract_text: String::new(),
+      pdf_url: None,
+      doi: None,
+      authors: Vec::new(),
+      references: vec!["10.1000/cited.b".to_string()],
+      version: None,
+      ..create_test_paper()
+    };
+    Add::paper(&sparse).execute(&mut learner.database).await?;
+    Ok((sparse, full))
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_conflict_error_is_default() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let (sparse, full) = store_sparse(&mut learner).await?;
+
+    let err = Add::paper(&full).on_conflict(Conflict::Error).execute(&mut learner.database).await;
+    assert!(matches!(err, Err(LearnerError::DatabaseDuplicatePaper(_))));
+    assert_eq!(Query::by_paper(&full).one(&mut learner.database).await?, sparse);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_conflict_skip() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let (sparse, full) = store_sparse(&mut learner).await?;
+
+    let added =
+      Add::paper(&full).on_conflict(Conflict::Skip).execute(&mut learner.database).await?;
+    assert!(added.is_empty());
+    assert_eq!(Query::by_paper(&full).one(&mut learner.database).await?, sparse);
+
+    // A new paper is still added
+    let new = create_second_test_paper();
+    let added = Add::paper(&new).on_conflict(Conflict::Skip).execute(&mut learner.database).await?;
+    assert_eq!(added, vec![new]);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_conflict_overwrite() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let (_, full) = store_sparse(&mut learner).await?;
+
+    Add::paper(&full).on_conflict(Conflict::Overwrite).execute(&mut learner.database).await?;
+    assert_eq!(Query::by_paper(&full).one(&mut learner.database).await?, full);
+
+    // The title index follows the new title
+    assert_eq!(Query::text("Full").execute(&mut learner.database).await?.len(), 1);
+    assert_eq!(Query::list_all().execute(&mut learner.database).await?.len(), 1);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_conflict_merge() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let (sparse, full) = store_sparse(&mut learner).await?;
+
+    let merged =
+      Add::paper(&full).on_conflict(Conflict::Merge).execute(&mut learner.database).await?;
+    let stored = Query::by_paper(&full).one(&mut learner.database).await?;
+    assert_eq!(merged, vec![stored.clone()]);
+
+    // Fields the stored paper had are kept, missing ones are filled in
+    assert_eq!(stored.title, sparse.title);
+    assert_eq!(stored.abstract_text, full.abstract_text);
+    assert_eq!(stored.pdf_url, full.pdf_url);
+    assert_eq!(stored.doi, full.doi);
+    assert_eq!(stored.version, full.version);
+    assert_eq!(stored.authors, full.authors);
+    let mut references = stored.references.clone();
+    references.sort();
+    assert_eq!(references, ["10.1000/cited.a", "10.1000/cited.b"]);
+
+    // Merging again changes nothing, including the authors already stored
+    Add::paper(&create_test_paper())
+      .on_conflict(Conflict::Merge)
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(Query::by_paper(&full).one(&mut learner.database).await?, stored);
+
+    Ok(())
+  }
+}
+
+/// Batch metadata addition tests
+mod batch_operations {
+  use learner::database::Conflict;
+
+  use super::*;
+
+  /// Fifty distinct papers, each with one author and keyword.
+  fn batch() -> Vec<Paper> {
+    (0..50)
+      .map(|i| {
+        let mut paper = create_test_paper();
+        paper.title = format!("Batch Paper {i}");
+        paper.source_identifier = format!("2301.{i:05}");
+        paper.doi = None;
+        paper.keywords = vec![format!("kw{}", i % 5)];
+        paper.authors.truncate(1);
+        paper
+      })
+      .collect()
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_papers_in_one_call() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let mut papers = batch();
+    // Repeats within the batch are only added once
+    papers.push(papers[3].clone());
+    papers.push(papers[17].clone());
+
+    let added = Add::papers(&papers).execute(&mut learner.database).await?;
+    assert_eq!(added.len(), 50);
+    assert_eq!(added, papers[..50]);
+
+    let stored = Query::list_all().execute(&mut learner.database).await?;
+    assert_eq!(stored.len(), 50);
+    assert!(stored.iter().all(|paper| paper.authors.len() == 1 && paper.keywords.len() == 1));
+    assert_eq!(Query::by_keyword("kw0").execute(&mut learner.database).await?.len(), 10);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_add_papers_dedupes_against_stored() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let papers = batch();
+    Add::papers(&papers[..10]).execute(&mut learner.database).await?;
+
+    // By default a stored paper fails the whole batch
+    let err = Add::papers(&papers).execute(&mut learner.database).await.unwrap_err();
+    assert!(matches!(err, LearnerError::DatabaseDuplicatePaper(_)));
+    assert_eq!(Query::list_all().execute(&mut learner.database).await?.len(), 10);
+
+    let added =
+      Add::papers(&papers).on_conflict(Conflict::Skip).execute(&mut learner.database).await?;
+    assert_eq!(added, papers[10..]);
+    assert_eq!(Query::list_all().execute(&mut learner.database).await?.len(), 50);
+
+    Ok(())
+  }
+}
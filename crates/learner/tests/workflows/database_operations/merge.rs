@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use learner::database::{DuplicateReason, Merge};
+
+use super::*;
+
+#[traced_test]
+#[tokio::test]
+async fn test_merge_combines_complementary_papers() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+  // The arXiv copy has a short abstract and no DOI; the DOI copy has the rest
+  let mut arxiv = create_test_paper();
+  arxiv.abstract_text = "Short".to_string();
+  arxiv.doi = None;
+  arxiv.references = vec!["10.1000/shared".to_string()];
+  let mut doi = create_second_test_paper();
+  doi.title = arxiv.title.clone();
+  doi.source = "doi".to_string();
+  doi.source_identifier = "10.0000/test.123".to_string();
+  doi.doi = Some("10.0000/test.123".to_string());
+  doi.authors = vec![
+    Author {
+      name:        "JANE SMITH".to_string(),
+      affiliation: Some("Smith Institute".to_string()),
+      email:       None,
+      orcid:       Some("0000-0002-1825-0097".to_string()),
+    },
+    Author {
+      name:        "Ada Lovelace".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    },
+  ];
+  doi.references = vec!["10.1000/SHARED".to_string(), "10.1000/extra".to_string()];
+  Add::paper(&arxiv).execute(&mut learner.database).await?;
+  Add::local_document(&doi, Path::new("tests/.data/test_paper.pdf"))
+    .execute(&mut learner.database)
+    .await?;
+
+  let merged = Merge::papers(&arxiv, &doi).execute(&mut learner.database).await?;
+
+  assert_eq!((merged.source.as_str(), merged.source_identifier.as_str()), ("arxiv", "2301.00000"));
+  assert_eq!(merged.abstract_text, doi.abstract_text);
+  assert_eq!(merged.doi, doi.doi);
+  assert_eq!(merged.pdf_url, arxiv.pdf_url);
+  assert_eq!(merged.references, vec!["10.1000/shared", "10.1000/extra"]);
+
+  // Matching authors are merged rather than duplicated
+  let names: Vec<_> = merged.authors.iter().map(|a| a.name.as_str()).collect();
+  assert_eq!(names, vec!["John Doe", "Jane Smith", "Ada Lovelace"]);
+  assert_eq!(merged.authors[1].affiliation.as_deref(), Some("Smith Institute"));
+  assert_eq!(merged.authors[1].orcid.as_deref(), Some("0000-0002-1825-0097"));
+
+  // Only the survivor is left, with the merged metadata and the absorbed paper's document
+  assert_eq!(Query::by_paper(&arxiv).execute(&mut learner.database).await?, vec![merged]);
+  assert!(Query::by_paper(&doi)
+    .include_archived()
+    .execute(&mut learner.database)
+    .await?
+    .is_empty());
+  assert!(Query::missing_documents().execute(&mut learner.database).await?.is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_merge_missing_paper() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let survivor = create_test_paper();
+  let absorbed = create_second_test_paper();
+  Add::paper(&survivor).execute(&mut learner.database).await?;
+
+  let result = Merge::papers(&survivor, &absorbed).execute(&mut learner.database).await;
+  assert!(matches!(result, Err(LearnerError::NotFound { .. })));
+
+  // The failed merge left the survivor untouched
+  assert_eq!(Query::by_paper(&survivor).execute(&mut learner.database).await?, vec![survivor]);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_find_duplicates_clusters_same_doi_across_sources() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+  // The same work added from arXiv and by DOI, under a different title spelling
+  let arxiv = create_test_paper();
+  let mut doi = create_test_paper();
+  doi.title = "A Different Title".to_string();
+  doi.source = "doi".to_string();
+  doi.source_identifier = "10.0000/TEST.123".to_string();
+  doi.doi = None;
+  let unrelated = create_second_test_paper();
+  for paper in [&arxiv, &doi, &unrelated] {
+    Add::paper(paper).execute(&mut learner.database).await?;
+  }
+
+  let clusters = learner.database.find_duplicates().await?;
+  assert_eq!(clusters.len(), 1);
+  assert_eq!(clusters[0].reason, DuplicateReason::Doi);
+  assert_eq!(clusters[0].key, "10.0000/test.123");
+  assert_eq!(clusters[0].papers.len(), 2);
+  assert!(clusters[0].papers.iter().any(|paper| paper.same_identity(&arxiv)));
+  assert!(clusters[0].papers.iter().any(|paper| paper.same_identity(&doi)));
+
+  // Finding duplicates is read-only
+  assert_eq!(Query::list_all().execute(&mut learner.database).await?.len(), 3);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_find_duplicates_clusters_title_and_first_author() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+  let first = create_test_paper();
+  let mut second = create_second_test_paper();
+  second.title = "TEST paper.".to_string();
+  second.doi = None;
+  second.authors[0].name = "Doe, John".to_string();
+  Add::paper(&first).execute(&mut learner.database).await?;
+  Add::paper(&second).execute(&mut learner.database).await?;
+
+  let clusters = learner.database.find_duplicates().await?;
+  assert_eq!(clusters.len(), 1);
+  assert_eq!(clusters[0].reason, DuplicateReason::TitleAndAuthor);
+  assert_eq!(clusters[0].key, "test paper / doe");
+  assert_eq!(clusters[0].papers.len(), 2);
+
+  Ok(())
+}
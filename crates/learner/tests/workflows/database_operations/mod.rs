@@ -1,53 +1,63 @@
 use chrono::{TimeZone, Utc};
-use learner::database::{Add, OrderField, Query, Remove};
+use learner::database::{Add, OrderField, Query, Remove, Update};
 
 use super::*;
 
 mod add;
+mod merge;
 mod query;
 mod remove;
+mod update;
 
 /// Helper function to create a test paper
-fn create_test_paper() -> Paper {
-  Paper {
-    title:             "Test Paper".to_string(),
-    abstract_text:     "This is a test abstract".to_string(),
-    publication_date:  chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2023, 1, 1, 0, 0, 0)
-      .unwrap(),
-    source:            "arxiv".to_string(),
-    source_identifier: "2301.00000".to_string(),
-    pdf_url:           Some("https://arxiv.org/pdf/2301.00000".to_string()),
-    doi:               Some("10.0000/test.123".to_string()),
-    authors:           vec![
-      Author {
-        name:        "John Doe".to_string(),
-        affiliation: Some("Test University".to_string()),
-        email:       Some("john@test.edu".to_string()),
-      },
-      Author { name: "Jane Smith".to_string(), affiliation: None, email: None },
-    ],
-  }
+pub fn create_test_paper() -> Paper {
+  Paper::builder()
+    .with_title("Test Paper")
+    .with_abstract("This is a test abstract")
+    .with_publication_date(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())
+    .with_source("arxiv")
+    .with_source_identifier("2301.00000")
+    .with_pdf_url("https://arxiv.org/pdf/2301.00000")
+    .with_doi("10.0000/test.123")
+    .with_author(Author {
+      name:        "John Doe".to_string(),
+      affiliation: Some("Test University".to_string()),
+      email:       Some("john@test.edu".to_string()),
+      orcid:       None,
+    })
+    .with_author(Author {
+      name:        "Jane Smith".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    })
+    .build()
+    .unwrap()
 }
 
-fn create_second_test_paper() -> Paper {
-  Paper {
-    title:             "Test Paper: Two".to_string(),
-    abstract_text:     "This is a test abstract, but again!".to_string(),
-    publication_date:  chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 1, 1, 0, 0, 0)
-      .unwrap(),
-    source:            "arxiv".to_string(),
-    source_identifier: "2401.00000".to_string(),
-    pdf_url:           Some("https://arxiv.org/pdf/2401.00000".to_string()),
-    doi:               Some("10.1000/test.1234".to_string()),
-    authors:           vec![
-      Author {
-        name:        "Alice Scientist".to_string(),
-        affiliation: Some("Test State University".to_string()),
-        email:       Some("john@test.edu".to_string()),
-      },
-      Author { name: "Bob Researcher".to_string(), affiliation: None, email: None },
-    ],
-  }
+pub fn create_second_test_paper() -> Paper {
+  Paper::builder()
+    .with_title("Test Paper: Two")
+    .with_abstract("This is a test abstract, but again!")
+    .with_publication_date(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    .with_source("arxiv")
+    .with_source_identifier("2401.00000")
+    .with_pdf_url("https://arxiv.org/pdf/2401.00000")
+    .with_doi("10.1000/test.1234")
+    .with_author(Author {
+      name:        "Alice Scientist".to_string(),
+      affiliation: Some("Test State University".to_string()),
+      email:       Some("john@test.edu".to_string()),
+      orcid:       None,
+    })
+    .with_author(Author {
+      name:        "Bob Researcher".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    })
+    .build()
+    .unwrap()
 }
 
 #[tokio::test]
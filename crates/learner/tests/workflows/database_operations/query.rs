@@ -1,5 +1,3 @@
-use chrono::Datelike;
-
 use super::*;
 
 /// Basic paper search functionality
@@ -20,6 +18,102 @@ mod paper_search {
   }
 }
 
+/// Lookups that must match exactly one paper
+mod single_result {
+  use super::*;
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_one_with_no_match() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let result = Query::by_source("arxiv", "2301.00000").one(&mut learner.database).await;
+    match result {
+      Err(LearnerError::NotFound { source_name, identifier }) => {
+        assert_eq!(source_name, "arxiv");
+        assert_eq!(identifier, "2301.00000");
+      },
+      other => panic!("expected NotFound, got {other:?}"),
+    }
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_one_with_single_match() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+
+    let found = Query::by_paper(&paper).one(&mut learner.database).await?;
+    assert_eq!(found.title, "Test Paper");
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_one_with_many_matches() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    Add::paper(&create_test_paper()).execute(&mut learner.database).await?;
+    Add::paper(&create_second_test_paper()).execute(&mut learner.database).await?;
+
+    let result = Query::text("test").one(&mut learner.database).await;
+    assert!(matches!(result, Err(LearnerError::Ambiguous(2))));
+    Ok(())
+  }
+}
+
+/// Papers the daemon still needs documents for
+mod missing_documents {
+  use super::*;
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_failed_downloads_are_missing() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let mut paper = create_test_paper();
+    paper.pdf_url = None;
+    assert!(Add::complete(&paper).execute(&mut learner.database).await.is_err());
+
+    let missing = Query::missing_documents().execute(&mut learner.database).await?;
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].title, "Test Paper");
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_stored_documents_are_not_missing() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let with_document = create_test_paper();
+    let without_document = create_second_test_paper();
+    Add::paper(&without_document).execute(&mut learner.database).await?;
+    Add::local_document(&with_document, Path::new("tests/.data/test_paper.pdf"))
+      .execute(&mut learner.database)
+      .await?;
+
+    let missing = Query::missing_documents().execute(&mut learner.database).await?;
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].title, without_document.title);
+
+    // Ordering applies as for any other criterion
+    let mut older = create_test_paper();
+    older.source_identifier = "2201.00000".to_string();
+    older.title = "Older Paper".to_string();
+    older.publication_date = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap().into();
+    Add::paper(&older).execute(&mut learner.database).await?;
+
+    let missing = Query::missing_documents()
+      .order_by(OrderField::PublicationDate)
+      .descending()
+      .execute(&mut learner.database)
+      .await?;
+    let titles: Vec<_> = missing.iter().map(|paper| paper.title.as_str()).collect();
+    assert_eq!(titles, ["Test Paper: Two", "Older Paper"]);
+    Ok(())
+  }
+}
+
 /// Basic text search functionality
 mod text_search {
   use super::*;
@@ -37,6 +131,42 @@ mod text_search {
     Ok(())
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_text_search_snippets() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let mut paper = create_test_paper();
+    paper.title = "Notes on Quantum Error Correction".to_string();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+
+    let results =
+      Query::text("quantum").with_snippets().execute_with_snippets(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    let (found, snippet) = &results[0];
+    assert_eq!(found, &paper);
+    assert_eq!(
+      snippet.as_deref(),
+      Some(
+        format!("Notes on {}Quantum{} Error Correction", Query::SNIPPET_OPEN, Query::SNIPPET_CLOSE)
+          .as_str()
+      )
+    );
+    assert_eq!(Query::snippet_segments(snippet.as_deref().unwrap()), [
+      ("Notes on ", false),
+      ("Quantum", true),
+      (" Error Correction", false)
+    ]);
+
+    // Snippets are only produced when asked for, and only by text searches
+    let results = Query::text("quantum").execute_with_snippets(&mut learner.database).await?;
+    assert_eq!(results, vec![(paper.clone(), None)]);
+    let results =
+      Query::list_all().with_snippets().execute_with_snippets(&mut learner.database).await?;
+    assert_eq!(results, vec![(paper, None)]);
+
+    Ok(())
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_case_insensitive_search() -> TestResult<()> {
@@ -135,12 +265,20 @@ mod author_search {
   async fn test_partial_author_name() -> TestResult<()> {
     let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
     let mut paper = create_test_paper();
-    paper.authors =
-      vec![Author { name: "John Smith".to_string(), affiliation: None, email: None }, Author {
+    paper.authors = vec![
+      Author {
+        name:        "John Smith".to_string(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
+      },
+      Author {
         name:        "Jane Smith".to_string(),
         affiliation: None,
         email:       None,
-      }];
+        orcid:       None,
+      },
+    ];
     Add::paper(&paper).execute(&mut learner.database).await?;
 
     let results = Query::by_author("Smith").execute(&mut learner.database).await?;
@@ -153,6 +291,30 @@ mod author_search {
     Ok(())
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_author_search_ignores_diacritics() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let mut paper = create_test_paper();
+    paper.authors = vec![Author {
+      name:        "Thomas Müller".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    }];
+    Add::paper(&paper).execute(&mut learner.database).await?;
+
+    for query in ["Muller", "MULLER", "Müller", "thomas müller"] {
+      let results = Query::by_author(query).execute(&mut learner.database).await?;
+      assert_eq!(results.len(), 1, "no match for {query:?}");
+      // The name is still shown as it was stored
+      assert_eq!(results[0].authors[0].name, "Thomas Müller");
+    }
+    assert!(Query::by_author("Mueller").execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_multiple_papers_same_author() -> TestResult<()> {
@@ -162,8 +324,12 @@ mod author_search {
     let mut paper2 = create_second_test_paper();
 
     // Give both papers the same author
-    let author =
-      Author { name: "Shared Author".to_string(), affiliation: None, email: None };
+    let author = Author {
+      name:        "Shared Author".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    };
     paper1.authors = vec![author.clone()];
     paper2.authors = vec![author];
 
@@ -186,6 +352,7 @@ mod author_search {
       name:        "John Doe".to_string(),
       affiliation: Some("Test University".to_string()),
       email:       Some("john@test.edu".to_string()),
+      orcid:       None,
     }];
 
     Add::paper(&paper).execute(&mut learner.database).await?;
@@ -198,6 +365,249 @@ mod author_search {
   }
 }
 
+/// Affiliation-based search functionality
+mod affiliation_search {
+  use super::*;
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_affiliation_search() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let mut paper1 = create_test_paper();
+    paper1.authors = vec![
+      Author {
+        name:        "Alice Affiliated".to_string(),
+        affiliation: Some("Tech University".to_string()),
+        email:       None,
+        orcid:       None,
+      },
+      Author {
+        name:        "Bob Unaffiliated".to_string(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
+      },
+    ];
+    let mut paper2 = create_second_test_paper();
+    paper2.authors = vec![Author {
+      name:        "Carol Elsewhere".to_string(),
+      affiliation: Some("Other Institute".to_string()),
+      email:       None,
+      orcid:       None,
+    }];
+    Add::paper(&paper1).execute(&mut learner.database).await?;
+    Add::paper(&paper2).execute(&mut learner.database).await?;
+
+    let results = Query::by_affiliation("Tech University").execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, paper1.title);
+
+    let results = Query::by_affiliation("tech").execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1, "Affiliation search should be partial and case insensitive");
+
+    let results = Query::by_affiliation("Nowhere").execute(&mut learner.database).await?;
+    assert!(results.is_empty());
+
+    Ok(())
+  }
+}
+
+/// Citation graph functionality
+mod citation_graph {
+  use super::*;
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_references_and_cited_by() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let cited = create_second_test_paper();
+    let mut citing = create_test_paper();
+    // DOIs are case-insensitive, and references to unknown papers are kept but not resolved
+    citing.references = vec!["10.1000/TEST.1234".to_string(), "10.9999/not.stored".to_string()];
+    Add::paper(&citing).execute(&mut learner.database).await?;
+    Add::paper(&cited).execute(&mut learner.database).await?;
+
+    let stored = Query::by_paper(&citing).execute(&mut learner.database).await?;
+    assert_eq!(stored[0].references, citing.references);
+
+    let references = Query::references_of(&citing).execute(&mut learner.database).await?;
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].title, cited.title);
+
+    let citations = Query::cited_by(&cited).execute(&mut learner.database).await?;
+    assert_eq!(citations.len(), 1);
+    assert_eq!(citations[0].title, citing.title);
+
+    assert!(Query::cited_by(&citing).execute(&mut learner.database).await?.is_empty());
+    assert!(Query::references_of(&cited).execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+}
+
+/// Keyword search functionality
+mod keyword_search {
+  use super::*;
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_keyword_search_ignores_case() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let mut paper = create_test_paper();
+    paper.keywords = vec!["cs.LG".to_string(), "cs.AI".to_string()];
+    Add::paper(&paper).execute(&mut learner.database).await?;
+    Add::paper(&create_second_test_paper()).execute(&mut learner.database).await?;
+
+    let results = Query::by_keyword("cs.ai").execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, paper.title);
+    assert_eq!(results[0].keywords, paper.keywords);
+
+    assert!(Query::by_keyword("math.CO").execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+}
+
+/// Language detection and filtering
+mod language_search {
+  use super::*;
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_language_is_detected_on_add() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let mut english = create_test_paper();
+    english.abstract_text = "We study the stability of solutions to nonlinear equations and show \
+                             that the method is efficient for the large systems that are not \
+                             handled by existing approaches."
+      .to_string();
+    let mut german = create_second_test_paper();
+    german.abstract_text = "Wir untersuchen die Stabilität der Lösungen und zeigen, dass sich das \
+                            Verfahren auch für große Systeme eignet, die mit den bisherigen \
+                            Methoden nicht behandelt werden können."
+      .to_string();
+    Add::paper(&english).execute(&mut learner.database).await?;
+    Add::paper(&german).execute(&mut learner.database).await?;
+
+    let stored = Query::by_paper(&english).one(&mut learner.database).await?;
+    assert_eq!(stored.language.as_deref(), Some("en"));
+    let stored = Query::by_paper(&german).one(&mut learner.database).await?;
+    assert_ne!(stored.language.as_deref(), Some("en"));
+
+    let results = Query::by_language("EN").execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, english.title);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_language_set_by_user_is_kept() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    // The test abstract is too short to guess from
+    let mut paper = create_test_paper();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+    assert_eq!(Query::by_paper(&paper).one(&mut learner.database).await?.language, None);
+
+    paper.language = Some("fr".to_string());
+    Update::paper(&paper).execute(&mut learner.database).await?;
+    let results = Query::by_language("fr").execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    assert!(Query::by_language("en").execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+}
+
+/// Filtering by when papers were added rather than published
+mod added_after {
+  use std::time::Duration;
+
+  use chrono::{DurationRound, TimeDelta};
+
+  use super::*;
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_added_after_boundary() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    // Add the more recently published paper first, so added and published order disagree
+    let imported_first = create_second_test_paper();
+    Add::paper(&imported_first).execute(&mut learner.database).await?;
+
+    // Timestamps are stored to the second, so step past the first paper's second
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let boundary = Utc::now().duration_trunc(TimeDelta::seconds(1)).unwrap();
+    let imported_last = create_test_paper();
+    Add::paper(&imported_last).execute(&mut learner.database).await?;
+
+    let results = Query::added_after(boundary).execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, imported_last.title);
+
+    let earlier = boundary - TimeDelta::days(1);
+    assert_eq!(Query::added_after(earlier).execute(&mut learner.database).await?.len(), 2);
+    let later = boundary + TimeDelta::days(1);
+    assert!(Query::added_after(later).execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+}
+
+/// Shared-author discovery functionality
+mod related_papers {
+  use super::*;
+
+  fn author(name: &str) -> Author {
+    Author {
+      name:        name.to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    }
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_related_to_ranks_by_shared_authors() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let mut base = create_test_paper();
+    base.authors = vec![author("Ada Lovelace"), author("Charles Babbage")];
+    let mut one_shared = create_second_test_paper();
+    one_shared.authors = vec![author("ada lovelace"), author("Someone Else")];
+    let mut unrelated = create_test_paper();
+    unrelated.title = "Unrelated Paper".to_string();
+    unrelated.source_identifier = "2302.00000".to_string();
+    unrelated.authors = vec![author("Grace Hopper")];
+    let mut two_shared = create_test_paper();
+    two_shared.title = "Closely Related Paper".to_string();
+    two_shared.source_identifier = "2303.00000".to_string();
+    two_shared.authors = vec![author("Charles Babbage"), author("Ada Lovelace")];
+
+    for paper in [&base, &one_shared, &unrelated, &two_shared] {
+      Add::paper(paper).execute(&mut learner.database).await?;
+    }
+
+    let related = Query::related_to(&base).execute(&mut learner.database).await?;
+    let titles: Vec<_> = related.iter().map(|p| p.title.as_str()).collect();
+    assert_eq!(titles, vec![two_shared.title.as_str(), one_shared.title.as_str()]);
+
+    let related = Query::related_to(&unrelated).execute(&mut learner.database).await?;
+    assert!(related.is_empty());
+
+    Ok(())
+  }
+}
+
 /// Source-based search functionality
 mod source_search {
   use super::*;
@@ -257,6 +667,238 @@ mod source_search {
   }
 }
 
+/// Several criteria combined in one query
+mod combined_criteria {
+  use learner::database::QueryCriteria;
+
+  use super::*;
+
+  fn paper(title: &str, identifier: &str, author: &str, year: i32) -> Paper {
+    let mut paper = create_test_paper();
+    paper.title = title.to_string();
+    paper.source_identifier = identifier.to_string();
+    paper.authors[0].name = author.to_string();
+    paper.authors.truncate(1);
+    paper.publication_date = Utc.with_ymd_and_hms(year, 6, 1, 0, 0, 0).unwrap().into();
+    paper
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_text_author_and_before() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    for paper in [
+      paper("Quantum Error Correction", "2101.00001", "Alice Scientist", 2021),
+      paper("Quantum Sensing", "2401.00002", "Alice Scientist", 2024),
+      paper("Quantum Advantage", "2001.00003", "Bob Researcher", 2020),
+      paper("Classical Codes", "2001.00004", "Alice Scientist", 2020),
+      paper("Quantum Walks", "1901.00005", "alice scientist", 2019),
+    ] {
+      Add::paper(&paper).execute(&mut learner.database).await?;
+    }
+    let before = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+    let results = Query::text("quantum")
+      .and(QueryCriteria::Author("Alice"))
+      .and(QueryCriteria::BeforeDate(before))
+      .order_by(OrderField::Title)
+      .execute(&mut learner.database)
+      .await?;
+    let titles: Vec<_> = results.iter().map(|p| p.title.as_str()).collect();
+    assert_eq!(titles, ["Quantum Error Correction", "Quantum Walks"]);
+
+    // Each criterion alone still finds everything it matches
+    assert_eq!(Query::text("quantum").execute(&mut learner.database).await?.len(), 4);
+    assert_eq!(Query::by_author("Alice").execute(&mut learner.database).await?.len(), 4);
+    assert_eq!(Query::before_date(before).execute(&mut learner.database).await?.len(), 4);
+
+    // Archived papers are left out of the combined results too
+    Remove::by_source("arxiv", "1901.00005").execute(&mut learner.database).await?;
+    let results = Query::text("quantum")
+      .and(QueryCriteria::Author("Alice"))
+      .and(QueryCriteria::BeforeDate(before))
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, "Quantum Error Correction");
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_first_criterion_keeps_snippets() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let mut arxiv = paper("Quantum Error Correction", "2101.00001", "Alice Scientist", 2021);
+    arxiv.keywords = vec!["quant-ph".to_string()];
+    let mut doi = paper("Quantum Sensing", "10.1000/sensing", "Alice Scientist", 2021);
+    doi.source = "doi".to_string();
+    Add::paper(&arxiv).execute(&mut learner.database).await?;
+    Add::paper(&doi).execute(&mut learner.database).await?;
+
+    let results = Query::text("quantum")
+      .and(QueryCriteria::Source("arxiv"))
+      .and(QueryCriteria::Keyword("QUANT-PH"))
+      .with_snippets()
+      .execute_with_snippets(&mut learner.database)
+      .await?;
+    assert_eq!(results.len(), 1);
+    let (found, snippet) = &results[0];
+    assert_eq!(found, &arxiv);
+    assert!(snippet.as_deref().is_some_and(|s| s.contains(Query::SNIPPET_OPEN)));
+
+    // Criteria that only filter can come first as well
+    let found = Query::new(QueryCriteria::Source("doi"))
+      .and(QueryCriteria::Text("sensing"))
+      .one(&mut learner.database)
+      .await?;
+    assert_eq!(found, doi);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_combined_query_keeps_rank_order() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    for paper in [
+      paper("Lattice Notes", "2101.00001", "Alice Scientist", 2021),
+      paper("Lattice Lattice Lattice", "2101.00002", "Alice Scientist", 2021),
+      paper("Lattice Lattice", "2101.00003", "Alice Scientist", 2021),
+      paper("Lattice Lattice Lattice Lattice", "2101.00004", "Bob Researcher", 2021),
+    ] {
+      Add::paper(&paper).execute(&mut learner.database).await?;
+    }
+
+    // The filter must not disturb the text ranking, and the limit keeps the best matches
+    let query = || Query::text("lattice").and(QueryCriteria::Author("Alice"));
+    let titles = |papers: Vec<Paper>| papers.into_iter().map(|p| p.title).collect::<Vec<_>>();
+    assert_eq!(titles(query().execute(&mut learner.database).await?), [
+      "Lattice Lattice Lattice",
+      "Lattice Lattice",
+      "Lattice Notes"
+    ]);
+    assert_eq!(titles(query().limit(2).execute(&mut learner.database).await?), [
+      "Lattice Lattice Lattice",
+      "Lattice Lattice"
+    ]);
+
+    Ok(())
+  }
+}
+
+/// Alternatives and negation of criteria
+mod boolean_criteria {
+  use learner::database::QueryCriteria;
+
+  use super::*;
+
+  /// A learner holding one paper per `(title, source, author)`, with the directories it uses.
+  async fn library(papers: &[(&str, &str, &str)]) -> (Learner, Vec<TempDir>) {
+    let (mut learner, cfg_dir, db_dir, strg_dir) = create_test_learner().await;
+    for (i, (title, source, author)) in papers.iter().enumerate() {
+      let mut paper = create_test_paper();
+      paper.title = title.to_string();
+      paper.source = source.to_string();
+      paper.source_identifier = format!("2301.{i:05}");
+      paper.authors.truncate(1);
+      paper.authors[0].name = author.to_string();
+      Add::paper(&paper).execute(&mut learner.database).await.unwrap();
+    }
+    (learner, vec![cfg_dir, db_dir, strg_dir])
+  }
+
+  async fn titles(query: Query<'_>, learner: &mut Learner) -> TestResult<Vec<String>> {
+    let papers = query.order_by(OrderField::Title).execute(&mut learner.database).await?;
+    Ok(papers.into_iter().map(|paper| paper.title).collect())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_or_group() -> TestResult<()> {
+    let (mut learner, _dirs) = library(&[
+      ("Alpha", "arxiv", "Alice Scientist"),
+      ("Beta", "doi", "Bob Researcher"),
+      ("Gamma", "arxiv", "Carol Engineer"),
+    ])
+    .await;
+
+    let query = Query::by_author("Alice").or(QueryCriteria::Author("Bob"));
+    assert_eq!(titles(query, &mut learner).await?, ["Alpha", "Beta"]);
+    let query = Query::new(QueryCriteria::Author("Carol").or(QueryCriteria::Author("Nobody")));
+    assert_eq!(titles(query, &mut learner).await?, ["Gamma"]);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_negated_source() -> TestResult<()> {
+    let (mut learner, _dirs) = library(&[
+      ("Alpha", "arxiv", "Alice Scientist"),
+      ("Beta", "doi", "Bob Researcher"),
+      ("Gamma", "iacr", "Carol Engineer"),
+    ])
+    .await;
+
+    let query = Query::new(!QueryCriteria::Source("doi"));
+    assert_eq!(titles(query, &mut learner).await?, ["Alpha", "Gamma"]);
+    // Negating twice gives back the original criterion
+    let query = Query::new(!!QueryCriteria::Source("doi"));
+    assert_eq!(titles(query, &mut learner).await?, ["Beta"]);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_mixed_and_or() -> TestResult<()> {
+    let (mut learner, _dirs) = library(&[
+      ("Lattice Alpha", "arxiv", "Alice Scientist"),
+      ("Lattice Beta", "doi", "Bob Researcher"),
+      ("Lattice Gamma", "arxiv", "Bob Researcher"),
+      ("Lattice Delta", "arxiv", "Carol Engineer"),
+      ("Sieve Epsilon", "arxiv", "Alice Scientist"),
+    ])
+    .await;
+
+    // lattice AND (Alice OR Bob) AND NOT doi
+    let query = Query::text("lattice")
+      .and(QueryCriteria::Author("Alice"))
+      .or(QueryCriteria::Author("Bob"))
+      .and(!QueryCriteria::Source("doi"));
+    assert_eq!(titles(query, &mut learner).await?, ["Lattice Alpha", "Lattice Gamma"]);
+
+    // (Alice AND sieve) OR (Carol AND NOT doi), with the groups parenthesized
+    let query = Query::new(
+      QueryCriteria::Author("Alice")
+        .and(QueryCriteria::Text("sieve"))
+        .or(QueryCriteria::Author("Carol").and(!QueryCriteria::Source("doi"))),
+    );
+    assert_eq!(titles(query, &mut learner).await?, ["Lattice Delta", "Sieve Epsilon"]);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_nonsensical_combinations_are_rejected() -> TestResult<()> {
+    let (mut learner, _dirs) = library(&[("Alpha", "arxiv", "Alice Scientist")]).await;
+
+    for query in [
+      Query::new(QueryCriteria::Or(Vec::new())),
+      Query::new(QueryCriteria::And(Vec::new())),
+      Query::by_author("Alice").or(QueryCriteria::All),
+      Query::text("alpha").and(!QueryCriteria::All),
+    ] {
+      let err = query.execute(&mut learner.database).await.unwrap_err();
+      assert!(matches!(err, LearnerError::InvalidQuery(_)), "unexpected error: {err:?}");
+    }
+
+    Ok(())
+  }
+}
+
 /// Ordering and pagination tests
 mod ordering {
   use super::*;
@@ -290,6 +932,73 @@ mod ordering {
     Ok(())
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_partial_dates_are_stored_and_ordered() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let mut year_only = create_test_paper();
+    year_only.source_identifier = "2023.year".to_string();
+    year_only.title = "Year Only".to_string();
+    year_only.publication_date = PartialDate::Year(2023);
+    let mut year_month = create_test_paper();
+    year_month.source_identifier = "2023.month".to_string();
+    year_month.title = "Year Month".to_string();
+    year_month.publication_date = PartialDate::YearMonth(2023, 1);
+    // Stored as a full RFC 3339 timestamp, as before partial dates existed
+    let full = create_test_paper();
+    for paper in [&full, &year_month, &year_only] {
+      Add::paper(paper).execute(&mut learner.database).await?;
+    }
+
+    let stored = Query::by_paper(&year_month).one(&mut learner.database).await?;
+    assert_eq!(stored.publication_date, PartialDate::YearMonth(2023, 1));
+    assert_eq!(stored.publication_date.to_string(), "2023-01");
+
+    // Less precise dates sort first
+    let results = Query::list_all()
+      .order_by(OrderField::PublicationDate)
+      .execute(&mut learner.database)
+      .await?;
+    let titles: Vec<_> = results.iter().map(|p| p.title.as_str()).collect();
+    assert_eq!(titles, ["Year Only", "Year Month", full.title.as_str()]);
+
+    // A partial date counts from its first day, so 2023 is not before 2023-01-01
+    let cutoff = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert!(Query::before_date(cutoff).execute(&mut learner.database).await?.is_empty());
+    let cutoff = Utc.with_ymd_and_hms(2023, 1, 2, 0, 0, 0).unwrap();
+    assert_eq!(Query::before_date(cutoff).execute(&mut learner.database).await?.len(), 3);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_date_added_ordering() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    // Added in the opposite order to their publication dates
+    let earlier = create_second_test_paper(); // 2024
+    let later = create_test_paper(); // 2023
+    Add::paper(&earlier).execute(&mut learner.database).await?;
+    // Stored timestamps have a resolution of one second
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    Add::paper(&later).execute(&mut learner.database).await?;
+
+    let results = Query::list_all()
+      .order_by(OrderField::DateAdded)
+      .descending()
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(results, vec![later.clone(), earlier.clone()]);
+
+    let results =
+      Query::list_all().order_by(OrderField::DateAdded).execute(&mut learner.database).await?;
+    assert_eq!(results, vec![earlier, later]);
+
+    Ok(())
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_title_ordering() -> TestResult<()> {
@@ -310,6 +1019,29 @@ mod ordering {
 
     Ok(())
   }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_limit_applies_after_ordering() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    Add::paper(&create_test_paper()).execute(&mut learner.database).await?; // 2023
+    Add::paper(&create_second_test_paper()).execute(&mut learner.database).await?; // 2024
+
+    let results = Query::list_all()
+      .order_by(OrderField::PublicationDate)
+      .descending()
+      .limit(1)
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].publication_date.year(), 2024);
+
+    let results = Query::text("test").limit(1).execute(&mut learner.database).await?;
+    assert_eq!(results.len(), 1);
+
+    Ok(())
+  }
 }
 
 /// Edge cases and special conditions
@@ -345,6 +1077,7 @@ mod edge_cases {
       name:        "O'Connor Smith".to_string(),
       affiliation: None,
       email:       None,
+      orcid:       None,
     }];
 
     Add::paper(&paper).execute(&mut learner.database).await?;
@@ -408,3 +1141,78 @@ async fn test_fts_behavior() -> TestResult<()> {
 
   Ok(())
 }
+
+/// Assembling query results from batched lookups
+mod batched_fetch {
+  use std::time::{Duration, Instant};
+
+  use super::*;
+
+  /// A paper with several authors, references and keywords, distinct per `n`.
+  fn numbered_paper(n: usize) -> Paper {
+    let mut paper = create_test_paper();
+    paper.title = format!("Batched Paper {n}");
+    paper.source_identifier = format!("2301.{n:05}");
+    paper.doi = Some(format!("10.0000/batched.{n}"));
+    paper.authors = (0..3)
+      .map(|i| Author {
+        name:        format!("Author {n}-{i}"),
+        affiliation: (i == 0).then(|| "Batch University".to_string()),
+        email:       None,
+        orcid:       None,
+      })
+      .collect();
+    paper.references = (0..2).map(|i| format!("10.0000/cited.{n}.{i}")).collect();
+    paper.keywords = vec![format!("topic {n}"), "batched".to_string()];
+    paper
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_results_match_stored_papers() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let papers: Vec<_> = (0..5).map(numbered_paper).collect();
+    for paper in &papers {
+      Add::paper(paper).execute(&mut learner.database).await?;
+    }
+
+    let results =
+      Query::list_all().order_by(OrderField::DateAdded).execute(&mut learner.database).await?;
+    assert_eq!(results, papers);
+
+    // Single-paper lookups assemble the same papers
+    for paper in &papers {
+      let found =
+        Query::by_source("arxiv", &paper.source_identifier).one(&mut learner.database).await?;
+      assert_eq!(&found, paper);
+    }
+
+    // A paper matched through several authors is still returned once with all its authors
+    let results = Query::by_author("Author 3").execute(&mut learner.database).await?;
+    assert_eq!(results, vec![papers[3].clone()]);
+
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_large_library_lists_quickly() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+    let count = 1000;
+    for n in 0..count {
+      Add::paper(&numbered_paper(n)).execute(&mut learner.database).await?;
+    }
+
+    let start = Instant::now();
+    let results = Query::list_all().execute(&mut learner.database).await?;
+    let elapsed = start.elapsed();
+
+    assert_eq!(results.len(), count);
+    assert!(results.iter().all(|paper| paper.authors.len() == 3 && paper.keywords.len() == 2));
+    assert!(elapsed < Duration::from_secs(2), "listing {count} papers took {elapsed:?}");
+
+    Ok(())
+  }
+}
@@ -1,3 +1,5 @@
+use learner::database::Restore;
+
 use super::*;
 
 /// Basic removal functionality tests
@@ -48,8 +50,8 @@ mod basic_operations {
     let paper = create_test_paper();
     Add::paper(&paper).execute(&mut learner.database).await?;
 
-    Remove::from_query(Query::text("test")).execute(&mut learner.database).await?;
-    let authors = Query::by_author("").execute(&mut learner.database).await?;
+    Remove::from_query(Query::text("test")).purge().execute(&mut learner.database).await?;
+    let authors = Query::by_author("").include_archived().execute(&mut learner.database).await?;
 
     assert_eq!(authors.len(), 0);
     Ok(())
@@ -303,6 +305,122 @@ mod query_based_removal {
   }
 }
 
+/// Tests that each removal criterion removes the same papers the matching query finds
+mod criteria {
+  use chrono::TimeZone;
+
+  use super::*;
+
+  /// Runs `remove` and returns the titles of the papers left in the database
+  async fn remaining_after(remove: Remove<'_>, learner: &mut Learner) -> TestResult<Vec<String>> {
+    remove.execute(&mut learner.database).await?;
+    let remaining = Query::list_all().execute(&mut learner.database).await?;
+    Ok(remaining.into_iter().map(|paper| paper.title).collect())
+  }
+
+  /// Stores both test papers, tagged with distinct keywords and languages
+  async fn learner_with_papers() -> (Learner, TempDir, TempDir, TempDir) {
+    let (mut learner, cfg_dir, db_dir, strg_dir) = create_test_learner().await;
+
+    let mut first = create_test_paper();
+    first.keywords = vec!["cs.LG".to_string()];
+    first.language = Some("en".to_string());
+    let mut second = create_second_test_paper();
+    second.keywords = vec!["quant-ph".to_string()];
+    second.language = Some("de".to_string());
+
+    Add::paper(&first).execute(&mut learner.database).await.unwrap();
+    Add::paper(&second).execute(&mut learner.database).await.unwrap();
+    (learner, cfg_dir, db_dir, strg_dir)
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_source() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+    let paper = create_test_paper();
+
+    let remaining =
+      remaining_after(Remove::by_source(&paper.source, &paper.source_identifier), &mut learner)
+        .await?;
+    assert_eq!(remaining, vec!["Test Paper: Two"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_author() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let remaining = remaining_after(Remove::by_author("alice"), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_text() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let remaining = remaining_after(Remove::text("two"), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_affiliation() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let remaining = remaining_after(Remove::by_affiliation("State"), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_keyword() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let remaining = remaining_after(Remove::by_keyword("CS.lg"), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper: Two"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_by_language() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let remaining = remaining_after(Remove::by_language("de"), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_before_date() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let cutoff = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let remaining = remaining_after(Remove::before_date(cutoff), &mut learner).await?;
+    assert_eq!(remaining, vec!["Test Paper: Two"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_remove_matches_query() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = learner_with_papers().await;
+
+    let matched = Query::by_affiliation("university").execute(&mut learner.database).await?;
+    let removed =
+      Remove::by_affiliation("university").dry_run().execute(&mut learner.database).await?;
+    assert_eq!(removed, matched);
+    Ok(())
+  }
+}
+
 /// Recovery and data integrity tests
 mod recovery {
   use super::*;
@@ -433,3 +551,94 @@ mod recovery {
     Ok(())
   }
 }
+
+/// Soft deletion, restoring and purging
+mod archiving {
+  use super::*;
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_removed_papers_are_archived_and_hidden() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+    Add::paper(&create_second_test_paper()).execute(&mut learner.database).await?;
+
+    Remove::by_source(&paper.source, &paper.source_identifier)
+      .execute(&mut learner.database)
+      .await?;
+
+    // Hidden from default queries of every kind
+    assert!(Query::by_paper(&paper).execute(&mut learner.database).await?.is_empty());
+    assert!(Query::by_author("John Doe").execute(&mut learner.database).await?.is_empty());
+    assert_eq!(Query::list_all().execute(&mut learner.database).await?.len(), 1);
+
+    // But still stored, with its authors
+    let archived = Query::list_all().only_archived().execute(&mut learner.database).await?;
+    assert_eq!(archived, vec![paper.clone()]);
+    assert_eq!(Query::list_all().include_archived().execute(&mut learner.database).await?.len(), 2);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_restore_archived_paper() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+
+    // Papers that are not archived are not restored
+    let restored = Restore::by_source(&paper.source, &paper.source_identifier)
+      .execute(&mut learner.database)
+      .await?;
+    assert!(restored.is_empty());
+
+    Remove::from_query(Query::text("test")).execute(&mut learner.database).await?;
+    let restored = Restore::from_query(Query::text("test")).execute(&mut learner.database).await?;
+    assert_eq!(restored, vec![paper.clone()]);
+
+    assert_eq!(Query::by_paper(&paper).execute(&mut learner.database).await?, vec![paper]);
+    assert!(Query::list_all().only_archived().execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[traced_test]
+  async fn test_purge_deletes_permanently() -> TestResult<()> {
+    let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+    let paper = create_test_paper();
+    let second = create_second_test_paper();
+    Add::paper(&paper).execute(&mut learner.database).await?;
+    Add::paper(&second).execute(&mut learner.database).await?;
+
+    let purged = Remove::by_source(&paper.source, &paper.source_identifier)
+      .purge()
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(purged.len(), 1);
+    assert!(Query::by_paper(&paper)
+      .include_archived()
+      .execute(&mut learner.database)
+      .await?
+      .is_empty());
+    let restored = Restore::by_source(&paper.source, &paper.source_identifier)
+      .execute(&mut learner.database)
+      .await?;
+    assert!(restored.is_empty());
+
+    // Purging also clears out papers that were already archived
+    Remove::by_source(&second.source, &second.source_identifier)
+      .execute(&mut learner.database)
+      .await?;
+    let purged = Remove::from_query(Query::list_all().only_archived())
+      .purge()
+      .execute(&mut learner.database)
+      .await?;
+    assert_eq!(purged, vec![second]);
+    assert!(Query::list_all().include_archived().execute(&mut learner.database).await?.is_empty());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+#[traced_test]
+#[tokio::test]
+async fn test_update_replaces_metadata() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let paper = create_test_paper();
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  let mut edited = paper.clone();
+  edited.title = "Corrected Heading".to_string();
+  edited.authors.truncate(1);
+  edited.references = vec!["10.1000/cited".to_string()];
+  Update::paper(&edited).execute(&mut learner.database).await?;
+
+  let stored = Query::by_paper(&paper).execute(&mut learner.database).await?;
+  assert_eq!(stored, vec![edited.clone()]);
+
+  // The search index follows the new title
+  assert_eq!(Query::text("Corrected").execute(&mut learner.database).await?.len(), 1);
+  assert!(Query::text("Test").execute(&mut learner.database).await?.is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_update_missing_paper() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+
+  let result = Update::paper(&create_test_paper()).execute(&mut learner.database).await;
+  assert!(matches!(result, Err(LearnerError::NotFound { .. })));
+
+  Ok(())
+}
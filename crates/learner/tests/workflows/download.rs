@@ -0,0 +1,313 @@
+//! Downloading paper documents, exercised against a local mock server.
+
+use learner::database::{Add, Query};
+
+use super::*;
+use crate::mock::{MockRequest, MockResponse, MockServer};
+
+/// A stand-in PDF large enough to be cut in half.
+fn document() -> Vec<u8> {
+  let mut document = b"%PDF-1.4\n".to_vec();
+  document.extend((0..4096).map(|i| (i % 251) as u8));
+  document
+}
+
+/// The `ETag` [`resumable_server`] sends [`document`] with.
+const ETAG: &str = "\"v1\"";
+
+/// Serves [`document`] with range support, cutting the first full response off halfway.
+///
+/// Range requests are only honored with an `If-Range` of [`ETAG`], like a server would for
+/// an unchanged document.
+fn resumable_server(request: &MockRequest) -> MockResponse {
+  let document = document();
+  let len = document.len();
+  let range = request.headers.get("range").and_then(|range| range.strip_prefix("bytes="));
+  match range.filter(|_| request.headers.get("if-range").map(String::as_str) == Some(ETAG)) {
+    Some(range) => {
+      let start: usize = range.trim_end_matches('-').parse().unwrap();
+      MockResponse { status: 206, ..MockResponse::ok(document[start..].to_vec()) }
+        .with_header("Accept-Ranges", "bytes")
+        .with_header("Content-Range", &format!("bytes {start}-{}/{len}", len - 1))
+        .with_header("ETag", ETAG)
+    },
+    None => MockResponse::ok(document[..len / 2].to_vec())
+      .with_header("Accept-Ranges", "bytes")
+      .with_header("Content-Length", &len.to_string())
+      .with_header("ETag", ETAG)
+      .closing(),
+  }
+}
+
+/// The files in `dir`, including downloads staged in its `.partial` directory.
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+  [dir.to_path_buf(), dir.join(".partial")]
+    .iter()
+    .filter_map(|dir| std::fs::read_dir(dir).ok())
+    .flatten()
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.is_file())
+    .collect()
+}
+
+/// Where an interrupted download of `paper` into `dir` is kept.
+fn partial_download(dir: &Path, paper: &Paper) -> PathBuf {
+  dir.join(".partial").join(format!("{}.part", paper.filename().display()))
+}
+
+/// A test paper whose PDF is served by `server`.
+fn paper_served_by(server: &MockServer) -> Paper {
+  let mut paper = database_operations::create_test_paper();
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  paper
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_resumes_interrupted_download() -> TestResult<()> {
+  let server = MockServer::start(resumable_server).await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+  let part = partial_download(dir.path(), &paper);
+
+  // The first attempt is cut off, leaving the half that arrived
+  assert!(paper.download_pdf(dir.path()).await.is_err());
+  assert_eq!(std::fs::read(&part)?, document()[..document().len() / 2]);
+
+  // The second attempt asks only for the rest and completes the document
+  let filename = paper.download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(&filename))?, document());
+  assert_eq!(files_in(dir.path()), [dir.path().join(&filename)]);
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(requests[1].headers.get("range"), Some(&format!("bytes={}-", document().len() / 2)));
+  assert_eq!(requests[1].headers.get("if-range").map(String::as_str), Some(ETAG));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_restarts_when_document_changed() -> TestResult<()> {
+  // The document is replaced after the first attempt, so `If-Range` no longer matches
+  let server = MockServer::start(|request| match request.headers.get("if-range") {
+    Some(_) => MockResponse::ok(b"%PDF-1.7 revised".to_vec()).with_header("ETag", "\"v2\""),
+    None => resumable_server(request),
+  })
+  .await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+
+  assert!(paper.download_pdf(dir.path()).await.is_err());
+  let filename = paper.download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(&filename))?, b"%PDF-1.7 revised");
+  assert_eq!(files_in(dir.path()), [dir.path().join(&filename)]);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_restarts_on_wrong_range() -> TestResult<()> {
+  // The server answers a range request with a different part of the document
+  let server = MockServer::start(|request| match request.headers.get("range") {
+    Some(_) => MockResponse { status: 206, ..MockResponse::ok(document()[..16].to_vec()) }
+      .with_header("Content-Type", "application/pdf")
+      .with_header("Content-Range", &format!("bytes 0-15/{}", document().len())),
+    None => MockResponse::ok(document()).with_header("Content-Type", "application/pdf"),
+  })
+  .await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+  let part = partial_download(dir.path(), &paper);
+  std::fs::create_dir_all(part.parent().unwrap())?;
+  std::fs::write(&part, &document()[..100])?;
+  std::fs::write(part.with_extension("validator"), ETAG)?;
+
+  // The mismatched part is thrown away and the whole document fetched without a range
+  let filename = paper.download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(&filename))?, document());
+  assert_eq!(files_in(dir.path()), [dir.path().join(&filename)]);
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(requests[1].headers.get("range"), None);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_rejects_unrequested_partial_content() -> TestResult<()> {
+  let server = MockServer::respond_with(
+    MockResponse { status: 206, ..MockResponse::ok(document()[..16].to_vec()) }
+      .with_header("Content-Type", "application/pdf")
+      .with_header("Content-Range", &format!("bytes 0-15/{}", document().len())),
+  )
+  .await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+
+  assert!(matches!(paper.download_pdf(dir.path()).await, Err(LearnerError::ApiError(_))));
+  assert!(files_in(dir.path()).is_empty());
+  assert_eq!(server.requests().len(), 1);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_restarts_without_range_support() -> TestResult<()> {
+  // The server ignores range requests and always sends the whole document
+  let server = MockServer::respond_with(MockResponse::ok(document())).await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+  let part = partial_download(dir.path(), &paper);
+  std::fs::create_dir_all(part.parent().unwrap())?;
+  std::fs::write(&part, b"stale partial download")?;
+  std::fs::write(part.with_extension("validator"), ETAG)?;
+
+  let filename = paper.download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(&filename))?, document());
+  assert_eq!(files_in(dir.path()), [dir.path().join(&filename)]);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_discards_interrupted_download_without_range_support() -> TestResult<()> {
+  let len = document().len();
+  let server = MockServer::respond_with(
+    MockResponse::ok(document()[..len / 2].to_vec())
+      .with_header("Content-Length", &len.to_string())
+      .closing(),
+  )
+  .await;
+  let dir = tempdir()?;
+  let paper = paper_served_by(&server);
+
+  assert!(paper.download_pdf(dir.path()).await.is_err());
+  assert!(files_in(dir.path()).is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_add_complete_rejects_short_download() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, strg_dir) = create_test_learner().await;
+  // The server claims twice the bytes it sends, without range support
+  let len = document().len();
+  let server = MockServer::respond_with(
+    MockResponse::ok(document()[..len / 2].to_vec())
+      .with_header("Content-Length", &len.to_string())
+      .closing(),
+  )
+  .await;
+  let paper = paper_served_by(&server);
+
+  let result = Add::complete(&paper).execute(&mut learner.database).await;
+  assert!(
+    matches!(
+      result,
+      Err(LearnerError::IncompleteDownload { expected, received })
+        if expected == len as u64 && received == (len / 2) as u64
+    ),
+    "unexpected result: {result:?}"
+  );
+
+  // The download is recorded as failed, and nothing was left in storage
+  assert_eq!(learner.database.document_uri(&paper).await?, None);
+  assert_eq!(Query::missing_documents().execute(&mut learner.database).await?.len(), 1);
+  assert!(files_in(strg_dir.path()).is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_stored_checksum_detects_corruption() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(document())).await;
+  let paper = paper_served_by(&server);
+  Add::complete(&paper).execute(&mut learner.database).await?;
+  assert_eq!(learner.database.verify_document(&paper).await?, Some(true));
+
+  let path = learner.database.document_path(&paper).await?.unwrap();
+  std::fs::write(&path, b"%PDF-1.4 silently replaced")?;
+  assert_eq!(learner.database.verify_document(&paper).await?, Some(false));
+
+  // Papers without a stored document have nothing to verify
+  let other = database_operations::create_second_test_paper();
+  Add::paper(&other).execute(&mut learner.database).await?;
+  assert_eq!(learner.database.verify_document(&other).await?, None);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_rejects_html_page() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok("<!DOCTYPE html><html><body>Please sign in</body></html>")
+      .with_header("Content-Type", "text/html; charset=utf-8"),
+  )
+  .await;
+  let paper = paper_served_by(&server);
+
+  let result = Add::complete(&paper).execute(&mut learner.database).await;
+  assert!(
+    matches!(&result, Err(LearnerError::NotPdf(content_type)) if content_type.starts_with("text/html")),
+    "unexpected result: {result:?}"
+  );
+  assert_eq!(learner.database.document_uri(&paper).await?, None);
+  assert!(files_in(strg_dir.path()).is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_accepts_pdf_sent_as_octet_stream() -> TestResult<()> {
+  let server = MockServer::respond_with(
+    MockResponse::ok(document()).with_header("Content-Type", "application/octet-stream"),
+  )
+  .await;
+  let dir = tempdir()?;
+
+  let filename = paper_served_by(&server).download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(filename))?, document());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_download_pdf_sniffs_across_chunks() -> TestResult<()> {
+  // The header arrives a byte at a time, after some leading whitespace
+  let mut body = b"\r\n".to_vec();
+  body.extend(b"%PDF-1.4\n%%EOF\n");
+  let server = MockServer::respond_with(
+    MockResponse::ok(body.clone())
+      .with_header("Content-Type", "application/octet-stream")
+      .in_chunks_of(1),
+  )
+  .await;
+  let dir = tempdir()?;
+
+  let filename = paper_served_by(&server).download_pdf(dir.path()).await?;
+  assert_eq!(std::fs::read(dir.path().join(filename))?, body);
+
+  // A page that is not a PDF is still turned away, however it is split up
+  let server = MockServer::respond_with(
+    MockResponse::ok("<html>Please sign in</html>")
+      .with_header("Content-Type", "application/octet-stream")
+      .in_chunks_of(1),
+  )
+  .await;
+  let result = paper_served_by(&server).download_pdf(dir.path()).await;
+  assert!(matches!(result, Err(LearnerError::NotPdf(_))), "unexpected result: {result:?}");
+
+  Ok(())
+}
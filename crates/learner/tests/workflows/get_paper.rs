@@ -0,0 +1,471 @@
+use learner::{
+  database::{Add, Query},
+  PaperOrigin,
+};
+
+use super::*;
+use crate::mock::{
+  mock_paper_json, mock_paper_xml, mock_retriever_toml, mock_xml_retriever_toml, MockResponse,
+  MockServer,
+};
+
+#[traced_test]
+#[tokio::test]
+async fn test_get_paper_miss_fetches_and_stores() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Mock Paper"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let (paper, origin) = learner.get_paper("mock:1").await?;
+  assert_eq!(origin, PaperOrigin::Network);
+  assert_eq!(paper.title, "Mock Paper");
+  assert_eq!(server.requests().len(), 1);
+  assert_eq!(server.requests()[0].path, "/works/1");
+
+  // The fetched paper was added to the database
+  let stored = Query::by_source("mock", "1").execute(&mut learner.database).await?;
+  assert_eq!(stored.len(), 1);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_get_paper_hit_skips_network() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::status(500)).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let mut paper = crate::workflows::database_operations::create_test_paper();
+  paper.source = "mock".to_string();
+  paper.source_identifier = "7".to_string();
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  let (found, origin) = learner.get_paper("mock:7").await?;
+  assert_eq!(origin, PaperOrigin::Database);
+  assert_eq!(found.title, paper.title);
+  assert!(server.requests().is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_offline_mode_refuses_retrieval() -> TestResult<()> {
+  let database_dir = tempdir()?;
+  let storage_dir = tempdir()?;
+  let config = Config::default()
+    .with_database_path(&database_dir.path().join("learner.db"))
+    .with_retrievers_path(Path::new("config/retrievers/"))
+    .with_storage_path(storage_dir.path());
+  let mut learner = Learner::builder().with_config(config).offline(true).build().await?;
+  assert!(learner.config.offline);
+
+  // A slow server must never be contacted
+  let server = MockServer::respond_with(
+    MockResponse::ok(mock_paper_json("Mock Paper")).with_delay(std::time::Duration::from_secs(30)),
+  )
+  .await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let start = std::time::Instant::now();
+  let err = learner.get_paper("mock:1").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Offline));
+  let err = learner.retriever.get_paper("2301.07041").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Offline));
+  assert!(start.elapsed() < std::time::Duration::from_secs(1));
+  assert!(server.requests().is_empty());
+
+  // Local operations keep working
+  let mut paper = crate::workflows::database_operations::create_test_paper();
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  Add::paper(&paper).execute(&mut learner.database).await?;
+  let stored = Query::list_all().execute(&mut learner.database).await?;
+  assert_eq!(stored.len(), 1);
+
+  // Batch downloads are refused too
+  let err = Add::documents(Query::list_all()).execute(&mut learner.database).await.unwrap_err();
+  assert!(matches!(err, LearnerError::Offline));
+  let err = Add::complete(&paper).execute(&mut learner.database).await.unwrap_err();
+  assert!(matches!(err, LearnerError::Offline));
+  assert!(start.elapsed() < std::time::Duration::from_secs(1));
+  assert!(server.requests().is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_empty_retrievers_dir_uses_defaults() -> TestResult<()> {
+  let (retrievers_dir, database_dir, storage_dir) = (tempdir()?, tempdir()?, tempdir()?);
+  let config = Config::default()
+    .with_database_path(&database_dir.path().join("learner.db"))
+    .with_retrievers_path(retrievers_dir.path())
+    .with_storage_path(storage_dir.path());
+  let learner = Learner::builder().with_config(config).build().await?;
+
+  assert!(learner.retriever.config_for_source("arxiv").is_some());
+  let (source, identifier) = learner.retriever.sanitize_identifier("2301.07041")?;
+  assert_eq!((source.as_str(), identifier.as_str()), ("arxiv", "2301.07041"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_user_retriever_config_layers_over_defaults() -> TestResult<()> {
+  let (retrievers_dir, database_dir, storage_dir) = (tempdir()?, tempdir()?, tempdir()?);
+  let custom = std::fs::read_to_string("config/retrievers/arxiv.toml")?
+    .replace("http://export.arxiv.org", "http://arxiv.mirror.example.org");
+  std::fs::write(retrievers_dir.path().join("arxiv.toml"), custom)?;
+  let config = Config::default()
+    .with_database_path(&database_dir.path().join("learner.db"))
+    .with_retrievers_path(retrievers_dir.path())
+    .with_storage_path(storage_dir.path());
+  let learner = Learner::builder().with_config(config).build().await?;
+
+  // The user's arxiv config replaced the bundled one...
+  assert_eq!(
+    learner.retriever.config("arxiv").unwrap().base_url,
+    "http://arxiv.mirror.example.org"
+  );
+  assert!(logs_contain("Retriever config 'arxiv' overridden"));
+  // ...while the other bundled retrievers are still there
+  let (source, identifier) = learner.retriever.sanitize_identifier("10.1145/1327452.1327492")?;
+  assert_eq!((source.as_str(), identifier.as_str()), ("doi", "10.1145/1327452.1327492"));
+  assert!(learner.retriever.config("iacr").is_some());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_get_paper_keeps_author_orcid() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Mock Paper"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let (paper, _) = learner.get_paper("mock:1").await?;
+  assert_eq!(paper.authors[0].orcid.as_deref(), Some("0000-0002-1825-0097"));
+
+  let stored = Query::by_author("Lovelace").execute(&mut learner.database).await?;
+  assert_eq!(stored.len(), 1);
+  assert_eq!(stored[0].authors[0].orcid.as_deref(), Some("0000-0002-1825-0097"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_get_paper_keeps_references() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Mock Paper"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  // Only the reference with a DOI is captured
+  let (paper, _) = learner.get_paper("mock:1").await?;
+  assert_eq!(paper.references, vec!["10.1000/cited.1".to_string()]);
+
+  let stored = Query::by_source("mock", "1").execute(&mut learner.database).await?;
+  assert_eq!(stored[0].references, paper.references);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_check_for_updates() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_xml("Versioned Paper"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_xml_retriever_toml(&server))?;
+
+  let (mut paper, _) = learner.get_paper("mockxml:1v1").await?;
+  assert_eq!(paper.version.as_deref(), Some("v2"), "the source-reported version wins");
+
+  assert_eq!(learner.check_for_updates(&paper).await?, None);
+
+  paper.version = Some("v1".to_string());
+  assert_eq!(learner.check_for_updates(&paper).await?, Some("v2".to_string()));
+
+  // Checking never touches the stored copy
+  let stored = Query::by_source("mock_xml", "1").execute(&mut learner.database).await?;
+  assert_eq!(stored[0].version.as_deref(), Some("v2"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_refresh_abstract_updates_stored_paper() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Served Title"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let mut paper = crate::workflows::database_operations::create_test_paper();
+  paper.source = "mock".to_string();
+  paper.source_identifier = "7".to_string();
+  paper.abstract_text = "An abstract".to_string();
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  let refreshed = learner.refresh_abstract(&paper).await?.expect("a longer abstract was served");
+  assert_eq!(refreshed.abstract_text, "An abstract served by the mock server.");
+  assert_eq!(server.requests()[0].path, "/works/7");
+
+  // Only the abstract changes, the rest of the stored paper is kept
+  let stored = Query::by_paper(&paper).one(&mut learner.database).await?;
+  assert_eq!(stored.abstract_text, "An abstract served by the mock server.");
+  assert_eq!(stored.title, paper.title);
+  assert_eq!(stored.authors.len(), paper.authors.len());
+
+  // Refreshing again finds nothing new
+  assert!(learner.refresh_abstract(&paper).await?.is_none());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_refresh_abstract_without_source_abstract() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  // A response without an abstract, which would not parse as a full paper
+  let body = serde_json::json!({ "message": { "title": ["No Abstract"] } }).to_string();
+  let server = MockServer::respond_with(MockResponse::ok(body)).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let mut paper = crate::workflows::database_operations::create_test_paper();
+  paper.source = "mock".to_string();
+  paper.source_identifier = "8".to_string();
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  assert!(learner.refresh_abstract(&paper).await?.is_none());
+  let stored = Query::by_paper(&paper).one(&mut learner.database).await?;
+  assert_eq!(stored.abstract_text, paper.abstract_text);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_iacr_oai_pmh_fixture() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok(include_str!("../fixtures/iacr_2016_260.xml"))
+      .with_header("Content-Type", "text/xml;charset=UTF-8"),
+  )
+  .await;
+  // Point the bundled IACR config at the mock server instead of eprint.iacr.org
+  let config = std::fs::read_to_string("config/retrievers/iacr.toml")?
+    .replace("https://eprint.iacr.org/oai", &server.url("/oai"));
+  learner.retriever = learner.retriever.with_config_str(&config)?;
+
+  let (paper, origin) = learner.get_paper("https://eprint.iacr.org/2016/260.pdf").await?;
+  assert_eq!(origin, PaperOrigin::Network);
+  assert_eq!(
+    server.requests()[0].path,
+    "/oai?verb=GetRecord&identifier=oai:eprint.iacr.org:2016/260&metadataPrefix=oai_dc"
+  );
+
+  assert_eq!(paper.source, "iacr");
+  assert_eq!(paper.source_identifier, "2016/260");
+  assert_eq!(paper.title, "On the Size of Pairing-based Non-interactive Arguments");
+  assert_eq!(paper.authors.len(), 1);
+  assert_eq!(paper.authors[0].name, "Jens Groth");
+  assert!(paper.abstract_text.starts_with("Non-interactive arguments enable a prover"));
+  // The first `dc:date` is the submission date
+  assert_eq!(paper.publication_date.to_iso8601(), "2016-03-07T00:00:00+00:00");
+  assert_eq!(paper.pdf_url.as_deref(), Some("https://eprint.iacr.org/2016/260.pdf"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_arxiv_atom_fixture() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok(include_str!("../fixtures/arxiv_1706_03762.xml"))
+      .with_header("Content-Type", "application/atom+xml; charset=UTF-8"),
+  )
+  .await;
+  // Point the bundled arXiv config at the mock server instead of export.arxiv.org
+  let config = std::fs::read_to_string("config/retrievers/arxiv.toml")?
+    .replace("http://export.arxiv.org", &server.url(""));
+  learner.retriever = learner.retriever.with_config_str(&config)?;
+
+  let (paper, _) = learner.get_paper("1706.03762").await?;
+  assert_eq!(paper.title, "Attention Is All You Need");
+  assert_eq!(paper.authors.len(), 8);
+  assert_eq!(paper.version.as_deref(), Some("v7"));
+  assert_eq!(paper.primary_category.as_deref(), Some("cs.CL"));
+  assert_eq!(paper.keywords, ["cs.CL", "cs.LG"]);
+
+  let stored = Query::by_source("arxiv", "1706.03762").one(&mut learner.database).await?;
+  assert_eq!(stored.primary_category.as_deref(), Some("cs.CL"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_search_online_arxiv_fixture() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok(include_str!("../fixtures/arxiv_search_transformer.xml"))
+      .with_header("Content-Type", "application/atom+xml; charset=UTF-8"),
+  )
+  .await;
+  let config = std::fs::read_to_string("config/retrievers/arxiv.toml")?
+    .replace("http://export.arxiv.org", &server.url(""));
+  learner.retriever = learner.retriever.with_config_str(&config)?;
+
+  let papers = learner.search_online("arxiv", "transformer attention").await?;
+  let identifiers: Vec<_> = papers.iter().map(|p| p.source_identifier.as_str()).collect();
+  assert_eq!(identifiers, ["1706.03762", "2010.11929", "hep-th/9711200"]);
+  assert!(papers.iter().all(|p| p.source == "arxiv"));
+  assert_eq!(papers[0].title, "Attention Is All You Need");
+  assert_eq!(papers[1].authors.len(), 3);
+  assert_eq!(papers[1].version.as_deref(), Some("v2"));
+  assert_eq!(papers[2].primary_category.as_deref(), Some("hep-th"));
+  assert!(server.requests()[0].path.contains("search_query=all:transformer%20attention"));
+
+  // Search results are not stored
+  assert!(Query::by_source("arxiv", "1706.03762").execute(&mut learner.database).await?.is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_search_online_crossref_without_abstract() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok(include_str!("../fixtures/crossref_search_lattice.json"))
+      .with_header("Content-Type", "application/json"),
+  )
+  .await;
+  let config = std::fs::read_to_string("config/retrievers/doi.toml")?
+    .replace("https://api.crossref.org", &server.url(""));
+  learner.retriever = learner.retriever.with_config_str(&config)?;
+
+  // The second result has no abstract, which search results often lack
+  let papers = learner.search_online("doi", "lattice learning with errors").await?;
+  let identifiers: Vec<_> = papers.iter().map(|p| p.source_identifier.as_str()).collect();
+  assert_eq!(identifiers, ["10.1145/1568318.1568324", "10.1007/978-3-642-13190-5_1"]);
+  assert_eq!(papers[0].abstract_text, "We introduce the learning with errors problem.");
+  assert_eq!(papers[1].title, "On Ideal Lattices and Learning with Errors over Rings");
+  assert_eq!(papers[1].abstract_text, "");
+  assert_eq!(papers[1].authors.len(), 3);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_crossref_unknown_issue_date_uses_created() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(
+    MockResponse::ok(include_str!("../fixtures/crossref_undated_work.json"))
+      .with_header("Content-Type", "application/json"),
+  )
+  .await;
+  let config = std::fs::read_to_string("config/retrievers/doi.toml")?
+    .replace("https://api.crossref.org", &server.url(""));
+  learner.retriever = learner.retriever.with_config_str(&config)?;
+
+  // `issued` is `[[null]]`, so the record's creation time stands in
+  let (paper, _) = learner.get_paper("10.5555/undated.1").await?;
+  assert_eq!(paper.title, "A Report Crossref Has No Issue Date For");
+  assert_eq!(paper.publication_date.to_iso8601(), "2019-04-02T10:15:30+00:00");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_search_online_requires_search_endpoint() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Mock Paper"))).await;
+  learner.retriever = learner.retriever.with_config_str(&mock_retriever_toml(&server))?;
+
+  let err = learner.search_online("mock", "anything").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Config(_)));
+  assert!(server.requests().is_empty());
+  assert!(matches!(
+    learner.search_online("nowhere", "anything").await,
+    Err(LearnerError::InvalidSource(_))
+  ));
+
+  Ok(())
+}
+
+/// Programmatic retriever claiming `internal:<id>` inputs, for exercising
+/// [`ResourceRetriever`](learner::retriever::ResourceRetriever) dispatch.
+#[derive(Debug)]
+struct InternalRetriever;
+
+#[async_trait::async_trait]
+impl learner::retriever::ResourceRetriever for InternalRetriever {
+  fn name(&self) -> &str { "internal" }
+
+  fn source(&self) -> &str { "internal" }
+
+  fn matches(&self, input: &str) -> bool { input.starts_with("internal:") }
+
+  fn identifier(&self, input: &str) -> learner::error::Result<String> {
+    input.strip_prefix("internal:").map(String::from).ok_or(LearnerError::InvalidIdentifier)
+  }
+
+  async fn retrieve(&self, input: &str) -> learner::error::Result<Paper> {
+    self.fetch(&self.identifier(input)?).await
+  }
+
+  async fn fetch(&self, identifier: &str) -> learner::error::Result<Paper> {
+    let mut paper = crate::workflows::database_operations::create_test_paper();
+    paper.title = format!("Internal paper {identifier}");
+    Ok(paper)
+  }
+
+  async fn search(&self, query: &str) -> learner::error::Result<Vec<Paper>> {
+    let mut paper = self.fetch("7").await?;
+    paper.source_identifier = "7".to_string();
+    paper.abstract_text = format!("Matched {query}");
+    Ok(vec![paper])
+  }
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_programmatic_retriever_dispatch() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  learner.retriever = learner.retriever.with_default_configs()?.with_retriever(InternalRetriever);
+
+  assert!(learner.retriever.names().contains(&"internal"));
+  assert!(learner.retriever.sources().contains(&"internal"));
+  assert_eq!(
+    learner.retriever.sanitize_identifier("internal:42")?,
+    ("internal".to_string(), "42".to_string())
+  );
+  // Configured retrievers still resolve their own identifiers
+  assert_eq!(learner.retriever.sanitize_identifier("2301.07041")?.0, "arxiv");
+
+  let (paper, origin) = learner.get_paper("internal:42").await?;
+  assert_eq!(origin, PaperOrigin::Network);
+  assert_eq!(paper.title, "Internal paper 42");
+  assert_eq!(paper.source, "internal");
+  assert_eq!(paper.source_identifier, "42");
+
+  let stored = Query::by_source("internal", "42").execute(&mut learner.database).await?;
+  assert_eq!(stored.len(), 1);
+
+  // Lookups by source reach the programmatic retriever too
+  let results = learner.search_online("internal", "lattices").await?;
+  assert_eq!(results.len(), 1);
+  assert_eq!(
+    (results[0].source.as_str(), results[0].source_identifier.as_str()),
+    ("internal", "7")
+  );
+  assert_eq!(learner.check_for_updates(&paper).await?, None);
+  assert_eq!(learner.refresh_abstract(&paper).await?, None);
+
+  Ok(())
+}
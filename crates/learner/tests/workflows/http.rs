@@ -0,0 +1,315 @@
+//! Retrieval behaviour over HTTP, exercised against a local mock server.
+
+use std::time::Duration;
+
+use learner::retriever::Retriever;
+
+use super::*;
+use crate::mock::{
+  mock_paper_json, mock_paper_xml, mock_retriever_toml, mock_xml_retriever_toml, MockResponse,
+  MockServer,
+};
+
+#[traced_test]
+#[tokio::test]
+async fn test_slow_server_times_out() -> TestResult<()> {
+  let server = MockServer::respond_with(
+    MockResponse::ok(mock_paper_json("Slow Paper")).with_delay(Duration::from_secs(10)),
+  )
+  .await;
+  let config = format!("timeout_secs = 1\n{}", mock_retriever_toml(&server));
+  let retriever = Retriever::new().with_config_str(&config)?;
+
+  let start = std::time::Instant::now();
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Timeout(_)), "unexpected error: {err:?}");
+  assert!(start.elapsed() < Duration::from_secs(5));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_client_is_reused_across_retrievals() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Pooled Paper"))).await;
+  let mirror = mock_retriever_toml(&server)
+    .replace("name              = \"mock\"", "name              = \"mock_mirror\"")
+    .replace("^mock:", "^mirror:");
+  let retriever =
+    Retriever::new().with_config_str(&mock_retriever_toml(&server))?.with_config_str(&mirror)?;
+
+  for input in ["mock:1", "mock:2", "mirror:3", "mirror:4"] {
+    retriever.get_paper(input).await?;
+  }
+
+  // Every request, across both configurations, went over one pooled connection
+  assert_eq!(server.requests().len(), 4);
+  assert_eq!(server.connections(), 1);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_user_agent_is_sent() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Polite Paper"))).await;
+  let mirror = mock_retriever_toml(&server)
+    .replace("name              = \"mock\"", "name              = \"mock_mirror\"")
+    .replace("^mock:", "^mirror:");
+  let config = format!(
+    "user_agent = \"mock-client (mailto:me@example.com)\"\n{}\n[headers]\nUser-Agent = \
+     \"ignored\"\n",
+    mock_retriever_toml(&server)
+  );
+  let retriever = Retriever::new()
+    .with_config_str(&config)?
+    .with_user_agent("learner-tests")
+    .with_config_str(&mirror)?;
+
+  retriever.get_paper("mock:1").await?;
+  retriever.get_paper("mirror:2").await?;
+
+  // A configuration's own agent wins over both the headers entry and the global default
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["user-agent"], "mock-client (mailto:me@example.com)");
+  assert_eq!(requests[1].headers["user-agent"], "learner-tests");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_config_user_agent_is_sent() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Polite Paper"))).await;
+  let retrievers_dir = tempdir()?;
+  let database_dir = tempdir()?;
+  let storage_dir = tempdir()?;
+  std::fs::write(retrievers_dir.path().join("mock.toml"), mock_retriever_toml(&server))?;
+  let config = Config::default()
+    .with_database_path(&database_dir.path().join("learner.db"))
+    .with_retrievers_path(retrievers_dir.path())
+    .with_storage_path(storage_dir.path())
+    .with_user_agent("learner-tests (mailto:me@example.com)");
+  let learner = Learner::builder().with_config(config).build().await?;
+
+  learner.retriever.get_paper("mock:1").await?;
+  assert_eq!(server.requests()[0].headers["user-agent"], "learner-tests (mailto:me@example.com)");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_not_modified_uses_cached_response() -> TestResult<()> {
+  let server = MockServer::start(|request| match request.headers.get("if-none-match") {
+    Some(etag) if etag == "\"v1\"" => MockResponse::status(304),
+    _ => MockResponse::ok(mock_paper_json("Cached Paper"))
+      .with_header("ETag", "\"v1\"")
+      .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+  })
+  .await;
+  let retriever = Retriever::new().with_config_str(&mock_retriever_toml(&server))?;
+
+  let first = retriever.get_paper("mock:1").await?;
+  let second = retriever.get_paper("mock:1").await?;
+  assert_eq!(second.title, "Cached Paper");
+  assert_eq!(second.title, first.title);
+
+  // The second request revalidated the first response instead of refetching it
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert!(!requests[0].headers.contains_key("if-none-match"));
+  assert_eq!(requests[1].headers["if-none-match"], "\"v1\"");
+  assert_eq!(requests[1].headers["if-modified-since"], "Wed, 21 Oct 2015 07:28:00 GMT");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_oversized_response_is_rejected() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok(vec![b' '; 64 * 1024])).await;
+  let config = format!("max_response_bytes = 1024\n{}", mock_retriever_toml(&server));
+  let retriever = Retriever::new().with_config_str(&config)?;
+
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(
+    matches!(&err, LearnerError::ApiError(msg) if msg.contains("exceeds 1024 bytes")),
+    "unexpected error: {err:?}"
+  );
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_oversized_compressed_response_is_rejected() -> TestResult<()> {
+  use std::io::Write;
+
+  // 32 MiB of zeros compresses to a small body with no usable length up front, so the limit
+  // has to be enforced while the decompressed body streams in
+  let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+  let block = vec![0; 1024 * 1024];
+  for _ in 0..32 {
+    encoder.write_all(&block)?;
+  }
+  let server = MockServer::respond_with(
+    MockResponse::ok(encoder.finish()?).with_header("Content-Encoding", "gzip"),
+  )
+  .await;
+  let config = format!("max_response_bytes = 65536\n{}", mock_retriever_toml(&server));
+  let retriever = Retriever::new().with_config_str(&config)?;
+
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(matches!(err, LearnerError::ApiError(_)), "unexpected error: {err:?}");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_gzip_response_is_decoded() -> TestResult<()> {
+  use std::io::Write;
+
+  let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+  encoder.write_all(mock_paper_json("Compressed Paper").as_bytes())?;
+  let body = encoder.finish()?;
+
+  let server =
+    MockServer::respond_with(MockResponse::ok(body).with_header("Content-Encoding", "gzip")).await;
+  let retriever = Retriever::new().with_config_str(&mock_retriever_toml(&server))?;
+
+  let paper = retriever.get_paper("mock:1").await?;
+  assert_eq!(paper.title, "Compressed Paper");
+  assert_eq!(paper.authors[0].name, "Ada Lovelace");
+
+  let accept_encoding = server.requests()[0].headers.get("accept-encoding").cloned();
+  assert!(accept_encoding.is_some_and(|value| value.contains("gzip")));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_utf16_response_is_decoded() -> TestResult<()> {
+  let xml = mock_paper_xml("Über Unicode Papers");
+
+  let mut little_endian = vec![0xFF, 0xFE];
+  little_endian.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+  let server = MockServer::respond_with(MockResponse::ok(little_endian)).await;
+  let retriever = Retriever::new().with_config_str(&mock_xml_retriever_toml(&server))?;
+  let paper = retriever.get_paper("mockxml:1").await?;
+  assert_eq!(paper.title, "Über Unicode Papers");
+  assert_eq!(paper.authors[0].name, "Ada Lovelace");
+
+  // Without a BOM the charset in the Content-Type decides
+  let big_endian: Vec<u8> = xml.encode_utf16().flat_map(u16::to_be_bytes).collect();
+  let server = MockServer::respond_with(
+    MockResponse::ok(big_endian).with_header("Content-Type", "application/xml; charset=UTF-16BE"),
+  )
+  .await;
+  let retriever = Retriever::new().with_config_str(&mock_xml_retriever_toml(&server))?;
+  let paper = retriever.get_paper("mockxml:1").await?;
+  assert_eq!(paper.title, "Über Unicode Papers");
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_connection_failure_is_network_error() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok(mock_paper_json("Unreachable"))).await;
+  // Nothing listens on a port that was just released
+  let closed = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+  let config = mock_retriever_toml(&server).replace(&server.url(""), &format!("http://{closed}"));
+  let retriever = Retriever::new().with_config_str(&config)?;
+
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Network(_)), "unexpected error: {err:?}");
+  assert!(server.requests().is_empty());
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_malformed_body_is_deserialize_error() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::ok("{\"message\": {")).await;
+  let retriever = Retriever::new().with_config_str(&mock_retriever_toml(&server))?;
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(
+    matches!(err, LearnerError::Deserialize { what: "JSON response", .. }),
+    "unexpected error: {err:?}"
+  );
+  assert!(std::error::Error::source(&err).is_some());
+
+  let server = MockServer::respond_with(MockResponse::ok("<feed><entry></feed>")).await;
+  let retriever = Retriever::new().with_config_str(&mock_xml_retriever_toml(&server))?;
+  let err = retriever.get_paper("mockxml:1").await.unwrap_err();
+  assert!(
+    matches!(err, LearnerError::Deserialize { what: "XML response", .. }),
+    "unexpected error: {err:?}"
+  );
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_error_status_is_api_error() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::status(503)).await;
+  let retriever = Retriever::new().with_config_str(&mock_retriever_toml(&server))?;
+
+  let err = retriever.get_paper("mock:1").await.unwrap_err();
+  assert!(matches!(err, LearnerError::ApiError(_)), "unexpected error: {err:?}");
+  assert!(err.to_string().contains("503"));
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_named_endpoint_is_requested() -> TestResult<()> {
+  let server = MockServer::start(|request| match request.path.starts_with("/oai/") {
+    true => MockResponse::ok(mock_paper_xml("Full Record")),
+    false => MockResponse::ok(mock_paper_json("Metadata Record")),
+  })
+  .await;
+  let config = format!(
+    r#"{}
+[endpoints.oai]
+template = "{}{{identifier}}?metadataPrefix=full"
+
+[endpoints.oai.response_format]
+strip_namespaces = true
+type             = "xml"
+
+[endpoints.oai.response_format.field_maps]
+title            = {{ path = "feed/entry/title" }}
+abstract         = {{ path = "feed/entry/summary" }}
+authors          = {{ path = "feed/entry/author/name" }}
+publication_date = {{ path = "feed/entry/published" }}
+"#,
+    mock_retriever_toml(&server),
+    server.url("/oai/")
+  );
+  let retriever = Retriever::new().with_config_str(&config)?;
+
+  // The selected template is substituted and parsed with the endpoint's own format
+  let paper = retriever.get_paper_from("mock:7", "oai").await?;
+  assert_eq!(paper.title, "Full Record");
+  assert_eq!(paper.source, "mock");
+  assert_eq!(paper.source_identifier, "7");
+
+  // The single-endpoint form is still the default
+  let paper = retriever.get_paper("mock:7").await?;
+  assert_eq!(paper.title, "Metadata Record");
+
+  let err = retriever.get_paper_from("mock:7", "missing").await.unwrap_err();
+  assert!(matches!(err, LearnerError::Config(_)), "unexpected error: {err:?}");
+
+  let paths: Vec<_> = server.requests().into_iter().map(|request| request.path).collect();
+  assert_eq!(paths, vec!["/oai/7?metadataPrefix=full", "/works/7"]);
+
+  Ok(())
+}
@@ -0,0 +1,89 @@
+//! Importing a directory of PDFs without metadata.
+
+use learner::{database::Query, retriever::Retriever, ImportOutcome};
+
+use super::*;
+use crate::mock::{mock_paper_json, mock_retriever_toml, MockResponse, MockServer};
+
+#[traced_test]
+#[tokio::test]
+async fn test_import_directory() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, strg_dir) = create_test_learner().await;
+  // Only the fixture's own DOI resolves, other DOIs are unknown to the source
+  let server = MockServer::start(|request| {
+    if request.path.ends_with("imported.42") {
+      MockResponse::ok(mock_paper_json("Resolved Paper"))
+    } else {
+      MockResponse::status(404)
+    }
+  })
+  .await;
+  let doi_retriever = mock_retriever_toml(&server)
+    .replace("name              = \"mock\"", "name              = \"doi\"")
+    .replace("source            = \"mock\"", "source            = \"doi\"")
+    .replace("^mock:(\\\\d+)$", "^(10\\\\.\\\\d{4,9}/\\\\S+)$");
+  learner.retriever = Retriever::new().with_config_str(&doi_retriever)?;
+
+  let import_dir = tempdir()?;
+  std::fs::copy("tests/.data/doi_paper.pdf", import_dir.path().join("a_with_doi.pdf"))?;
+  std::fs::copy("tests/.data/test_paper.pdf", import_dir.path().join("b_unresolved.pdf"))?;
+  std::fs::write(import_dir.path().join("c_broken.pdf"), b"not a pdf")?;
+  std::fs::write(import_dir.path().join("notes.txt"), b"ignored")?;
+
+  let report = learner.import_directory(import_dir.path()).await?;
+  assert_eq!(report.len(), 3);
+
+  let (path, ImportOutcome::Resolved(resolved)) = &report[0] else {
+    panic!("expected the DOI to resolve, got {:?}", report[0]);
+  };
+  assert!(path.ends_with("a_with_doi.pdf"));
+  assert_eq!(resolved.title, "Resolved Paper");
+  assert_eq!(resolved.source, "doi");
+  assert_eq!(resolved.source_identifier, "10.1000/imported.42");
+  assert_eq!(server.requests()[0].path, "/works/10.1000/imported.42");
+
+  let (_, ImportOutcome::Minimal(minimal)) = &report[1] else {
+    panic!("expected a minimal paper, got {:?}", report[1]);
+  };
+  assert_eq!(minimal.title, "b_unresolved");
+  assert_eq!(minimal.source, "local");
+  assert!(minimal.doi.is_some(), "the unresolved DOI is kept");
+
+  assert!(matches!(report[2].1, ImportOutcome::Failed(_)));
+
+  // Both imported papers are stored with their documents copied into storage
+  for paper in [resolved, minimal] {
+    assert_eq!(Query::by_paper(paper).execute(&mut learner.database).await?.len(), 1);
+    assert!(strg_dir.path().join(paper.filename()).exists());
+  }
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_import_same_name_from_different_directories() -> TestResult<()> {
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  learner.retriever = Retriever::new().with_offline(true);
+
+  let (first_dir, second_dir) = (tempdir()?, tempdir()?);
+  std::fs::copy("tests/.data/test_paper.pdf", first_dir.path().join("paper.pdf"))?;
+  std::fs::copy("tests/.data/doi_paper.pdf", second_dir.path().join("paper.pdf"))?;
+
+  let mut imported = Vec::new();
+  for dir in [&first_dir, &second_dir] {
+    let report = learner.import_directory(dir.path()).await?;
+    let [(_, ImportOutcome::Minimal(paper))] = report.as_slice() else {
+      panic!("expected a minimal paper, got {report:?}");
+    };
+    imported.push(paper.clone());
+  }
+
+  assert_ne!(imported[0].source_identifier, imported[1].source_identifier);
+  for paper in &imported {
+    assert_eq!(paper.title, "paper");
+    assert_eq!(Query::by_paper(paper).execute(&mut learner.database).await?.len(), 1);
+  }
+
+  Ok(())
+}
@@ -0,0 +1,150 @@
+//! Moving a whole library between databases with a bundle.
+
+use learner::{
+  database::{Add, OrderField, Query, Remove},
+  error::LearnerError,
+  resource::PAPER_JSON_VERSION,
+  storage::MemoryStorage,
+  LIBRARY_DOCUMENTS_DIR, LIBRARY_FILE,
+};
+
+use super::*;
+use crate::workflows::database_operations::{create_second_test_paper, create_test_paper};
+
+#[traced_test]
+#[tokio::test]
+async fn test_library_round_trip() -> TestResult<()> {
+  let (mut source, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let with_document = create_test_paper();
+  let metadata_only = create_second_test_paper();
+  Add::local_document(&with_document, Path::new("tests/.data/test_paper.pdf"))
+    .execute(&mut source.database)
+    .await?;
+  Add::paper(&metadata_only).execute(&mut source.database).await?;
+
+  let bundle = tempdir()?;
+  let exported = source.export_library(bundle.path(), true).await?;
+  assert_eq!(exported.papers, 2);
+  assert!(exported.skipped.is_empty());
+  assert!(bundle.path().join(LIBRARY_FILE).exists());
+  assert_eq!(std::fs::read_dir(bundle.path().join(LIBRARY_DOCUMENTS_DIR))?.count(), 1);
+
+  let (mut target, _target_cfg_dir, _target_db_dir, _target_strg_dir) = create_test_learner().await;
+  let added = target.import_library(bundle.path()).await?;
+  assert_eq!(added.len(), 2);
+
+  // The metadata survives unchanged and the document is restored into the new storage
+  let list = Query::list_all().order_by(OrderField::Title);
+  assert_eq!(list.execute(&mut target.database).await?, list.execute(&mut source.database).await?);
+  let restored = target.database.document_path(&with_document).await?.unwrap();
+  assert!(restored.starts_with(target.database.get_storage_path().await?));
+  assert_eq!(std::fs::read(restored)?, std::fs::read("tests/.data/test_paper.pdf")?);
+  assert_eq!(target.database.document_path(&metadata_only).await?, None);
+
+  // Importing again only skips the duplicates
+  assert!(target.import_library(bundle.path()).await?.is_empty());
+  assert_eq!(list.execute(&mut target.database).await?.len(), 2);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_library_round_trip_keeps_archived_papers_and_stored_documents() -> TestResult<()> {
+  let (mut source, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  source.database.set_storage(MemoryStorage::default());
+  let archived = create_test_paper();
+  let in_memory = create_second_test_paper();
+  Add::paper(&archived).execute(&mut source.database).await?;
+  Remove::by_source(&archived.source, &archived.source_identifier)
+    .execute(&mut source.database)
+    .await?;
+  Add::local_document(&in_memory, Path::new("tests/.data/test_paper.pdf"))
+    .execute(&mut source.database)
+    .await?;
+
+  let bundle = tempdir()?;
+  let exported = source.export_library(bundle.path(), true).await?;
+  assert_eq!(exported.papers, 2);
+  assert!(exported.skipped.is_empty());
+
+  let (mut target, _target_cfg_dir, _target_db_dir, _target_strg_dir) = create_test_learner().await;
+  assert_eq!(target.import_library(bundle.path()).await?.len(), 2);
+
+  // The archived paper is still archived, and the document came out of the memory backend
+  assert_eq!(Query::list_all().only_archived().execute(&mut target.database).await?, vec![
+    archived
+  ]);
+  assert_eq!(Query::list_all().execute(&mut target.database).await?, vec![in_memory.clone()]);
+  let restored = target.database.document_path(&in_memory).await?.unwrap();
+  assert_eq!(std::fs::read(restored)?, std::fs::read("tests/.data/test_paper.pdf")?);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_library_export_reports_unreadable_documents() -> TestResult<()> {
+  let (mut source, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let paper = create_test_paper();
+  let pdf = tempdir()?;
+  let path = pdf.path().join("paper.pdf");
+  std::fs::copy("tests/.data/test_paper.pdf", &path)?;
+  Add::local_document(&paper, &path).execute(&mut source.database).await?;
+  let stored = source.database.document_path(&paper).await?.unwrap();
+  std::fs::remove_file(stored)?;
+
+  let bundle = tempdir()?;
+  let exported = source.export_library(bundle.path(), true).await?;
+  assert_eq!(exported.papers, 1);
+  assert_eq!(exported.skipped.len(), 1);
+  assert_eq!(exported.skipped[0].0, paper);
+  assert_eq!(std::fs::read_dir(bundle.path().join(LIBRARY_DOCUMENTS_DIR))?.count(), 0);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_library_export_without_documents() -> TestResult<()> {
+  let (mut source, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let paper = create_test_paper();
+  Add::local_document(&paper, Path::new("tests/.data/test_paper.pdf"))
+    .execute(&mut source.database)
+    .await?;
+
+  let bundle = tempdir()?;
+  source.export_library(bundle.path(), false).await?;
+  assert!(!bundle.path().join(LIBRARY_DOCUMENTS_DIR).exists());
+
+  // Without the file itself only the metadata comes across
+  let (mut target, _target_cfg_dir, _target_db_dir, _target_strg_dir) = create_test_learner().await;
+  assert_eq!(target.import_library(bundle.path()).await?, vec![paper.clone()]);
+  assert_eq!(target.database.document_path(&paper).await?, None);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_library_bundle_uses_paper_json() -> TestResult<()> {
+  let (mut source, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  Add::paper(&create_test_paper()).execute(&mut source.database).await?;
+
+  let bundle = tempdir()?;
+  source.export_library(bundle.path(), false).await?;
+  let library = std::fs::read_to_string(bundle.path().join(LIBRARY_FILE))?;
+  let mut json: serde_json::Value = serde_json::from_str(&library)?;
+  assert_eq!(json["papers"][0]["schema_version"], PAPER_JSON_VERSION);
+
+  // A bundle from a release with a newer paper layout is refused rather than misread
+  json["papers"][0]["schema_version"] = (PAPER_JSON_VERSION + 1).into();
+  std::fs::write(bundle.path().join(LIBRARY_FILE), json.to_string())?;
+  let (mut target, _target_cfg_dir, _target_db_dir, _target_strg_dir) = create_test_learner().await;
+  assert!(matches!(
+    target.import_library(bundle.path()).await,
+    Err(LearnerError::Deserialize { .. })
+  ));
+
+  Ok(())
+}
@@ -0,0 +1,86 @@
+//! Checking that stored URLs still resolve.
+
+use learner::{
+  database::{Add, Query},
+  LinkStatus,
+};
+
+use super::*;
+use crate::{
+  mock::{MockResponse, MockServer},
+  workflows::database_operations::{create_second_test_paper, create_test_paper},
+};
+
+#[traced_test]
+#[tokio::test]
+async fn test_verify_links_reports_dead_urls() -> TestResult<()> {
+  let server = MockServer::start(|request| match request.path.as_str() {
+    "/live.pdf" => MockResponse::status(200),
+    _ => MockResponse::status(404),
+  })
+  .await;
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  // Papers from a source without landing pages, so only their PDF URLs are checked
+  let mut live = create_test_paper();
+  live.source = "mock".to_string();
+  live.pdf_url = Some(server.url("/live.pdf"));
+  let mut dead = create_second_test_paper();
+  dead.source = "mock".to_string();
+  dead.pdf_url = Some(server.url("/dead.pdf"));
+  Add::paper(&live).execute(&mut learner.database).await?;
+  Add::paper(&dead).execute(&mut learner.database).await?;
+  let before = Query::list_all().execute(&mut learner.database).await?;
+
+  let checks = learner.verify_links().await?;
+  assert_eq!(checks.len(), 2);
+  let status = |url: String| checks.iter().find(|check| check.url == url).unwrap();
+  assert_eq!(status(server.url("/live.pdf")).status, LinkStatus::Live(200));
+  assert!(status(server.url("/live.pdf")).is_live());
+  assert_eq!(status(server.url("/dead.pdf")).status, LinkStatus::Dead(404));
+  assert_eq!(status(server.url("/dead.pdf")).paper.title, dead.title);
+  assert!(server.requests().iter().all(|request| request.method == "HEAD"));
+
+  // Dead links are only reported
+  assert_eq!(Query::list_all().execute(&mut learner.database).await?, before);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_verify_links_falls_back_to_get() -> TestResult<()> {
+  let server = MockServer::start(|request| match request.method.as_str() {
+    "HEAD" => MockResponse::status(405),
+    _ => MockResponse::ok("%PDF-1.4"),
+  })
+  .await;
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  let mut paper = create_test_paper();
+  paper.source = "mock".to_string();
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  let checks = learner.verify_links().await?;
+  assert_eq!(checks[0].status, LinkStatus::Live(200));
+  let methods: Vec<_> = server.requests().into_iter().map(|request| request.method).collect();
+  assert_eq!(methods, ["HEAD", "GET"]);
+
+  Ok(())
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_verify_links_sends_user_agent() -> TestResult<()> {
+  let server = MockServer::respond_with(MockResponse::status(200)).await;
+  let (mut learner, _cfg_dir, _db_dir, _strg_dir) = create_test_learner().await;
+  learner.retriever = learner.retriever.with_user_agent("learner-tests (mailto:me@example.com)");
+  let mut paper = create_test_paper();
+  paper.source = "mock".to_string();
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  Add::paper(&paper).execute(&mut learner.database).await?;
+
+  learner.verify_links().await?;
+  assert_eq!(server.requests()[0].headers["user-agent"], "learner-tests (mailto:me@example.com)");
+
+  Ok(())
+}
@@ -4,4 +4,11 @@ use super::*;
 
 mod build_retriever;
 mod database_operations;
+mod download;
+mod get_paper;
+mod http;
+mod import;
+mod library;
+mod links;
 mod paper_retrieval;
+#[cfg(feature = "object-store")] mod storage;
@@ -0,0 +1,83 @@
+//! Keeping documents in an HTTP object store, exercised against a local mock server.
+
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+use learner::storage::{HttpStorage, ObjectStoreOptions, Storage};
+use reqwest::Url;
+
+use super::*;
+use crate::mock::{MockResponse, MockServer};
+
+/// Starts a server that keeps the bodies `PUT` to it, answering only requests that carry the
+/// bearer token `secret`.
+async fn object_store() -> MockServer {
+  let objects = Arc::new(Mutex::new(HashMap::<String, Vec<u8>>::new()));
+  MockServer::start(move |request| {
+    if request.headers.get("authorization").map(String::as_str) != Some("Bearer secret") {
+      return MockResponse::status(403);
+    }
+    let mut objects = objects.lock().unwrap();
+    match (request.method.as_str(), objects.get(&request.path)) {
+      ("PUT", _) => {
+        objects.insert(request.path.clone(), request.body.clone());
+        MockResponse::status(201)
+      },
+      ("GET", Some(body)) => MockResponse::ok(body.clone()),
+      ("HEAD", Some(_)) => MockResponse::status(200),
+      ("DELETE", Some(_)) => {
+        objects.remove(&request.path);
+        MockResponse::status(204)
+      },
+      _ => MockResponse::status(404),
+    }
+  })
+  .await
+}
+
+#[tokio::test]
+async fn test_http_storage_round_trip() -> TestResult<()> {
+  let server = object_store().await;
+  let storage = HttpStorage::new(Url::parse(&server.url("/bucket/"))?).with_bearer_token("secret");
+
+  assert!(!storage.exists("paper.pdf").await?);
+  assert!(matches!(storage.get("paper.pdf").await, Err(LearnerError::Path(_))));
+
+  storage.put("paper.pdf", b"%PDF-1.4").await?;
+  assert!(storage.exists("paper.pdf").await?);
+  assert_eq!(storage.get("paper.pdf").await?, b"%PDF-1.4");
+  assert_eq!(storage.uri("paper.pdf"), server.url("/bucket/paper.pdf"));
+
+  storage.delete("paper.pdf").await?;
+  assert!(!storage.exists("paper.pdf").await?);
+  storage.delete("paper.pdf").await?;
+
+  let unauthenticated = HttpStorage::new(Url::parse(&server.url("/bucket/"))?);
+  assert!(matches!(
+    unauthenticated.put("paper.pdf", b"%PDF-1.4").await,
+    Err(LearnerError::ApiError(_))
+  ));
+  Ok(())
+}
+
+#[tokio::test]
+async fn test_config_selects_object_store() -> TestResult<()> {
+  let server = object_store().await;
+  let (config_dir, database_dir, storage_dir) = (tempdir()?, tempdir()?, tempdir()?);
+  let mut config = Config::default()
+    .with_database_path(&database_dir.path().join("learner.db"))
+    .with_retrievers_path(Path::new("config/retrievers/"))
+    .with_storage_path(storage_dir.path());
+  config.object_store =
+    Some(ObjectStoreOptions { url: server.url("/bucket/"), token: Some("secret".to_string()) });
+  let learner = Learner::builder().with_path(config_dir.path()).with_config(config).build().await?;
+
+  let storage = learner.database.storage().await?;
+  storage.put("paper.pdf", b"%PDF-1.4").await?;
+  assert_eq!(storage.uri("paper.pdf"), server.url("/bucket/paper.pdf"));
+  assert!(server.requests().iter().any(|request| request.method == "PUT"));
+  assert!(!storage_dir.path().join("paper.pdf").exists());
+  Ok(())
+}
@@ -5,10 +5,13 @@ use super::*;
 // TODO (autoparallel): This could probably be made even more streamlined if we use the result/error
 // type from `learner` more cleverly
 
+/// Source searched by `learner add --search` when no `--source` is given
+pub const DEFAULT_SEARCH_SOURCE: &str = "arxiv";
+
 /// Arguments that can be used for the [`Commands::Add`]
 #[derive(Args, Clone)]
 pub struct AddArgs {
-  /// Paper identifier (arXiv ID, DOI, IACR ID)
+  /// Paper identifier (arXiv ID, DOI, IACR ID), or keywords to search for with `--search`
   pub identifier: String,
 
   /// Force PDF download
@@ -18,11 +21,19 @@ pub struct AddArgs {
   /// Skip PDF download
   #[arg(long, group = "pdf_behavior")]
   pub no_pdf: bool,
+
+  /// Search a source online for the keywords and pick which of the results to add
+  #[arg(long)]
+  pub search: bool,
+
+  /// Source to search with `--search` (defaults to arxiv)
+  #[arg(long, requires = "search")]
+  pub source: Option<String>,
 }
 
 /// Function for the [`Commands::Add`] in the CLI.
 pub async fn add<I: UserInteraction>(interaction: &mut I, add_args: AddArgs) -> Result<Paper> {
-  let AddArgs { identifier, pdf, no_pdf } = add_args;
+  let AddArgs { identifier, pdf, no_pdf, .. } = add_args;
 
   if interaction.learner().retriever.is_empty() {
     return Err(LearnerdError::Learner(LearnerError::Config(
@@ -39,7 +50,7 @@ pub async fn add<I: UserInteraction>(interaction: &mut I, add_args: AddArgs) ->
   if papers.is_empty() {
     interaction.reply(ResponseContent::Info(&format!("Fetching paper: {}", identifier)))?;
     let paper = interaction.learner().retriever.get_paper(&identifier).await?;
-    interaction.reply(ResponseContent::Paper(&paper))?;
+    interaction.reply(ResponseContent::Paper(&paper, None))?;
 
     let with_pdf = paper.pdf_url.is_some()
       && if pdf {
@@ -63,11 +74,11 @@ pub async fn add<I: UserInteraction>(interaction: &mut I, add_args: AddArgs) ->
     let paper = papers[0].clone();
     interaction.reply(ResponseContent::Info("Paper already exists in database"))?;
 
-    let pdf_dir = interaction.learner().database.get_storage_path().await?;
-    let pdf_path = pdf_dir.join(paper.filename());
+    let document = interaction.learner().database.stored_document(&paper).await?;
 
-    if pdf_path.exists() {
-      interaction.reply(ResponseContent::Info(&format!("PDF exists at: {}", pdf_path.display())))?
+    if let Some(document) = document.filter(is_present) {
+      interaction
+        .reply(ResponseContent::Info(&format!("PDF exists at: {}", location(&document))))?
     } else if paper.pdf_url.is_some() {
       let should_download = if pdf {
         true
@@ -78,6 +89,9 @@ pub async fn add<I: UserInteraction>(interaction: &mut I, add_args: AddArgs) ->
       };
 
       if should_download {
+        if interaction.learner().config.offline {
+          return Err(LearnerdError::from(LearnerError::Offline));
+        }
         match Add::complete(&paper).execute(&mut interaction.learner().database).await {
           Ok(_) => interaction.reply(ResponseContent::Success("PDF downloaded successfully"))?,
           Err(e) => interaction.reply(ResponseContent::Error(LearnerdError::from(e)))?,
@@ -87,3 +101,199 @@ pub async fn add<I: UserInteraction>(interaction: &mut I, add_args: AddArgs) ->
     Ok(paper)
   }
 }
+
+/// Function for the [`Commands::Add`] in the CLI with `--search`.
+///
+/// Searches the source online for the keywords, lets the user pick any of the results and
+/// adds the picked papers. Papers already in the database are skipped, and the search results
+/// themselves are stored, so nothing is fetched again.
+///
+/// # Returns
+///
+/// The papers that were added.
+pub async fn add_from_search<I: UserInteraction>(
+  interaction: &mut I,
+  add_args: AddArgs,
+) -> Result<Vec<Paper>> {
+  let AddArgs { identifier: query, pdf, no_pdf, source, .. } = add_args;
+  let source = source.as_deref().unwrap_or(DEFAULT_SEARCH_SOURCE);
+
+  interaction.reply(ResponseContent::Info(&format!("Searching {source} for: {query}")))?;
+  let results = interaction.learner().search_online(source, &query).await?;
+  if results.is_empty() {
+    interaction.reply(ResponseContent::Info("No papers found matching the search"))?;
+    return Ok(Vec::new());
+  }
+
+  let items: Vec<String> = results
+    .iter()
+    .map(|p| {
+      format!("{} ({}: {}, {})", p.title, p.source, p.source_identifier, p.publication_date.year())
+    })
+    .collect();
+  let selection = interaction.select("Select the papers to add", &items)?;
+  let selected = select_papers(results, &selection);
+  if selected.is_empty() {
+    interaction.reply(ResponseContent::Info("No papers selected"))?;
+    return Ok(Vec::new());
+  }
+
+  let with_pdf = if pdf {
+    true
+  } else if no_pdf {
+    false
+  } else {
+    selected.iter().any(|p| p.pdf_url.is_some()) && interaction.confirm("Download PDFs?")?
+  };
+
+  let mut added = Vec::new();
+  for paper in selected {
+    let database = &mut interaction.learner().database;
+    if !Query::by_source(&paper.source, &paper.source_identifier)
+      .execute(database)
+      .await?
+      .is_empty()
+    {
+      interaction
+        .reply(ResponseContent::Info(&format!("Already in the database: {}", paper.title)))?;
+      continue;
+    }
+
+    match if with_pdf && paper.pdf_url.is_some() {
+      Add::complete(&paper).execute(database).await
+    } else {
+      Add::paper(&paper).execute(database).await
+    } {
+      Ok(_) => {
+        interaction.reply(ResponseContent::Success(&format!(
+          "Added {}: {} ({})",
+          paper.source, paper.source_identifier, paper.title
+        )))?;
+        added.push(paper);
+      },
+      Err(e) => interaction.reply(ResponseContent::Error(LearnerdError::from(e)))?,
+    }
+  }
+  Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+  use learner::Config;
+  use tempfile::tempdir;
+
+  use super::*;
+  use crate::mock::{MockResponse, MockServer};
+
+  /// Interaction that picks fixed entries from every selection and declines confirmations.
+  struct Scripted {
+    /// The learner searched and added to
+    learner:   Learner,
+    /// Indices returned from [`UserInteraction::select`]
+    selection: Vec<usize>,
+    /// Entries offered by the last selection
+    offered:   Vec<String>,
+  }
+
+  impl UserInteraction for Scripted {
+    fn learner(&mut self) -> &mut Learner { &mut self.learner }
+
+    fn confirm(&mut self, _message: &str) -> Result<bool> { Ok(false) }
+
+    fn prompt(&mut self, _message: &str) -> Result<String> { Ok(String::new()) }
+
+    fn select(&mut self, _message: &str, items: &[String]) -> Result<Vec<usize>> {
+      self.offered = items.to_vec();
+      Ok(self.selection.clone())
+    }
+
+    fn reply(&mut self, _content: ResponseContent) -> Result<()> { Ok(()) }
+  }
+
+  fn result(id: &str, title: &str) -> Paper {
+    Paper::builder()
+      .with_title(title)
+      .with_source("mock")
+      .with_source_identifier(id)
+      .with_publication_date(Utc::now())
+      .build()
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_add_from_search_adds_selected_results() {
+    let server = MockServer::respond_with(MockResponse::ok(
+      r#"{"items": [
+        {"id": "1", "title": "Attention Is All You Need", "abstract": "Transformers",
+         "author": [{"given": "Ashish", "family": "Vaswani"}], "date": [2017, 6, 12]},
+        {"id": "2", "title": "Attention Is Not Explanation", "abstract": "Probing",
+         "author": [{"given": "Sarthak", "family": "Jain"}], "date": [2019]},
+        {"id": "3", "title": "Attention Is Turing Complete", "abstract": "Expressivity",
+         "author": [{"given": "Jorge", "family": "Pérez"}], "date": [2021, 1]}
+      ]}"#,
+    ))
+    .await;
+    let base = server.url("");
+    let dir = tempdir().unwrap();
+    let retrievers = tempdir().unwrap();
+    let config = Config::default()
+      .with_database_path(&dir.path().join("learner.db"))
+      .with_retrievers_path(retrievers.path())
+      .with_storage_path(dir.path());
+    let mut learner =
+      Learner::builder().with_path(dir.path()).with_config(config).build().await.unwrap();
+    learner.retriever = learner
+      .retriever
+      .with_config_str(&format!(
+        r#"
+name                     = "mock"
+base_url                 = "{base}"
+endpoint_template        = "{base}/works/{{identifier}}"
+pattern                  = "^(?:mock:)?(\\d+)$"
+search_endpoint_template = "{base}/search?q={{query}}"
+search_results_path      = "items"
+source                   = "mock"
+
+[response_format]
+type = "json"
+
+[response_format.field_maps]
+abstract         = {{ path = "abstract" }}
+authors          = {{ path = "author" }}
+identifier       = {{ path = "id" }}
+publication_date = {{ path = "date" }}
+title            = {{ path = "title" }}
+"#
+      ))
+      .unwrap();
+    Add::paper(&result("3", "Attention Is Turing Complete"))
+      .execute(&mut learner.database)
+      .await
+      .unwrap();
+
+    let mut interaction = Scripted { learner, selection: vec![0, 2], offered: Vec::new() };
+    let args = AddArgs {
+      identifier: "attention is all".to_string(),
+      pdf:        false,
+      no_pdf:     false,
+      search:     true,
+      source:     Some("mock".to_string()),
+    };
+    let added = add_from_search(&mut interaction, args).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/search?q=attention%20is%20all");
+    assert_eq!(interaction.offered, [
+      "Attention Is All You Need (mock: 1, 2017)",
+      "Attention Is Not Explanation (mock: 2, 2019)",
+      "Attention Is Turing Complete (mock: 3, 2021)",
+    ]);
+    // The third pick was already stored, so only the first is added
+    assert_eq!(added.iter().map(|p| p.source_identifier.as_str()).collect::<Vec<_>>(), ["1"]);
+    let stored = Query::text("attention").execute(&mut interaction.learner.database).await.unwrap();
+    let mut identifiers: Vec<_> = stored.iter().map(|p| p.source_identifier.as_str()).collect();
+    identifiers.sort_unstable();
+    assert_eq!(identifiers, ["1", "3"]);
+  }
+}
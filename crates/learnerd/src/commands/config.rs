@@ -0,0 +1,118 @@
+//! Module for inspecting the [`learner`] configuration.
+
+use std::path::{Path, PathBuf};
+
+use super::*;
+
+/// Subcommands available for [`Commands::Config`]
+#[derive(Subcommand, Clone, Copy, Debug)]
+pub enum ConfigCommands {
+  /// List the retriever configurations that are currently loaded
+  List,
+  /// Check every retriever configuration in the retrievers directory, reporting all failures
+  Validate,
+  /// Print where the config file, database, documents and retriever configs live
+  Path,
+  /// Print the effective configuration, after environment overrides and the profile, as TOML
+  Show,
+}
+
+impl ConfigCommands {
+  /// Whether the subcommand needs a built [`Learner`].
+  ///
+  /// The others only read the [`Config`], so they still work when a broken retriever config
+  /// would keep the learner from building.
+  pub fn needs_learner(self) -> bool { matches!(self, ConfigCommands::List) }
+}
+
+/// Function for the [`Commands::Config`] in the CLI, for the configuration in `config_dir`.
+pub async fn config<I: UserInteraction>(
+  interaction: &mut I,
+  cmd: ConfigCommands,
+  config_dir: &Path,
+) -> Result<()> {
+  match cmd {
+    ConfigCommands::List => {
+      let retriever = &interaction.learner().retriever;
+      if retriever.is_empty() {
+        return interaction.reply(ResponseContent::Info("No retrievers configured"));
+      }
+
+      let lines: Vec<String> = retriever
+        .names()
+        .into_iter()
+        .filter_map(|name| retriever.config(name))
+        .map(|config| {
+          format!("{} (source: {}, base url: {})", config.name, config.source, config.base_url)
+        })
+        .collect();
+
+      interaction.reply(ResponseContent::Info(&format!("Retrievers:\n{}", lines.join("\n"))))
+    },
+    ConfigCommands::Validate | ConfigCommands::Path | ConfigCommands::Show => {
+      let config = interaction.learner().config.clone();
+      inspect_config(interaction, cmd, &config, config_dir)
+    },
+  }
+}
+
+/// Runs a [`ConfigCommands`] subcommand that only reads `config`, loaded from `config_dir`,
+/// without a [`Learner`].
+pub fn inspect_config<I: UserInteraction>(
+  interaction: &mut I,
+  cmd: ConfigCommands,
+  config: &Config,
+  config_dir: &Path,
+) -> Result<()> {
+  match cmd {
+    ConfigCommands::Path => {
+      let lines: Vec<String> = config_paths(config, config_dir)
+        .into_iter()
+        .map(|(label, path)| format!("{label:<10} {}", path.display()))
+        .collect();
+      interaction.reply(ResponseContent::Text(&lines.join("\n")))
+    },
+    ConfigCommands::Show =>
+      interaction.reply(ResponseContent::Text(toml::to_string_pretty(config)?.trim_end())),
+    ConfigCommands::Validate => validate_config(interaction, config),
+    ConfigCommands::List => Err(LearnerdError::from(LearnerError::Config(
+      "Listing retrievers needs an initialized learner".to_string(),
+    ))),
+  }
+}
+
+/// The locations `config`, loaded from `config_dir`, resolves to, labelled the way
+/// `learner config path` prints them.
+pub fn config_paths(config: &Config, config_dir: &Path) -> Vec<(&'static str, PathBuf)> {
+  vec![
+    ("config", config_dir.join("config.toml")),
+    ("database", config.database_path.clone()),
+    ("storage", config.storage_path.clone()),
+    ("retrievers", config.retrievers_path.clone()),
+  ]
+}
+
+/// Checks every retriever configuration in the retrievers directory of `config`.
+///
+/// This needs no [`Learner`], which would refuse to build over a broken file, so the CLI runs it
+/// straight from the loaded [`Config`]. Each failing file is reported before returning an error.
+pub fn validate_config<I: UserInteraction>(interaction: &mut I, config: &Config) -> Result<()> {
+  let (retriever, errors) = Retriever::new().load_config_dir(&config.retrievers_path)?;
+  let loaded = retriever.names().len();
+  if errors.is_empty() {
+    return interaction.reply(ResponseContent::Success(&format!(
+      "All {loaded} retriever configs in {} are valid",
+      config.retrievers_path.display()
+    )));
+  }
+
+  for (file, error) in &errors {
+    interaction.reply(ResponseContent::Info(&format!("{file}: {}", report(error))))?;
+  }
+  Err(LearnerdError::from(LearnerError::Config(format!(
+    "{} of {} retriever configs in {} failed to load",
+    errors.len(),
+    errors.len() + loaded,
+    config.retrievers_path.display()
+  ))))
+}
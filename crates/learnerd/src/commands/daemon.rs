@@ -3,16 +3,29 @@
 use super::*;
 
 /// Function for the [`Commands::Daemon`] in the CLI.
-pub async fn daemon(cmd: DaemonCommands) -> Result<()> {
-  let daemon = daemon::Daemon::new();
+///
+/// The `learner` is required by the commands that run the daemon loop (`start` and `restart`).
+pub async fn daemon(cmd: DaemonCommands, learner: Option<Learner>) -> Result<()> {
+  let mut daemon = daemon::Daemon::new();
+  let learner = || {
+    learner.ok_or_else(|| {
+      LearnerdError::from(LearnerError::Config("Failed to initialize learner".to_string()))
+    })
+  };
 
   match cmd {
-    DaemonCommands::Start => {
+    DaemonCommands::Start { interval } => {
+      daemon.poll_interval = interval;
+      let learner = learner()?;
       println!("{} Starting daemon...", style(INFO_PREFIX).cyan());
-      match daemon.start() {
-        Ok(_) => println!("{} Daemon started successfully", style(SUCCESS_PREFIX).green()),
+      match daemon.start(learner).await {
+        Ok(_) => println!("{} Daemon shut down", style(SUCCESS_PREFIX).green()),
         Err(e) => {
-          println!("{} Failed to start daemon: {}", style(ERROR_PREFIX).yellow(), style(&e).red());
+          println!(
+            "{} Failed to start daemon: {}",
+            style(ERROR_PREFIX).yellow(),
+            style(report(&e)).red()
+          );
           return Err(e);
         },
       }
@@ -22,20 +35,26 @@ pub async fn daemon(cmd: DaemonCommands) -> Result<()> {
       match daemon.stop() {
         Ok(_) => println!("{} Daemon stopped", style(SUCCESS_PREFIX).green()),
         Err(e) => {
-          println!("{} Failed to stop daemon: {}", style(ERROR_PREFIX).yellow(), style(&e).red());
+          println!(
+            "{} Failed to stop daemon: {}",
+            style(ERROR_PREFIX).yellow(),
+            style(report(&e)).red()
+          );
           return Err(e);
         },
       }
     },
-    DaemonCommands::Restart => {
+    DaemonCommands::Restart { interval } => {
+      daemon.poll_interval = interval;
+      let learner = learner()?;
       println!("{} Restarting daemon...", style(INFO_PREFIX).cyan());
-      match daemon.restart() {
-        Ok(_) => println!("{} Daemon restarted successfully", style(SUCCESS_PREFIX).green()),
+      match daemon.restart(learner).await {
+        Ok(_) => println!("{} Daemon shut down", style(SUCCESS_PREFIX).green()),
         Err(e) => {
           println!(
             "{} Failed to restart daemon: {}",
             style(WARNING_PREFIX).yellow(),
-            style(&e).red()
+            style(report(&e)).red()
           );
           return Err(e);
         },
@@ -52,7 +71,7 @@ pub async fn daemon(cmd: DaemonCommands) -> Result<()> {
           println!(
             "{} Failed to install daemon: {}",
             style(WARNING_PREFIX).yellow(),
-            style(&e).red()
+            style(report(&e)).red()
           );
           return Err(e);
         },
@@ -75,7 +94,7 @@ pub async fn daemon(cmd: DaemonCommands) -> Result<()> {
           println!(
             "{} Failed to uninstall daemon: {}",
             style(WARNING_PREFIX).yellow(),
-            style(&e).red()
+            style(report(&e)).red()
           );
           return Err(e);
         },
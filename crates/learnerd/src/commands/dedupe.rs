@@ -0,0 +1,49 @@
+//! Module for abstracting the "dedupe" functionality to the [`learner`] database.
+
+use learner::database::DuplicateReason;
+
+use super::*;
+
+/// Arguments that can be used for the [`Commands::Dedupe`]
+#[derive(Args, Clone, Debug)]
+pub struct DedupeArgs {
+  /// List the likely duplicates without changing anything, with the `learner merge` command
+  /// that would combine each pair
+  #[arg(long, required = true)]
+  pub report: bool,
+}
+
+/// Function for the [`Commands::Dedupe`] in the CLI.
+///
+/// Reports stored papers that look like copies of the same work, by DOI or by title and first
+/// author. Nothing is merged; each cluster comes with the `learner merge` commands to run.
+pub async fn dedupe<I: UserInteraction>(
+  interaction: &mut I,
+  _dedupe_args: DedupeArgs,
+) -> Result<()> {
+  let clusters = interaction.learner().database.find_duplicates().await?;
+  if clusters.is_empty() {
+    return interaction.reply(ResponseContent::Info("No likely duplicates found"));
+  }
+
+  interaction.reply(ResponseContent::Info(&format!(
+    "Found {} cluster(s) of likely duplicates",
+    clusters.len()
+  )))?;
+  for cluster in clusters {
+    let reason = match cluster.reason {
+      DuplicateReason::Doi => "Same DOI",
+      DuplicateReason::TitleAndAuthor => "Same title and first author",
+    };
+    interaction.reply(ResponseContent::Text(&format!("\n{reason}: {}", cluster.key)))?;
+    interaction.reply(ResponseContent::Papers(&cluster.papers))?;
+    let (first, rest) = cluster.papers.split_first().expect("clusters hold at least two papers");
+    for other in rest {
+      interaction.reply(ResponseContent::Text(&format!(
+        "  learner merge {} {}",
+        first.source_identifier, other.source_identifier
+      )))?;
+    }
+  }
+  Ok(())
+}
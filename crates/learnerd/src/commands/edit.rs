@@ -0,0 +1,189 @@
+//! Module for abstracting the "edit" functionality to the [`learner`] database.
+
+use std::{fs, path::Path, process::ExitStatus};
+
+use learner::{
+  database::Update,
+  format::{is_web_url, normalize_doi, sanitize_filename},
+};
+
+use super::*;
+
+/// Arguments that can be used for the [`Commands::Edit`]
+#[derive(Args, Clone, Debug)]
+pub struct EditArgs {
+  /// Paper identifier (arXiv ID, DOI, IACR ID) of a paper in the database
+  pub identifier: String,
+}
+
+/// Function for the [`Commands::Edit`] in the CLI.
+///
+/// Writes the paper's metadata to a temporary TOML file, opens it in `$VISUAL` or `$EDITOR`
+/// (falling back to `vi`) and stores the result once the editor exits successfully.
+pub async fn edit<I: UserInteraction>(interaction: &mut I, edit_args: EditArgs) -> Result<()> {
+  let (source, identifier) =
+    interaction.learner().retriever.sanitize_identifier(&edit_args.identifier)?;
+  let paper =
+    Query::by_source(&source, &identifier).one(&mut interaction.learner().database).await?;
+
+  let original = toml::to_string_pretty(&paper)?;
+  // A uniquely named file, so editing two papers (or one twice) at once can't mix them up. It
+  // is removed when dropped, and keeps the `.toml` suffix for the editor's syntax highlighting.
+  let file = tempfile::Builder::new()
+    .prefix(&format!("learner-edit-{source}-{}-", sanitize_filename(&identifier, 50)))
+    .suffix(".toml")
+    .tempfile_in(std::env::temp_dir())?;
+  fs::write(file.path(), &original)?;
+
+  let status = run_editor(file.path());
+  let edited = fs::read_to_string(file.path());
+  drop(file);
+
+  match status {
+    Ok(status) if status.success() => {},
+    Ok(status) =>
+      return interaction
+        .reply(ResponseContent::Info(&format!("Editor exited with {status}, nothing changed"))),
+    Err(e) => return Err(e),
+  }
+  let edited = edited?;
+  if edited == original {
+    return interaction.reply(ResponseContent::Info("No changes made"));
+  }
+
+  let updated = parse_edit(&paper, &edited)?;
+  Update::paper(&updated).execute(&mut interaction.learner().database).await?;
+  interaction.reply(ResponseContent::Success(&format!("Updated paper: {}", updated.title)))
+}
+
+/// Opens `path` in the user's editor and waits for it to exit.
+fn run_editor(path: &Path) -> Result<ExitStatus> {
+  let editor = std::env::var("VISUAL")
+    .or_else(|_| std::env::var("EDITOR"))
+    .unwrap_or_else(|_| "vi".to_string());
+  // Allow editors configured with arguments, e.g. `code --wait`
+  let mut parts = editor.split_whitespace();
+  let program = parts.next().unwrap_or("vi");
+  Ok(std::process::Command::new(program).args(parts).arg(path).status()?)
+}
+
+/// Parses edited TOML metadata and checks that it can replace `original`.
+///
+/// The edit must deserialize into a [`Paper`], keep its source and identifier (these are how
+/// the paper is found in the database) and keep a non-empty title. A DOI is stored in its
+/// canonical form and the PDF URL must be a web URL; setting either to `""` clears it.
+fn parse_edit(original: &Paper, edited: &str) -> Result<Paper> {
+  let mut paper: Paper =
+    toml::from_str(edited).map_err(|e| LearnerdError::InvalidEdit(e.message().to_string()))?;
+
+  if !paper.same_identity(original) {
+    return Err(LearnerdError::InvalidEdit(
+      "`source` and `source_identifier` cannot be changed".to_string(),
+    ));
+  }
+  if paper.title.trim().is_empty() {
+    return Err(LearnerdError::InvalidEdit("`title` must not be empty".to_string()));
+  }
+
+  paper.doi = match paper.doi.filter(|doi| !doi.trim().is_empty()) {
+    Some(doi) => Some(
+      normalize_doi(&doi)
+        .ok_or_else(|| LearnerdError::InvalidEdit(format!("`doi` is not a DOI: {doi}")))?,
+    ),
+    None => None,
+  };
+  paper.pdf_url = paper.pdf_url.filter(|url| !url.trim().is_empty());
+  if let Some(url) = paper.pdf_url.as_ref().filter(|url| !is_web_url(url)) {
+    return Err(LearnerdError::InvalidEdit(format!("`pdf_url` is not an http(s) URL: {url}")));
+  }
+  Ok(paper)
+}
+
+#[cfg(test)]
+mod tests {
+  use learner::database::Database;
+  use tempfile::tempdir;
+
+  use super::*;
+
+  fn paper() -> Paper {
+    Paper {
+      title:             "Original Title".to_string(),
+      authors:           Vec::new(),
+      abstract_text:     "An abstract".to_string(),
+      publication_date:  Utc::now().into(),
+      source:            "arxiv".to_string(),
+      source_identifier: "2301.00001".to_string(),
+      pdf_url:           None,
+      doi:               None,
+      references:        Vec::new(),
+      keywords:          Vec::new(),
+      version:           None,
+      primary_category:  None,
+      language:          None,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_parse_and_apply_edit() {
+    let dir = tempdir().unwrap();
+    let mut db = Database::open(dir.path().join("test.db")).await.unwrap();
+    let original = paper();
+    Add::paper(&original).execute(&mut db).await.unwrap();
+
+    let blob = toml::to_string_pretty(&original)
+      .unwrap()
+      .replace("Original Title", "Edited Title")
+      .replace("An abstract", "A better abstract");
+    let updated = parse_edit(&original, &blob).unwrap();
+    Update::paper(&updated).execute(&mut db).await.unwrap();
+
+    let stored = Query::by_paper(&original).execute(&mut db).await.unwrap();
+    assert_eq!(stored[0].title, "Edited Title");
+    assert_eq!(stored[0].abstract_text, "A better abstract");
+  }
+
+  #[test]
+  fn test_parse_edit_rejects_invalid_edits() {
+    let original = paper();
+    let blob = toml::to_string_pretty(&original).unwrap();
+
+    let changed_identity = blob.replace("2301.00001", "2301.99999");
+    assert!(matches!(parse_edit(&original, &changed_identity), Err(LearnerdError::InvalidEdit(_))));
+
+    let empty_title = blob.replace("Original Title", " ");
+    assert!(matches!(parse_edit(&original, &empty_title), Err(LearnerdError::InvalidEdit(_))));
+
+    let bad_date = blob
+      .lines()
+      .map(|line| {
+        if line.starts_with("publication_date") {
+          "publication_date = \"yesterday\""
+        } else {
+          line
+        }
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    assert!(matches!(parse_edit(&original, &bad_date), Err(LearnerdError::InvalidEdit(_))));
+
+    let bad_doi = blob.replace("arxiv\"", "arxiv\"\ndoi = \"not a doi\"");
+    assert!(matches!(parse_edit(&original, &bad_doi), Err(LearnerdError::InvalidEdit(_))));
+
+    let bad_url = blob.replace("arxiv\"", "arxiv\"\npdf_url = \"arxiv.org/pdf/2301.00001\"");
+    assert!(matches!(parse_edit(&original, &bad_url), Err(LearnerdError::InvalidEdit(_))));
+  }
+
+  #[test]
+  fn test_parse_edit_normalizes_doi() {
+    let original = paper();
+    let blob = toml::to_string_pretty(&original).unwrap().replace(
+      "source = \"arxiv\"",
+      "source = \"arxiv\"\ndoi = \"https://doi.org/10.48550/arXiv.2301.00001\"\npdf_url = \"\"",
+    );
+
+    let updated = parse_edit(&original, &blob).unwrap();
+    assert_eq!(updated.doi.as_deref(), Some("10.48550/arxiv.2301.00001"));
+    assert_eq!(updated.pdf_url, None);
+  }
+}
@@ -1,5 +1,7 @@
 //! Module for setting up a [`learner`] environment
 
+use std::path::Path;
+
 use super::*;
 
 /// Arguments that can be used for the [`Commands::Add`]
@@ -21,14 +23,17 @@ pub struct InitArgs {
   pub no_default_retrievers: bool,
 }
 
-/// Function for the [`Commands::Init`] in the CLI.
-pub async fn init<I: UserInteraction>(interaction: &mut I, init_args: InitArgs) -> Result<()> {
+/// Function for the [`Commands::Init`] in the CLI, writing the configuration into `config_dir`.
+pub async fn init<I: UserInteraction>(
+  interaction: &mut I,
+  init_args: InitArgs,
+  config_dir: &Path,
+) -> Result<()> {
   let InitArgs { db_path, storage_path, no_default_retrievers } = init_args;
-  // Throughout, assume we are using default config path (`~/.learner`)
 
   // Set database storage location
   let config = if let Some(db_path) = db_path {
-    Config::default().with_database_path(&db_path)
+    Config::default_in(config_dir).with_database_path(&db_path)
   } else if !interaction.confirm(&format!(
     "Would you like to use the default path {:?} for storing the Learner database?",
     Database::default_path(),
@@ -38,7 +43,7 @@ pub async fn init<I: UserInteraction>(interaction: &mut I, init_args: InitArgs)
     ))?;
     return Ok(());
   } else {
-    Config::default()
+    Config::default_in(config_dir)
   };
 
   if config.database_path.exists()
@@ -71,19 +76,17 @@ pub async fn init<I: UserInteraction>(interaction: &mut I, init_args: InitArgs)
   if !no_default_retrievers {
     interaction
       .reply(ResponseContent::Info("Using the default set of retrievers (arXiv, DOI, and DOI)."))?;
-    std::fs::create_dir_all(Config::default_path()?.join("retrievers"))?;
+    std::fs::create_dir_all(&config.retrievers_path)?;
     std::fs::write(config.retrievers_path.join("arxiv.toml"), learner::ARXIV_CONFIG)?;
     std::fs::write(config.retrievers_path.join("doi.toml"), learner::DOI_CONFIG)?;
     std::fs::write(config.retrievers_path.join("iacr.toml"), learner::IACR_CONFIG)?;
   }
   Learner::builder().with_config(config.clone()).build().await?;
-  std::fs::write(Config::default_path()?.join("config.toml"), toml::to_string(&config)?)?;
+  config.save_to(config_dir)?;
   interaction.reply(ResponseContent::Success(&format!(
     "Created Learner configuration with\nConfig path: {:?}\nDatabase path: {:?}\nDocument storage \
      path: {:?}",
-    Config::default_path()?,
-    config.database_path,
-    config.storage_path,
+    config_dir, config.database_path, config.storage_path,
   )))?;
   Ok(())
 }
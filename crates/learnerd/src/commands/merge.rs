@@ -0,0 +1,69 @@
+//! Module for abstracting the "merge" functionality to the [`learner`] database.
+
+use learner::database::Merge;
+
+use super::*;
+
+/// Arguments that can be used for the [`Commands::Merge`]
+#[derive(Args, Clone, Debug)]
+pub struct MergeArgs {
+  /// Identifier of the first paper, kept as the merged paper unless `--prefer` says otherwise
+  pub first: String,
+
+  /// Identifier of the second paper, a duplicate of the first
+  pub second: String,
+
+  /// Keep this paper's source, identifier and field values as the base of the merge
+  #[arg(long)]
+  pub prefer: Option<String>,
+
+  /// Skip confirmation prompts
+  #[arg(long)]
+  pub force: bool,
+}
+
+/// Function for the [`Commands::Merge`] in the CLI.
+///
+/// Combines two stored copies of the same paper, e.g. one added from arXiv and one by DOI,
+/// and deletes the absorbed copy.
+pub async fn merge<I: UserInteraction>(interaction: &mut I, merge_args: MergeArgs) -> Result<()> {
+  let MergeArgs { first, second, prefer, force } = merge_args;
+  let retriever = &interaction.learner().retriever;
+  let first = retriever.sanitize_identifier(&first)?;
+  let second = retriever.sanitize_identifier(&second)?;
+  if first == second {
+    return Err(LearnerdError::InvalidMerge("Cannot merge a paper with itself".to_string()));
+  }
+  let (survivor, absorbed) = match prefer {
+    None => (first, second),
+    Some(prefer) => match retriever.sanitize_identifier(&prefer)? {
+      preferred if preferred == first => (first, second),
+      preferred if preferred == second => (second, first),
+      _ =>
+        return Err(LearnerdError::InvalidMerge(format!(
+          "--prefer {prefer} is not one of the papers being merged"
+        ))),
+    },
+  };
+
+  let database = &mut interaction.learner().database;
+  let survivor = Query::by_source(&survivor.0, &survivor.1).one(database).await?;
+  let absorbed = Query::by_source(&absorbed.0, &absorbed.1).one(database).await?;
+
+  interaction.reply(ResponseContent::Papers(&[survivor.clone(), absorbed.clone()]))?;
+  if !force
+    && !interaction.confirm(&format!(
+      "Merge {}: {} into {}: {}?",
+      absorbed.source, absorbed.source_identifier, survivor.source, survivor.source_identifier
+    ))?
+  {
+    return interaction.reply(ResponseContent::Info("Operation cancelled"));
+  }
+
+  let merged =
+    Merge::papers(&survivor, &absorbed).execute(&mut interaction.learner().database).await?;
+  interaction.reply(ResponseContent::Success(&format!(
+    "Merged into {}: {} ({})",
+    merged.source, merged.source_identifier, merged.title
+  )))
+}
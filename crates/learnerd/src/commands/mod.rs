@@ -6,7 +6,9 @@
 //! - Paper Management
 //!   - Adding papers from various sources (arXiv, DOI, IACR)
 //!   - Searching and filtering papers
-//!   - Removing papers
+//!   - Editing paper metadata
+//!   - Reporting and merging duplicate papers
+//!   - Archiving, restoring and purging papers
 //!
 //! - PDF Management
 //!   - Configurable PDF downloading
@@ -14,6 +16,10 @@
 //!
 //! - System Management
 //!   - Database initialization
+//!   - Configuration inspection
+//!   - Search index maintenance
+//!   - Library statistics and papers missing a document
+//!   - Dead link reports
 //!   - Daemon control
 //!
 //! # Usage
@@ -28,11 +34,39 @@
 //! # Search papers with filters
 //! learner search "quantum" --author "Alice" --before 2023
 //!
-//! # Remove papers with confirmation
+//! # Find papers imported since the start of 2024, whenever they were published
+//! learner search "quantum" --added-since 2024-01
+//!
+//! # Fix up a paper's metadata in $EDITOR
+//! learner edit 2301.07041
+//!
+//! # List papers that look like duplicates, with the merge commands to combine them
+//! learner dedupe --report
+//!
+//! # Merge a paper stored twice, keeping the DOI entry
+//! learner merge 2301.07041 10.48550/arXiv.2301.07041 --prefer 10.48550/arXiv.2301.07041
+//!
+//! # Archive papers with confirmation
 //! learner remove "quantum computing"
 //!
-//! # Remove papers without confirmation
-//! learner remove "quantum computing" --force --remove-pdf
+//! # Archive papers without confirmation, or delete them for good
+//! learner remove "quantum computing" --force
+//! learner remove "quantum computing" --force --purge --remove-pdf
+//!
+//! # Bring archived papers back
+//! learner restore "quantum computing"
+//!
+//! # Normalize stored DOIs and rebuild the search index after a bulk import
+//! learner reindex
+//!
+//! # Count papers per source, per year and by the most prolific authors
+//! learner stats --breakdown
+//!
+//! # List the papers whose PDF has not been downloaded yet
+//! learner stats --missing
+//!
+//! # List stored PDF and source URLs that no longer resolve
+//! learner verify-links
 //! ```
 //!
 //! # Command Organization
@@ -48,21 +82,35 @@
 use super::*;
 
 pub mod add;
+pub mod config;
+pub mod dedupe;
+pub mod edit;
 
 #[cfg(not(target_os = "windows"))] pub mod daemon;
 
 pub mod init;
+pub mod merge;
+pub mod reindex;
 pub mod remove;
+pub mod restore;
 pub mod search;
+pub mod stats;
+pub mod verify_links;
 
 use chrono::{DateTime, Utc};
-use clap::{arg, Args};
-use dialoguer::{Confirm, Input};
+use clap::Args;
+use dialoguer::{Confirm, Input, MultiSelect};
 use interaction::*;
-use learner::database::{Add, Query};
+use learner::{
+  database::{Add, Query},
+  retriever::Retriever,
+};
 
 #[cfg(not(target_os = "windows"))] pub use self::daemon::*;
-pub use self::{add::*, init::*, remove::*, search::*};
+pub use self::{
+  add::*, config::*, dedupe::*, edit::*, init::*, merge::*, reindex::*, remove::*, restore::*,
+  search::*, stats::*, verify_links::*,
+};
 
 /// Available commands for the CLI
 #[derive(Subcommand, Clone)]
@@ -78,12 +126,39 @@ pub enum Commands {
   /// Add a paper to the database by its identifier
   Add(AddArgs),
 
-  /// Remove papers from the database
+  /// Archive papers, or delete them permanently with --purge
   Remove(RemoveArgs),
 
+  /// Restore archived papers
+  Restore(RestoreArgs),
+
   /// Search for papers in the database
   Search(SearchArgs),
 
+  /// Edit a stored paper's metadata in $EDITOR
+  Edit(EditArgs),
+  /// List papers that look like duplicates, by DOI or by title and first author
+  Dedupe(DedupeArgs),
+
+  /// Merge two stored copies of the same paper into one
+  Merge(MergeArgs),
+
+  /// Normalize stored DOIs and rebuild the full-text search index from the stored papers
+  Reindex,
+
+  /// Summarize the library, optionally broken down by source, year and author
+  Stats(StatsArgs),
+
+  /// Check that stored PDF and source URLs still resolve, without changing anything
+  VerifyLinks,
+
+  /// Inspect the current configuration
+  Config {
+    /// Commands for inspecting the configuration
+    #[command(subcommand)]
+    cmd: ConfigCommands,
+  },
+
   #[cfg(not(target_os = "windows"))]
   /// Manage the learnerd daemon
   Daemon {
@@ -112,7 +187,13 @@ impl FromStr for Commands {
 impl Commands {
   /// Parse arguments for the add command
   fn parse_add(args: &[&str]) -> std::result::Result<Self, String> {
-    let mut add_args = AddArgs { identifier: String::new(), pdf: false, no_pdf: false };
+    let mut add_args = AddArgs {
+      identifier: String::new(),
+      pdf:        false,
+      no_pdf:     false,
+      search:     false,
+      source:     None,
+    };
 
     let mut i = 0;
     while i < args.len() {
@@ -150,18 +231,30 @@ impl Commands {
   /// Parse arguments for the remove command
   fn parse_remove(args: &[&str]) -> std::result::Result<Self, String> {
     let mut remove_args = RemoveArgs {
-      query:      String::new(),
-      filter:     SearchFilter { author: None, source: None, before: None },
-      dry_run:    false,
-      force:      false,
-      remove_pdf: false,
-      keep_pdf:   false,
+      query:       String::new(),
+      filter:      SearchFilter {
+        author:      None,
+        affiliation: None,
+        keyword:     None,
+        lang:        None,
+        source:      None,
+        before:      None,
+        added_since: None,
+      },
+      dry_run:     false,
+      purge:       false,
+      interactive: false,
+      force:       false,
+      remove_pdf:  false,
+      keep_pdf:    false,
     };
 
     let mut i = 0;
     while i < args.len() {
       match args[i] {
         "--dry-run" => remove_args.dry_run = true,
+        "--purge" => remove_args.purge = true,
+        "--interactive" | "-i" => remove_args.interactive = true,
         "--force" => remove_args.force = true,
         "--remove-pdf" => {
           if remove_args.keep_pdf {
@@ -182,6 +275,27 @@ impl Commands {
           }
           remove_args.filter.author = Some(args[i].to_string());
         },
+        "--affiliation" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --affiliation".to_string());
+          }
+          remove_args.filter.affiliation = Some(args[i].to_string());
+        },
+        "--keyword" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --keyword".to_string());
+          }
+          remove_args.filter.keyword = Some(args[i].to_string());
+        },
+        "--lang" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --lang".to_string());
+          }
+          remove_args.filter.lang = Some(args[i].to_string());
+        },
         "--source" => {
           i += 1;
           if i >= args.len() {
@@ -196,6 +310,13 @@ impl Commands {
           }
           remove_args.filter.before = Some(args[i].to_string());
         },
+        "--added-since" | "--since" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --added-since".to_string());
+          }
+          remove_args.filter.added_since = Some(args[i].to_string());
+        },
         s if !s.starts_with("--") => {
           if !remove_args.query.is_empty() {
             return Err("Multiple queries specified".to_string());
@@ -210,6 +331,9 @@ impl Commands {
     if remove_args.query.is_empty() {
       return Err("Missing search query".to_string());
     }
+    if remove_args.remove_pdf && !remove_args.purge {
+      return Err("--remove-pdf requires --purge".to_string());
+    }
 
     Ok(Commands::Remove(remove_args))
   }
@@ -219,13 +343,41 @@ impl Commands {
     let mut search_args = SearchArgs {
       query:    String::new(),
       detailed: false,
-      filter:   SearchFilter { author: None, source: None, before: None },
+      limit:    DEFAULT_SEARCH_LIMIT,
+      sort:     None,
+      desc:     false,
+      format:   None,
+      filter:   SearchFilter {
+        author:      None,
+        affiliation: None,
+        keyword:     None,
+        lang:        None,
+        source:      None,
+        before:      None,
+        added_since: None,
+      },
     };
 
     let mut i = 0;
     while i < args.len() {
       match args[i] {
         "--detailed" => search_args.detailed = true,
+        "--desc" => search_args.desc = true,
+        "--limit" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --limit".to_string());
+          }
+          search_args.limit =
+            args[i].parse().map_err(|_| format!("Invalid value for --limit: {}", args[i]))?;
+        },
+        "--sort" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --sort".to_string());
+          }
+          search_args.sort = Some(clap::ValueEnum::from_str(args[i], true)?);
+        },
         "--author" => {
           i += 1;
           if i >= args.len() {
@@ -233,6 +385,27 @@ impl Commands {
           }
           search_args.filter.author = Some(args[i].to_string());
         },
+        "--affiliation" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --affiliation".to_string());
+          }
+          search_args.filter.affiliation = Some(args[i].to_string());
+        },
+        "--keyword" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --keyword".to_string());
+          }
+          search_args.filter.keyword = Some(args[i].to_string());
+        },
+        "--lang" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --lang".to_string());
+          }
+          search_args.filter.lang = Some(args[i].to_string());
+        },
         "--source" => {
           i += 1;
           if i >= args.len() {
@@ -247,6 +420,13 @@ impl Commands {
           }
           search_args.filter.before = Some(args[i].to_string());
         },
+        "--added-since" | "--since" => {
+          i += 1;
+          if i >= args.len() {
+            return Err("Missing value for --added-since".to_string());
+          }
+          search_args.filter.added_since = Some(args[i].to_string());
+        },
         s if !s.starts_with("--") => {
           if !search_args.query.is_empty() {
             return Err("Multiple queries specified".to_string());
@@ -272,11 +452,16 @@ impl Commands {
         "Usage: add <identifier> [--pdf|--no-pdf]\nAdd a paper to the database by its identifier \
          (arXiv ID, DOI, or IACR ID)",
       Commands::Remove(_) =>
-        "Usage: remove <query> [--force] [--dry-run] [--remove-pdf|--keep-pdf] [--author <name>] \
-         [--source <source>] [--before <date>]\nRemove papers matching the query from the database",
+        "Usage: remove <query> [--purge] [--interactive] [--force] [--dry-run] \
+         [--remove-pdf|--keep-pdf] [--author <name>] [--affiliation <name>] [--keyword <keyword>] \
+         [--lang <code>] [--source <source>] [--before <date>] [--added-since <date>]\nArchive \
+         papers matching the query, keeping their PDFs and letting `restore` bring them back. With \
+         --purge, delete them permanently instead, including archived papers, and with \
+         --remove-pdf their PDFs too",
       Commands::Search(_) =>
         "Usage: search <query> [--detailed] [--author <name>] [--source <source>] [--before \
-         <date>]\nSearch for papers in the database",
+         <date>] [--added-since <date>] [--limit <n>] [--sort <title|date|source|added>] \
+         [--desc]\nSearch for papers in the database",
       _ => "Command help not available",
     }
   }
@@ -288,9 +473,31 @@ impl Commands {
   pub fn flags_for_command(command: &str) -> &'static [&'static str] {
     match command {
       "add" => &["--pdf", "--no-pdf"],
-      "remove" =>
-        &["--force", "--dry-run", "--remove-pdf", "--keep-pdf", "--author", "--source", "--before"],
-      "search" => &["--detailed", "--author", "--source", "--before"],
+      "remove" => &[
+        "--purge",
+        "--interactive",
+        "--force",
+        "--dry-run",
+        "--remove-pdf",
+        "--keep-pdf",
+        "--author",
+        "--affiliation",
+        "--keyword",
+        "--lang",
+        "--source",
+        "--before",
+        "--added-since",
+      ],
+      "search" => &[
+        "--detailed",
+        "--author",
+        "--source",
+        "--before",
+        "--added-since",
+        "--limit",
+        "--sort",
+        "--desc",
+      ],
       _ => &[],
     }
   }
@@ -322,6 +529,23 @@ impl UserInteraction for Cli {
     Ok(Input::with_theme(&theme).with_prompt(message).interact_text()?)
   }
 
+  /// Request a multiple choice selection from the user
+  ///
+  /// Displays a checklist of the items and returns the indices the user picked.
+  /// If `accept_defaults` is true, automatically returns no selection without prompting.
+  fn select(&mut self, message: &str, items: &[String]) -> Result<Vec<usize>> {
+    println!("\n{} {}", style(PROMPT_PREFIX).yellow(), style(message).yellow().bold());
+
+    if self.args.accept_defaults {
+      return Ok(Vec::new());
+    }
+
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let mut selection = MultiSelect::with_theme(&theme).items(items).interact()?;
+    selection.sort_unstable();
+    Ok(selection)
+  }
+
   /// Display content to the user
   ///
   /// Handles different types of content with appropriate formatting:
@@ -348,7 +572,7 @@ impl UserInteraction for Cli {
 
           println!("{} {}", style(prefix).cyan(), style(&paper.title).white().bold());
 
-          let continuation = if is_last { "   " } else { CONTINUE_PREFIX };
+          let continuation = if is_last { "   " } else { CONTINUE_PREFIX.as_str() };
           println!(
             "{}Authors: {}",
             style(continuation).cyan(),
@@ -362,7 +586,7 @@ impl UserInteraction for Cli {
           println!("\nTip: Use --author, --source, or --before together to further refine results");
         }
       },
-      ResponseContent::Paper(paper) => {
+      ResponseContent::Paper(paper, document) => {
         println!("{} Paper details:", style(TREE_VERT).cyan());
         println!("{} {}", style(TREE_BRANCH).cyan(), style(&paper.title).white().bold());
 
@@ -373,48 +597,66 @@ impl UserInteraction for Cli {
             .white()
         );
 
-        println!("{}   Abstract:", style(TREE_BRANCH).cyan());
+        if let Some(category) = &paper.primary_category {
+          println!("{}   Category: {}", style(TREE_BRANCH).cyan(), style(category).white());
+        }
 
-        let width = 80;
-        let mut current_line = String::new();
-        for word in paper.abstract_text.split_whitespace() {
-          if current_line.len() + word.len() + 1 > width {
-            println!("{}   {}", style(TREE_VERT).cyan(), style(&current_line).white());
-            current_line.clear();
-          }
-          if !current_line.is_empty() {
-            current_line.push(' ');
-          }
-          current_line.push_str(word);
+        if let Some(language) = &paper.language {
+          println!("{}   Language: {}", style(TREE_BRANCH).cyan(), style(language).white());
         }
-        if !current_line.is_empty() {
-          println!("{}   {}", style(TREE_VERT).cyan(), style(&current_line).white());
+
+        if !paper.keywords.is_empty() {
+          println!(
+            "{}   Keywords: {}",
+            style(TREE_BRANCH).cyan(),
+            style(paper.keywords.join(", ")).white()
+          );
         }
 
+        println!("{}   Abstract:", style(TREE_BRANCH).cyan());
+        println!(
+          "{}   {}",
+          style(TREE_VERT).cyan(),
+          style(paper.abstract_summary(usize::MAX)).white()
+        );
+
         println!(
           "{}   Published: {}",
           style(TREE_BRANCH).cyan(), // Changed to TREE_LEAF
           style(&paper.publication_date).white()
         );
 
+        if let Some(url) = paper.source_url() {
+          println!("{}   URL: {}", style(TREE_BRANCH).cyan(), style(url).blue().underlined());
+        }
+
         // The following don't use tree characters
         if let Some(url) = &paper.pdf_url {
           println!("{}   PDF URL: {}", style(TREE_BRANCH).cyan(), style(url).blue().underlined());
-
-          let pdf_path = self.learner().config.storage_path.join(paper.filename());
-          if pdf_path.exists() {
-            println!(
-              "{}   {} PDF available at:",
-              style(TREE_LEAF).cyan(),
-              style(SUCCESS_PREFIX).green()
-            );
-            println!("      {}", style(pdf_path.display()).white());
-          } else {
-            println!(
+        }
+        if paper.pdf_url.is_some() || document.is_some() {
+          match document {
+            Some(document) if is_present(document) => {
+              println!(
+                "{}   {} PDF available at:",
+                style(TREE_LEAF).cyan(),
+                style(SUCCESS_PREFIX).green()
+              );
+              println!("      {}", style(location(document)).white());
+            },
+            Some(document) => {
+              println!(
+                "{}   {} PDF missing from:",
+                style(TREE_LEAF).cyan(),
+                style(ERROR_PREFIX).yellow()
+              );
+              println!("      {}", style(location(document)).white());
+            },
+            None => println!(
               "{}   {} PDF not downloaded",
               style(TREE_LEAF).cyan(),
               style(ERROR_PREFIX).yellow()
-            );
+            ),
           }
         }
       },
@@ -424,8 +666,9 @@ impl UserInteraction for Cli {
       ResponseContent::Info(message) => {
         println!("{} {}", style(INFO_PREFIX).green(), style(message).white());
       },
+      ResponseContent::Text(text) => println!("{text}"),
       ResponseContent::Error(error) => {
-        println!("{} {}", style(ERROR_PREFIX).red(), style(error).red());
+        println!("{} {}", style(ERROR_PREFIX).red(), style(report(&error)).red());
       },
     }
     Ok(())
@@ -461,3 +704,10 @@ fn parse_date(date_str: &str) -> Result<DateTime<Utc>> {
 
   Ok(parsed.with_timezone(&Utc))
 }
+
+/// Keeps the papers at the given `indices`, in their original order.
+///
+/// Out of range and repeated indices are ignored.
+fn select_papers(papers: Vec<Paper>, indices: &[usize]) -> Vec<Paper> {
+  papers.into_iter().enumerate().filter(|(i, _)| indices.contains(i)).map(|(_, p)| p).collect()
+}
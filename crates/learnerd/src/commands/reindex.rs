@@ -0,0 +1,20 @@
+//! Module for abstracting the "reindex" functionality to the [`learner`] database.
+
+use super::*;
+
+/// Function for the [`Commands::Reindex`] in the CLI.
+///
+/// Rewrites stored DOIs into their canonical form, see [`Database::normalize_dois`], then
+/// rebuilds the full-text search index so papers written around the index triggers become
+/// searchable again.
+///
+/// [`Database::normalize_dois`]: learner::database::Database::normalize_dois
+pub async fn reindex<I: UserInteraction>(interaction: &mut I) -> Result<()> {
+  let normalized = interaction.learner().database.normalize_dois().await?;
+  if normalized > 0 {
+    interaction
+      .reply(ResponseContent::Info(&format!("Normalized the DOIs of {normalized} papers")))?;
+  }
+  let indexed = interaction.learner().database.reindex_fts().await?;
+  interaction.reply(ResponseContent::Success(&format!("Rebuilt search index for {indexed} papers")))
+}
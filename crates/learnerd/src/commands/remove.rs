@@ -1,8 +1,8 @@
 //! Module for abstracting the "remove" functionality to the [`learner`] database.
 
-use std::fs;
+use std::{fs, path::PathBuf};
 
-use learner::database::Remove;
+use learner::database::{Database, DocumentRecord, Remove};
 
 use super::*;
 
@@ -20,46 +20,138 @@ pub struct RemoveArgs {
   #[arg(long)]
   pub dry_run: bool,
 
+  /// Delete papers permanently instead of archiving them, including already archived ones
+  #[arg(long)]
+  pub purge: bool,
+
+  /// Choose which of the matching papers to remove
+  #[arg(long, short)]
+  pub interactive: bool,
+
   /// Skip confirmation prompts
   #[arg(long)]
   pub force: bool,
 
-  /// Remove associated PDFs
-  #[arg(long, group = "pdf_behavior")]
+  /// Remove associated PDFs (archived papers always keep theirs)
+  #[arg(long, group = "pdf_behavior", requires = "purge")]
   pub remove_pdf: bool,
 
-  /// Keep PDFs when removing papers
+  /// Keep PDFs when purging papers
   #[arg(long, group = "pdf_behavior")]
   pub keep_pdf: bool,
 }
 
-// TODO (autoparallel): Address this lint
-#[allow(clippy::too_many_arguments)]
-/// Function for the [`Commands::Remove`] in the CLI.
-pub async fn remove<I: UserInteraction>(
-  interaction: &mut I,
-  remove_args: RemoveArgs,
-) -> Result<()> {
-  let RemoveArgs { query, filter, dry_run, force, remove_pdf, keep_pdf } = remove_args;
+impl RemoveArgs {
+  /// Finds the papers these arguments select, before any interactive selection.
+  ///
+  /// Archived papers are included only when purging, since only those can be removed again.
+  pub async fn matching_papers(&self, db: &mut Database) -> Result<Vec<Paper>> {
+    Ok(scoped(self.filter.query(&self.query)?, self.purge).execute(db).await?)
+  }
+}
+
+/// The stored PDFs a removal would delete, and the disk space they take up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfSummary {
+  /// How many of the PDFs were found on disk
+  pub files:   usize,
+  /// The combined size of the PDFs found on disk, in bytes
+  pub bytes:   u64,
+  /// PDFs recorded in the database that no longer exist on disk
+  pub missing: Vec<PathBuf>,
+}
+
+impl PdfSummary {
+  /// Sums the sizes of the files at `paths`; paths that can't be stat-ed are noted as missing.
+  pub fn from_paths(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+    let mut summary = Self::default();
+    for path in paths {
+      match fs::metadata(&path) {
+        Ok(metadata) if metadata.is_file() => {
+          summary.files += 1;
+          summary.bytes += metadata.len();
+        },
+        _ => summary.missing.push(path),
+      }
+    }
+    summary
+  }
+
+  /// Summarizes the documents recorded for `papers` in the database.
+  pub async fn for_papers(db: &Database, papers: &[Paper]) -> Result<Self> {
+    Ok(Self::from_documents(&recorded_documents(db, papers).await?))
+  }
 
-  // First find matching papers
-  let mut papers = Query::text(&query).execute(&mut interaction.learner().database).await?;
+  /// Summarizes the `documents` found by [`recorded_documents`]; only those on the filesystem
+  /// are counted.
+  pub fn from_documents(documents: &[Option<DocumentRecord>]) -> Self {
+    Self::from_paths(documents.iter().flatten().filter_map(DocumentRecord::path))
+  }
 
-  // Apply filters
-  if let Some(author) = &filter.author {
-    let author_papers =
-      Query::by_author(author).execute(&mut interaction.learner().database).await?;
-    papers.retain(|p| author_papers.contains(p));
+  /// Describes the space freed in a single line, e.g. `2 PDFs, 1.5 MiB`.
+  pub fn describe(&self) -> String {
+    let mut description = format!(
+      "{} PDF{}, {}",
+      self.files,
+      if self.files == 1 { "" } else { "s" },
+      format_size(self.bytes)
+    );
+    if !self.missing.is_empty() {
+      description.push_str(&format!(" ({} recorded PDF(s) missing)", self.missing.len()));
+    }
+    description
   }
+}
 
-  if let Some(source) = &filter.source {
-    papers.retain(|p| p.source == *source);
+/// Looks up the stored document recorded for each of `papers`, in the same order.
+pub async fn recorded_documents(
+  db: &Database,
+  papers: &[Paper],
+) -> Result<Vec<Option<DocumentRecord>>> {
+  let mut documents = Vec::with_capacity(papers.len());
+  for paper in papers {
+    documents.push(db.stored_document(paper).await?);
   }
+  Ok(documents)
+}
 
-  if let Some(date_str) = &filter.before {
-    let date = parse_date(date_str)?;
-    papers.retain(|p| p.publication_date < date);
+/// Whether `document` is still there; documents outside the filesystem are assumed to be.
+pub fn is_present(document: &DocumentRecord) -> bool {
+  document.path().is_none_or(|path| path.exists())
+}
+
+/// Where `document` is, as a path for documents on the filesystem and a URI otherwise.
+pub fn location(document: &DocumentRecord) -> String {
+  document.path().map_or_else(|| document.uri.clone(), |path| path.display().to_string())
+}
+
+/// Formats a byte count with binary units, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
   }
+  format!("{size:.1} {}", UNITS[unit])
+}
+
+// TODO (autoparallel): Address this lint
+#[allow(clippy::too_many_arguments)]
+/// Function for the [`Commands::Remove`] in the CLI.
+///
+/// Papers are archived so `learner restore` can bring them back, unless `--purge` is given.
+pub async fn remove<I: UserInteraction>(
+  interaction: &mut I,
+  remove_args: RemoveArgs,
+) -> Result<()> {
+  remove_args.filter.validate(&interaction.learner().retriever)?;
+  let mut papers = remove_args.matching_papers(&mut interaction.learner().database).await?;
+  let RemoveArgs { dry_run, purge, interactive, force, remove_pdf, keep_pdf, .. } = remove_args;
 
   if papers.is_empty() {
     interaction.reply(ResponseContent::Info("No papers found matching criteria"))?;
@@ -69,62 +161,185 @@ pub async fn remove<I: UserInteraction>(
   // Show matching papers and their PDF status
   interaction.reply(ResponseContent::Papers(&papers))?;
 
-  // For dry run, stop here
+  if interactive {
+    let items: Vec<String> = papers
+      .iter()
+      .map(|p| format!("{} ({}: {})", p.title, p.source, p.source_identifier))
+      .collect();
+    let selection = interaction.select("Select the papers to remove", &items)?;
+    papers = select_papers(papers, &selection);
+    if papers.is_empty() {
+      interaction.reply(ResponseContent::Info("No papers selected"))?;
+      return Ok(());
+    }
+  }
+
+  // Find the recorded documents now, since purging drops the records of where they are
+  let documents = if purge {
+    recorded_documents(&interaction.learner().database, &papers).await?
+  } else {
+    vec![None; papers.len()]
+  };
+
+  // For dry run, report what would happen to the PDFs and stop before anything is deleted
   if dry_run {
-    interaction
-      .reply(ResponseContent::Info(&format!("Dry run: would remove {} papers", papers.len())))?;
+    for document in documents.iter().flatten().filter(|document| is_present(document)) {
+      let action = if remove_pdf {
+        "would be removed"
+      } else if keep_pdf {
+        "would be kept"
+      } else {
+        "would be removed if confirmed"
+      };
+      interaction.reply(ResponseContent::Info(&format!("PDF {action}: {}", location(document))))?;
+    }
+    if purge && remove_pdf {
+      let pdfs = PdfSummary::from_documents(&documents);
+      report_missing_pdfs(interaction, &pdfs)?;
+      interaction.reply(ResponseContent::Info(&format!("Would free {}", pdfs.describe())))?;
+    }
+    interaction.reply(ResponseContent::Info(&format!(
+      "Dry run: would {} {} papers, nothing deleted.",
+      if purge { "purge" } else { "archive" },
+      papers.len()
+    )))?;
     return Ok(());
   }
 
-  // TODO: This is absurd with alloc... lol
-  let reply: String;
-  if !force
-    && !interaction.confirm(if papers.len() == 1 {
-      reply = "Are you sure you want to remove this paper?".to_string();
-      &reply
-    } else {
-      reply = format!("Are you sure you want to remove these {} papers?", papers.len());
-      &reply
-    })?
-  {
+  // Note the space the PDFs take up before asking, since the files are deleted along with them
+  let pdfs = if purge && remove_pdf && !force {
+    let pdfs = PdfSummary::from_documents(&documents);
+    report_missing_pdfs(interaction, &pdfs)?;
+    Some(pdfs)
+  } else {
+    None
+  };
+
+  let mut reply = if papers.len() == 1 {
+    "Are you sure you want to remove this paper?".to_string()
+  } else {
+    format!("Are you sure you want to remove these {} papers?", papers.len())
+  };
+  if let Some(pdfs) = &pdfs {
+    reply.push_str(&format!(" This frees {}.", pdfs.describe()));
+  }
+  if !force && !interaction.confirm(&reply)? {
     interaction.reply(ResponseContent::Info("Operation cancelled"))?;
     return Ok(());
   }
 
-  // Determine PDF handling
-  let should_remove_pdfs = if remove_pdf {
+  // Determine PDF handling; archived papers keep their PDFs so they can be restored
+  let should_remove_pdfs = if !purge {
+    false
+  } else if remove_pdf {
     true
   } else if keep_pdf {
     false
   } else {
-    let storage_path = &interaction.learner().config.storage_path;
-    let has_pdfs = papers.iter().any(|p| storage_path.join(p.filename()).exists());
-    has_pdfs && interaction.confirm("Do you also want to remove associated PDFs?")?
+    let has_pdfs = documents.iter().flatten().any(is_present);
+    has_pdfs && {
+      let pdfs = PdfSummary::from_documents(&documents);
+      report_missing_pdfs(interaction, &pdfs)?;
+      interaction.confirm(&format!(
+        "Do you also want to remove associated PDFs? This frees {}.",
+        pdfs.describe()
+      ))?
+    }
   };
 
   // Remove papers and optionally their PDFs
-  for paper in &papers {
-    // Remove paper from database
-    if let Err(e) = Remove::by_source(&paper.source, &paper.source_identifier)
-      .execute(&mut interaction.learner().database)
-      .await
-    {
+  for (paper, document) in papers.iter().zip(documents) {
+    // Archive or purge paper from database
+    let remove = Remove::by_source(&paper.source, &paper.source_identifier);
+    let remove = if purge { remove.purge() } else { remove };
+    if let Err(e) = remove.execute(&mut interaction.learner().database).await {
       interaction.reply(ResponseContent::Error(e.into()))?;
       continue;
     }
 
-    interaction.reply(ResponseContent::Success(&format!("Removed paper: {}", paper.title)))?;
+    interaction.reply(ResponseContent::Success(&if purge {
+      format!("Removed paper: {}", paper.title)
+    } else {
+      format!("Archived paper: {} (undo with `learner restore`)", paper.title)
+    }))?;
 
     // Handle PDF removal if requested
-    if should_remove_pdfs {
-      let pdf_path = interaction.learner().config.storage_path.join(paper.filename());
-      if pdf_path.exists() {
-        fs::remove_file(&pdf_path)?;
-        interaction
-          .reply(ResponseContent::Success(&format!("Removed PDF: {}", pdf_path.display())))?;
-      }
+    if let Some(document) = document.filter(|document| should_remove_pdfs && is_present(document)) {
+      interaction.learner().database.delete_document(&document).await?;
+      interaction
+        .reply(ResponseContent::Success(&format!("Removed PDF: {}", location(&document))))?;
     }
   }
 
   Ok(())
 }
+
+/// Notes each recorded PDF that is missing from disk, since it frees no space.
+fn report_missing_pdfs<I: UserInteraction>(interaction: &mut I, pdfs: &PdfSummary) -> Result<()> {
+  for path in &pdfs.missing {
+    interaction
+      .reply(ResponseContent::Info(&format!("PDF missing, nothing to free: {}", path.display())))?;
+  }
+  Ok(())
+}
+
+/// Widens `query` to archived papers when purging, since those can be purged too.
+fn scoped(query: Query<'_>, purge: bool) -> Query<'_> {
+  if purge {
+    query.include_archived()
+  } else {
+    query
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn paper(title: &str) -> Paper {
+    Paper {
+      title:             title.to_string(),
+      authors:           Vec::new(),
+      abstract_text:     String::new(),
+      publication_date:  Utc::now().into(),
+      source:            "arxiv".to_string(),
+      source_identifier: title.to_string(),
+      pdf_url:           None,
+      doi:               None,
+      references:        Vec::new(),
+      keywords:          Vec::new(),
+      version:           None,
+      primary_category:  None,
+      language:          None,
+    }
+  }
+
+  #[test]
+  fn test_pdf_summary_sums_file_sizes() {
+    let dir = tempfile::tempdir().unwrap();
+    let small = dir.path().join("small.pdf");
+    let large = dir.path().join("large.pdf");
+    fs::write(&small, vec![0; 100]).unwrap();
+    fs::write(&large, vec![0; 3 * 1024 * 1024 / 2]).unwrap();
+    let missing = dir.path().join("missing.pdf");
+
+    let summary = PdfSummary::from_paths([small, large, missing.clone()]);
+    assert_eq!(summary.files, 2);
+    assert_eq!(summary.bytes, 100 + 3 * 1024 * 1024 / 2);
+    assert_eq!(summary.missing, vec![missing]);
+    assert_eq!(summary.describe(), "2 PDFs, 1.5 MiB (1 recorded PDF(s) missing)");
+
+    assert_eq!(PdfSummary::from_paths([]).describe(), "0 PDFs, 0 B");
+    assert_eq!(format_size(2048), "2.0 KiB");
+  }
+
+  #[test]
+  fn test_select_papers_maps_indices() {
+    let papers: Vec<_> = ["a", "b", "c", "d"].into_iter().map(paper).collect();
+
+    let titles = |papers: Vec<Paper>| papers.into_iter().map(|p| p.title).collect::<Vec<_>>();
+    assert_eq!(titles(select_papers(papers.clone(), &[2, 0])), vec!["a", "c"]);
+    assert_eq!(titles(select_papers(papers.clone(), &[1, 1, 7])), vec!["b"]);
+    assert!(select_papers(papers, &[]).is_empty());
+  }
+}
@@ -0,0 +1,32 @@
+//! Module for abstracting the "restore" functionality to the [`learner`] database.
+
+use learner::database::Restore;
+
+use super::*;
+
+/// Arguments that can be used for the [`Commands::Restore`]
+#[derive(Args, Clone, Debug)]
+pub struct RestoreArgs {
+  /// Search terms matching the archived papers to restore
+  pub query: String,
+}
+
+/// Function for the [`Commands::Restore`] in the CLI.
+///
+/// Brings back papers archived by [`Commands::Remove`]; purged papers are gone for good.
+pub async fn restore<I: UserInteraction>(
+  interaction: &mut I,
+  restore_args: RestoreArgs,
+) -> Result<()> {
+  let restored = Restore::from_query(Query::text(&restore_args.query))
+    .execute(&mut interaction.learner().database)
+    .await?;
+
+  if restored.is_empty() {
+    return interaction.reply(ResponseContent::Info("No archived papers found matching criteria"));
+  }
+  for paper in &restored {
+    interaction.reply(ResponseContent::Success(&format!("Restored paper: {}", paper.title)))?;
+  }
+  Ok(())
+}
@@ -1,7 +1,16 @@
 //! Module for abstracting the "search" functionality to the [`learner`] database.
 
+use clap::ValueEnum;
+use learner::{
+  database::{OrderField, QueryCriteria},
+  format::PaperTemplate,
+};
+
 use super::*;
 
+/// Number of papers `search` shows unless `--limit` says otherwise.
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
 /// Arguments that can be used for the [`Commands::Search`]
 #[derive(Args, Clone)]
 pub struct SearchArgs {
@@ -12,18 +21,73 @@ pub struct SearchArgs {
   #[arg(long)]
   pub detailed: bool,
 
+  /// Maximum number of papers to show, or 0 to show all of them
+  #[arg(long, default_value_t = DEFAULT_SEARCH_LIMIT)]
+  pub limit: usize,
+
+  /// Sort results by this field instead of by search relevance
+  #[arg(long, value_enum)]
+  pub sort: Option<SortField>,
+
+  /// Sort in descending order
+  #[arg(long, requires = "sort")]
+  pub desc: bool,
+
+  /// Print one line per paper from a template, e.g. '{year} {first_author} — {title}'
+  ///
+  /// Placeholders: {title}, {authors}, {first_author}, {year}, {date}, {source},
+  /// {identifier}, {doi}, {version}, {category}, {keywords}, {pdf_url} and {url}.
+  #[arg(long, conflicts_with = "detailed")]
+  pub format: Option<String>,
+
   /// Search filters
   #[command(flatten)]
   pub filter: SearchFilter,
 }
 
+/// Fields search results can be sorted by
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortField {
+  /// Alphabetically by title
+  Title,
+  /// Chronologically by publication date
+  Date,
+  /// By source, then identifier
+  Source,
+  /// By when the paper was added to the library
+  Added,
+}
+
+impl From<SortField> for OrderField {
+  fn from(field: SortField) -> Self {
+    match field {
+      SortField::Title => OrderField::Title,
+      SortField::Date => OrderField::PublicationDate,
+      SortField::Source => OrderField::Source,
+      SortField::Added => OrderField::DateAdded,
+    }
+  }
+}
+
 /// Filter options for paper searches
-#[derive(Args, Clone, Debug)]
+#[derive(Args, Clone, Debug, Default)]
 pub struct SearchFilter {
   /// Filter by author name
   #[arg(long)]
   pub author: Option<String>,
 
+  /// Filter by author affiliation
+  #[arg(long)]
+  pub affiliation: Option<String>,
+
+  /// Filter by subject keyword, e.g. an arXiv category like cs.LG
+  #[arg(long)]
+  pub keyword: Option<String>,
+
+  /// Filter by language, as an ISO 639-1 code like en or de
+  #[arg(long)]
+  pub lang: Option<String>,
+
   /// Filter by paper source (arxiv, doi, iacr)
   #[arg(long)]
   pub source: Option<String>,
@@ -31,6 +95,10 @@ pub struct SearchFilter {
   /// Filter by publication date (YYYY-MM-DD)
   #[arg(long)]
   pub before: Option<String>,
+
+  /// Only papers added to the library on or after this date (YYYY, YYYY-MM or YYYY-MM-DD)
+  #[arg(long, visible_alias = "since")]
+  pub added_since: Option<String>,
   // TODO (autoparallel): Allow for proper scoped searches
   // /// Search only titles
   // #[arg(long, group = "search_scope")]
@@ -41,49 +109,201 @@ pub struct SearchFilter {
   // abstract_only: bool,
 }
 
+impl SearchFilter {
+  /// Checks that the `--source` filter (if any) names a source provided by a loaded retriever.
+  pub fn validate(&self, retriever: &Retriever) -> Result<()> {
+    match &self.source {
+      Some(source) if !retriever.sources().contains(&source.as_str()) =>
+        Err(LearnerdError::from(LearnerError::Config(format!(
+          "Unknown source '{source}', expected one of: {}",
+          retriever.sources().join(", ")
+        )))),
+      _ => Ok(()),
+    }
+  }
+
+  /// Builds the query for the papers matching the search `terms` that also pass every filter.
+  ///
+  /// Commands that act on "papers matching these terms and filters" build their query here, so
+  /// `search`, `remove` and the TUI agree on what matches. Author, affiliation, keyword,
+  /// language and added-since filters match exactly like [`Query::by_author`],
+  /// [`Query::by_affiliation`], [`Query::by_keyword`], [`Query::by_language`] and
+  /// [`Query::added_after`]. Archived papers are left out unless the caller includes them.
+  pub fn query<'a>(&'a self, terms: &'a str) -> Result<Query<'a>> {
+    let before = self.before.as_deref().map(parse_date).transpose()?;
+    let added_since = self.added_since.as_deref().map(parse_date).transpose()?;
+
+    let criteria = [
+      self.author.as_deref().map(QueryCriteria::Author),
+      self.affiliation.as_deref().map(QueryCriteria::Affiliation),
+      self.keyword.as_deref().map(QueryCriteria::Keyword),
+      self.lang.as_deref().map(QueryCriteria::Language),
+      self.source.as_deref().map(QueryCriteria::Source),
+      before.map(QueryCriteria::BeforeDate),
+      added_since.map(QueryCriteria::AddedAfter),
+    ];
+    Ok(criteria.into_iter().flatten().fold(Query::text(terms), Query::and))
+  }
+}
+
 /// Function for the [`Commands::Search`] in the CLI.
 pub async fn search<I: UserInteraction>(
   interaction: &mut I,
   search_args: SearchArgs,
 ) -> Result<()> {
-  let SearchArgs { query, detailed, filter } = search_args;
+  let SearchArgs { query, detailed, limit, sort, desc, format, filter } = search_args;
+  filter.validate(&interaction.learner().retriever)?;
+  let template = format.as_deref().map(PaperTemplate::parse);
+  // Warnings go to stderr so they never end up in templated output piped into a report
+  if let Some(template) = &template {
+    for name in template.unknown_placeholders() {
+      eprintln!("Warning: unknown placeholder {{{name}}} in --format renders empty");
+    }
+  }
 
   // Get initial result set from text search
-  let mut papers = Query::text(&query).execute(&mut interaction.learner().database).await?;
-
-  // Filter by author if specified
-  if let Some(author) = &filter.author {
-    let author_papers =
-      Query::by_author(author).execute(&mut interaction.learner().database).await?;
-    papers.retain(|p| author_papers.contains(p));
+  let mut text_query = filter.query(&query)?.with_snippets();
+  if let Some(field) = sort {
+    text_query = text_query.order_by(field.into());
+    if desc {
+      text_query = text_query.descending();
+    }
+  }
+  // One extra paper is fetched to tell whether anything was cut off
+  if limit > 0 {
+    text_query = text_query.limit(limit + 1);
   }
+  let database = &mut interaction.learner().database;
+  let mut results = text_query.execute_with_snippets(database).await?;
 
-  // Filter by source if specified
-  if let Some(source) = &filter.source {
-    papers.retain(|p| p.source == *source);
+  let truncated = limit > 0 && results.len() > limit;
+  if truncated {
+    results.truncate(limit);
   }
 
-  // Filter by date if specified
-  if let Some(date_str) = &filter.before {
-    let date = parse_date(date_str)?;
-    papers.retain(|p| p.publication_date < date);
+  // Templated output is meant for reports and scripts, so it is printed without decoration
+  if let Some(template) = template {
+    for (paper, _) in &results {
+      interaction.reply(ResponseContent::Text(&template.render(paper)))?;
+    }
+    return Ok(());
   }
 
   interaction.reply(ResponseContent::Info(&format!("Searching for: {}", query)))?;
 
   // Rest of the display logic remains the same
-  if papers.is_empty() {
+  if results.is_empty() {
     interaction.reply(ResponseContent::Info("No papers found matching all criteria"))
   } else {
     if detailed {
-      // Only show detailed view
-      for paper in papers.iter() {
-        interaction.reply(ResponseContent::Paper(paper))?;
+      // Only show detailed view, along with where each paper matched
+      for (paper, snippet) in results.iter() {
+        let document = interaction.learner().database.stored_document(paper).await?;
+        interaction.reply(ResponseContent::Paper(paper, document.as_ref()))?;
+        if let Some(snippet) = snippet {
+          let snippet: String = Query::snippet_segments(snippet)
+            .into_iter()
+            .map(
+              |(text, matched)| {
+                if matched {
+                  style(text).bold().to_string()
+                } else {
+                  text.to_string()
+                }
+              },
+            )
+            .collect();
+          interaction.reply(ResponseContent::Info(&format!("Matched: {snippet}")))?;
+        }
       }
     } else {
       // Show summary view
+      let papers: Vec<_> = results.into_iter().map(|(paper, _)| paper).collect();
       interaction.reply(ResponseContent::Papers(&papers))?;
     }
+    if truncated {
+      interaction.reply(ResponseContent::Info(&format!(
+        "Showing the first {limit} results, use --limit to see more (0 shows all)"
+      )))?;
+    }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use learner::{
+    database::{Database, Remove},
+    resource::Author,
+  };
+  use tempfile::tempdir;
+
+  use super::*;
+
+  fn paper(title: &str, source: &str, author: &str, keyword: &str, date: &str) -> Paper {
+    Paper::builder()
+      .with_title(title)
+      .with_source(source)
+      .with_source_identifier(title)
+      .with_author(Author {
+        name:        author.to_string(),
+        affiliation: None,
+        email:       None,
+        orcid:       None,
+      })
+      .with_keywords(vec![keyword.to_string()])
+      .with_publication_date(parse_date(date).unwrap())
+      .build()
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_filter_query_semantics() {
+    let dir = tempdir().unwrap();
+    let mut db = Database::open(dir.path().join("test.db")).await.unwrap();
+    for paper in [
+      paper("Lattice Sieving", "arxiv", "José Müller", "cs.CR", "2020-05-01"),
+      paper("Lattice Reduction", "doi", "Ada Lovelace", "cs.DS", "2023-01-01"),
+      paper("Lattice Archive", "arxiv", "Jose Muller", "cs.CR", "2019-01-01"),
+    ] {
+      Add::paper(&paper).execute(&mut db).await.unwrap();
+    }
+    Remove::by_source("arxiv", "Lattice Archive").execute(&mut db).await.unwrap();
+
+    let mut matching = async |filter: SearchFilter, archived: bool| {
+      let query = filter.query("Lattice").unwrap();
+      let query = if archived { query.include_archived() } else { query };
+      let mut titles: Vec<_> =
+        query.execute(&mut db).await.unwrap().into_iter().map(|paper| paper.title).collect();
+      titles.sort();
+      titles
+    };
+
+    assert_eq!(matching(SearchFilter::default(), false).await, [
+      "Lattice Reduction",
+      "Lattice Sieving"
+    ]);
+    // Author names match in part and regardless of case and diacritics
+    let author = || SearchFilter { author: Some("muller".to_string()), ..Default::default() };
+    assert_eq!(matching(author(), false).await, ["Lattice Sieving"]);
+    // Archived papers are only included when asked for
+    assert_eq!(matching(author(), true).await, ["Lattice Archive", "Lattice Sieving"]);
+
+    let keyword = SearchFilter { keyword: Some("CS.ds".to_string()), ..Default::default() };
+    assert_eq!(matching(keyword, false).await, ["Lattice Reduction"]);
+    let source = SearchFilter { source: Some("doi".to_string()), ..Default::default() };
+    assert_eq!(matching(source, false).await, ["Lattice Reduction"]);
+    // `--before` is exclusive and accepts a bare year
+    let before = SearchFilter { before: Some("2023".to_string()), ..Default::default() };
+    assert_eq!(matching(before, false).await, ["Lattice Sieving"]);
+    // `--added-since` looks at when papers were stored, so old publications still match
+    let since =
+      |date: &str| SearchFilter { added_since: Some(date.to_string()), ..Default::default() };
+    assert_eq!(matching(since("2021"), false).await, ["Lattice Reduction", "Lattice Sieving"]);
+    assert!(matching(since("2999-01"), false).await.is_empty());
+
+    // Every filter has to match
+    let combined = SearchFilter { before: Some("2020-05-01".to_string()), ..author() };
+    assert!(matching(combined, false).await.is_empty());
+  }
+}
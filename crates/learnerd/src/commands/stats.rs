@@ -0,0 +1,56 @@
+//! Module for abstracting the "stats" functionality to the [`learner`] database.
+
+use super::*;
+
+/// Arguments that can be used for the [`Commands::Stats`]
+#[derive(Args, Clone, Debug)]
+pub struct StatsArgs {
+  /// Also count papers per source and per publication year, and list the top authors
+  #[arg(long)]
+  pub breakdown: bool,
+
+  /// Also list the papers that have no stored document
+  #[arg(long)]
+  pub missing: bool,
+}
+
+/// Function for the [`Commands::Stats`] in the CLI.
+///
+/// Summarizes the library, leaving out archived papers. With `--missing` the papers matched by
+/// [`Query::missing_documents`] are listed too, the same ones the daemon tries to download.
+pub async fn stats<I: UserInteraction>(interaction: &mut I, stats_args: StatsArgs) -> Result<()> {
+  let stats = interaction.learner().stats().await?;
+  interaction.reply(ResponseContent::Info(&format!(
+    "{} papers by {} authors, {} with a stored document",
+    stats.papers, stats.authors, stats.documents
+  )))?;
+  if stats_args.missing {
+    let missing = Query::missing_documents().execute(&mut interaction.learner().database).await?;
+    if missing.is_empty() {
+      interaction.reply(ResponseContent::Info("Every paper has a stored document"))?;
+    } else {
+      interaction.reply(ResponseContent::Info(&format!(
+        "{} papers without a stored document:",
+        missing.len()
+      )))?;
+      interaction.reply(ResponseContent::Papers(&missing))?;
+    }
+  }
+  if !stats_args.breakdown || stats.papers == 0 {
+    return Ok(());
+  }
+
+  let sections = [
+    ("By source", stats.by_source),
+    ("By year", stats.by_year.into_iter().map(|(year, count)| (year.to_string(), count)).collect()),
+    ("Top authors", stats.top_authors),
+  ];
+  for (heading, counts) in sections {
+    interaction.reply(ResponseContent::Text(&format!("\n{heading}:")))?;
+    let width = counts.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    for (label, count) in counts {
+      interaction.reply(ResponseContent::Text(&format!("  {label:<width$}  {count}")))?;
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,35 @@
+//! Module for abstracting the "verify-links" functionality to the [`learner`] database.
+
+use learner::LinkStatus;
+
+use super::*;
+
+/// Function for the [`Commands::VerifyLinks`] in the CLI.
+///
+/// Checks every stored PDF and landing page URL and lists the ones that no longer resolve.
+/// The library is left unchanged.
+pub async fn verify_links<I: UserInteraction>(interaction: &mut I) -> Result<()> {
+  let checks = interaction.learner().verify_links().await?;
+  let dead: Vec<_> = checks.iter().filter(|check| !check.is_live()).collect();
+  if dead.is_empty() {
+    return interaction
+      .reply(ResponseContent::Success(&format!("All {} links resolve", checks.len())));
+  }
+
+  interaction.reply(ResponseContent::Info(&format!(
+    "{} of {} links are dead",
+    dead.len(),
+    checks.len()
+  )))?;
+  for check in dead {
+    let status = match &check.status {
+      LinkStatus::Live(code) | LinkStatus::Dead(code) => code.to_string(),
+      LinkStatus::Unreachable(e) => format!("unreachable: {e}"),
+    };
+    interaction.reply(ResponseContent::Text(&format!(
+      "  {} ({}) {} [{status}]",
+      check.paper.source_identifier, check.paper.source, check.url
+    )))?;
+  }
+  Ok(())
+}
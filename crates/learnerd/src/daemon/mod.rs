@@ -2,7 +2,7 @@
 //!
 //! This module provides functionality for running learnerd as a system service, with support
 //! for both systemd (Linux) and launchd (macOS) environments. The daemon handles background
-//! tasks such as paper monitoring, metadata updates, and system integration.
+//! tasks such as downloading documents for papers that were added without one.
 //!
 //! # Architecture
 //!
@@ -18,6 +18,7 @@
 //! - System service installation and removal
 //! - Structured logging with rotation
 //! - Graceful shutdown handling
+//! - Periodic download of missing paper documents
 //! - Platform-specific service integration
 //!
 //! # Examples
@@ -76,7 +77,8 @@
 //!    - Contextual metadata (thread IDs, source location)
 //!
 //! 2. Graceful shutdown handling:
-//!    - SIGTERM signal handling on Unix systems
+//!    - SIGTERM and SIGINT handling on Unix systems
+//!    - An in-progress download pass is finished before exiting
 //!    - Proper cleanup of PID files and resources
 //!
 //! 3. Error handling:
@@ -87,7 +89,6 @@
 //! # Future Improvements
 //!
 //! - [ ] Implement Windows service support
-//! - [ ] Add configurable monitoring intervals
 //! - [ ] Support for plugins/extensions
 //! - [ ] Health check endpoint
 //! - [ ] Metrics collection
@@ -100,14 +101,16 @@
 //! - [systemd documentation](https://www.freedesktop.org/software/systemd/man/systemd.service.html)
 //! - [launchd documentation](https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html)
 
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, time::Duration};
 
+use learner::database::{Add, Query};
 use nix::{
   sys::signal::{self, Signal},
   unistd::Pid,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info};
+use tokio::signal::unix::{signal as unix_signal, SignalKind};
+use tracing::{debug, error, info, warn};
 use tracing_appender::rolling;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -118,6 +121,9 @@ use super::*;
 #[cfg(target_os = "macos")] pub mod macos;
 #[cfg(target_os = "macos")] pub use macos::*;
 
+/// Default number of seconds between passes looking for missing documents.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 300;
+
 /// Commands available for daemon management through the CLI.
 #[derive(Subcommand, Clone, Copy)]
 pub enum DaemonCommands {
@@ -126,9 +132,13 @@ pub enum DaemonCommands {
   /// This command will:
   /// 1. Create required directories
   /// 2. Initialize logging
-  /// 3. Start the main daemon process
-  /// 4. Create PID file
-  Start,
+  /// 3. Create PID file
+  /// 4. Periodically download documents for papers missing one
+  Start {
+    /// Seconds between passes looking for papers without documents
+    #[arg(long, default_value_t = DEFAULT_POLL_INTERVAL_SECS)]
+    interval: u64,
+  },
   /// Stop a running daemon process.
   ///
   /// This command will:
@@ -140,7 +150,11 @@ pub enum DaemonCommands {
   ///
   /// Equivalent to running `stop` followed by `start` with a 1-second delay
   /// between operations to ensure clean shutdown.
-  Restart,
+  Restart {
+    /// Seconds between passes looking for papers without documents
+    #[arg(long, default_value_t = DEFAULT_POLL_INTERVAL_SECS)]
+    interval: u64,
+  },
   /// Install the daemon as a system service.
   ///
   /// This command will:
@@ -187,27 +201,34 @@ pub struct Daemon {
   ///
   /// This file contains the process ID of the running daemon and is used
   /// for process management and status checks.
-  pub pid_file:    PathBuf,
+  pub pid_file:      PathBuf,
   /// Working directory for the daemon.
   ///
   /// This directory holds runtime data and temporary files. It should be
   /// persistent across daemon restarts.
-  pub working_dir: PathBuf,
+  pub working_dir:   PathBuf,
   /// Directory for log files.
   ///
   /// Contains:
   /// - Daily rotating log files
   /// - stdout/stderr capture
   /// - Debug logs
-  pub log_dir:     PathBuf,
+  pub log_dir:       PathBuf,
+  /// Seconds between passes looking for papers without documents.
+  #[serde(default = "default_poll_interval_secs")]
+  pub poll_interval: u64,
 }
 
+/// Serde default for [`Daemon::poll_interval`].
+fn default_poll_interval_secs() -> u64 { DEFAULT_POLL_INTERVAL_SECS }
+
 impl Default for Daemon {
   fn default() -> Self {
     Self {
-      pid_file:    PathBuf::from(DEFAULT_PID_FILE),
-      working_dir: PathBuf::from(DEFAULT_WORKING_DIR),
-      log_dir:     PathBuf::from(DEFAULT_LOG_DIR),
+      pid_file:      PathBuf::from(DEFAULT_PID_FILE),
+      working_dir:   PathBuf::from(DEFAULT_WORKING_DIR),
+      log_dir:       PathBuf::from(DEFAULT_LOG_DIR),
+      poll_interval: DEFAULT_POLL_INTERVAL_SECS,
     }
   }
 }
@@ -227,15 +248,17 @@ impl Daemon {
   /// Starts the daemon process and initializes logging.
   ///
   /// Sets up daily log rotation and dual logging to both files and system journal.
-  /// Creates required directories if they don't exist.
+  /// Creates required directories if they don't exist, writes the PID file and then runs
+  /// the download loop for `learner` until a shutdown signal arrives.
   ///
   /// # Errors
   ///
   /// Returns `LearnerdErrors` if:
   /// - Directory creation fails
   /// - Log initialization fails
-  /// - Daemon process fails to start
-  pub fn start(&self) -> Result<()> {
+  /// - The PID file cannot be written
+  /// - Signal handlers cannot be installed
+  pub async fn start(&self, learner: Learner) -> Result<()> {
     // Ensure directories exist
     fs::create_dir_all(&self.working_dir)?;
     fs::create_dir_all(&self.log_dir)?;
@@ -269,8 +292,14 @@ impl Daemon {
     info!("Starting learnerd daemon");
     debug!("Using config: {:?}", self);
 
+    fs::write(&self.pid_file, std::process::id().to_string())?;
+
     info!("Daemon started successfully");
-    self.run()
+    let result = self.run(learner).await;
+    if let Err(e) = fs::remove_file(&self.pid_file) {
+      warn!("Failed to remove PID file: {}", e);
+    }
+    result
   }
 
   // TODO (autoparallel): this is actually never really able to be used at the moment.
@@ -320,10 +349,10 @@ impl Daemon {
   /// # Errors
   ///
   /// Returns `LearnerdErrors` if either stop or start operations fail.
-  pub fn restart(&self) -> Result<()> {
+  pub async fn restart(&self, learner: Learner) -> Result<()> {
     self.stop()?;
-    std::thread::sleep(std::time::Duration::from_secs(1));
-    self.start()
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    self.start(learner).await
   }
 
   /// Installs the daemon as a system service using platform-specific mechanisms.
@@ -347,15 +376,50 @@ impl Daemon {
 
   /// Main daemon loop that handles background tasks.
   ///
-  /// Currently implements a basic heartbeat for monitoring.
-  fn run(&self) -> Result<()> {
-    info!("Daemon running");
+  /// Every [`poll_interval`](Daemon::poll_interval) seconds, starting immediately, the
+  /// documents of papers matched by [`Query::missing_documents`] are downloaded. The loop
+  /// returns once SIGTERM or SIGINT is received, finishing any pass already in progress and
+  /// checkpointing the database.
+  async fn run(&self, mut learner: Learner) -> Result<()> {
+    info!("Daemon running, checking for missing documents every {}s", self.poll_interval);
+
+    let mut terminate = unix_signal(SignalKind::terminate())?;
+    let mut interrupt = unix_signal(SignalKind::interrupt())?;
+    let mut interval = tokio::time::interval(Duration::from_secs(self.poll_interval.max(1)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
-    // TODO: Implement actual daemon functionality
     loop {
-      std::thread::sleep(std::time::Duration::from_secs(5));
-      debug!("Daemon heartbeat");
+      tokio::select! {
+        _ = interval.tick() => download_missing_documents(&mut learner).await,
+        _ = terminate.recv() => {
+          info!("Received SIGTERM, shutting down");
+          break;
+        },
+        _ = interrupt.recv() => {
+          info!("Received SIGINT, shutting down");
+          break;
+        },
+      }
     }
+    learner.database.checkpoint().await?;
+    Ok(())
+  }
+}
+
+/// Downloads documents for every stored paper that does not have one yet.
+///
+/// Failures are logged rather than returned so a single unreachable source does not stop the
+/// daemon; failed downloads are recorded and retried on the next pass.
+async fn download_missing_documents(learner: &mut Learner) {
+  if learner.retriever.is_offline() {
+    debug!("Offline mode is enabled, skipping document downloads");
+    return;
+  }
+
+  match Add::documents(Query::missing_documents()).execute(&mut learner.database).await {
+    Ok(added) if added.is_empty() => debug!("No missing documents to download"),
+    Ok(added) => info!("Downloaded {} missing documents", added.len()),
+    Err(e) => warn!("Failed to download missing documents: {}", report(&e)),
   }
 }
 
@@ -369,22 +433,41 @@ mod tests {
   fn setup_test_daemon() -> (Daemon, tempfile::TempDir) {
     let test_dir = tempdir().expect("Failed to create temp directory");
     let daemon = Daemon {
-      pid_file:    test_dir.path().join("test.pid"),
-      working_dir: test_dir.path().join("work"),
-      log_dir:     test_dir.path().join("logs"),
+      pid_file:      test_dir.path().join("test.pid"),
+      working_dir:   test_dir.path().join("work"),
+      log_dir:       test_dir.path().join("logs"),
+      poll_interval: DEFAULT_POLL_INTERVAL_SECS,
     };
     (daemon, test_dir)
   }
 
+  /// An offline learner stored entirely under `dir`.
+  async fn setup_test_learner(dir: &std::path::Path) -> Learner {
+    std::fs::create_dir_all(dir.join("retrievers")).unwrap();
+    let config = Config::default()
+      .with_database_path(&dir.join("learner.db"))
+      .with_retrievers_path(&dir.join("retrievers"))
+      .with_storage_path(&dir.join("papers"));
+    Learner::builder().with_config(config).offline(true).build().await.unwrap()
+  }
+
   #[test]
   fn test_daemon_directory_creation() {
-    let (daemon, _temp) = setup_test_daemon();
+    let (daemon, temp) = setup_test_daemon();
     let daemon_clone = daemon.clone();
+    let learner_dir = temp.path().to_path_buf();
     // Start should create directories
-    let _handle = std::thread::spawn(move || daemon.start());
+    let _handle = std::thread::spawn(move || {
+      let runtime = tokio::runtime::Runtime::new().unwrap();
+      runtime.block_on(async {
+        let learner = setup_test_learner(&learner_dir).await;
+        daemon.start(learner).await
+      })
+    });
     std::thread::sleep(std::time::Duration::from_secs(1));
 
     assert!(daemon_clone.working_dir.exists(), "Working directory should be created");
     assert!(daemon_clone.log_dir.exists(), "Log directory should be created");
+    assert!(daemon_clone.pid_file.exists(), "PID file should be written");
   }
 }
@@ -73,4 +73,12 @@ pub enum LearnerdError {
   /// Error serializing toml
   #[error(transparent)]
   Toml(#[from] toml::ser::Error),
+
+  /// Edited paper metadata that cannot be stored.
+  #[error("Invalid edit: {0}")]
+  InvalidEdit(String),
+
+  /// Papers that cannot be merged.
+  #[error("Invalid merge: {0}")]
+  InvalidMerge(String),
 }
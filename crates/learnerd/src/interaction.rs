@@ -7,6 +7,8 @@
 //! - Response content types for different kinds of output
 //! - A consistent way to handle user input and display feedback
 
+use learner::database::DocumentRecord;
+
 use super::*;
 
 /// Content types for user interaction responses.
@@ -16,8 +18,8 @@ use super::*;
 /// references to avoid unnecessary allocations.
 #[derive(Debug)]
 pub enum ResponseContent<'a> {
-  /// Single paper with its details
-  Paper(&'a Paper),
+  /// Single paper with its details, along with its stored document if it has one
+  Paper(&'a Paper, Option<&'a DocumentRecord>),
   /// Collection of papers (e.g., search results)
   Papers(&'a [Paper]),
   /// Success message
@@ -26,6 +28,8 @@ pub enum ResponseContent<'a> {
   Error(LearnerdError),
   /// Informational message
   Info(&'a str),
+  /// Plain text shown as is, e.g. a line of templated output
+  Text(&'a str),
 }
 
 /// Trait for implementing user interactions.
@@ -34,9 +38,10 @@ pub enum ResponseContent<'a> {
 /// of this trait can provide different ways of interacting with users (CLI, TUI, API)
 /// while maintaining consistent behavior.
 ///
-/// The trait handles three main types of interactions:
+/// The trait handles four main types of interactions:
 /// - Confirmation prompts (`confirm`)
 /// - Text input prompts (`prompt`)
+/// - Multiple choice selections (`select`)
 /// - Content display (`reply`)
 pub trait UserInteraction {
   /// Return back a mutable reference to an underlying `Learner` struct
@@ -64,6 +69,19 @@ pub trait UserInteraction {
   /// Returns the user's input as a String, or an error if the interaction fails.
   fn prompt(&mut self, message: &str) -> Result<String>;
 
+  /// Let the user pick any number of entries from a list.
+  ///
+  /// # Arguments
+  ///
+  /// * `message` - The prompt message to display above the list
+  /// * `items` - The entries to choose from
+  ///
+  /// # Returns
+  ///
+  /// Returns the indices of the chosen entries in ascending order, or an error if the
+  /// interaction fails.
+  fn select(&mut self, message: &str, items: &[String]) -> Result<Vec<usize>>;
+
   /// Display content to the user.
   ///
   /// This method handles formatting and displaying different types of content
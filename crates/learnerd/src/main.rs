@@ -27,9 +27,19 @@
 //! learner search "quantum" --author "Alice" --before 2023
 //! learner search "neural networks" --detailed
 //!
-//! # Remove papers (with confirmation)
-//! learner remove "quantum computing" --remove-pdf
+//! # Archive papers (with confirmation), restore them, or purge them for good
 //! learner remove --author "Smith" --before 2020
+//! learner restore "quantum computing"
+//! learner remove "quantum computing" --purge --remove-pdf
+//!
+//! # Merge a paper added from two sources into one entry
+//! learner merge 2301.07041 10.48550/arXiv.2301.07041
+//!
+//! # List configured retrievers
+//! learner config list
+//!
+//! # Show where the database, documents and configs live
+//! learner config path
 //!
 //! # Daemon management
 //! learner daemon start
@@ -54,6 +64,11 @@
 //!
 //! All paths can be customized during initialization or via command line options.
 //!
+//! The configuration itself lives in `~/.learner`. Point `LEARNER_CONFIG` or `--config` at
+//! another directory to keep separate libraries, e.g. `learner --config ~/.learner-work init`.
+//! Alternatively, name libraries in a `[profiles]` section of `config.toml` and pick one with
+//! `--profile`, e.g. `learner --profile work search "quantum"`.
+//!
 //! The CLI provides:
 //! - Colored output for better readability
 //! - Interactive confirmations for destructive operations
@@ -62,42 +77,79 @@
 
 #![warn(missing_docs, clippy::missing_docs_in_private_items)]
 
-use std::{path::PathBuf, str::FromStr};
+use std::{
+  fmt::{self, Display},
+  path::PathBuf,
+  str::FromStr,
+  sync::atomic::{AtomicBool, Ordering},
+};
 
 use clap::{builder::ArgAction, Parser, Subcommand};
 use console::style;
 use error::LearnerdError;
-use learner::{database::Database, prelude::*, resource::Paper, Config, Learner};
+use learner::{database::Database, error::report, prelude::*, resource::Paper, Config, Learner};
 use tracing_subscriber::EnvFilter;
 
 pub mod commands;
 #[cfg(not(target_os = "windows"))] pub mod daemon;
 pub mod error;
 pub mod interaction;
+#[cfg(test)]
+#[path = "../../learner/tests/mock/mod.rs"]
+mod mock;
 #[cfg(feature = "tui")] pub mod tui;
 
 #[cfg(not(target_os = "windows"))] use crate::daemon::*;
 use crate::{commands::*, error::*};
 
 /// Prefix for information messages
-static INFO_PREFIX: &str = "ℹ ";
+static INFO_PREFIX: Glyph = Glyph::new("ℹ ", "i ");
 /// Prefix for success messages
-static SUCCESS_PREFIX: &str = "✓ ";
+static SUCCESS_PREFIX: Glyph = Glyph::new("✓ ", "+ ");
 /// Prefix for warning messages
 #[cfg(not(target_os = "windows"))]
-static WARNING_PREFIX: &str = "⚠️ ";
+static WARNING_PREFIX: Glyph = Glyph::new("⚠️ ", "! ");
 /// Prefix for error messages
-static ERROR_PREFIX: &str = "✗ ";
+static ERROR_PREFIX: Glyph = Glyph::new("✗ ", "x ");
 /// Prefix for user prompts
-static PROMPT_PREFIX: &str = "❯ ";
+static PROMPT_PREFIX: Glyph = Glyph::new("❯ ", "> ");
 /// Continuation line for tree structure
-static CONTINUE_PREFIX: &str = "│  ";
+static CONTINUE_PREFIX: Glyph = Glyph::new("│  ", "|  ");
 /// Vertical line for tree structure
-static TREE_VERT: &str = "│";
+static TREE_VERT: Glyph = Glyph::new("│", "|");
 /// Branch character for tree structure
-static TREE_BRANCH: &str = "├";
+static TREE_BRANCH: Glyph = Glyph::new("├", "|");
 /// Leaf character for tree structure (end of branch)
-static TREE_LEAF: &str = "└";
+static TREE_LEAF: Glyph = Glyph::new("└", "`");
+/// Whether [`Glyph`]s are printed in their ASCII form, set from `--ascii` at startup
+static ASCII_GLYPHS: AtomicBool = AtomicBool::new(false);
+
+/// An output symbol with a plain ASCII stand-in for terminals that can't render it
+#[derive(Clone, Copy)]
+struct Glyph {
+  /// The symbol printed by default
+  unicode: &'static str,
+  /// The symbol printed under `--ascii`
+  ascii:   &'static str,
+}
+
+impl Glyph {
+  /// Creates a glyph from its Unicode and ASCII forms.
+  const fn new(unicode: &'static str, ascii: &'static str) -> Self { Self { unicode, ascii } }
+
+  /// Returns the form selected for this run.
+  fn as_str(self) -> &'static str {
+    if ASCII_GLYPHS.load(Ordering::Relaxed) {
+      self.ascii
+    } else {
+      self.unicode
+    }
+  }
+}
+
+impl Display for Glyph {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
 
 /// Command line interface configuration and argument parsing
 #[derive(Parser)]
@@ -120,6 +172,27 @@ pub struct CliArgs {
   #[arg(long, short, global = true)]
   path: Option<PathBuf>,
 
+  /// Configuration directory to use instead of `~/.learner`, taking precedence over the
+  /// `LEARNER_CONFIG` environment variable
+  #[arg(long, global = true)]
+  config: Option<PathBuf>,
+
+  /// Library profile from the `[profiles]` section of `config.toml` to work with
+  #[arg(long, global = true, default_value = Config::DEFAULT_PROFILE)]
+  profile: String,
+
+  /// Refuse all network access and only work with the local database
+  #[arg(long, global = true)]
+  offline: bool,
+
+  /// Print without colors or styling, as does setting the `NO_COLOR` environment variable
+  #[arg(long, global = true)]
+  no_color: bool,
+
+  /// Print tree lines and symbols as plain ASCII
+  #[arg(long, global = true)]
+  ascii: bool,
+
   /// The subcommand to execute
   #[command(subcommand)]
   command: Option<Commands>,
@@ -141,6 +214,16 @@ pub struct Cli {
   learner: Option<Learner>,
 }
 
+impl Cli {
+  /// The configuration directory: `--config` if given, otherwise [`Config::default_path`].
+  fn config_dir(&self) -> Result<PathBuf> {
+    match &self.args.config {
+      Some(dir) => Ok(dir.clone()),
+      None => Ok(Config::default_path()?),
+    }
+  }
+}
+
 /// Configures the logging system based on the verbosity level
 ///
 /// # Arguments
@@ -188,7 +271,11 @@ fn setup_logging(verbosity: u8) {
 #[tokio::main]
 async fn main() -> Result<()> {
   let args = CliArgs::parse();
-
+  if args.no_color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+  }
+  ASCII_GLYPHS.store(args.ascii, Ordering::Relaxed);
   let cmd = &mut <CliArgs as clap::CommandFactory>::command();
   if let Some(generator) = args.generator {
     clap_complete::generate(generator, cmd, cmd.get_name().to_string(), &mut std::io::stdout());
@@ -216,21 +303,93 @@ async fn main() -> Result<()> {
   setup_logging(args.verbose);
 
   let mut cli = Cli { args, learner: None };
-  // Initialize learner unless it's an init command
-  if !matches!(command, Commands::Init(_)) {
-    cli.learner = Some(Learner::from_path(Config::default_path()?).await?);
+  // Initialize learner unless it's an init command or a config subcommand that only reads the
+  // config, which must work even when the retriever configs would keep the learner from building
+  if !matches!(command, Commands::Init(_))
+    && !matches!(command, Commands::Config { cmd } if !cmd.needs_learner())
+  {
+    let mut builder =
+      Learner::builder().with_path(cli.config_dir()?).with_profile(&cli.args.profile);
+    if cli.args.offline {
+      builder = builder.offline(true);
+    }
+    cli.learner = Some(builder.build().await?);
+  }
+
+  // The daemon stops on its own signals, once the pass in progress is done
+  #[cfg(not(target_os = "windows"))]
+  if matches!(command, Commands::Daemon { .. }) {
+    return run_command(&mut cli, command).await;
   }
 
+  // On Ctrl-C or SIGTERM, let the database finish the operation in flight and checkpoint it, so
+  // nothing is left only in the write-ahead log. This runs as its own task so that it still
+  // fires while the command blocks on a prompt.
+  let database = cli.learner.as_ref().map(|learner| learner.database.clone());
+  #[cfg(feature = "tui")]
+  let in_tui = matches!(command, Commands::Tui);
+  tokio::spawn(async move {
+    let status = match shutdown_signal().await {
+      Ok(status) => status,
+      Err(e) => {
+        eprintln!("Could not listen for shutdown signals: {e}");
+        return;
+      },
+    };
+    #[cfg(feature = "tui")]
+    if in_tui {
+      let _ = tui::restore_terminal();
+    }
+    if let Some(database) = database {
+      if let Err(e) = database.checkpoint().await {
+        eprintln!("Failed to checkpoint the database: {}", report(&e));
+      }
+    }
+    std::process::exit(status)
+  });
+
+  run_command(&mut cli, command).await
+}
+
+/// Runs `command` with the learner already set up in `cli`, as needed.
+async fn run_command(cli: &mut Cli, command: Commands) -> Result<()> {
   match command {
-    Commands::Init(init_options) => init(&mut cli, init_options).await,
+    Commands::Init(init_options) => {
+      let config_dir = cli.config_dir()?;
+      init(cli, init_options, &config_dir).await
+    },
+    Commands::Add(add_options) if add_options.search => {
+      add_from_search(cli, add_options).await?;
+      Ok(())
+    },
     Commands::Add(add_options) => {
-      add(&mut cli, add_options).await?;
+      add(cli, add_options).await?;
       Ok(())
     },
-    Commands::Remove(remove_options) => remove(&mut cli, remove_options).await,
-    Commands::Search(search_options) => search(&mut cli, search_options).await,
+    Commands::Remove(remove_options) => remove(cli, remove_options).await,
+    Commands::Restore(restore_options) => restore(cli, restore_options).await,
+    Commands::Search(search_options) => search(cli, search_options).await,
+    Commands::Edit(edit_options) => edit(cli, edit_options).await,
+    Commands::Dedupe(dedupe_options) => dedupe(cli, dedupe_options).await,
+    Commands::Merge(merge_options) => merge(cli, merge_options).await,
+    Commands::Reindex => reindex(cli).await,
+    Commands::Stats(stats_options) => stats(cli, stats_options).await,
+    Commands::VerifyLinks => verify_links(cli).await,
+    Commands::Config { cmd } if !cmd.needs_learner() => {
+      // Inspecting the configuration must not write a default `config.toml`
+      let config_dir = cli.config_dir()?;
+      let mut config = Config::read_from(&config_dir)?.with_profile(&cli.args.profile)?;
+      if cli.args.offline {
+        config.offline = true;
+      }
+      inspect_config(cli, cmd, &config, &config_dir)
+    },
+    Commands::Config { cmd } => {
+      let config_dir = cli.config_dir()?;
+      config(cli, cmd, &config_dir).await
+    },
     #[cfg(not(target_os = "windows"))]
-    Commands::Daemon { cmd } => daemon(cmd).await,
+    Commands::Daemon { cmd } => daemon(cmd, cli.learner.take()).await,
     #[cfg(feature = "tui")]
     Commands::Tui =>
       if let Some(learner) = cli.learner.take() {
@@ -240,3 +399,23 @@ async fn main() -> Result<()> {
       },
   }
 }
+
+/// Waits for SIGINT or SIGTERM, returning the exit status shells report for that signal.
+#[cfg(not(target_os = "windows"))]
+async fn shutdown_signal() -> std::io::Result<i32> {
+  use tokio::signal::unix::{signal, SignalKind};
+
+  let mut terminate = signal(SignalKind::terminate())?;
+  let mut interrupt = signal(SignalKind::interrupt())?;
+  Ok(tokio::select! {
+    _ = interrupt.recv() => 130,
+    _ = terminate.recv() => 143,
+  })
+}
+
+/// Waits for Ctrl-C, returning the exit status shells report for it.
+#[cfg(target_os = "windows")]
+async fn shutdown_signal() -> std::io::Result<i32> {
+  tokio::signal::ctrl_c().await?;
+  Ok(130)
+}
@@ -0,0 +1,47 @@
+//! Copying text to the system clipboard through the platform's clipboard tool.
+//!
+//! Like opening PDFs, this shells out to the tools each platform ships rather than talking to
+//! the clipboard directly, so nothing extra is linked in.
+
+use std::{
+  io::Write,
+  process::{Command, Stdio},
+};
+
+/// Clipboard tools to try in order, as a program and its arguments, each reading from stdin.
+#[cfg(target_os = "windows")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["clip"]];
+
+/// Clipboard tools to try in order, as a program and its arguments, each reading from stdin.
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+
+/// Clipboard tools to try in order, as a program and its arguments, each reading from stdin.
+///
+/// `wl-copy` serves Wayland sessions, and `xclip` or `xsel` X11 ones.
+#[cfg(target_os = "linux")]
+const CLIPBOARD_COMMANDS: &[&[&str]] =
+  &[&["wl-copy"], &["xclip", "-selection", "clipboard"], &["xsel", "--clipboard", "--input"]];
+
+/// Copies `text` to the clipboard with the first tool in [`CLIPBOARD_COMMANDS`] that works.
+///
+/// Returns `false` if none of them is installed or they all fail, e.g. without a display.
+pub fn copy(text: &str) -> bool {
+  CLIPBOARD_COMMANDS.iter().any(|command| copy_with(command, text))
+}
+
+/// Pipes `text` into `command`, returning whether it ran and exited successfully.
+fn copy_with(command: &[&str], text: &str) -> bool {
+  let Ok(mut child) = Command::new(command[0])
+    .args(&command[1..])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+  else {
+    return false;
+  };
+  let written =
+    child.stdin.take().is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+  child.wait().is_ok_and(|status| status.success()) && written
+}
@@ -19,6 +19,10 @@
 //! - `→`/`l`: Focus right pane
 //! - `:`: Enter command mode
 //! - `o`: Open PDF (if available)
+//! - `1`-`9`: Open the numbered link from the details pane
+//! - `y`: Copy the paper's citation to the clipboard
+//! - `a`: Fetch the paper's full abstract from its source
+//! - `s`: Switch between sorting by title and by date added, newest first
 //! - `q`: Quit application
 
 use std::io::{self, Stdout};
@@ -30,19 +34,20 @@ use crossterm::{
 };
 use learner::{
   database::{OrderField, Query},
-  format::format_title,
+  format::PaperTemplate,
   Learner,
 };
 use ratatui::{backend::CrosstermBackend, widgets::ListState, Terminal};
 
 use super::*;
 
+mod clipboard;
 mod state;
 mod styles;
 mod ui;
 
 use interaction::{ResponseContent, UserInteraction};
-use state::{DialogType, UIState};
+use state::{index_documents, DialogType, PaperOrder, UIState};
 use ui::UIDrawer;
 
 /// Main TUI application struct that handles the interface and interactions
@@ -59,8 +64,7 @@ impl Tui {
   /// Creates a new TUI instance
   pub async fn new(mut learner: Learner) -> Result<Self> {
     // Get initial paper list
-    let papers =
-      Query::list_all().order_by(OrderField::Title).execute(&mut learner.database).await?;
+    let papers = PaperOrder::default().query().execute(&mut learner.database).await?;
 
     // Setup terminal
     enable_raw_mode()?;
@@ -68,7 +72,10 @@ impl Tui {
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
 
-    Ok(Self { terminal, state: UIState::new(papers), learner })
+    let documents = index_documents(&papers, recorded_documents(&learner.database, &papers).await?);
+    let citation_format = learner.config.citation_format.as_deref().map(PaperTemplate::parse);
+    let state = UIState::new(papers, documents, citation_format);
+    Ok(Self { terminal, state, learner })
   }
 
   /// Runs the TUI main loop
@@ -76,7 +83,15 @@ impl Tui {
     loop {
       if let Some(cmd) = self.state.pending_command.take() {
         if let Err(e) = self.execute_command(cmd).await {
-          self.state.set_status_message(format!("Error: {}", e));
+          self.state.set_status_message(format!("Error: {}", report(&e)));
+        }
+      }
+      if let Some(paper) = self.state.pending_refresh.take() {
+        self.refresh_abstract(&paper).await;
+      }
+      if std::mem::take(&mut self.state.pending_reorder) {
+        if let Err(e) = self.refresh_papers().await {
+          self.state.set_status_message(format!("Error: {}", report(&e)));
         }
       }
       // Draw UI if needed
@@ -110,8 +125,6 @@ impl Tui {
     Ok(())
   }
 
-  // TODO (autoparallel): This is definitely just replicating what these commands do to an extent.
-  // This abstraction isn't good.
   /// Executes a given command from the TUI command prompt
   pub async fn execute_command(&mut self, command: Commands) -> Result<()> {
     match command {
@@ -133,37 +146,19 @@ impl Tui {
       Commands::Remove(args) => {
         // If not forced, show confirmation first
         if !args.force {
-          // Find matching papers
-          let mut matching_papers = Vec::new();
-
-          // Get papers matching query
-          let papers = Query::text(&args.query).execute(&mut self.learner.database).await?;
-
-          // Apply filters if any
-          for paper in papers {
-            if let Some(author) = &args.filter.author {
-              if !paper.authors.iter().any(|a| a.name.contains(author)) {
-                continue;
-              }
-            }
-            if let Some(source) = &args.filter.source {
-              if &paper.source.to_string() != source {
-                continue;
-              }
-            }
-            if let Some(before) = &args.filter.before {
-              if !paper.publication_date.to_string().starts_with(before) {
-                continue;
-              }
-            }
-            matching_papers.push(paper);
-          }
+          // Find the papers `remove` would act on
+          let matching_papers = args.matching_papers(&mut self.learner.database).await?;
 
           if matching_papers.is_empty() {
             self.state.set_status_message("No papers found matching criteria".to_string());
           } else {
-            // Show confirmation dialog
-            self.state.dialog = DialogType::RemoveConfirm { papers: matching_papers, args };
+            // Show confirmation dialog, with the space freed if the PDFs go too
+            let pdfs = if args.purge && args.remove_pdf {
+              Some(PdfSummary::for_papers(&self.learner.database, &matching_papers).await?)
+            } else {
+              None
+            };
+            self.state.dialog = DialogType::RemoveConfirm { papers: matching_papers, args, pdfs };
           }
         } else {
           // Execute removal and show success
@@ -174,19 +169,13 @@ impl Tui {
         }
       },
       Commands::Search(args) => {
-        // Perform the search
-        let mut papers = Query::text(&args.query).execute(&mut self.learner.database).await?;
-
-        // Apply filters if any
-        if let Some(author) = &args.filter.author {
-          papers.retain(|p| p.authors.iter().any(|a| a.name.contains(author)));
-        }
-        if let Some(source) = &args.filter.source {
-          papers.retain(|p| &p.source.to_string() == source);
-        }
-        if let Some(before) = &args.filter.before {
-          papers.retain(|p| p.publication_date.to_string().starts_with(before));
-        }
+        // Perform the search, with the same filters as the `search` command
+        let papers = args
+          .filter
+          .query(&args.query)?
+          .with_snippets()
+          .execute_with_snippets(&mut self.learner.database)
+          .await?;
 
         if papers.is_empty() {
           self.state.set_status_message("No papers found matching criteria".to_string());
@@ -202,10 +191,27 @@ impl Tui {
     Ok(())
   }
 
-  /// Refreshes the list of papers in the TUI
+  /// Fetches the abstract of `paper` from its source, reporting the outcome in the status bar.
+  async fn refresh_abstract(&mut self, paper: &Paper) {
+    let message = match self.learner.refresh_abstract(paper).await {
+      Ok(Some(updated)) => {
+        if let Some(entry) = self.state.papers.iter_mut().find(|p| p.same_identity(&updated)) {
+          *entry = updated;
+        }
+        format!("Updated the abstract of {}", paper.title)
+      },
+      Ok(None) => format!("No newer abstract available for {}", paper.title),
+      Err(e) => format!("Error: {}", report(&e)),
+    };
+    self.state.set_status_message(message);
+  }
+
+  /// Refreshes the list of papers in the TUI, in the selected order
   async fn refresh_papers(&mut self) -> Result<()> {
-    self.state.papers =
-      Query::list_all().order_by(OrderField::Title).execute(&mut self.learner.database).await?;
+    let papers = self.state.order.query().execute(&mut self.learner.database).await?;
+    let documents = recorded_documents(&self.learner.database, &papers).await?;
+    self.state.documents = index_documents(&papers, documents);
+    self.state.papers = papers;
     self.state.needs_redraw = true;
     Ok(())
   }
@@ -228,18 +234,25 @@ impl UserInteraction for Tui {
     Ok(String::new())
   }
 
+  fn select(&mut self, message: &str, _items: &[String]) -> Result<Vec<usize>> {
+    // For now, just show the selection message and select nothing
+    // TODO: Add proper selection dialog
+    self.state.set_status_message(format!("Select: {}", message));
+    Ok(Vec::new())
+  }
+
   fn reply(&mut self, content: ResponseContent) -> Result<()> {
     match content {
       ResponseContent::Success(msg) => {
         self.state.set_status_message(msg.to_string());
       },
       ResponseContent::Error(e) => {
-        self.state.set_status_message(format!("Error: {}", e));
+        self.state.set_status_message(format!("Error: {}", report(&e)));
       },
-      ResponseContent::Info(msg) => {
+      ResponseContent::Info(msg) | ResponseContent::Text(msg) => {
         self.state.set_status_message(msg.to_string());
       },
-      ResponseContent::Paper(paper) => {
+      ResponseContent::Paper(paper, _) => {
         // For now, just show paper title in status
         // TODO: Consider showing in a popup or updating the paper list
         self.state.set_status_message(format!("Paper: {}", paper.title));
@@ -260,3 +273,10 @@ pub async fn run(learner: Learner) -> Result<()> {
   let mut tui = Tui::new(learner).await?;
   tui.run().await
 }
+
+/// Puts the terminal back the way [`run`] found it, for when the TUI is stopped from outside.
+pub fn restore_terminal() -> Result<()> {
+  disable_raw_mode()?;
+  execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+  Ok(())
+}
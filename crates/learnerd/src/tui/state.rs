@@ -10,8 +10,11 @@
 //! The state is designed to be self-contained and manages all user interactions
 //! and view updates through a clean state transition system.
 
+use std::collections::HashMap;
+
 use crossterm::event::KeyCode;
 use event::KeyModifiers;
+use learner::database::DocumentRecord;
 use ratatui::widgets::ListState;
 
 use super::*;
@@ -52,13 +55,15 @@ pub enum DialogType {
     papers: Vec<Paper>,
     /// The additional arguments used for removal
     args:   RemoveArgs,
+    /// The PDFs that will be deleted along with the papers, when `--remove-pdf` is given
+    pdfs:   Option<PdfSummary>,
   },
   /// The results of search made
   SearchResults {
     /// The search term used
     query:    String,
-    /// The papers that match the search
-    papers:   Vec<Paper>,
+    /// The papers that match the search, each with a snippet highlighting the match
+    papers:   Vec<(Paper, Option<String>)>,
     /// Which paper in the list is currently selected
     selected: ListState,
   },
@@ -69,6 +74,82 @@ pub enum DialogType {
   },
 }
 
+/// The order the paper list is shown in, switched with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperOrder {
+  /// Alphabetically by title
+  #[default]
+  Title,
+  /// Most recently added first
+  DateAdded,
+}
+
+impl PaperOrder {
+  /// The order `s` switches to from this one.
+  pub fn next(self) -> Self {
+    match self {
+      PaperOrder::Title => PaperOrder::DateAdded,
+      PaperOrder::DateAdded => PaperOrder::Title,
+    }
+  }
+
+  /// How the order is described in the paper list title.
+  pub fn label(self) -> &'static str {
+    match self {
+      PaperOrder::Title => "by title",
+      PaperOrder::DateAdded => "newest first",
+    }
+  }
+
+  /// The query listing every paper in this order.
+  pub fn query(self) -> Query<'static> {
+    match self {
+      PaperOrder::Title => Query::list_all().order_by(OrderField::Title),
+      PaperOrder::DateAdded => Query::list_all().order_by(OrderField::DateAdded).descending(),
+    }
+  }
+}
+
+/// Most links a paper's details can list, since each is opened with a single digit key.
+pub const MAX_LINKS: usize = 9;
+
+/// Returns the links shown for `paper` in the details pane, as a label and URL each.
+///
+/// These are, when known: the landing page at its source ([`Paper::source_url`]), the DOI
+/// resolver page and the PDF. A URL already listed under another label is left out, so e.g.
+/// a `doi` paper's landing page is listed once.
+pub fn paper_links(paper: &Paper) -> Vec<(&'static str, String)> {
+  let candidates = [
+    ("Page", paper.source_url().map(|url| url.to_string())),
+    ("DOI", paper.doi.as_ref().map(|doi| format!("https://doi.org/{doi}"))),
+    ("PDF", paper.pdf_url.clone()),
+  ];
+  let mut links: Vec<(&'static str, String)> = Vec::new();
+  for (label, url) in candidates {
+    if let Some(url) = url.filter(|url| !links.iter().any(|(_, listed)| listed == url)) {
+      links.push((label, url));
+    }
+  }
+  links.truncate(MAX_LINKS);
+  links
+}
+
+/// Keys the stored documents of `papers`, as found by
+/// [`recorded_documents`](crate::commands::remove::recorded_documents), by each paper's source
+/// and identifier.
+pub fn index_documents(
+  papers: &[Paper],
+  documents: Vec<Option<DocumentRecord>>,
+) -> HashMap<(String, String), DocumentRecord> {
+  papers
+    .iter()
+    .zip(documents)
+    .filter_map(|(paper, document)| {
+      Some(((paper.source.clone(), paper.source_identifier.clone()), document?))
+    })
+    .collect()
+}
+
 /// Maintains the complete state of the terminal interface.
 pub struct UIState {
   /// List of papers from the database
@@ -91,11 +172,26 @@ pub struct UIState {
   pub command_buffer:  CommandBuffer,
   /// The command that is to be executed
   pub pending_command: Option<Commands>,
+  /// A paper whose abstract is to be fetched from its source
+  pub pending_refresh: Option<Paper>,
+  /// The order of the paper list
+  pub order:           PaperOrder,
+  /// Whether the paper list is to be reloaded in [`UIState::order`]
+  pub pending_reorder: bool,
+  /// The stored document of each paper that has one, keyed by source and identifier
+  pub documents:       HashMap<(String, String), DocumentRecord>,
+  /// Template for copied citations, BibTeX when unset
+  pub citation_format: Option<PaperTemplate>,
 }
 
 impl UIState {
-  /// Creates a new UI state with the given papers.
-  pub fn new(papers: Vec<Paper>) -> Self {
+  /// Creates a new UI state with the given papers, their stored documents (see
+  /// [`index_documents`]) and citation format.
+  pub fn new(
+    papers: Vec<Paper>,
+    documents: HashMap<(String, String), DocumentRecord>,
+    citation_format: Option<PaperTemplate>,
+  ) -> Self {
     let mut selected = ListState::default();
     selected.select(Some(0));
     Self {
@@ -109,15 +205,33 @@ impl UIState {
       status_message: None,
       command_buffer: CommandBuffer::new(),
       pending_command: None,
+      pending_refresh: None,
+      order: PaperOrder::default(),
+      pending_reorder: false,
+      documents,
+      citation_format,
     }
   }
 
+  /// Returns the `files` record of the given paper's stored PDF, if it has one.
+  pub fn document(&self, paper: &Paper) -> Option<&DocumentRecord> {
+    self.documents.get(&(paper.source.clone(), paper.source_identifier.clone()))
+  }
+
   /// Sets a status message to display
   pub fn set_status_message(&mut self, message: String) {
     self.status_message = Some(message);
     self.needs_redraw = true;
   }
 
+  /// Returns the citation copied for `paper`, in the configured format or else as BibTeX.
+  pub fn citation(&self, paper: &Paper) -> String {
+    match &self.citation_format {
+      Some(template) => template.render(paper),
+      None => learner::format::bibtex(paper),
+    }
+  }
+
   /// Returns a reference to the currently selected paper.
   ///
   /// Returns None if no paper is selected (should never happen in practice
@@ -131,7 +245,7 @@ impl UIState {
     match &self.dialog {
       DialogType::ExitConfirm => self.handle_exit_dialog(key),
       DialogType::PDFNotFound => self.handle_pdf_not_found_dialog(key),
-      DialogType::CommandInput { .. } => self.handle_command_input(key, modifiers),
+      DialogType::CommandInput => self.handle_command_input(key, modifiers),
       DialogType::RemoveConfirm { .. } => self.handle_remove_confirm(key),
       DialogType::SearchResults { .. } => self.handle_search_results(key),
       DialogType::PDFConfirm { .. } => self.handle_pdf_confirm(key),
@@ -171,13 +285,11 @@ impl UIState {
         KeyCode::Enter => {
           // Find the selected paper in the main list and focus on it
           if let Some(selected_idx) = selected.selected() {
-            let selected_paper = &papers[selected_idx];
+            let (selected_paper, _) = &papers[selected_idx];
 
             // Find this paper in the main list
-            if let Some(main_idx) = self.papers.iter().position(|p| {
-              p.source == selected_paper.source
-                && p.source_identifier == selected_paper.source_identifier
-            }) {
+            if let Some(main_idx) = self.papers.iter().position(|p| p.same_identity(selected_paper))
+            {
               // Focus on the paper in the main list
               self.selected.select(Some(main_idx));
             }
@@ -302,6 +414,8 @@ impl UIState {
             identifier: paper.source_identifier.clone(),
             pdf:        true,
             no_pdf:     false,
+            search:     false,
+            source:     None,
           }));
           self.dialog = DialogType::None;
           self.needs_redraw = true;
@@ -346,7 +460,10 @@ impl UIState {
   /// - Vim-style navigation (h,j,k,l)
   /// - Arrow key navigation
   /// - Pane switching
-  /// - PDF opening
+  /// - PDF and link opening
+  /// - Citation copying
+  /// - Abstract fetching
+  /// - Sorting by title or date added
   /// - Quit command
   fn handle_normal_input(&mut self, key: KeyCode) -> bool {
     match key {
@@ -383,6 +500,31 @@ impl UIState {
         self.handle_open_pdf();
         false
       },
+      KeyCode::Char('b') => {
+        self.handle_open_in_browser();
+        false
+      },
+      KeyCode::Char('y') => {
+        self.handle_copy_citation();
+        false
+      },
+      KeyCode::Char('a') => {
+        if let Some(paper) = self.selected_paper().cloned() {
+          self.set_status_message(format!("Fetching the abstract of {}...", paper.title));
+          self.pending_refresh = Some(paper);
+        }
+        false
+      },
+      KeyCode::Char('s') => {
+        self.order = self.order.next();
+        self.pending_reorder = true;
+        self.set_status_message(format!("Sorting papers {}", self.order.label()));
+        false
+      },
+      KeyCode::Char(digit @ '1'..='9') => {
+        self.handle_open_link(digit as usize - '1' as usize);
+        false
+      },
       KeyCode::Char(':') => {
         self.dialog = DialogType::CommandInput;
         self.needs_redraw = true;
@@ -432,21 +574,53 @@ impl UIState {
 
   /// Attempts to open the selected paper's PDF with the system viewer.
   ///
-  /// Shows an error dialog if the PDF file is not found.
+  /// Shows an error dialog if no PDF is recorded or its file is gone. A PDF stored outside the
+  /// filesystem is opened by URI when it is reachable over HTTP, and otherwise its URI is
+  /// reported in the status bar.
   fn handle_open_pdf(&mut self) {
-    if let Some(paper) = self.selected_paper() {
-      let pdf_path = format!(
-        "{}/{}.pdf",
-        Database::default_storage_path().display(),
-        format_title(&paper.title, Some(50))
-      );
-
-      if std::path::Path::new(&pdf_path).exists() {
-        self.open_pdf_with_system_viewer(&pdf_path);
-      } else {
+    let Some(paper) = self.selected_paper() else { return };
+    match self.document(paper).map(|document| (document.path(), document.uri.clone())) {
+      Some((Some(path), _)) if path.exists() => self.open_with_system_viewer(path.as_os_str()),
+      Some((None, uri)) if uri.starts_with("http://") || uri.starts_with("https://") =>
+        self.open_with_system_viewer(uri.as_ref()),
+      Some((None, uri)) => self.set_status_message(format!("PDF stored at {uri}")),
+      _ => {
         self.dialog = DialogType::PDFNotFound;
         self.needs_redraw = true;
-      }
+      },
+    }
+  }
+
+  /// Opens the selected paper's landing page (see [`Paper::source_url`]) in the browser.
+  ///
+  /// Does nothing for sources without a known landing page.
+  fn handle_open_in_browser(&mut self) {
+    if let Some(url) = self.selected_paper().and_then(Paper::source_url) {
+      self.open_with_system_viewer(url.as_str().as_ref());
+    }
+  }
+
+  /// Opens the selected paper's link at `index` in [`paper_links`] in the browser.
+  ///
+  /// Does nothing if the paper has fewer links.
+  fn handle_open_link(&mut self, index: usize) {
+    if let Some((_, url)) =
+      self.selected_paper().map(paper_links).and_then(|links| links.into_iter().nth(index))
+    {
+      self.open_with_system_viewer(url.as_ref());
+    }
+  }
+
+  /// Copies the selected paper's citation to the clipboard, reporting the outcome in the
+  /// status bar.
+  fn handle_copy_citation(&mut self) {
+    if let Some(paper) = self.selected_paper() {
+      let message = if clipboard::copy(&self.citation(paper)) {
+        format!("Copied citation for {}", paper.title)
+      } else {
+        "No clipboard tool available to copy the citation".to_string()
+      };
+      self.set_status_message(message);
     }
   }
 
@@ -460,22 +634,22 @@ impl UIState {
     self.max_scroll = Some(available_lines.saturating_sub(visible_lines));
   }
 
-  /// Opens a PDF file using the Windows system viewer.
+  /// Opens a file or URL using the Windows system handler.
   #[cfg(target_os = "windows")]
-  fn open_pdf_with_system_viewer(&self, path: &str) {
-    let _ = std::process::Command::new("cmd").args(["/C", "start", "", path]).spawn();
+  fn open_with_system_viewer(&self, target: &std::ffi::OsStr) {
+    let _ = std::process::Command::new("cmd").args(["/C", "start", ""]).arg(target).spawn();
   }
 
-  /// Opens a PDF file using the macOS system viewer.
+  /// Opens a file or URL using the macOS system handler.
   #[cfg(target_os = "macos")]
-  fn open_pdf_with_system_viewer(&self, path: &str) {
-    let _ = std::process::Command::new("open").arg(path).spawn();
+  fn open_with_system_viewer(&self, target: &std::ffi::OsStr) {
+    let _ = std::process::Command::new("open").arg(target).spawn();
   }
 
-  /// Opens a PDF file using the Linux system viewer.
+  /// Opens a file or URL using the Linux system handler.
   #[cfg(target_os = "linux")]
-  fn open_pdf_with_system_viewer(&self, path: &str) {
-    let _ = std::process::Command::new("xdg-open").arg(path).spawn();
+  fn open_with_system_viewer(&self, target: &std::ffi::OsStr) {
+    let _ = std::process::Command::new("xdg-open").arg(target).spawn();
   }
 }
 
@@ -655,3 +829,72 @@ impl CommandBuffer {
     self.error = None;
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn paper(source: &str, identifier: &str) -> Paper {
+    Paper::builder()
+      .with_title("Links")
+      .with_source(source)
+      .with_source_identifier(identifier)
+      .build()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_paper_links() {
+    let mut arxiv = paper("arxiv", "2301.07041");
+    arxiv.doi = Some("10.48550/arxiv.2301.07041".to_string());
+    arxiv.pdf_url = Some("https://arxiv.org/pdf/2301.07041".to_string());
+    assert_eq!(paper_links(&arxiv), [
+      ("Page", "https://arxiv.org/abs/2301.07041".to_string()),
+      ("DOI", "https://doi.org/10.48550/arxiv.2301.07041".to_string()),
+      ("PDF", "https://arxiv.org/pdf/2301.07041".to_string()),
+    ]);
+
+    // A DOI paper's landing page is its DOI page, listed once
+    let mut doi = paper("doi", "10.1145/1327452.1327492");
+    doi.doi = Some("10.1145/1327452.1327492".to_string());
+    assert_eq!(paper_links(&doi), [(
+      "Page",
+      "https://doi.org/10.1145/1327452.1327492".to_string()
+    )]);
+
+    assert!(paper_links(&paper("local", "notes")).is_empty());
+  }
+
+  #[test]
+  fn test_documents_are_looked_up_by_identity() {
+    let papers = vec![paper("arxiv", "2301.07041"), paper("arxiv", "2301.07042")];
+    let record = DocumentRecord {
+      uri:    "memory:///arxiv_2301.07041-2.pdf".to_string(),
+      key:    "arxiv_2301.07041-2.pdf".to_string(),
+      sha256: None,
+    };
+    let state = UIState::new(
+      papers.clone(),
+      index_documents(&papers, vec![Some(record.clone()), None]),
+      None,
+    );
+
+    assert_eq!(state.document(&papers[0]), Some(&record));
+    assert_eq!(state.document(&papers[1]), None);
+  }
+
+  #[test]
+  fn test_sort_key_switches_order() {
+    let mut state = UIState::new(vec![paper("arxiv", "2301.07041")], HashMap::new(), None);
+    assert_eq!(state.order, PaperOrder::Title);
+
+    state.handle_input(KeyCode::Char('s'), KeyModifiers::NONE);
+    assert_eq!(state.order, PaperOrder::DateAdded);
+    assert!(state.pending_reorder);
+
+    state.pending_reorder = false;
+    state.handle_input(KeyCode::Char('s'), KeyModifiers::NONE);
+    assert_eq!(state.order, PaperOrder::Title);
+    assert!(state.pending_reorder);
+  }
+}
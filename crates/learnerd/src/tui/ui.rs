@@ -11,7 +11,6 @@
 //! Layout is handled through constraint-based positioning, ensuring
 //! proper scaling across different terminal sizes.
 
-use learner::format::format_title;
 use ratatui::{
   layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
   style::{Color, Modifier, Style},
@@ -21,7 +20,7 @@ use ratatui::{
 };
 
 use super::{
-  state::{DialogType, FocusedPane, UIState},
+  state::{paper_links, DialogType, FocusedPane, UIState},
   *,
 };
 
@@ -67,8 +66,8 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
       DialogType::ExitConfirm => self.draw_exit_dialog(),
       DialogType::PDFNotFound => self.draw_pdf_not_found_dialog(),
       DialogType::CommandInput => self.draw_command_input(),
-      DialogType::RemoveConfirm { papers, args } =>
-        self.draw_remove_confirm_dialog(&papers.clone(), &args.clone()),
+      DialogType::RemoveConfirm { papers, args, pdfs } =>
+        self.draw_remove_confirm_dialog(&papers.clone(), &args.clone(), &pdfs.clone()),
       DialogType::SearchResults { papers, query, selected } =>
         self.draw_search_results(&papers.clone(), &query.clone(), &selected.clone()),
       DialogType::PDFConfirm { paper } => self.draw_pdf_confirm_dialog(&paper.clone()),
@@ -80,7 +79,12 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
   }
 
   /// Draws the pop up with search results
-  fn draw_search_results(&mut self, papers: &[Paper], query: &str, selected: &ListState) {
+  fn draw_search_results(
+    &mut self,
+    papers: &[(Paper, Option<String>)],
+    query: &str,
+    selected: &ListState,
+  ) {
     // Calculate dialog size based on content
     let width = 60u16.min(self.frame.area().width.saturating_sub(4));
     let height = 20u16.min(self.frame.area().height.saturating_sub(4));
@@ -100,12 +104,13 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
     self.frame.render_widget(Clear, area);
     self.frame.render_widget(block.clone(), area);
 
-    // Create list items for papers
+    // Create list items for papers, with abstracts cut to fit beside the highlight symbol
+    let summary_width = width.saturating_sub(4) as usize;
     let items: Vec<ListItem> = papers
       .iter()
-      .map(|p| {
+      .map(|(p, snippet)| {
         ListItem::new(vec![
-          Line::from(vec![Span::styled(&p.title, Style::default().fg(Color::White))]),
+          Line::from(highlighted_title(&p.title, snippet.as_deref())),
           Line::from(vec![Span::styled(
             format!(
               "Authors: {}",
@@ -113,6 +118,10 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
             ),
             Style::default().fg(Color::DarkGray),
           )]),
+          Line::from(Span::styled(
+            p.abstract_summary(summary_width),
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+          )),
           Line::from(""),
         ])
       })
@@ -145,7 +154,12 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
   }
 
   /// Draws the pop up for removal
-  fn draw_remove_confirm_dialog(&mut self, papers: &[Paper], remove_args: &RemoveArgs) {
+  fn draw_remove_confirm_dialog(
+    &mut self,
+    papers: &[Paper],
+    remove_args: &RemoveArgs,
+    pdfs: &Option<PdfSummary>,
+  ) {
     let mut content = vec![
       Line::from(vec![
         Span::styled(
@@ -173,6 +187,21 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
       )]));
     }
 
+    // Show how much space deleting the PDFs frees, and which recorded ones are already gone
+    if let Some(pdfs) = pdfs {
+      content.push(Line::from(""));
+      content.push(Line::from(vec![
+        Span::styled("Frees ", Style::default().fg(Color::White)),
+        Span::styled(pdfs.describe(), Style::default().fg(Color::Yellow)),
+      ]));
+      for path in pdfs.missing.iter().take(3) {
+        content.push(Line::from(Span::styled(
+          format!("  missing: {}", path.display()),
+          Style::default().fg(Color::DarkGray),
+        )));
+      }
+    }
+
     content.push(Line::from(""));
     content.push(Line::from(vec![
       Span::styled("Press ", styles::HELP),
@@ -324,7 +353,10 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
           .title(Line::from(vec![
             Span::styled("📚 ", Style::default().fg(Color::LightBlue)),
             Span::styled("Papers", styles::TITLE),
-            Span::styled(format!(" ({})", self.state.papers.len()), styles::NORMAL),
+            Span::styled(
+              format!(" ({}, {})", self.state.papers.len(), self.state.order.label()),
+              styles::NORMAL,
+            ),
           ]))
           .borders(Borders::ALL)
           .border_style(styles::border_style(self.state.focused_pane == FocusedPane::List)),
@@ -349,6 +381,21 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
       Span::styled("o", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
       Span::styled(":open", styles::HELP),
       Span::styled(" • ", Style::default().fg(Color::Blue)),
+      Span::styled("b", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
+      Span::styled(":browser", styles::HELP),
+      Span::styled(" • ", Style::default().fg(Color::Blue)),
+      Span::styled("1-9", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
+      Span::styled(":link", styles::HELP),
+      Span::styled(" • ", Style::default().fg(Color::Blue)),
+      Span::styled("y", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
+      Span::styled(":cite", styles::HELP),
+      Span::styled(" • ", Style::default().fg(Color::Blue)),
+      Span::styled("a", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
+      Span::styled(":abstract", styles::HELP),
+      Span::styled(" • ", Style::default().fg(Color::Blue)),
+      Span::styled("s", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
+      Span::styled(":sort", styles::HELP),
+      Span::styled(" • ", Style::default().fg(Color::Blue)),
       Span::styled(":", styles::KEY_HIGHLIGHT.add_modifier(ratatui::style::Modifier::BOLD)),
       Span::styled(":command", styles::HELP),
       Span::styled(" • ", Style::default().fg(Color::Blue)),
@@ -365,18 +412,21 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
   /// - Authors
   /// - Source information
   /// - Abstract (scrollable)
+  /// - Numbered links
   /// - PDF status
   fn draw_paper_details(&mut self, paper: &Paper, area: Rect) {
+    let links = paper_links(paper);
     let chunks = Layout::default()
       .direction(Direction::Vertical)
       .margin(1)
       .constraints([
-        Constraint::Length(3), // Title
-        Constraint::Length(2), // Authors
-        Constraint::Length(2), // Source
-        Constraint::Length(1), // Abstract header
-        Constraint::Min(5),    // Abstract content
-        Constraint::Length(2), // PDF status
+        Constraint::Length(3),                             // Title
+        Constraint::Length(2),                             // Authors
+        Constraint::Length(2),                             // Source
+        Constraint::Length(1),                             // Abstract header
+        Constraint::Min(5),                                // Abstract content
+        Constraint::Length(links.len().max(1) as u16 + 1), // Links
+        Constraint::Length(2),                             // PDF status
       ])
       .split(area);
 
@@ -394,7 +444,8 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
     self.draw_authors(paper, chunks[1]);
     self.draw_source(paper, chunks[2]);
     self.draw_abstract(paper, chunks[3], chunks[4]);
-    self.draw_pdf_status(paper, chunks[5]);
+    self.draw_links(&links, chunks[5]);
+    self.draw_pdf_status(paper, chunks[6]);
 
     if self.state.focused_pane == FocusedPane::Details {
       self.draw_scroll_indicator(area);
@@ -441,14 +492,23 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
   /// - The source type (e.g., "arXiv", "DOI")
   /// - The source-specific identifier in parentheses
   /// - Both source and identifier in light yellow
+  /// - The primary category in brackets, when the source assigns one
   fn draw_source(&mut self, paper: &Paper, area: Rect) {
-    let source = Paragraph::new(Line::from(vec![
+    let mut lines = vec![Line::from(vec![
       Span::styled("Source: ", styles::LABEL),
       Span::styled(paper.source.to_string(), Style::default().fg(Color::LightYellow)),
       Span::raw(" ("),
       Span::styled(&paper.source_identifier, Style::default().fg(Color::LightYellow)),
       Span::raw(")"),
-    ]));
+    ])];
+    if let Some(category) = &paper.primary_category {
+      lines[0].spans.extend([
+        Span::raw(" ["),
+        Span::styled(category.as_str(), Style::default().fg(Color::LightYellow)),
+        Span::raw("]"),
+      ]);
+    }
+    let source = Paragraph::new(lines);
     self.frame.render_widget(source, area);
   }
 
@@ -473,7 +533,7 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
     let abstract_header = Paragraph::new(Span::styled("Abstract:", styles::LABEL));
     self.frame.render_widget(abstract_header, header_area);
 
-    let abstract_text = normalize_whitespace(&paper.abstract_text);
+    let abstract_text = paper.abstract_summary(usize::MAX);
     let lines = calculate_abstract_lines(&abstract_text, content_area);
 
     let abstract_content = Paragraph::new(abstract_text)
@@ -486,34 +546,46 @@ impl<'a, 'b> UIDrawer<'a, 'b> {
     self.frame.render_widget(abstract_content, content_area);
   }
 
+  /// Draws the paper's links from [`paper_links`], numbered by the key that opens each.
+  ///
+  /// Papers without any known link show "none" instead.
+  fn draw_links(&mut self, links: &[(&str, String)], area: Rect) {
+    let mut lines = vec![Line::from(Span::styled("Links:", styles::LABEL))];
+    if links.is_empty() {
+      lines[0].spans.push(Span::styled(" none", styles::HELP));
+    }
+    for (i, (label, url)) in links.iter().enumerate() {
+      lines.push(Line::from(vec![
+        Span::styled(format!("  {} ", i + 1), styles::KEY_HIGHLIGHT.add_modifier(Modifier::BOLD)),
+        Span::styled(format!("{label}: "), styles::NORMAL),
+        Span::styled(url.as_str(), Style::default().fg(Color::LightBlue)),
+      ]));
+    }
+    self.frame.render_widget(Paragraph::new(lines), area);
+  }
+
   /// Draws the PDF availability status.
   ///
-  /// Shows the current status of the paper's PDF:
-  /// - A checkmark (✓) and path in green if the PDF is available
+  /// Shows the current status of the paper's PDF, as recorded in the database:
+  /// - A checkmark (✓) and its path, or URI outside the filesystem, in green if it is stored
+  /// - A cross (✗) and the recorded path in red if the file has since gone missing
   /// - A cross (✗) in red if the PDF is not downloaded
-  /// - The full path where the PDF is/would be stored
   /// - Word wrapping for long paths
   fn draw_pdf_status(&mut self, paper: &Paper, area: Rect) {
-    let pdf_path = format!(
-      "{}/{}.pdf",
-      Database::default_storage_path().display(),
-      format_title(&paper.title, Some(50))
-    );
-    let pdf_exists = std::path::Path::new(&pdf_path).exists();
+    let (text, available) = match self.state.document(paper) {
+      Some(document) => match document.path() {
+        Some(path) if path.exists() => (format!("✓ Available: {}", path.display()), true),
+        Some(path) => (format!("✗ Missing: {}", path.display()), false),
+        None => (format!("✓ Available: {}", document.uri), true),
+      },
+      None => ("✗ Not downloaded".to_string(), false),
+    };
 
     let status = Paragraph::new(Line::from(vec![
       Span::styled("PDF Status: ", styles::LABEL),
       Span::styled(
-        if pdf_exists {
-          format!("✓ Available: {}", pdf_path)
-        } else {
-          "✗ Not downloaded".to_string()
-        },
-        if pdf_exists {
-          Style::default().fg(Color::Green)
-        } else {
-          Style::default().fg(Color::Red)
-        },
+        text,
+        if available { Style::default().fg(Color::Green) } else { Style::default().fg(Color::Red) },
       ),
     ]))
     .wrap(Wrap { trim: true });
@@ -651,12 +723,24 @@ fn create_dialog_box(title: &str, content: &[Line], r: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
-/// Normalizes whitespace in text for consistent display.
+/// Builds the spans for a search result's title, highlighting the matched terms.
 ///
-/// Converts multiple spaces and newlines into single spaces
-/// for clean presentation.
-fn normalize_whitespace(text: &str) -> String {
-  text.split_whitespace().collect::<Vec<_>>().join(" ")
+/// The terms are taken from the result's snippet (see [`Query::with_snippets`]), falling back to
+/// the plain title when there is none.
+fn highlighted_title(title: &str, snippet: Option<&str>) -> Vec<Span<'static>> {
+  let plain = Style::default().fg(Color::White);
+  let Some(snippet) = snippet else {
+    return vec![Span::styled(title.to_string(), plain)];
+  };
+
+  Query::snippet_segments(snippet)
+    .into_iter()
+    .map(|(text, matched)| {
+      let style =
+        if matched { plain.fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { plain };
+      Span::styled(text.to_string(), style)
+    })
+    .collect()
 }
 
 /// Calculates how many lines a text will occupy given a width constraint.
@@ -5,10 +5,20 @@
 use std::path::PathBuf;
 
 use assert_cmd::Command;
+use learner::{
+  database::{Add, Query},
+  prelude::*,
+  resource::{Author, Paper},
+  Config, Learner, Profile,
+};
 use predicates::prelude::*;
 use serial_test::serial;
 use tempfile::tempdir;
 
+use crate::mock::{MockResponse, MockServer};
+
+#[path = "../../learner/tests/mock/mod.rs"] mod mock;
+
 // Helper function to create a clean command instance
 fn learner() -> Command { Command::cargo_bin("learner").unwrap() }
 
@@ -121,3 +131,592 @@ async fn test_basic_paper_workflow() {
 
   dir.close().unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn test_remove_dry_run_leaves_database_unchanged() {
+  let server = MockServer::respond_with(
+    MockResponse::ok(b"%PDF-1.4".to_vec()).with_header("Content-Type", "application/pdf"),
+  )
+  .await;
+
+  let home = tempdir().unwrap();
+  let config_dir = home.path().join(".learner");
+  let config = Config::default()
+    .with_database_path(&home.path().join("learner.db"))
+    .with_storage_path(&home.path().join("papers"))
+    .with_retrievers_path(&config_dir.join("retrievers"));
+  std::fs::create_dir_all(&config.retrievers_path).unwrap();
+  std::fs::write(config_dir.join("config.toml"), toml::to_string(&config).unwrap()).unwrap();
+
+  let paper = Paper {
+    title:             "Dry Run Paper".to_string(),
+    authors:           Vec::new(),
+    abstract_text:     "Nothing to see here".to_string(),
+    publication_date:  chrono::Utc::now().into(),
+    source:            "arxiv".to_string(),
+    source_identifier: "2301.00001".to_string(),
+    pdf_url:           Some(server.url("/paper.pdf")),
+    doi:               None,
+    references:        Vec::new(),
+    keywords:          Vec::new(),
+    version:           None,
+    primary_category:  None,
+    language:          None,
+  };
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  Add::complete(&paper).execute(&mut library.database).await.unwrap();
+  let pdf_path = library.database.document_path(&paper).await.unwrap().unwrap();
+  drop(library);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["remove", "Dry", "--dry-run", "--purge", "--remove-pdf", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Dry Run Paper"))
+    .stdout(predicate::str::contains(format!("PDF would be removed: {}", pdf_path.display())))
+    .stdout(predicate::str::contains("Would free 1 PDF"))
+    .stdout(predicate::str::contains("nothing deleted"));
+
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  let papers = Query::list_all().execute(&mut library.database).await.unwrap();
+  assert_eq!(papers, vec![paper]);
+  assert!(pdf_path.exists());
+}
+
+// Helper to create a paper with the given title and arXiv identifier
+fn paper(title: &str, source_identifier: &str) -> Paper {
+  Paper {
+    title:             title.to_string(),
+    authors:           Vec::new(),
+    abstract_text:     "Nothing to see here".to_string(),
+    publication_date:  chrono::Utc::now().into(),
+    source:            "arxiv".to_string(),
+    source_identifier: source_identifier.to_string(),
+    pdf_url:           None,
+    doi:               None,
+    references:        Vec::new(),
+    keywords:          Vec::new(),
+    version:           None,
+    primary_category:  None,
+    language:          None,
+  }
+}
+
+// Helper to set up a home directory whose default config points at a library holding `papers`.
+// Returns the home directory and the loaded config.
+async fn library_home(papers: &[Paper]) -> (tempfile::TempDir, Config) {
+  let home = tempdir().unwrap();
+  let config_dir = home.path().join(".learner");
+  let config = Config::default()
+    .with_database_path(&home.path().join("learner.db"))
+    .with_storage_path(&home.path().join("papers"))
+    .with_retrievers_path(&config_dir.join("retrievers"));
+  std::fs::create_dir_all(&config.retrievers_path).unwrap();
+  std::fs::write(config_dir.join("config.toml"), toml::to_string(&config).unwrap()).unwrap();
+
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  for paper in papers {
+    Add::paper(paper).execute(&mut library.database).await.unwrap();
+  }
+  (home, config)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_purge_removes_recorded_pdf() {
+  let server = MockServer::respond_with(
+    MockResponse::ok(b"%PDF-1.4 suffixed".to_vec()).with_header("Content-Type", "application/pdf"),
+  )
+  .await;
+  let mut paper = paper("Suffixed Paper", "2301.00001");
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  let (home, config) = library_home(&[]).await;
+  let config_dir = home.path().join(".learner");
+
+  // A different file already holds the paper's filename, so its PDF gets a `-2` suffix
+  let unrelated = config.storage_path.join(paper.filename());
+  std::fs::write(&unrelated, b"%PDF-1.4 unrelated").unwrap();
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  Add::complete(&paper).execute(&mut library.database).await.unwrap();
+  let stored = library.database.document_path(&paper).await.unwrap().unwrap();
+  assert_ne!(stored, unrelated);
+  drop(library);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["remove", "Suffixed", "--purge", "--remove-pdf", "--force"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(format!("Removed PDF: {}", stored.display())));
+
+  assert!(!stored.exists());
+  assert!(unrelated.exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_detailed_reports_recorded_pdf() {
+  let server = MockServer::respond_with(
+    MockResponse::ok(b"%PDF-1.4 suffixed".to_vec()).with_header("Content-Type", "application/pdf"),
+  )
+  .await;
+  let mut paper = paper("Suffixed Paper", "2301.00001");
+  paper.pdf_url = Some(server.url("/paper.pdf"));
+  let (home, config) = library_home(&[]).await;
+  let config_dir = home.path().join(".learner");
+
+  // The filename is taken, so the recorded `-2` key is the only way to find the PDF
+  std::fs::write(config.storage_path.join(paper.filename()), b"%PDF-1.4 unrelated").unwrap();
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  Add::complete(&paper).execute(&mut library.database).await.unwrap();
+  let stored = library.database.document_path(&paper).await.unwrap().unwrap();
+  drop(library);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["search", "Suffixed", "--detailed"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("PDF available at:"))
+    .stdout(predicate::str::contains(stored.display().to_string()));
+
+  std::fs::remove_file(&stored).unwrap();
+  learner()
+    .env("HOME", home.path())
+    .args(["search", "Suffixed", "--detailed"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("PDF missing from:"))
+    .stdout(predicate::str::contains(stored.display().to_string()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_remove_archives_until_restored() {
+  let paper = paper("Archived Paper", "2301.00001");
+  let (home, config) = library_home(std::slice::from_ref(&paper)).await;
+  let config_dir = home.path().join(".learner");
+  let pdf_path = config.storage_path.join(paper.filename());
+  std::fs::write(&pdf_path, b"%PDF").unwrap();
+
+  learner()
+    .env("HOME", home.path())
+    .args(["remove", "Archived", "--force"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Archived paper: Archived Paper"));
+
+  // The paper is hidden but its PDF is kept for when it comes back
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  assert!(Query::list_all().execute(&mut library.database).await.unwrap().is_empty());
+  assert!(pdf_path.exists());
+  drop(library);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["restore", "Archived"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Restored paper: Archived Paper"));
+
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  assert_eq!(Query::list_all().execute(&mut library.database).await.unwrap(), vec![paper]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_merge_prefers_requested_paper() {
+  let arxiv = paper("Duplicated Paper", "2301.00001");
+  let mut doi = paper("Duplicated Paper", "10.1000/dup.1");
+  doi.source = "doi".to_string();
+  doi.doi = Some("10.1000/dup.1".to_string());
+  doi.abstract_text = "A much longer abstract than the arXiv copy has".to_string();
+  let (home, config) = library_home(&[arxiv.clone(), doi.clone()]).await;
+  let config_dir = home.path().join(".learner");
+  std::fs::write(config.retrievers_path.join("arxiv.toml"), learner::ARXIV_CONFIG).unwrap();
+  std::fs::write(config.retrievers_path.join("doi.toml"), learner::DOI_CONFIG).unwrap();
+
+  learner()
+    .env("HOME", home.path())
+    .args(["merge", "2301.00001", "10.1000/dup.1", "--prefer", "10.1000/dup.1", "--force"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Merged into doi: 10.1000/dup.1"));
+
+  let mut library = Learner::builder().with_path(&config_dir).build().await.unwrap();
+  assert_eq!(Query::list_all().execute(&mut library.database).await.unwrap(), vec![doi]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_dedupe_report_suggests_merge() {
+  let arxiv = paper("Duplicated Paper", "2301.00001");
+  let mut doi = paper("Duplicated Paper", "10.1000/dup.1");
+  doi.source = "doi".to_string();
+  let (home, _config) = library_home(&[arxiv, doi]).await;
+
+  learner()
+    .env("HOME", home.path())
+    .args(["dedupe", "--report"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Same title and first author"))
+    .stdout(predicate::str::contains("learner merge 2301.00001 10.1000/dup.1"));
+
+  // The report leaves both copies in place
+  let mut library =
+    Learner::builder().with_path(home.path().join(".learner")).build().await.unwrap();
+  assert_eq!(Query::list_all().execute(&mut library.database).await.unwrap().len(), 2);
+}
+
+// The CLI blocks this thread, so the mock server needs a worker of its own
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn test_verify_links_reports_dead_pdf() {
+  let server = MockServer::respond_with(MockResponse::status(404)).await;
+  let pdf_url = server.url("/gone.pdf");
+  let mut paper = paper("Rotten Paper", "rotten-1");
+  paper.source = "local".to_string();
+  paper.pdf_url = Some(pdf_url.clone());
+  let (home, _config) = library_home(&[paper]).await;
+
+  learner()
+    .env("HOME", home.path())
+    .args(["verify-links"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("1 of 1 links are dead"))
+    .stdout(predicate::str::contains(format!("rotten-1 (local) {pdf_url} [404]")));
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("HEAD", "/gone.pdf"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_flag_selects_library() {
+  let paper = paper("Work Paper", "2301.00001");
+  let (home, config) = library_home(std::slice::from_ref(&paper)).await;
+  let work_dir = home.path().join(".learner");
+
+  // A second, empty library in its own configuration directory
+  let personal_dir = home.path().join("personal");
+  let personal = config.with_database_path(&personal_dir.join("learner.db"));
+  std::fs::create_dir_all(&personal_dir).unwrap();
+  std::fs::write(personal_dir.join("config.toml"), toml::to_string(&personal).unwrap()).unwrap();
+
+  learner()
+    .env("HOME", home.path())
+    .env("LEARNER_CONFIG", &personal_dir)
+    .args(["search", "Work", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No papers found"));
+
+  // The flag wins over the environment variable
+  learner()
+    .env("HOME", home.path())
+    .env("LEARNER_CONFIG", &personal_dir)
+    .args(["--config", work_dir.to_str().unwrap(), "search", "Work", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Work Paper"));
+  learner()
+    .env("HOME", home.path())
+    .args(["--config", personal_dir.to_str().unwrap(), "config", "path", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(personal_dir.join("config.toml").to_str().unwrap()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_profiles_are_independent_libraries() {
+  let (home, mut config) = library_home(&[]).await;
+  let config_dir = home.path().join(".learner");
+  for name in ["work", "personal"] {
+    config.profiles.insert(name.to_string(), Profile {
+      database_path:   home.path().join(name).join("learner.db"),
+      storage_path:    Some(home.path().join(name).join("papers")),
+      retrievers_path: None,
+    });
+  }
+  std::fs::write(config_dir.join("config.toml"), toml::to_string(&config).unwrap()).unwrap();
+
+  let mut work =
+    Learner::builder().with_path(&config_dir).with_profile("work").build().await.unwrap();
+  Add::paper(&paper("Work Paper", "2301.00001")).execute(&mut work.database).await.unwrap();
+  drop(work);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["--profile", "work", "search", "Work", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Work Paper"));
+
+  for profile in ["personal", "default"] {
+    learner()
+      .env("HOME", home.path())
+      .args(["--profile", profile, "search", "Work", "--accept-defaults"])
+      .assert()
+      .success()
+      .stdout(predicate::str::contains("No papers found"));
+  }
+
+  learner()
+    .env("HOME", home.path())
+    .args(["--profile", "play", "search", "Work", "--accept-defaults"])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("Unknown profile"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_applies_limit_and_sort() {
+  let papers = [
+    paper("Beta Lattice Paper", "2301.00002"),
+    paper("Alpha Lattice Paper", "2301.00001"),
+    paper("Gamma Lattice Paper", "2301.00003"),
+  ];
+  let (home, _config) = library_home(&papers).await;
+
+  let output = learner()
+    .env("HOME", home.path())
+    .args(["search", "Lattice", "--sort", "title", "--desc", "--limit", "2", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 2 papers"))
+    .stdout(predicate::str::contains("Showing the first 2 results"))
+    .stdout(predicate::str::contains("Alpha Lattice Paper").not())
+    .get_output()
+    .stdout
+    .clone();
+  let stdout = String::from_utf8(output).unwrap();
+  let gamma = stdout.find("Gamma Lattice Paper").unwrap();
+  let beta = stdout.find("Beta Lattice Paper").unwrap();
+  assert!(gamma < beta, "papers should be sorted by descending title:\n{stdout}");
+
+  // Everything fits within the default limit, so nothing is reported as truncated
+  learner()
+    .env("HOME", home.path())
+    .args(["search", "Lattice", "--accept-defaults"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 3 papers"))
+    .stdout(predicate::str::contains("Showing the first").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_format_prints_one_line_per_paper() {
+  let mut lattice = paper("Lattice Sieving", "2301.00001");
+  lattice.publication_date = "2023-01-02T00:00:00Z".parse().unwrap();
+  lattice.authors = vec![
+    Author {
+      name:        "Ada Lovelace".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    },
+    Author {
+      name:        "Alan Turing".to_string(),
+      affiliation: None,
+      email:       None,
+      orcid:       None,
+    },
+  ];
+  let (home, _config) = library_home(&[lattice]).await;
+
+  let output = learner()
+    .env("HOME", home.path())
+    .args(["search", "Lattice", "--format", "{year} {first_author} — {title}", "--accept-defaults"])
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  assert_eq!(String::from_utf8(output).unwrap(), "2023 Ada Lovelace — Lattice Sieving\n");
+
+  learner()
+    .env("HOME", home.path())
+    .args(["search", "Lattice", "--format", "{identifier}: {authors}{pages}", "--accept-defaults"])
+    .assert()
+    .success()
+    .stderr(predicate::str::contains("unknown placeholder {pages}"))
+    .stdout("2301.00001: Ada Lovelace, Alan Turing\n");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_no_color_disables_styling() {
+  let papers = [paper("Lattice Sieving", "2301.00001"), paper("Lattice Reduction", "2301.00002")];
+  let (home, _config) = library_home(&papers).await;
+  let search = |envs: &[(&str, &str)], flags: &[&str]| {
+    let output = learner()
+      .env("HOME", home.path())
+      .env_remove("NO_COLOR")
+      .envs(envs.iter().copied())
+      .args(["search", "Lattice", "--accept-defaults"])
+      .args(flags)
+      .assert()
+      .success()
+      .get_output()
+      .stdout
+      .clone();
+    String::from_utf8(output).unwrap()
+  };
+
+  // Forced colors show up even though the output is piped...
+  assert!(search(&[("CLICOLOR_FORCE", "1")], &[]).contains('\x1b'));
+  // ...unless NO_COLOR or --no-color turns them off
+  let plain = search(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], &[]);
+  assert!(!plain.contains('\x1b'), "unexpected ANSI escapes:\n{plain}");
+  assert!(plain.contains("├ Lattice"));
+  assert!(!search(&[("CLICOLOR_FORCE", "1")], &["--no-color"]).contains('\x1b'));
+
+  let ascii = search(&[], &["--ascii"]);
+  assert!(ascii.is_ascii(), "unexpected non-ASCII output:\n{ascii}");
+  assert!(ascii.lines().any(|line| line.starts_with("| Lattice")));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_stats_breakdown() {
+  let mut doi_paper = paper("Lattice Reduction", "10.1000/lattice");
+  doi_paper.source = "doi".to_string();
+  let (home, _config) = library_home(&[
+    paper("Lattice Sieving", "2301.00001"),
+    paper("Lattice Walks", "2301.00002"),
+    doi_paper,
+  ])
+  .await;
+
+  let output = learner()
+    .env("HOME", home.path())
+    .args(["stats", "--breakdown"])
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let output = String::from_utf8(output).unwrap();
+  assert!(output.contains("3 papers by 0 authors"), "{output}");
+  assert!(output.lines().any(|line| line.split_whitespace().eq(["arxiv", "2"])), "{output}");
+  assert!(output.lines().any(|line| line.split_whitespace().eq(["doi", "1"])), "{output}");
+
+  learner()
+    .env("HOME", home.path())
+    .arg("stats")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("By source").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_stats_missing_lists_papers_without_documents() {
+  let server = MockServer::respond_with(
+    MockResponse::ok(b"%PDF-1.4".to_vec()).with_header("Content-Type", "application/pdf"),
+  )
+  .await;
+  let mut downloaded = paper("Downloaded Paper", "2301.00001");
+  downloaded.pdf_url = Some(server.url("/paper.pdf"));
+  let (home, _config) = library_home(&[paper("Pending Paper", "2301.00002")]).await;
+  let mut library =
+    Learner::builder().with_path(home.path().join(".learner")).build().await.unwrap();
+  Add::complete(&downloaded).execute(&mut library.database).await.unwrap();
+  drop(library);
+
+  learner()
+    .env("HOME", home.path())
+    .args(["stats", "--missing"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("1 papers without a stored document"))
+    .stdout(predicate::str::contains("Pending Paper"))
+    .stdout(predicate::str::contains("Downloaded Paper").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_validate_reports_every_broken_file() {
+  let (home, config) = library_home(&[]).await;
+  std::fs::write(config.retrievers_path.join("arxiv.toml"), learner::ARXIV_CONFIG).unwrap();
+  std::fs::write(config.retrievers_path.join("broken.toml"), "name = ").unwrap();
+
+  learner()
+    .env("HOME", home.path())
+    .args(["config", "validate", "--accept-defaults"])
+    .assert()
+    .failure()
+    .stdout(predicate::str::contains("broken.toml"))
+    .stderr(predicate::str::contains("1 of 2 retriever configs"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_path_prints_resolved_paths() {
+  let (home, config) = library_home(&[]).await;
+  // A broken retriever config must not keep the paths from being shown
+  std::fs::write(config.retrievers_path.join("broken.toml"), "name = ").unwrap();
+
+  let output = learner()
+    .env("HOME", home.path())
+    .args(["config", "path", "--accept-defaults"])
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let stdout = String::from_utf8(output).unwrap();
+  let printed: Vec<(&str, PathBuf)> = stdout
+    .lines()
+    .filter_map(|line| line.split_once(' '))
+    .map(|(label, path)| (label, PathBuf::from(path.trim())))
+    .collect();
+  assert_eq!(printed, vec![
+    ("config", home.path().join(".learner").join("config.toml")),
+    ("database", config.database_path.clone()),
+    ("storage", config.storage_path.clone()),
+    ("retrievers", config.retrievers_path.clone()),
+  ]);
+
+  let shown = learner()
+    .env("HOME", home.path())
+    .args(["config", "show", "--accept-defaults"])
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let shown: Config = toml::from_str(&String::from_utf8(shown).unwrap()).unwrap();
+  assert_eq!(shown.database_path, config.database_path);
+  assert_eq!(shown.storage_path, config.storage_path);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_inspection_writes_nothing() {
+  let home = tempdir().unwrap();
+
+  learner()
+    .env("HOME", home.path())
+    .args(["config", "path", "--accept-defaults"])
+    .assert()
+    .success();
+  let shown = learner()
+    .env("HOME", home.path())
+    .args(["config", "show", "--offline", "--accept-defaults"])
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let shown: Config = toml::from_str(&String::from_utf8(shown).unwrap()).unwrap();
+  assert!(shown.offline, "--offline applies to the shown configuration");
+  assert!(!home.path().join(".learner").join("config.toml").exists());
+}
@@ -15,19 +15,48 @@ struct LearnerSdk {
 
 #[derive(Subcommand)]
 enum Commands {
+  /// Validate resource and retriever configs
+  Validate {
+    #[command(subcommand)]
+    target: ValidateCommands,
+  },
+}
+
+/// What [`Commands::Validate`] checks
+#[derive(Subcommand)]
+enum ValidateCommands {
   /// Validate a resource config
-  ValidateResource {
+  Resource {
     /// Path to the configuration file
     path: PathBuf,
   },
+  /// Validate every resource config in a directory, reporting all failures at once
+  Resources {
+    /// Path to the directory of configuration files
+    dir: PathBuf,
+  },
   /// Validate a retriever config for an optional given input
-  ValidateRetriever {
+  Retriever {
     /// Path to the configuration file
     path: PathBuf,
 
     /// Identifier or URL
     input: Option<String>,
   },
+  /// Validate every retriever config in a directory, reporting all failures at once
+  Retrievers {
+    /// Path to the directory of configuration files
+    dir: PathBuf,
+  },
+  /// Check that a changed resource config keeps resources stored under the older one valid,
+  /// exiting with an error if it does not
+  Compatibility {
+    /// Path to the older configuration file
+    older: PathBuf,
+
+    /// Path to the newer configuration file
+    newer: PathBuf,
+  },
 }
 
 #[tokio::main]
@@ -42,8 +71,9 @@ async fn main() {
 
   let cli = LearnerSdk::parse();
 
-  match &cli.command {
-    Commands::ValidateRetriever { path, input } => {
+  let Commands::Validate { target } = &cli.command;
+  match target {
+    ValidateCommands::Retriever { path, input } => {
       info!("Validating retriever...");
       if !path.exists() {
         error!("Path to retriever config was invalid.\nPath used: {path:?}");
@@ -52,7 +82,16 @@ async fn main() {
       debug!("Validating retriever config at {:?}", path);
       validate::validate_retriever(path, input).await;
     },
-    Commands::ValidateResource { path } => {
+    ValidateCommands::Retrievers { dir } => {
+      info!("Validating retrievers...");
+      if !dir.is_dir() {
+        error!("Path to retriever config directory was invalid.\nPath used: {dir:?}");
+        return;
+      }
+      debug!("Validating retriever configs in {:?}", dir);
+      validate::validate_retriever_dir(dir);
+    },
+    ValidateCommands::Resource { path } => {
       info!("Validating resource...");
       if !path.exists() {
         error!("Path to resource config was invalid.\nPath used: {path:?}");
@@ -61,5 +100,20 @@ async fn main() {
       debug!("Validating resource config at {:?}", path);
       validate::validate_resource(path);
     },
+    ValidateCommands::Resources { dir } => {
+      info!("Validating resources...");
+      if !dir.is_dir() {
+        error!("Path to resource config directory was invalid.\nPath used: {dir:?}");
+        return;
+      }
+      debug!("Validating resource configs in {:?}", dir);
+      validate::validate_resource_dir(dir);
+    },
+    ValidateCommands::Compatibility { older, newer } => {
+      info!("Checking resource compatibility...");
+      if !validate::check_resource_compatibility(older, newer) {
+        std::process::exit(1);
+      }
+    },
   }
 }
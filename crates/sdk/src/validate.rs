@@ -2,7 +2,7 @@ use std::fs::read_to_string;
 
 use learner::{
   resource::ResourceConfig,
-  retriever::{ResponseFormat, RetrieverConfig},
+  retriever::{ResponseFormat, Retriever, RetrieverConfig},
 };
 
 use super::*;
@@ -30,6 +30,69 @@ pub fn validate_resource(path: &PathBuf) {
   debug!("All config fields are:\n{:#?}", resource.fields());
 }
 
+/// Reports what `newer` breaks for resources stored under `older`, returning whether the two
+/// are compatible.
+pub fn check_resource_compatibility(older: &PathBuf, newer: &PathBuf) -> bool {
+  let load = |path: &PathBuf| -> Option<ResourceConfig> {
+    let config_str = read_to_string(path)
+      .map_err(|e| error!("Failed to read {path:?} to string due to: {e:?}"))
+      .ok()?;
+    toml::from_str(&config_str).map_err(|e| error!("Failed to parse {path:?} due to: {e:?}")).ok()
+  };
+  let (Some(older), Some(newer)) = (load(older), load(newer)) else {
+    return false;
+  };
+
+  let breakages = newer.is_backward_compatible(&older);
+  for breakage in &breakages {
+    error!("Breaking change: {breakage}");
+  }
+  if breakages.is_empty() {
+    info!("Resource type `{}` is backward compatible.", newer.type_name);
+  }
+  breakages.is_empty()
+}
+
+pub fn validate_resource_dir(dir: &PathBuf) {
+  let (resources, errors) = match ResourceConfig::load_dir(dir) {
+    Ok(loaded) => loaded,
+    Err(e) => {
+      error!("Failed to read config directory due to: {e:?}");
+      return;
+    },
+  };
+
+  for resource in &resources {
+    info!("Loaded resource type: {}", resource.type_name);
+  }
+  for (file, e) in &errors {
+    error!("Failed to load `{file}` due to: {e}");
+  }
+  if errors.is_empty() {
+    info!("All resource configs are valid.");
+  }
+}
+
+pub fn validate_retriever_dir(dir: &PathBuf) {
+  let (retrievers, errors) = match Retriever::new().load_config_dir(dir) {
+    Ok(loaded) => loaded,
+    Err(e) => {
+      error!("Failed to read config directory due to: {e:?}");
+      return;
+    },
+  };
+
+  for name in retrievers.names() {
+    info!("Loaded retriever: {name}");
+  }
+  for (file, e) in &errors {
+    error!("Failed to load `{file}` due to: {e}");
+  }
+  if errors.is_empty() {
+    info!("All retriever configs are valid.");
+  }
+}
+
 pub async fn validate_retriever(path: &PathBuf, input: &Option<String>) {
   let config_str = match read_to_string(path) {
     Ok(str) => str,
@@ -56,6 +119,20 @@ pub async fn validate_retriever(path: &PathBuf, input: &Option<String>) {
     },
   }
 
+  info!("Checking pattern against the bundled retrievers for overlaps...");
+  match Retriever::new().with_default_configs().and_then(|r| r.with_config_str(&config_str)) {
+    Ok(retrievers) => {
+      let overlaps = retrievers.check_overlaps(Retriever::DEFAULT_OVERLAP_SAMPLES);
+      if overlaps.is_empty() {
+        info!("No overlapping patterns found.");
+      }
+      for (sample, names) in overlaps {
+        warn!("Input `{sample}` is matched by multiple retrievers: {}", names.join(", "));
+      }
+    },
+    Err(e) => error!("Failed to load bundled retrievers for overlap check due to: {e:?}"),
+  }
+
   if let Some(input) = input {
     info!("Attempting to match against pattern...");
     match retriever.extract_identifier(input) {